@@ -1,5 +1,6 @@
 #![deny(clippy::all)]
 mod import_finder;
+pub mod sarif;
 mod tracer;
 
-pub use tracer::{TraceError, TraceResult, Tracer};
+pub use tracer::{FanIn, TraceError, TraceResult, Tracer};
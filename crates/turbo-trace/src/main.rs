@@ -1,8 +1,9 @@
 mod import_finder;
+mod sarif;
 mod tracer;
 
 use camino::Utf8PathBuf;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use tracer::Tracer;
 use turbopath::{AbsoluteSystemPathBuf, PathError};
 
@@ -15,6 +16,21 @@ struct Args {
     files: Vec<Utf8PathBuf>,
     #[clap(long)]
     depth: Option<usize>,
+    #[clap(long, value_enum, default_value_t)]
+    format: Format,
+    /// Lists how many of the traced files import each file, directly and
+    /// transitively, with the most imported files first
+    #[clap(long, conflicts_with = "format")]
+    fan_in: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum Format {
+    /// Traced files on stdout, errors on stderr
+    #[default]
+    Text,
+    /// A SARIF log of the errors on stdout, for code scanning tools
+    Sarif,
 }
 
 fn main() -> Result<(), PathError> {
@@ -32,15 +48,29 @@ fn main() -> Result<(), PathError> {
         .map(|f| AbsoluteSystemPathBuf::from_unknown(&abs_cwd, f))
         .collect();
 
-    let tracer = Tracer::new(abs_cwd, files, args.ts_config);
+    let tracer = Tracer::new(abs_cwd.clone(), files, args.ts_config);
 
     let result = tracer.trace(args.depth);
 
-    if !result.errors.is_empty() {
+    if let Format::Sarif = args.format {
+        let log = sarif::Log::new(&abs_cwd, &result.errors);
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&log).expect("SARIF log is serializable")
+        );
+        if !result.errors.is_empty() {
+            std::process::exit(1);
+        }
+    } else if !result.errors.is_empty() {
         for error in &result.errors {
             eprintln!("error: {}", error);
         }
         std::process::exit(1);
+    } else if args.fan_in {
+        println!("DIRECT\tTRANSITIVE\tFILE");
+        for fan_in in result.fan_in() {
+            println!("{}\t{}\t{}", fan_in.direct, fan_in.transitive, fan_in.file);
+        }
     } else {
         for file in result.files.keys() {
             println!("{}", file);
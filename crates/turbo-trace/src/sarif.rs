@@ -0,0 +1,278 @@
+//! Converts trace errors into a [SARIF](https://sarifweb.azurewebsites.net/)
+//! log, so code scanning tools like GitHub's can annotate the files they were
+//! found in.
+
+use miette::SourceCode;
+use serde::Serialize;
+use turbopath::AbsoluteSystemPath;
+
+use crate::tracer::TraceError;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+struct Rule {
+    id: &'static str,
+    description: &'static str,
+}
+
+const UNRESOLVED_IMPORT: Rule = Rule {
+    id: "unresolved-import",
+    description: "An import or require that couldn't be resolved to a file",
+};
+const UNREADABLE_FILE: Rule = Rule {
+    id: "unreadable-file",
+    description: "A traced file that couldn't be read or parsed",
+};
+const ROOT_FILE: Rule = Rule {
+    id: "root-file",
+    description: "A traced file at the root of the filesystem",
+};
+const INVALID_PATH: Rule = Rule {
+    id: "invalid-path",
+    description: "An import that resolved to a path that isn't valid UTF-8",
+};
+
+const RULES: [Rule; 4] = [UNRESOLVED_IMPORT, UNREADABLE_FILE, ROOT_FILE, INVALID_PATH];
+
+#[derive(Serialize)]
+pub struct Log {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: [Run; 1],
+}
+
+#[derive(Serialize)]
+struct Run {
+    tool: Tool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct Tool {
+    driver: Driver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Driver {
+    name: &'static str,
+    version: &'static str,
+    rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportingDescriptor {
+    id: &'static str,
+    short_description: Message,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: Message,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location>,
+}
+
+#[derive(Serialize)]
+struct Message {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location {
+    physical_location: PhysicalLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region>,
+}
+
+#[derive(Serialize)]
+struct ArtifactLocation {
+    uri: String,
+}
+
+// SARIF lines and columns start at 1
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region {
+    start_line: usize,
+    start_column: usize,
+    snippet: Message,
+}
+
+impl Log {
+    /// Builds a log with a result for each error. File locations are made
+    /// relative to `cwd` when they're inside of it, and are `file://` URLs
+    /// otherwise.
+    pub fn new(cwd: &AbsoluteSystemPath, errors: &[TraceError]) -> Self {
+        let results = errors.iter().map(|error| result(cwd, error)).collect();
+        Self {
+            schema: SCHEMA,
+            version: "2.1.0",
+            runs: [Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "turbo-trace",
+                        version: env!("CARGO_PKG_VERSION"),
+                        rules: RULES
+                            .iter()
+                            .map(|rule| ReportingDescriptor {
+                                id: rule.id,
+                                short_description: Message {
+                                    text: rule.description.to_string(),
+                                },
+                            })
+                            .collect(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+fn result(cwd: &AbsoluteSystemPath, error: &TraceError) -> SarifResult {
+    let (rule, location) = match error {
+        TraceError::FileNotFound(path) => (UNREADABLE_FILE, Some(location(cwd, path, None))),
+        TraceError::RootFile(path) => (ROOT_FILE, Some(location(cwd, path, None))),
+        TraceError::PathEncoding(_) => (INVALID_PATH, None),
+        TraceError::Resolve { span, text } => {
+            let region = text.read_span(span, 0, 0).ok().map(|contents| Region {
+                start_line: contents.line() + 1,
+                start_column: contents.column() + 1,
+                snippet: Message {
+                    text: String::from_utf8_lossy(contents.data()).into_owned(),
+                },
+            });
+            let location = match AbsoluteSystemPath::new(text.name()) {
+                Ok(path) => location(cwd, path, region),
+                Err(_) => PhysicalLocation {
+                    artifact_location: ArtifactLocation {
+                        uri: text.name().to_string(),
+                    },
+                    region,
+                },
+            };
+            (UNRESOLVED_IMPORT, Some(location))
+        }
+    };
+
+    SarifResult {
+        rule_id: rule.id,
+        level: "error",
+        message: Message {
+            text: error.to_string(),
+        },
+        locations: location
+            .map(|physical_location| Location { physical_location })
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn location(
+    cwd: &AbsoluteSystemPath,
+    path: &AbsoluteSystemPath,
+    region: Option<Region>,
+) -> PhysicalLocation {
+    let uri = match cwd.anchor(path) {
+        Ok(relative) => relative.to_unix().to_string(),
+        Err(_) => path.to_file_url(),
+    };
+    PhysicalLocation {
+        artifact_location: ArtifactLocation { uri },
+        region,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use miette::NamedSource;
+    use serde_json::json;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::*;
+
+    #[test]
+    fn test_sarif_log() {
+        let cwd =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { "C:\\repo" } else { "/repo" }).unwrap();
+        let file = cwd.join_components(&["src", "index.ts"]);
+        let source = "import a from './a';\nimport b from './missing';\n";
+        let errors = [
+            TraceError::Resolve {
+                span: (21, 26).into(),
+                text: NamedSource::new(file.to_string(), source.to_string()),
+            },
+            TraceError::FileNotFound(cwd.join_component("gone.js")),
+        ];
+
+        let log = serde_json::to_value(Log::new(&cwd, &errors)).unwrap();
+        let results = &log["runs"][0]["results"];
+        assert_eq!(
+            results[0],
+            json!({
+                "ruleId": "unresolved-import",
+                "level": "error",
+                "message": { "text": "failed to resolve import" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/index.ts" },
+                        "region": {
+                            "startLine": 2,
+                            "startColumn": 1,
+                            "snippet": { "text": "import b from './missing';" }
+                        }
+                    }
+                }]
+            })
+        );
+        assert_eq!(results[1]["ruleId"], "unreadable-file");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "gone.js"
+        );
+        assert_eq!(
+            log["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "unresolved-import"
+        );
+    }
+
+    #[test]
+    fn test_location_outside_cwd() {
+        let (cwd, file, expected) = if cfg!(windows) {
+            (
+                "C:\\repo",
+                "C:\\other repo\\index.js",
+                "file:///C:/other%20repo/index.js",
+            )
+        } else {
+            (
+                "/repo",
+                "/other repo/index.js",
+                "file:///other%20repo/index.js",
+            )
+        };
+        let cwd = AbsoluteSystemPathBuf::new(cwd).unwrap();
+        let file = AbsoluteSystemPathBuf::new(file).unwrap();
+        let log = Log::new(&cwd, &[TraceError::FileNotFound(file)]);
+
+        let log = serde_json::to_value(log).unwrap();
+        assert_eq!(
+            log["runs"][0]["results"][0]["locations"][0]["physicalLocation"]["artifactLocation"]
+                ["uri"],
+            expected
+        );
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, fs, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    rc::Rc,
+};
 
 use camino::Utf8PathBuf;
 use miette::{Diagnostic, NamedSource, SourceSpan};
@@ -17,6 +21,9 @@ use crate::import_finder::ImportFinder;
 #[derive(Default)]
 pub struct SeenFile {
     pub ast: Option<swc_ecma_ast::Module>,
+    /// The files this file imports, including ones that weren't traced
+    /// further because of their type or the depth limit
+    pub imports: Vec<AbsoluteSystemPathBuf>,
 }
 
 pub struct Tracer {
@@ -47,6 +54,56 @@ pub struct TraceResult {
     pub files: HashMap<AbsoluteSystemPathBuf, SeenFile>,
 }
 
+/// How many of the traced files import a file
+#[derive(Debug, PartialEq, Eq)]
+pub struct FanIn {
+    pub file: AbsoluteSystemPathBuf,
+    pub direct: usize,
+    pub transitive: usize,
+}
+
+impl TraceResult {
+    /// Counts the importers of every file in the trace, sorted with the most
+    /// transitive importers first
+    pub fn fan_in(&self) -> Vec<FanIn> {
+        let mut importers: HashMap<&AbsoluteSystemPathBuf, HashSet<&AbsoluteSystemPathBuf>> = self
+            .files
+            .keys()
+            .map(|file| (file, HashSet::new()))
+            .collect();
+        for (file, seen) in &self.files {
+            for import in &seen.imports {
+                importers.entry(import).or_default().insert(file);
+            }
+        }
+
+        let mut fan_in = importers
+            .iter()
+            .map(|(file, direct)| {
+                let mut transitive = HashSet::new();
+                let mut stack = direct.iter().copied().collect::<Vec<_>>();
+                while let Some(importer) = stack.pop() {
+                    if importer != *file && transitive.insert(importer) {
+                        stack.extend(importers.get(importer).into_iter().flatten().copied());
+                    }
+                }
+                FanIn {
+                    file: (*file).clone(),
+                    direct: direct.iter().filter(|importer| *importer != file).count(),
+                    transitive: transitive.len(),
+                }
+            })
+            .collect::<Vec<_>>();
+        fan_in.sort_by(|a, b| {
+            b.transitive
+                .cmp(&a.transitive)
+                .then(b.direct.cmp(&a.direct))
+                .then_with(|| a.file.cmp(&b.file))
+        });
+        fan_in
+    }
+}
+
 impl Tracer {
     pub fn new(
         cwd: AbsoluteSystemPathBuf,
@@ -156,8 +213,12 @@ impl Tracer {
                     continue;
                 };
                 match resolver.resolve(file_dir, import) {
-                    Ok(resolved) => match resolved.into_path_buf().try_into() {
-                        Ok(path) => self.files.push((path, file_depth + 1)),
+                    Ok(resolved) => match AbsoluteSystemPathBuf::try_from(resolved.into_path_buf())
+                    {
+                        Ok(path) => {
+                            entry.imports.push(path.clone());
+                            self.files.push((path, file_depth + 1))
+                        }
                         Err(err) => {
                             errors.push(TraceError::PathEncoding(err));
                         }
@@ -167,7 +228,7 @@ impl Tracer {
                         let (start, end) = self.source_map.span_to_char_offset(&source_file, *span);
 
                         errors.push(TraceError::Resolve {
-                            span: (start as usize, end as usize).into(),
+                            span: (start as usize, (end - start) as usize).into(),
                             text: NamedSource::new(file_path.to_string(), file_content.clone()),
                         });
                     }
@@ -181,3 +242,53 @@ impl Tracer {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fan_in() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { "C:\\repo" } else { "/repo" }).unwrap();
+        let path = |name: &str| root.join_component(name);
+        let file = |imports: &[&str]| SeenFile {
+            ast: None,
+            imports: imports.iter().map(|name| path(name)).collect(),
+        };
+        // a -> b -> c -> d, a -> c, d -> b, e -> c, c -> styles.css
+        let result = TraceResult {
+            errors: Vec::new(),
+            files: HashMap::from([
+                (path("a.ts"), file(&["b.ts", "c.ts"])),
+                (path("b.ts"), file(&["c.ts"])),
+                (path("c.ts"), file(&["d.ts", "styles.css"])),
+                (path("d.ts"), file(&["b.ts"])),
+                (path("e.ts"), file(&["c.ts"])),
+            ]),
+        };
+
+        let fan_in = result
+            .fan_in()
+            .into_iter()
+            .map(|fan_in| {
+                (
+                    fan_in.file.file_name().unwrap().to_string(),
+                    fan_in.direct,
+                    fan_in.transitive,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fan_in,
+            [
+                ("styles.css".to_string(), 1, 5),
+                ("c.ts".to_string(), 3, 4),
+                ("b.ts".to_string(), 2, 4),
+                ("d.ts".to_string(), 1, 4),
+                ("a.ts".to_string(), 0, 0),
+                ("e.ts".to_string(), 0, 0),
+            ]
+        );
+    }
+}
@@ -9,7 +9,7 @@ use lazy_static::lazy_static;
 use regex::Regex;
 pub use reqwest::Response;
 use reqwest::{Body, Method, RequestBuilder, StatusCode};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use turborepo_ci::{is_ci, Vendor};
 use turborepo_vercel_api::{
     token::ResponseTokenMetadata, APIError, CachingStatus, CachingStatusResponse,
@@ -73,6 +73,17 @@ pub trait CacheClient {
         team_id: Option<&str>,
         team_slug: Option<&str>,
     ) -> impl Future<Output = Result<Option<Response>>> + Send;
+    /// Fetches an artifact starting at byte `start` to resume an interrupted
+    /// download. Servers that don't support ranges respond with the whole
+    /// artifact.
+    fn fetch_artifact_range(
+        &self,
+        hash: &str,
+        start: u64,
+        token: &str,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+    ) -> impl Future<Output = Result<Option<Response>>> + Send;
     #[allow(clippy::too_many_arguments)]
     fn put_artifact(
         &self,
@@ -305,43 +316,8 @@ impl CacheClient for APIClient {
         team_slug: Option<&str>,
         method: Method,
     ) -> Result<Option<Response>> {
-        let mut request_url = self.make_url(&format!("/v8/artifacts/{}", hash))?;
-        let mut allow_auth = true;
-
-        if self.use_preflight {
-            let preflight_response = self
-                .do_preflight(
-                    token,
-                    request_url.clone(),
-                    "GET",
-                    "Authorization, User-Agent",
-                )
-                .await?;
-
-            allow_auth = preflight_response.allow_authorization_header;
-            request_url = preflight_response.location;
-        };
-
-        let mut request_builder = self
-            .client
-            .request(method, request_url)
-            .header("User-Agent", self.user_agent.clone());
-
-        if allow_auth {
-            request_builder = request_builder.header("Authorization", format!("Bearer {}", token));
-        }
-
-        request_builder = Self::add_team_params(request_builder, team_id, team_slug);
-
-        let response =
-            retry::make_retryable_request(request_builder, retry::RetryStrategy::Timeout).await?;
-        let response = response.into_response();
-
-        match response.status() {
-            StatusCode::FORBIDDEN => Err(Self::handle_403(response).await),
-            StatusCode::NOT_FOUND => Ok(None),
-            _ => Ok(Some(response.error_for_status()?)),
-        }
+        self.request_artifact(hash, None, token, team_id, team_slug, method)
+            .await
     }
 
     #[tracing::instrument(skip_all)]
@@ -368,6 +344,19 @@ impl CacheClient for APIClient {
             .await
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn fetch_artifact_range(
+        &self,
+        hash: &str,
+        start: u64,
+        token: &str,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+    ) -> Result<Option<Response>> {
+        self.request_artifact(hash, Some(start), token, team_id, team_slug, Method::GET)
+            .await
+    }
+
     #[tracing::instrument(skip_all)]
     async fn put_artifact(
         &self,
@@ -628,6 +617,99 @@ impl APIClient {
         self.base_url.as_str()
     }
 
+    /// Exchanges an OIDC ID token issued by a CI provider for a token that can
+    /// be used with the Remote Cache
+    pub async fn exchange_oidc_token(
+        &self,
+        id_token: &str,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+    ) -> Result<String> {
+        #[derive(Serialize)]
+        struct Request<'a> {
+            token: &'a str,
+        }
+        #[derive(Deserialize)]
+        struct Response {
+            token: String,
+        }
+
+        let url = self.make_url("/v8/artifacts/oidc/token")?;
+        let request_builder = self
+            .client
+            .post(url)
+            .header("User-Agent", self.user_agent.clone())
+            .json(&Request { token: id_token });
+        let request_builder = Self::add_team_params(request_builder, team_id, team_slug);
+
+        let response =
+            retry::make_retryable_request(request_builder, retry::RetryStrategy::Timeout)
+                .await?
+                .into_response();
+
+        match response.status() {
+            StatusCode::FORBIDDEN => Err(Self::handle_403(response).await),
+            _ => Ok(response.error_for_status()?.json::<Response>().await?.token),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn request_artifact(
+        &self,
+        hash: &str,
+        range_start: Option<u64>,
+        token: &str,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+        method: Method,
+    ) -> Result<Option<Response>> {
+        let mut request_url = self.make_url(&format!("/v8/artifacts/{}", hash))?;
+        let mut allow_auth = true;
+
+        if self.use_preflight {
+            let preflight_response = self
+                .do_preflight(
+                    token,
+                    request_url.clone(),
+                    "GET",
+                    if range_start.is_some() {
+                        "Authorization, Range, User-Agent"
+                    } else {
+                        "Authorization, User-Agent"
+                    },
+                )
+                .await?;
+
+            allow_auth = preflight_response.allow_authorization_header;
+            request_url = preflight_response.location;
+        };
+
+        let mut request_builder = self
+            .client
+            .request(method, request_url)
+            .header("User-Agent", self.user_agent.clone());
+
+        if allow_auth {
+            request_builder = request_builder.header("Authorization", format!("Bearer {}", token));
+        }
+
+        if let Some(start) = range_start {
+            request_builder = request_builder.header("Range", format!("bytes={start}-"));
+        }
+
+        request_builder = Self::add_team_params(request_builder, team_id, team_slug);
+
+        let response =
+            retry::make_retryable_request(request_builder, retry::RetryStrategy::Timeout).await?;
+        let response = response.into_response();
+
+        match response.status() {
+            StatusCode::FORBIDDEN => Err(Self::handle_403(response).await),
+            StatusCode::NOT_FOUND => Ok(None),
+            _ => Ok(Some(response.error_for_status()?)),
+        }
+    }
+
     async fn do_preflight(
         &self,
         token: &str,
@@ -904,6 +986,30 @@ mod test {
         assert_snapshot!(err.to_string(), @"unknown status forbidden: Not authorized");
     }
 
+    #[tokio::test]
+    async fn test_exchange_oidc_token() -> Result<()> {
+        let server = httpmock::MockServer::start_async().await;
+        let exchange = server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::POST)
+                    .path("/v8/artifacts/oidc/token")
+                    .query_param("teamId", "team_123")
+                    .json_body(serde_json::json!({ "token": "id-token" }));
+                then.status(200)
+                    .json_body(serde_json::json!({ "token": "cache-token" }));
+            })
+            .await;
+
+        let client = APIClient::new(server.base_url(), None, None, "2.0.0", false)?;
+        let token = client
+            .exchange_oidc_token("id-token", Some("team_123"), None)
+            .await?;
+
+        exchange.assert_async().await;
+        assert_eq!(token, "cache-token");
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_content_length() -> Result<()> {
         let port = port_scanner::request_open_port().unwrap();
@@ -0,0 +1,380 @@
+//! Login flows for self-hosted Remote Caches, which can't rely on the Vercel
+//! login pages and user endpoints that the default flow uses.
+//!
+//! A cache configures one of these under `auth` in `.turbo/config.json`:
+//! - `browser` opens a page on the cache that redirects back to `turbo` with
+//!   `?token=<token>`, the same contract as the default flow.
+//! - `device` uses the OAuth 2.0 Device Authorization Grant (RFC 8628), so it
+//!   also works over SSH and other places a browser can't redirect back to.
+
+use std::{sync::Arc, time::Duration};
+
+use reqwest::Url;
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+use turborepo_api_client::{CacheClient, Client, TokenClient};
+use turborepo_ui::{start_spinner, BOLD};
+
+use crate::{ui, Error, LoginOptions, Token};
+
+const DEFAULT_HOST_NAME: &str = "127.0.0.1";
+const DEFAULT_PORT: u16 = 9789;
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+// RFC 8628 section 3.2, used when the server doesn't send an interval
+const DEFAULT_POLL_INTERVAL: u64 = 5;
+// RFC 8628 section 3.5, added to the interval every time we're told to slow
+// down
+const SLOW_DOWN_INCREMENT: u64 = 5;
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum AuthFlow {
+    #[serde(rename_all = "camelCase")]
+    Browser {
+        /// Page that's opened with a `redirect_uri` query parameter
+        url: String,
+        /// Page the browser is sent to once `turbo` has the token
+        success_url: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Device {
+        device_authorization_url: String,
+        token_url: String,
+        client_id: String,
+        scope: Option<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TokenResponse {
+    Token { access_token: String },
+    Error { error: String },
+}
+
+#[derive(Debug, PartialEq)]
+enum Poll {
+    Token(String),
+    Pending,
+    SlowDown,
+}
+
+/// Logs in with the flow a self-hosted Remote Cache configured. An existing
+/// token is kept if the cache still accepts it.
+pub(crate) async fn flow_login<T: Client + TokenClient + CacheClient>(
+    options: &LoginOptions<'_, T>,
+    auth_flow: &AuthFlow,
+) -> Result<Token, Error> {
+    let LoginOptions {
+        api_client,
+        color_config,
+        login_server,
+        existing_token,
+        force,
+        ..
+    } = *options;
+
+    if !force {
+        if let Some(token) = existing_token {
+            debug!("found existing turbo token");
+            let token = Token::existing(token.into());
+            // Self-hosted caches don't have the token and user endpoints that
+            // `Token::is_valid` checks, so cache access is all we can verify.
+            // The token may be for another cache entirely, so any error means
+            // we need a new one.
+            if matches!(token.has_cache_access(api_client, None).await, Ok(true)) {
+                println!(
+                    "{}",
+                    color_config.apply(BOLD.apply_to("Existing token found!"))
+                );
+                ui::print_cli_authorized_for_cache(color_config);
+                return Ok(token);
+            }
+        }
+    }
+
+    let token = match auth_flow {
+        AuthFlow::Browser { url, success_url } => {
+            browser_login(login_server, url, success_url.as_deref()).await?
+        }
+        AuthFlow::Device {
+            device_authorization_url,
+            token_url,
+            client_id,
+            scope,
+        } => {
+            device_login(
+                device_authorization_url,
+                token_url,
+                client_id,
+                scope.as_deref(),
+            )
+            .await?
+        }
+    };
+
+    ui::print_cli_authorized_for_cache(color_config);
+    Ok(Token::new(token))
+}
+
+async fn browser_login(
+    login_server: &dyn crate::LoginServer,
+    url: &str,
+    success_url: Option<&str>,
+) -> Result<String, Error> {
+    let redirect_url = format!("http://{DEFAULT_HOST_NAME}:{DEFAULT_PORT}");
+    let mut login_url = Url::parse(url)?;
+    login_url
+        .query_pairs_mut()
+        .append_pair("redirect_uri", &redirect_url);
+
+    println!(">>> Opening browser to {login_url}");
+    let spinner = start_spinner("Waiting for your authorization...");
+
+    let url = login_url.as_str();
+    if !cfg!(test) && webbrowser::open(url).is_err() {
+        warn!("Failed to open browser. Please visit {url} in your browser.");
+    }
+
+    let token_cell = Arc::new(OnceCell::new());
+    login_server
+        .run(
+            DEFAULT_PORT,
+            crate::LoginType::Custom {
+                success_redirect: success_url.map(str::to_string),
+            },
+            token_cell.clone(),
+        )
+        .await?;
+
+    spinner.finish_and_clear();
+
+    token_cell.get().cloned().ok_or(Error::FailedToGetToken)
+}
+
+async fn device_login(
+    device_authorization_url: &str,
+    token_url: &str,
+    client_id: &str,
+    scope: Option<&str>,
+) -> Result<String, Error> {
+    let client = reqwest::Client::new();
+
+    let mut form = vec![("client_id", client_id)];
+    if let Some(scope) = scope {
+        form.push(("scope", scope));
+    }
+    let authorization = client
+        .post(device_authorization_url)
+        .form(&form)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(Error::DeviceAuthorizationRequest)?
+        .json::<DeviceAuthorization>()
+        .await
+        .map_err(Error::DeviceAuthorizationRequest)?;
+
+    let verification_url = authorization
+        .verification_uri_complete
+        .as_deref()
+        .unwrap_or(&authorization.verification_uri);
+    println!(
+        ">>> Visit {} and enter the code {}",
+        authorization.verification_uri, authorization.user_code
+    );
+    if !cfg!(test) && webbrowser::open(verification_url).is_err() {
+        debug!("failed to open browser to {verification_url}");
+    }
+    let spinner = start_spinner("Waiting for your authorization...");
+
+    let token = poll_for_token(&client, token_url, client_id, &authorization).await;
+    spinner.finish_and_clear();
+
+    token
+}
+
+async fn poll_for_token(
+    client: &reqwest::Client,
+    token_url: &str,
+    client_id: &str,
+    authorization: &DeviceAuthorization,
+) -> Result<String, Error> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(authorization.expires_in);
+    let mut interval = authorization.interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err(Error::DeviceCodeExpired);
+        }
+
+        // Errors are sent with a 400, so the status is only checked by
+        // `poll_result` after looking for one
+        let response = client
+            .post(token_url)
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                ("device_code", &authorization.device_code),
+                ("client_id", client_id),
+            ])
+            .send()
+            .await
+            .map_err(Error::DeviceTokenRequest)?;
+        let status = response.status();
+        let body = response.text().await.map_err(Error::DeviceTokenRequest)?;
+
+        match poll_result(status, &body)? {
+            Poll::Token(token) => return Ok(token),
+            Poll::Pending => {}
+            Poll::SlowDown => interval += SLOW_DOWN_INCREMENT,
+        }
+    }
+}
+
+fn poll_result(status: reqwest::StatusCode, body: &str) -> Result<Poll, Error> {
+    match serde_json::from_str::<TokenResponse>(body) {
+        Ok(TokenResponse::Token { access_token }) if status.is_success() => {
+            Ok(Poll::Token(access_token))
+        }
+        Ok(TokenResponse::Error { error }) => match error.as_str() {
+            "authorization_pending" => Ok(Poll::Pending),
+            "slow_down" => Ok(Poll::SlowDown),
+            "expired_token" => Err(Error::DeviceCodeExpired),
+            "access_denied" => Err(Error::DeviceAuthorizationDenied),
+            _ => Err(Error::DeviceTokenError(error)),
+        },
+        _ => Err(Error::DeviceTokenError(format!(
+            "unexpected {status} response"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use reqwest::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize_auth_flow() {
+        let flow: AuthFlow = serde_json::from_value(json!({
+            "type": "device",
+            "deviceAuthorizationUrl": "https://cache.example.com/oauth/device",
+            "tokenUrl": "https://cache.example.com/oauth/token",
+            "clientId": "turbo"
+        }))
+        .unwrap();
+        assert_eq!(
+            flow,
+            AuthFlow::Device {
+                device_authorization_url: "https://cache.example.com/oauth/device".to_string(),
+                token_url: "https://cache.example.com/oauth/token".to_string(),
+                client_id: "turbo".to_string(),
+                scope: None,
+            }
+        );
+
+        let flow: AuthFlow = serde_json::from_value(json!({
+            "type": "browser",
+            "url": "https://cache.example.com/login"
+        }))
+        .unwrap();
+        assert_eq!(
+            flow,
+            AuthFlow::Browser {
+                url: "https://cache.example.com/login".to_string(),
+                success_url: None,
+            }
+        );
+
+        assert!(serde_json::from_value::<AuthFlow>(json!({ "type": "password" })).is_err());
+    }
+
+    #[test]
+    fn test_poll_result() {
+        assert_eq!(
+            poll_result(
+                StatusCode::OK,
+                r#"{"access_token":"token","token_type":"Bearer"}"#
+            )
+            .unwrap(),
+            Poll::Token("token".to_string())
+        );
+        assert_eq!(
+            poll_result(
+                StatusCode::BAD_REQUEST,
+                r#"{"error":"authorization_pending"}"#
+            )
+            .unwrap(),
+            Poll::Pending
+        );
+        assert_eq!(
+            poll_result(StatusCode::BAD_REQUEST, r#"{"error":"slow_down"}"#).unwrap(),
+            Poll::SlowDown
+        );
+        assert!(matches!(
+            poll_result(StatusCode::BAD_REQUEST, r#"{"error":"access_denied"}"#),
+            Err(Error::DeviceAuthorizationDenied)
+        ));
+        assert!(matches!(
+            poll_result(StatusCode::BAD_REQUEST, r#"{"error":"expired_token"}"#),
+            Err(Error::DeviceCodeExpired)
+        ));
+        assert!(matches!(
+            poll_result(StatusCode::BAD_GATEWAY, "<html></html>"),
+            Err(Error::DeviceTokenError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_device_login() {
+        let server = httpmock::MockServer::start_async().await;
+        let authorization = server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::POST)
+                    .path("/device")
+                    .body("client_id=turbo&scope=cache");
+                then.status(200).json_body(json!({
+                    "device_code": "device-code",
+                    "user_code": "ABCD-EFGH",
+                    "verification_uri": server.url("/activate"),
+                    "expires_in": 60,
+                    "interval": 0
+                }));
+            })
+            .await;
+        let token = server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::POST)
+                    .path("/token")
+                    .body_contains("device_code=device-code");
+                then.status(200)
+                    .json_body(json!({ "access_token": "cache-token" }));
+            })
+            .await;
+
+        let result = device_login(
+            &server.url("/device"),
+            &server.url("/token"),
+            "turbo",
+            Some("cache"),
+        )
+        .await;
+
+        authorization.assert_async().await;
+        token.assert_async().await;
+        assert_eq!(result.unwrap(), "cache-token");
+    }
+}
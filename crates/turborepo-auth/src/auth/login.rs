@@ -7,7 +7,10 @@ use tracing::{debug, warn};
 use turborepo_api_client::{CacheClient, Client, TokenClient};
 use turborepo_ui::{start_spinner, ColorConfig, BOLD};
 
-use crate::{auth::extract_vercel_token, error, ui, LoginOptions, Token};
+use crate::{
+    auth::{extract_vercel_token, flow::flow_login},
+    error, ui, LoginOptions, Token,
+};
 
 const DEFAULT_HOST_NAME: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 9789;
@@ -17,10 +20,15 @@ const DEFAULT_PORT: u16 = 9789;
 /// setting the `exists` field to `true`.
 ///
 /// First checks if an existing option has been passed in, then if the login is
-/// to Vercel, checks if the user has a Vercel CLI token on disk.
+/// to Vercel, checks if the user has a Vercel CLI token on disk. When an
+/// `auth_flow` is configured, it's used instead of the Vercel login flow.
 pub async fn login<T: Client + TokenClient + CacheClient>(
     options: &LoginOptions<'_, T>,
 ) -> Result<Token, Error> {
+    if let Some(auth_flow) = options.auth_flow {
+        return flow_login(options, auth_flow).await;
+    }
+
     let LoginOptions {
         api_client,
         color_config,
@@ -29,6 +37,7 @@ pub async fn login<T: Client + TokenClient + CacheClient>(
         existing_token,
         force,
         sso_team: _,
+        auth_flow: _,
     } = *options; // Deref or we get double references for each of these
 
     // I created a closure that gives back a closure since the `is_valid` checks do
@@ -341,6 +350,16 @@ mod tests {
         ) -> Result<Option<Response>, turborepo_api_client::Error> {
             unimplemented!("fetch_artifact")
         }
+        async fn fetch_artifact_range(
+            &self,
+            _hash: &str,
+            _start: u64,
+            _token: &str,
+            _team_id: Option<&str>,
+            _team_slug: Option<&str>,
+        ) -> Result<Option<Response>, turborepo_api_client::Error> {
+            unimplemented!("fetch_artifact_range")
+        }
         async fn artifact_exists(
             &self,
             _hash: &str,
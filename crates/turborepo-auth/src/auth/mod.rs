@@ -1,7 +1,9 @@
+mod flow;
 mod login;
 mod logout;
 mod sso;
 
+pub use flow::AuthFlow;
 pub use login::*;
 pub use logout::*;
 pub use sso::*;
@@ -24,6 +26,8 @@ pub struct LoginOptions<'a, T: Client + TokenClient + CacheClient> {
     pub sso_team: Option<&'a str>,
     pub existing_token: Option<&'a str>,
     pub force: bool,
+    /// Replaces the Vercel login flow for self-hosted Remote Caches
+    pub auth_flow: Option<&'a AuthFlow>,
 }
 impl<'a, T: Client + TokenClient + CacheClient> LoginOptions<'a, T> {
     pub fn new(
@@ -40,6 +44,7 @@ impl<'a, T: Client + TokenClient + CacheClient> LoginOptions<'a, T> {
             sso_team: None,
             existing_token: None,
             force: false,
+            auth_flow: None,
         }
     }
 }
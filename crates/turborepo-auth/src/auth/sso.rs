@@ -35,8 +35,12 @@ pub async fn sso_login<'a, T: Client + TokenClient + CacheClient>(
         sso_team,
         existing_token,
         force,
+        auth_flow,
     } = *options;
 
+    if auth_flow.is_some() {
+        return Err(Error::SSOWithAuthFlow);
+    }
     let sso_team = sso_team.ok_or(Error::EmptySSOTeam)?;
     // I created a closure that gives back a closure since the `is_valid` checks do
     // a call to get the user, so instead of doing that multiple times we have
@@ -336,6 +340,16 @@ mod tests {
         ) -> Result<Option<Response>, turborepo_api_client::Error> {
             unimplemented!("fetch_artifact")
         }
+        async fn fetch_artifact_range(
+            &self,
+            _hash: &str,
+            _start: u64,
+            _token: &str,
+            _team_id: Option<&str>,
+            _team_slug: Option<&str>,
+        ) -> Result<Option<Response>, turborepo_api_client::Error> {
+            unimplemented!("fetch_artifact_range")
+        }
         async fn artifact_exists(
             &self,
             _hash: &str,
@@ -49,4 +49,34 @@ pub enum Error {
 
     #[error(transparent)]
     Path(#[from] PathError),
+
+    #[error(
+        "no OIDC token found: set {} to an ID token, or on GitHub Actions, grant the job the \
+         `id-token: write` permission",
+        crate::oidc::TURBO_OIDC_TOKEN
+    )]
+    OidcTokenNotFound,
+    #[error("failed to request an OIDC token: {0}")]
+    OidcRequest(#[source] reqwest::Error),
+    #[error("OIDC token is not a valid JWT")]
+    InvalidOidcToken,
+    #[error(
+        "OIDC token was issued by `{actual}`, but the issuer is configured to be `{expected}`"
+    )]
+    OidcIssuerMismatch { expected: String, actual: String },
+    #[error("OIDC token is for `{actual}`, but the audience is configured to be `{expected}`")]
+    OidcAudienceMismatch { expected: String, actual: String },
+
+    #[error("failed to request a device code: {0}")]
+    DeviceAuthorizationRequest(#[source] reqwest::Error),
+    #[error("failed to request a token for the device code: {0}")]
+    DeviceTokenRequest(#[source] reqwest::Error),
+    #[error("device code expired before it was authorized, run `turbo login` again")]
+    DeviceCodeExpired,
+    #[error("device code authorization was denied")]
+    DeviceAuthorizationDenied,
+    #[error("failed to get a token for the device code: {0}")]
+    DeviceTokenError(String),
+    #[error("SSO login isn't supported with a custom `auth` flow")]
+    SSOWithAuthFlow,
 }
@@ -7,6 +7,7 @@
 mod auth;
 mod error;
 mod login_server;
+pub mod oidc;
 mod ui;
 
 pub use auth::*;
@@ -424,6 +425,16 @@ mod tests {
         ) -> Result<Option<Response>, turborepo_api_client::Error> {
             unimplemented!()
         }
+        async fn fetch_artifact_range(
+            &self,
+            _hash: &str,
+            _start: u64,
+            _token: &str,
+            _team_id: Option<&str>,
+            _team_slug: Option<&str>,
+        ) -> Result<Option<Response>, turborepo_api_client::Error> {
+            unimplemented!()
+        }
 
         async fn put_artifact(
             &self,
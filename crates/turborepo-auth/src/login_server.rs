@@ -2,7 +2,12 @@ use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Result;
 use async_trait::async_trait;
-use axum::{extract::Query, response::Redirect, routing::get, Router};
+use axum::{
+    extract::Query,
+    response::{IntoResponse, Redirect},
+    routing::get,
+    Router,
+};
 use serde::Deserialize;
 use tokio::sync::OnceCell;
 use url::Url;
@@ -10,8 +15,15 @@ use url::Url;
 use crate::Error;
 
 pub enum LoginType {
-    Basic { success_redirect: String },
+    Basic {
+        success_redirect: String,
+    },
     SSO,
+    /// A self-hosted Remote Cache's login page, which may not have a page to
+    /// send the browser to afterwards
+    Custom {
+        success_redirect: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -31,7 +43,7 @@ pub struct SsoPayload {
 }
 
 #[async_trait]
-pub trait LoginServer {
+pub trait LoginServer: Sync {
     async fn run(
         &self,
         port: u16,
@@ -87,6 +99,28 @@ impl LoginServer for DefaultLoginServer {
                     }),
                 );
 
+                axum_server::bind(addr)
+                    .handle(handle)
+                    .serve(app.into_make_service())
+                    .await
+                    .expect("failed to start one-shot server");
+            }
+            LoginType::Custom { success_redirect } => {
+                let app = Router::new().route(
+                    "/",
+                    get(|login_payload: Query<LoginPayload>| async move {
+                        let _ = login_token.set(login_payload.0.token);
+                        route_handle.shutdown();
+                        match success_redirect {
+                            Some(success_redirect) => {
+                                Redirect::to(&success_redirect).into_response()
+                            }
+                            None => "Turborepo CLI authorized, you can close this window."
+                                .into_response(),
+                        }
+                    }),
+                );
+
                 axum_server::bind(addr)
                     .handle(handle)
                     .serve(app.into_make_service())
@@ -0,0 +1,221 @@
+//! Authenticates with the Remote Cache using the OIDC ID tokens that CI
+//! providers issue to jobs, so CI doesn't need a long-lived token.
+//!
+//! The ID token is read from `TURBO_OIDC_TOKEN` when it's set, which is how
+//! GitLab's `id_tokens` and most other providers expose them, or requested
+//! from GitHub Actions. It's then exchanged with the Remote Cache for a token
+//! that's only used for the current command.
+
+use std::env;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::Deserialize;
+use turborepo_api_client::APIClient;
+
+use crate::Error;
+
+pub const TURBO_OIDC_TOKEN: &str = "TURBO_OIDC_TOKEN";
+const GITHUB_REQUEST_URL: &str = "ACTIONS_ID_TOKEN_REQUEST_URL";
+const GITHUB_REQUEST_TOKEN: &str = "ACTIONS_ID_TOKEN_REQUEST_TOKEN";
+
+pub struct OidcOptions<'a> {
+    /// The audience the ID token must be issued for
+    pub audience: &'a str,
+    /// The issuer the ID token must come from, if restricted
+    pub issuer: Option<&'a str>,
+}
+
+/// Exchanges the CI provider's ID token for a Remote Cache token
+pub async fn exchange_oidc_token(
+    api_client: &APIClient,
+    options: &OidcOptions<'_>,
+    team_id: Option<&str>,
+    team_slug: Option<&str>,
+) -> Result<String, Error> {
+    let id_token = id_token(options, |name| env::var(name).ok()).await?;
+    Ok(api_client
+        .exchange_oidc_token(&id_token, team_id, team_slug)
+        .await?)
+}
+
+async fn id_token(
+    options: &OidcOptions<'_>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<String, Error> {
+    let id_token = match (
+        env(TURBO_OIDC_TOKEN),
+        env(GITHUB_REQUEST_URL),
+        env(GITHUB_REQUEST_TOKEN),
+    ) {
+        (Some(id_token), _, _) => id_token,
+        (None, Some(url), Some(request_token)) => {
+            github_id_token(&url, &request_token, options.audience).await?
+        }
+        _ => return Err(Error::OidcTokenNotFound),
+    };
+    check_claims(&id_token, options)?;
+    Ok(id_token)
+}
+
+// GitHub Actions hands out ID tokens for any audience to jobs with the
+// `id-token: write` permission
+async fn github_id_token(url: &str, request_token: &str, audience: &str) -> Result<String, Error> {
+    #[derive(Deserialize)]
+    struct Response {
+        value: String,
+    }
+
+    let mut url = url::Url::parse(url)?;
+    url.query_pairs_mut().append_pair("audience", audience);
+    let response = reqwest::Client::new()
+        .get(url)
+        .bearer_auth(request_token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(Error::OidcRequest)?;
+    let body = response.text().await.map_err(Error::OidcRequest)?;
+    Ok(serde_json::from_str::<Response>(&body)?.value)
+}
+
+// The Remote Cache verifies the token's signature, we only check that it's
+// meant for it so a misconfigured job fails with a useful error
+fn check_claims(id_token: &str, options: &OidcOptions<'_>) -> Result<(), Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Audience {
+        One(String),
+        Many(Vec<String>),
+    }
+    #[derive(Deserialize)]
+    struct Claims {
+        iss: String,
+        aud: Audience,
+    }
+
+    let claims = id_token
+        .split('.')
+        .nth(1)
+        .and_then(|payload| URL_SAFE_NO_PAD.decode(payload.trim_end_matches('=')).ok())
+        .and_then(|payload| serde_json::from_slice::<Claims>(&payload).ok())
+        .ok_or(Error::InvalidOidcToken)?;
+
+    if let Some(issuer) = options.issuer {
+        if claims.iss != issuer {
+            return Err(Error::OidcIssuerMismatch {
+                expected: issuer.to_string(),
+                actual: claims.iss,
+            });
+        }
+    }
+    let audiences = match claims.aud {
+        Audience::One(audience) => vec![audience],
+        Audience::Many(audiences) => audiences,
+    };
+    if !audiences
+        .iter()
+        .any(|audience| audience == options.audience)
+    {
+        return Err(Error::OidcAudienceMismatch {
+            expected: options.audience.to_string(),
+            actual: audiences.join(", "),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    const ISSUER: &str = "https://token.actions.githubusercontent.com";
+
+    fn jwt(claims: serde_json::Value) -> String {
+        let encode = |value: serde_json::Value| URL_SAFE_NO_PAD.encode(value.to_string());
+        format!(
+            "{}.{}.signature",
+            encode(json!({ "alg": "RS256" })),
+            encode(claims)
+        )
+    }
+
+    fn options(issuer: Option<&str>) -> OidcOptions<'_> {
+        OidcOptions {
+            audience: "https://cache.example.com",
+            issuer,
+        }
+    }
+
+    #[test]
+    fn test_check_claims() {
+        let token = jwt(json!({ "iss": ISSUER, "aud": "https://cache.example.com" }));
+        assert!(check_claims(&token, &options(None)).is_ok());
+        assert!(check_claims(&token, &options(Some(ISSUER))).is_ok());
+        assert!(matches!(
+            check_claims(&token, &options(Some("https://gitlab.com"))),
+            Err(Error::OidcIssuerMismatch { .. })
+        ));
+
+        let token = jwt(
+            json!({ "iss": ISSUER, "aud": ["sts.amazonaws.com", "https://cache.example.com"] }),
+        );
+        assert!(check_claims(&token, &options(None)).is_ok());
+
+        let token = jwt(json!({ "iss": ISSUER, "aud": "sts.amazonaws.com" }));
+        assert!(matches!(
+            check_claims(&token, &options(None)),
+            Err(Error::OidcAudienceMismatch { .. })
+        ));
+
+        assert!(matches!(
+            check_claims("not-a-jwt", &options(None)),
+            Err(Error::InvalidOidcToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_github_id_token() {
+        let server = httpmock::MockServer::start_async().await;
+        let token = jwt(json!({ "iss": ISSUER, "aud": "https://cache.example.com" }));
+        let request = server
+            .mock_async(|when, then| {
+                when.method(httpmock::Method::GET)
+                    .path("/token")
+                    .query_param("api-version", "2.0")
+                    .query_param("audience", "https://cache.example.com")
+                    .header("Authorization", "Bearer request-token");
+                then.status(200).json_body(json!({ "value": token }));
+            })
+            .await;
+
+        let env = HashMap::from([
+            (GITHUB_REQUEST_URL, server.url("/token?api-version=2.0")),
+            (GITHUB_REQUEST_TOKEN, "request-token".to_string()),
+        ]);
+        let result = id_token(&options(Some(ISSUER)), |name| env.get(name).cloned()).await;
+
+        request.assert_async().await;
+        assert_eq!(result.unwrap(), token);
+    }
+
+    #[tokio::test]
+    async fn test_id_token_from_env() {
+        let token = jwt(json!({ "iss": "https://gitlab.com", "aud": "https://cache.example.com" }));
+        let env = HashMap::from([
+            (TURBO_OIDC_TOKEN, token.clone()),
+            (GITHUB_REQUEST_URL, "http://localhost:1/token".to_string()),
+            (GITHUB_REQUEST_TOKEN, "request-token".to_string()),
+        ]);
+        let result = id_token(&options(None), |name| env.get(name).cloned()).await;
+        assert_eq!(result.unwrap(), token);
+
+        assert!(matches!(
+            id_token(&options(None), |_| None).await,
+            Err(Error::OidcTokenNotFound)
+        ));
+    }
+}
@@ -15,3 +15,19 @@ pub fn print_cli_authorized(user: &str, color_config: &ColorConfig) {
         color_config.apply(BOLD.apply_to("  npx turbo link"))
     );
 }
+
+/// Self-hosted Remote Caches don't tell us who the token belongs to
+pub fn print_cli_authorized_for_cache(color_config: &ColorConfig) {
+    println!(
+        "
+{} Turborepo CLI authorized for your Remote Cache
+{}
+{}
+",
+        color_config.rainbow(">>> Success!"),
+        color_config.apply(
+            CYAN.apply_to("To connect to your Remote Cache, run the following in any turborepo:")
+        ),
+        color_config.apply(BOLD.apply_to("  npx turbo link"))
+    );
+}
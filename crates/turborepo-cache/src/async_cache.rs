@@ -8,7 +8,8 @@ use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
 use crate::{
-    http::UploadMap, multiplexer::CacheMultiplexer, CacheError, CacheHitMetadata, CacheOpts,
+    http::UploadMap, multiplexer::CacheMultiplexer, ArtifactTask, CacheError, CacheHitMetadata,
+    CacheOpts,
 };
 
 const WARNING_CUTOFF: u8 = 4;
@@ -25,6 +26,7 @@ enum WorkerRequest {
         key: String,
         duration: u64,
         files: Vec<AnchoredSystemPathBuf>,
+        task: Option<ArtifactTask>,
     },
     Flush(oneshot::Sender<()>),
     /// Shutdown the cache. The first oneshot notifies when shutdown starts and
@@ -67,6 +69,7 @@ impl AsyncCache {
                         key,
                         duration,
                         files,
+                        task,
                     } => {
                         let permit = semaphore.clone().acquire_owned().await.unwrap();
                         let real_cache = real_cache.clone();
@@ -74,8 +77,9 @@ impl AsyncCache {
                         let worker_span = tracing::span!(Level::TRACE, "cache worker: cache PUT");
                         workers.push(tokio::spawn(
                             async move {
-                                if let Err(err) =
-                                    real_cache.put(&anchor, &key, &files, duration).await
+                                if let Err(err) = real_cache
+                                    .put(&anchor, &key, &files, duration, task.as_ref())
+                                    .await
                                 {
                                     let num_warnings =
                                         warnings.load(std::sync::atomic::Ordering::Acquire);
@@ -121,6 +125,10 @@ impl AsyncCache {
                 let _ = worker;
             }
 
+            // Evict once the writes have landed so that this run's artifacts count
+            // toward the local cache limits
+            let _ = tokio::task::spawn_blocking(move || real_cache.evict()).await;
+
             if let Some(callback) = shutdown_callback {
                 callback.send(()).ok();
             }
@@ -139,6 +147,7 @@ impl AsyncCache {
         key: String,
         files: Vec<AnchoredSystemPathBuf>,
         duration: u64,
+        task: Option<ArtifactTask>,
     ) -> Result<(), CacheError> {
         if self
             .writer_sender
@@ -147,6 +156,7 @@ impl AsyncCache {
                 key,
                 duration,
                 files,
+                task,
             })
             .await
             .is_err()
@@ -167,12 +177,28 @@ impl AsyncCache {
         &self,
         anchor: &AbsoluteSystemPath,
         key: &str,
+        task: Option<&ArtifactTask>,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        self.real_cache.fetch(anchor, key).await
+        self.real_cache.fetch(anchor, key, task).await
     }
 
-    // Used for testing to ensure that the workers resolve
-    // before checking the cache.
+    /// Downloads an artifact into the local cache without restoring it.
+    #[tracing::instrument(skip_all)]
+    pub async fn prefetch(
+        &self,
+        key: &str,
+        task: Option<&ArtifactTask>,
+    ) -> Result<Option<CacheHitMetadata>, CacheError> {
+        self.real_cache.prefetch(key, task).await
+    }
+
+    /// The number of requests to the remote cache that have failed so far.
+    /// Call `wait` first to include uploads that are still in flight.
+    pub fn remote_errors(&self) -> usize {
+        self.real_cache.remote_errors()
+    }
+
+    /// Waits for the writes queued so far to finish
     #[tracing::instrument(skip_all)]
     pub async fn wait(&self) -> Result<(), CacheError> {
         let (tx, rx) = oneshot::channel();
@@ -227,7 +253,8 @@ mod tests {
 
     use crate::{
         test_cases::{get_test_cases, TestCase},
-        AsyncCache, CacheHitMetadata, CacheOpts, CacheSource, RemoteCacheOpts,
+        AsyncCache, CacheHitMetadata, CacheOpts, CacheSource, EvictionPolicy, RemoteCacheOpts,
+        RetryPolicy, SignatureMismatchPolicy,
     };
 
     #[tokio::test]
@@ -238,7 +265,9 @@ mod tests {
         try_join_all(get_test_cases().into_iter().map(|test_case| async move {
             round_trip_test_with_both_caches(&test_case, port).await?;
             round_trip_test_without_remote_cache(&test_case).await?;
-            round_trip_test_without_fs(&test_case, port).await
+            round_trip_test_without_fs(&test_case, port).await?;
+            round_trip_test_write_only(&test_case, port).await?;
+            round_trip_test_prefetch(&test_case, port).await
         }))
         .await?;
 
@@ -246,6 +275,257 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_remote_errors() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        let test_case = &get_test_cases()[0];
+        test_case.initialize(&repo_root_path)?;
+
+        // Nothing is listening on this port, so every remote request fails
+        let port = port_scanner::request_open_port().unwrap();
+        let opts = CacheOpts {
+            cache_dir: Utf8PathBuf::from(".turbo/cache"),
+            remote_cache_read_only: false,
+            remote_cache_write_only: false,
+            skip_remote: false,
+            skip_filesystem: false,
+            workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy { retries: 0 },
+            remote_cache_opts: None,
+        };
+        let api_client = APIClient::new(
+            format!("http://localhost:{}", port),
+            Some(Duration::from_secs(5)),
+            None,
+            "2.0.0",
+            true,
+        )?;
+        let api_auth = Some(APIAuth {
+            team_id: Some("my-team-id".to_string()),
+            token: "my-token".to_string(),
+            team_slug: None,
+        });
+        let async_cache = AsyncCache::new(&opts, &repo_root_path, api_client, api_auth, None)?;
+
+        // Misses in the local cache go to the remote cache
+        assert_matches!(
+            async_cache
+                .fetch(&repo_root_path, test_case.hash, None)
+                .await,
+            Ok(None)
+        );
+        assert_eq!(async_cache.remote_errors(), 1);
+
+        async_cache
+            .put(
+                repo_root_path.clone(),
+                test_case.hash.to_string(),
+                test_case
+                    .files
+                    .iter()
+                    .map(|f| f.path().to_owned())
+                    .collect(),
+                test_case.duration,
+                None,
+            )
+            .await?;
+        async_cache.wait().await?;
+        assert_eq!(async_cache.remote_errors(), 2);
+
+        // Local hits never reach the remote cache
+        assert_matches!(
+            async_cache
+                .fetch(&repo_root_path, test_case.hash, None)
+                .await,
+            Ok(Some(_))
+        );
+        assert_eq!(async_cache.remote_errors(), 2);
+        async_cache.shutdown().await?;
+
+        Ok(())
+    }
+
+    async fn round_trip_test_write_only(test_case: &TestCase, port: u16) -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        test_case.initialize(&repo_root_path)?;
+
+        let hash = format!("{}-write-only", test_case.hash);
+
+        let mut opts = CacheOpts {
+            cache_dir: Utf8PathBuf::from(".turbo/cache"),
+            remote_cache_read_only: false,
+            remote_cache_write_only: true,
+            skip_remote: false,
+            skip_filesystem: true,
+            workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            remote_cache_opts: Some(RemoteCacheOpts {
+                unused_team_id: Some("my-team".to_string()),
+                signature: false,
+                signature_mismatch: SignatureMismatchPolicy::Error,
+                chunking: false,
+            }),
+        };
+
+        let api_client = APIClient::new(
+            format!("http://localhost:{}", port),
+            Some(Duration::from_secs(200)),
+            None,
+            "2.0.0",
+            true,
+        )?;
+        let api_auth = Some(APIAuth {
+            team_id: Some("my-team-id".to_string()),
+            token: "my-token".to_string(),
+            team_slug: None,
+        });
+        let async_cache = AsyncCache::new(
+            &opts,
+            &repo_root_path,
+            api_client.clone(),
+            api_auth.clone(),
+            None,
+        )?;
+
+        async_cache
+            .put(
+                repo_root_path.clone(),
+                hash.clone(),
+                test_case
+                    .files
+                    .iter()
+                    .map(|f| f.path().to_owned())
+                    .collect(),
+                test_case.duration,
+                None,
+            )
+            .await
+            .unwrap();
+        async_cache.wait().await.unwrap();
+
+        // The artifact was uploaded, but a write-only cache never reads it back
+        assert_matches!(async_cache.exists(&hash).await, Ok(None));
+        assert_matches!(
+            async_cache.fetch(&repo_root_path, &hash, None).await,
+            Ok(None)
+        );
+        async_cache.shutdown().await.unwrap();
+
+        opts.remote_cache_write_only = false;
+        let async_cache = AsyncCache::new(&opts, &repo_root_path, api_client, api_auth, None)?;
+        assert_eq!(
+            async_cache.exists(&hash).await?,
+            Some(CacheHitMetadata {
+                source: CacheSource::Remote,
+                time_saved: test_case.duration
+            })
+        );
+        async_cache.shutdown().await.unwrap();
+
+        Ok(())
+    }
+
+    async fn round_trip_test_prefetch(test_case: &TestCase, port: u16) -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        test_case.initialize(&repo_root_path)?;
+
+        let hash = format!("{}-prefetch", test_case.hash);
+
+        let mut opts = CacheOpts {
+            cache_dir: Utf8PathBuf::from(".turbo/cache"),
+            remote_cache_read_only: false,
+            remote_cache_write_only: false,
+            skip_remote: false,
+            skip_filesystem: true,
+            workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
+            remote_cache_opts: Some(RemoteCacheOpts {
+                unused_team_id: Some("my-team".to_string()),
+                signature: false,
+                signature_mismatch: SignatureMismatchPolicy::Error,
+                chunking: false,
+            }),
+        };
+
+        let api_client = APIClient::new(
+            format!("http://localhost:{}", port),
+            Some(Duration::from_secs(200)),
+            None,
+            "2.0.0",
+            true,
+        )?;
+        let api_auth = Some(APIAuth {
+            team_id: Some("my-team-id".to_string()),
+            token: "my-token".to_string(),
+            team_slug: None,
+        });
+        let async_cache = AsyncCache::new(
+            &opts,
+            &repo_root_path,
+            api_client.clone(),
+            api_auth.clone(),
+            None,
+        )?;
+
+        async_cache
+            .put(
+                repo_root_path.clone(),
+                hash.clone(),
+                test_case
+                    .files
+                    .iter()
+                    .map(|f| f.path().to_owned())
+                    .collect(),
+                test_case.duration,
+                None,
+            )
+            .await
+            .unwrap();
+        async_cache.wait().await.unwrap();
+        async_cache.shutdown().await.unwrap();
+
+        // Prefetching with only the remote cache has nowhere to put the artifact
+        assert_matches!(async_cache.prefetch(&hash, None).await, Ok(None));
+
+        opts.skip_filesystem = false;
+        let async_cache = AsyncCache::new(&opts, &repo_root_path, api_client, api_auth, None)?;
+        let remote_hit = CacheHitMetadata {
+            source: CacheSource::Remote,
+            time_saved: test_case.duration,
+        };
+        assert_eq!(async_cache.prefetch(&hash, None).await?, Some(remote_hit));
+
+        // The artifact is now local, so prefetching again doesn't download it
+        let local_hit = CacheHitMetadata {
+            source: CacheSource::Local,
+            time_saved: test_case.duration,
+        };
+        assert_eq!(async_cache.prefetch(&hash, None).await?, Some(local_hit));
+        assert_eq!(async_cache.exists(&hash).await?, Some(local_hit));
+
+        let (status, files) = async_cache
+            .fetch(&repo_root_path, &hash, None)
+            .await?
+            .unwrap();
+        assert_eq!(status, local_hit);
+        assert_eq!(files.len(), test_case.files.len());
+        async_cache.shutdown().await.unwrap();
+
+        Ok(())
+    }
+
     async fn round_trip_test_without_fs(test_case: &TestCase, port: u16) -> Result<()> {
         let repo_root = tempdir()?;
         let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
@@ -256,12 +536,19 @@ mod tests {
         let opts = CacheOpts {
             cache_dir: Utf8PathBuf::from(".turbo/cache"),
             remote_cache_read_only: false,
+            remote_cache_write_only: false,
             skip_remote: false,
             skip_filesystem: true,
             workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
             remote_cache_opts: Some(RemoteCacheOpts {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
+                signature_mismatch: SignatureMismatchPolicy::Error,
+                chunking: false,
             }),
         };
 
@@ -295,6 +582,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
@@ -338,12 +626,19 @@ mod tests {
         let opts = CacheOpts {
             cache_dir: Utf8PathBuf::from(".turbo/cache"),
             remote_cache_read_only: false,
+            remote_cache_write_only: false,
             skip_remote: true,
             skip_filesystem: false,
             workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
             remote_cache_opts: Some(RemoteCacheOpts {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
+                signature_mismatch: SignatureMismatchPolicy::Error,
+                chunking: false,
             }),
         };
 
@@ -379,6 +674,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
@@ -430,12 +726,19 @@ mod tests {
         let opts = CacheOpts {
             cache_dir: Utf8PathBuf::from(".turbo/cache"),
             remote_cache_read_only: false,
+            remote_cache_write_only: false,
             skip_remote: false,
             skip_filesystem: false,
             workers: 10,
+            storage_url: None,
+            compression_level: 0,
+            eviction_policy: EvictionPolicy::default(),
+            retry_policy: RetryPolicy::default(),
             remote_cache_opts: Some(RemoteCacheOpts {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
+                signature_mismatch: SignatureMismatchPolicy::Error,
+                chunking: false,
             }),
         };
 
@@ -469,6 +772,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
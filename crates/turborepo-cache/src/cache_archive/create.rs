@@ -11,6 +11,9 @@ use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, IntoUnix};
 
 use crate::CacheError;
 
+/// zstd interprets level 0 as its own default level.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 0;
+
 pub struct CacheWriter<'a> {
     builder: tar::Builder<Box<dyn Write + 'a>>,
 }
@@ -39,9 +42,13 @@ impl<'a> CacheWriter<'a> {
         Ok(self.builder.finish()?)
     }
 
-    pub fn from_writer(writer: impl Write + 'a, use_compression: bool) -> Result<Self, CacheError> {
-        if use_compression {
-            let zw = zstd::Encoder::new(writer, 0)?.auto_finish();
+    // Passing a `compression_level` compresses the archive with zstd at that level
+    pub fn from_writer(
+        writer: impl Write + 'a,
+        compression_level: Option<i32>,
+    ) -> Result<Self, CacheError> {
+        if let Some(level) = compression_level {
+            let zw = zstd::Encoder::new(writer, level)?.auto_finish();
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(zw)),
             })
@@ -55,7 +62,8 @@ impl<'a> CacheWriter<'a> {
     // Makes a new CacheArchive at the specified path
     // Wires up the chain of writers:
     // tar::Builder -> zstd::Encoder (optional) -> BufWriter -> File
-    pub fn create(path: &AbsoluteSystemPath) -> Result<Self, CacheError> {
+    // `compression_level` is only used if the path has a `.zst` extension.
+    pub fn create(path: &AbsoluteSystemPath, compression_level: i32) -> Result<Self, CacheError> {
         let mut options = OpenOptions::new();
         options.write(true).create(true).truncate(true);
 
@@ -67,7 +75,7 @@ impl<'a> CacheWriter<'a> {
         let is_compressed = path.extension() == Some("zst");
 
         if is_compressed {
-            let zw = zstd::Encoder::new(file_buffer, 0)?.auto_finish();
+            let zw = zstd::Encoder::new(file_buffer, compression_level)?.auto_finish();
 
             Ok(CacheWriter {
                 builder: tar::Builder::new(Box::new(zw)),
@@ -335,7 +343,7 @@ mod tests {
                 AbsoluteSystemPathBuf::try_from(archive_dir.path().join("out.tar"))?
             };
 
-            let mut cache_archive = CacheWriter::create(&archive_path)?;
+            let mut cache_archive = CacheWriter::create(&archive_path, DEFAULT_COMPRESSION_LEVEL)?;
 
             for file in files.iter() {
                 let result = create_entry(&input_dir_path, file);
@@ -394,7 +402,7 @@ mod tests {
         let tar_dir_path = AbsoluteSystemPath::new(tar_dir.path().to_str().unwrap())?;
 
         let tar_path = tar_dir_path.join_component("test.tar");
-        let mut archive = CacheWriter::create(&tar_path)?;
+        let mut archive = CacheWriter::create(&tar_path, DEFAULT_COMPRESSION_LEVEL)?;
         let base = "this-is-a-really-really-really-long-path-like-so-very-long-that-i-can-list-all-of-my-favorite-directors-like-edward-yang-claire-denis-lucrecia-martel-wong-kar-wai-even-kurosawa";
         let file_name = format!("{base}.txt");
         let dir_symlink_name = format!("{base}-dir");
@@ -5,5 +5,5 @@ mod restore_directory;
 mod restore_regular;
 mod restore_symlink;
 
-pub use create::CacheWriter;
+pub use create::{CacheWriter, DEFAULT_COMPRESSION_LEVEL};
 pub use restore::CacheReader;
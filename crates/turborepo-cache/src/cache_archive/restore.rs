@@ -16,6 +16,10 @@ use crate::{
     CacheError,
 };
 
+// Every zstd frame starts with this magic number. Checking for it lets us
+// restore artifacts regardless of the codec the uploading client used.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
 pub struct CacheReader<'a> {
     reader: Box<dyn Read + 'a>,
 }
@@ -31,6 +35,15 @@ impl<'a> CacheReader<'a> {
         Ok(CacheReader { reader })
     }
 
+    // Detects whether the artifact is compressed from its contents
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, CacheError> {
+        Self::from_reader(bytes, Self::is_compressed(bytes))
+    }
+
+    pub fn is_compressed(bytes: &[u8]) -> bool {
+        bytes.starts_with(&ZSTD_MAGIC)
+    }
+
     pub fn open(path: &AbsoluteSystemPathBuf) -> Result<Self, CacheError> {
         let file = path.open()?;
         let is_compressed = path.extension() == Some("zst");
@@ -353,6 +366,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_detects_compression() -> Result<()> {
+        let uncompressed_tar = include_bytes!("../../fixtures/name-traversal.tar");
+        let compressed_tar = include_bytes!("../../fixtures/name-traversal.tar.zst");
+        for tar_bytes in [&uncompressed_tar[..], &compressed_tar[..]] {
+            let cache_reader = CacheReader::from_bytes(tar_bytes)?;
+            assert_eq!(
+                cache_reader.get_sha()?,
+                CacheReader::from_reader(&uncompressed_tar[..], false)?.get_sha()?
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_windows_unsafe() -> Result<()> {
         let uncompressed_tar = include_bytes!("../../fixtures/windows-unsafe.tar");
@@ -0,0 +1,243 @@
+//! Content-defined chunking for large remote cache artifacts
+//!
+//! Artifacts are split at boundaries picked from their contents with a
+//! FastCDC-style gear hash instead of at fixed offsets, so a small edit only
+//! changes the chunks around it. Each chunk is uploaded under the hash of its
+//! contents, and the artifact itself is a manifest listing those chunks.
+
+use std::backtrace::Backtrace;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::CacheError;
+
+pub const MIN_CHUNK_SIZE: usize = 256 * 1024;
+pub const AVG_CHUNK_SIZE: usize = 1024 * 1024;
+pub const MAX_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+// Normalized chunking: a stricter mask before the average size and a looser
+// one after it keeps most chunks close to the average.
+const MASK_SMALL: u64 = mask(AVG_CHUNK_SIZE.trailing_zeros() + 2);
+const MASK_LARGE: u64 = mask(AVG_CHUNK_SIZE.trailing_zeros() - 2);
+
+// Prefixes the manifest so it can be told apart from a tar archive
+const MANIFEST_HEADER: &[u8] = b"turbo-chunked-artifact-v1\n";
+
+// Sets the top `bits` bits, which the gear hash mixes from the most bytes
+const fn mask(bits: u32) -> u64 {
+    !(u64::MAX >> bits)
+}
+
+// Changing this table moves every chunk boundary, so it must stay stable for
+// chunks to be shared across turbo versions.
+const GEAR: [u64; 256] = {
+    let mut table = [0; 256];
+    let mut state: u64 = 0;
+    let mut i = 0;
+    while i < table.len() {
+        // splitmix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+};
+
+/// Returns the length of the first chunk of `data`
+fn cut_point(data: &[u8]) -> usize {
+    if data.len() <= MIN_CHUNK_SIZE {
+        return data.len();
+    }
+    let end = data.len().min(MAX_CHUNK_SIZE);
+    let normal = end.min(AVG_CHUNK_SIZE);
+
+    let mut hash = 0u64;
+    for (i, byte) in data.iter().enumerate().take(end).skip(MIN_CHUNK_SIZE) {
+        hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+        let mask = if i < normal { MASK_SMALL } else { MASK_LARGE };
+        if hash & mask == 0 {
+            return i + 1;
+        }
+    }
+    end
+}
+
+#[derive(Debug)]
+pub struct Chunk<'a> {
+    pub hash: String,
+    pub data: &'a [u8],
+}
+
+/// Splits `data` into content-defined chunks
+pub fn split(data: &[u8]) -> Vec<Chunk<'_>> {
+    let mut chunks = Vec::new();
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (data, tail) = rest.split_at(cut_point(rest));
+        chunks.push(Chunk {
+            hash: hash_chunk(data),
+            data,
+        });
+        rest = tail;
+    }
+    chunks
+}
+
+fn hash_chunk(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChunkRef {
+    pub hash: String,
+    pub size: usize,
+}
+
+impl ChunkRef {
+    /// Checks that a downloaded chunk is the one the manifest refers to
+    pub fn verify(&self, data: &[u8]) -> Result<(), CacheError> {
+        if data.len() != self.size || hash_chunk(data) != self.hash {
+            return Err(CacheError::InvalidChunk(
+                self.hash.clone(),
+                Backtrace::capture(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The chunks that make up an artifact, in order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub chunks: Vec<ChunkRef>,
+}
+
+impl Manifest {
+    pub fn new(chunks: &[Chunk]) -> Self {
+        Self {
+            chunks: chunks
+                .iter()
+                .map(|chunk| ChunkRef {
+                    hash: chunk.hash.clone(),
+                    size: chunk.data.len(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.chunks.iter().map(|chunk| chunk.size).sum()
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, CacheError> {
+        let mut bytes = MANIFEST_HEADER.to_vec();
+        serde_json::to_writer(&mut bytes, self)
+            .map_err(|e| CacheError::InvalidManifest(e, Backtrace::capture()))?;
+        Ok(bytes)
+    }
+
+    /// Parses a downloaded artifact as a manifest, returning `None` if it is
+    /// a regular archive
+    pub fn from_bytes(bytes: &[u8]) -> Result<Option<Self>, CacheError> {
+        let Some(manifest) = bytes.strip_prefix(MANIFEST_HEADER) else {
+            return Ok(None);
+        };
+        serde_json::from_slice(manifest)
+            .map(Some)
+            .map_err(|e| CacheError::InvalidManifest(e, Backtrace::capture()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Deterministic noise so chunk boundaries don't depend on repeated bytes
+    fn noise(len: usize, seed: u64) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_sizes() {
+        let data = noise(20 * 1024 * 1024, 1);
+        let chunks = split(&data);
+
+        assert!(chunks.len() > 1);
+        let (last, rest) = chunks.split_last().unwrap();
+        for chunk in rest {
+            assert!((MIN_CHUNK_SIZE..=MAX_CHUNK_SIZE).contains(&chunk.data.len()));
+        }
+        assert!(last.data.len() <= MAX_CHUNK_SIZE);
+
+        let joined: Vec<u8> = chunks
+            .iter()
+            .flat_map(|chunk| chunk.data)
+            .copied()
+            .collect();
+        assert_eq!(joined, data);
+    }
+
+    #[test]
+    fn test_small_input_is_one_chunk() {
+        let data = noise(MIN_CHUNK_SIZE, 2);
+        let chunks = split(&data);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].data, &data[..]);
+        assert!(split(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_edit_only_changes_nearby_chunks() {
+        let original = noise(20 * 1024 * 1024, 3);
+        let mut edited = original.clone();
+        // Insert a few bytes in the middle, shifting everything after them
+        let middle = original.len() / 2;
+        edited.splice(middle..middle, *b"one-line change");
+
+        let original_hashes: Vec<_> = split(&original).into_iter().map(|c| c.hash).collect();
+        let edited_hashes: Vec<_> = split(&edited).into_iter().map(|c| c.hash).collect();
+        let changed = edited_hashes
+            .iter()
+            .filter(|hash| !original_hashes.contains(hash))
+            .count();
+
+        assert!(changed > 0);
+        assert!(
+            changed <= 2,
+            "{changed} of {} chunks changed",
+            edited_hashes.len()
+        );
+    }
+
+    #[test]
+    fn test_manifest_round_trip() -> Result<(), CacheError> {
+        let data = noise(10 * 1024 * 1024, 4);
+        let chunks = split(&data);
+        let manifest = Manifest::new(&chunks);
+        assert_eq!(manifest.size(), data.len());
+
+        let bytes = manifest.to_bytes()?;
+        assert_eq!(Manifest::from_bytes(&bytes)?, Some(manifest.clone()));
+        // A regular archive isn't mistaken for a manifest
+        assert_eq!(Manifest::from_bytes(&data)?, None);
+
+        for (chunk_ref, chunk) in manifest.chunks.iter().zip(&chunks) {
+            chunk_ref.verify(chunk.data)?;
+        }
+        assert!(manifest.chunks[0].verify(chunks[1].data).is_err());
+
+        Ok(())
+    }
+}
@@ -1,25 +1,40 @@
-use std::{backtrace::Backtrace, fs::OpenOptions};
+use std::{
+    backtrace::Backtrace,
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io,
+    time::SystemTime,
+};
 
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
+use tracing::debug;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{analytics, analytics::AnalyticsEvent};
 
 use crate::{
     cache_archive::{CacheReader, CacheWriter},
-    CacheError, CacheHitMetadata, CacheSource,
+    ArtifactTask, CacheError, CacheHitMetadata, CacheSource, EvictionPolicy,
 };
 
+// Every artifact is stored as an archive alongside a metadata file, all
+// named after the artifact's hash.
+const ARTIFACT_SUFFIXES: &[&str] = &[".tar.zst", ".tar", "-meta.json"];
+
 pub struct FSCache {
     cache_directory: AbsoluteSystemPathBuf,
     analytics_recorder: Option<AnalyticsSender>,
+    compression_level: i32,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct CacheMetadata {
-    hash: String,
-    duration: u64,
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CacheMetadata {
+    pub hash: String,
+    pub duration: u64,
+    // Artifacts written before this was recorded won't have it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<ArtifactTask>,
 }
 
 impl CacheMetadata {
@@ -29,6 +44,39 @@ impl CacheMetadata {
     }
 }
 
+/// The files making up a single artifact in the local cache
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub hash: String,
+    /// Combined size of the archive and its metadata in bytes
+    pub size: u64,
+    /// When the artifact was written
+    pub created: SystemTime,
+    /// When the artifact was last written or restored
+    pub last_used: SystemTime,
+    paths: Vec<AbsoluteSystemPathBuf>,
+}
+
+impl CacheEntry {
+    fn remove(&self) -> Result<(), CacheError> {
+        for path in &self.paths {
+            match path.remove_file() {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvictionSummary {
+    pub evicted: usize,
+    pub evicted_bytes: u64,
+    pub retained: usize,
+    pub retained_bytes: u64,
+}
+
 impl FSCache {
     fn resolve_cache_dir(
         repo_root: &AbsoluteSystemPath,
@@ -42,6 +90,7 @@ impl FSCache {
         cache_dir: &Utf8Path,
         repo_root: &AbsoluteSystemPath,
         analytics_recorder: Option<AnalyticsSender>,
+        compression_level: i32,
     ) -> Result<Self, CacheError> {
         let cache_directory = Self::resolve_cache_dir(repo_root, cache_dir);
         cache_directory.create_dir_all()?;
@@ -49,6 +98,7 @@ impl FSCache {
         Ok(FSCache {
             cache_directory,
             analytics_recorder,
+            compression_level,
         })
     }
 
@@ -67,12 +117,7 @@ impl FSCache {
         }
     }
 
-    #[tracing::instrument(skip_all)]
-    pub fn fetch(
-        &self,
-        anchor: &AbsoluteSystemPath,
-        hash: &str,
-    ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
+    fn archive_path(&self, hash: &str) -> Option<AbsoluteSystemPathBuf> {
         let uncompressed_cache_path = self
             .cache_directory
             .join_component(&format!("{}.tar", hash));
@@ -80,11 +125,27 @@ impl FSCache {
             .cache_directory
             .join_component(&format!("{}.tar.zst", hash));
 
-        let cache_path = if uncompressed_cache_path.exists() {
-            uncompressed_cache_path
+        if uncompressed_cache_path.exists() {
+            Some(uncompressed_cache_path)
         } else if compressed_cache_path.exists() {
-            compressed_cache_path
+            Some(compressed_cache_path)
         } else {
+            None
+        }
+    }
+
+    fn metadata_path(&self, hash: &str) -> AbsoluteSystemPathBuf {
+        self.cache_directory
+            .join_component(&format!("{}-meta.json", hash))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn fetch(
+        &self,
+        anchor: &AbsoluteSystemPath,
+        hash: &str,
+    ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
+        let Some(cache_path) = self.archive_path(hash) else {
             self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
             return Ok(None);
         };
@@ -92,12 +153,9 @@ impl FSCache {
         let mut cache_reader = CacheReader::open(&cache_path)?;
 
         let restored_files = cache_reader.restore(anchor)?;
+        Self::mark_used(&cache_path);
 
-        let meta = CacheMetadata::read(
-            &self
-                .cache_directory
-                .join_component(&format!("{}-meta.json", hash)),
-        )?;
+        let meta = CacheMetadata::read(&self.metadata_path(hash))?;
 
         self.log_fetch(analytics::CacheEvent::Hit, hash, meta.duration);
 
@@ -112,24 +170,13 @@ impl FSCache {
 
     #[tracing::instrument(skip_all)]
     pub(crate) fn exists(&self, hash: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
-        let uncompressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar", hash));
-        let compressed_cache_path = self
-            .cache_directory
-            .join_component(&format!("{}.tar.zst", hash));
-
-        if !uncompressed_cache_path.exists() && !compressed_cache_path.exists() {
+        if self.archive_path(hash).is_none() {
             return Ok(None);
         }
 
-        let duration = CacheMetadata::read(
-            &self
-                .cache_directory
-                .join_component(&format!("{}-meta.json", hash)),
-        )
-        .map(|meta| meta.duration)
-        .unwrap_or(0);
+        let duration = CacheMetadata::read(&self.metadata_path(hash))
+            .map(|meta| meta.duration)
+            .unwrap_or(0);
 
         Ok(Some(CacheHitMetadata {
             time_saved: duration,
@@ -137,6 +184,122 @@ impl FSCache {
         }))
     }
 
+    /// Reads the metadata recorded for an artifact, if the artifact is in
+    /// the cache.
+    pub fn metadata(&self, hash: &str) -> Result<Option<CacheMetadata>, CacheError> {
+        let path = self.metadata_path(hash);
+        if !path.exists() {
+            return Ok(None);
+        }
+        CacheMetadata::read(&path).map(Some)
+    }
+
+    /// Unpacks an artifact into `dir` without counting as a use of it.
+    #[tracing::instrument(skip_all)]
+    pub fn extract(
+        &self,
+        hash: &str,
+        dir: &AbsoluteSystemPath,
+    ) -> Result<Option<Vec<AnchoredSystemPathBuf>>, CacheError> {
+        let Some(cache_path) = self.archive_path(hash) else {
+            return Ok(None);
+        };
+        dir.create_dir_all()?;
+        let mut cache_reader = CacheReader::open(&cache_path)?;
+        cache_reader.restore(dir).map(Some)
+    }
+
+    // Eviction goes by modification time, so bump it whenever an artifact is
+    // restored. Failing to do so only makes the artifact a likelier candidate
+    // for eviction.
+    fn mark_used(path: &AbsoluteSystemPath) {
+        let result = File::options()
+            .write(true)
+            .open(path.as_std_path())
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        if let Err(e) = result {
+            debug!("failed to update last use of {path}: {e}");
+        }
+    }
+
+    /// Lists every artifact in the cache directory, grouping the archive
+    /// and metadata files of each one together.
+    pub fn entries(&self) -> Result<Vec<CacheEntry>, CacheError> {
+        let mut entries: HashMap<String, CacheEntry> = HashMap::new();
+        for dir_entry in self.cache_directory.as_std_path().read_dir()? {
+            let dir_entry = dir_entry?;
+            let Some(file_name) = dir_entry.file_name().to_str().map(|s| s.to_owned()) else {
+                continue;
+            };
+            let Some(hash) = ARTIFACT_SUFFIXES
+                .iter()
+                .find_map(|suffix| file_name.strip_suffix(suffix))
+            else {
+                continue;
+            };
+            let metadata = dir_entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata.modified()?;
+
+            let entry = entries
+                .entry(hash.to_string())
+                .or_insert_with(|| CacheEntry {
+                    hash: hash.to_string(),
+                    size: 0,
+                    created: modified,
+                    last_used: modified,
+                    paths: Vec::new(),
+                });
+            entry.size += metadata.len();
+            // Only the archive is touched on restore, so the metadata file keeps the
+            // time the artifact was written
+            entry.created = entry.created.min(modified);
+            entry.last_used = entry.last_used.max(modified);
+            entry
+                .paths
+                .push(self.cache_directory.join_component(&file_name));
+        }
+
+        Ok(entries.into_values().collect())
+    }
+
+    /// Removes artifacts that have gone unused for longer than the policy's
+    /// maximum age, then removes the least recently used artifacts until the
+    /// cache fits within the policy's maximum size.
+    #[tracing::instrument(skip_all)]
+    pub fn evict(&self, policy: &EvictionPolicy) -> Result<EvictionSummary, CacheError> {
+        let mut entries = self.entries()?;
+        entries.sort_by_key(|entry| entry.last_used);
+
+        let now = SystemTime::now();
+        let mut summary = EvictionSummary {
+            retained_bytes: entries.iter().map(|entry| entry.size).sum(),
+            ..Default::default()
+        };
+        for entry in entries {
+            let expired = policy.max_age.is_some_and(|max_age| {
+                now.duration_since(entry.last_used)
+                    .is_ok_and(|age| age > max_age)
+            });
+            let over_size = policy
+                .max_size
+                .is_some_and(|max_size| summary.retained_bytes > max_size);
+
+            if expired || over_size {
+                entry.remove()?;
+                summary.evicted += 1;
+                summary.evicted_bytes += entry.size;
+                summary.retained_bytes -= entry.size;
+            } else {
+                summary.retained += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn put(
         &self,
@@ -144,24 +307,55 @@ impl FSCache {
         hash: &str,
         files: &[AnchoredSystemPathBuf],
         duration: u64,
+        task: Option<&ArtifactTask>,
     ) -> Result<(), CacheError> {
         let cache_path = self
             .cache_directory
             .join_component(&format!("{}.tar.zst", hash));
 
-        let mut cache_item = CacheWriter::create(&cache_path)?;
+        let mut cache_item = CacheWriter::create(&cache_path, self.compression_level)?;
 
         for file in files {
             cache_item.add_file(anchor, file)?;
         }
 
-        let metadata_path = self
-            .cache_directory
-            .join_component(&format!("{}-meta.json", hash));
+        self.write_metadata(hash, duration, task)
+    }
+
+    /// Stores an archive downloaded from a remote cache as is, so it can be
+    /// restored later without going over the network.
+    #[tracing::instrument(skip_all)]
+    pub fn put_archive(
+        &self,
+        hash: &str,
+        archive: &[u8],
+        duration: u64,
+        task: Option<&ArtifactTask>,
+    ) -> Result<(), CacheError> {
+        let extension = if CacheReader::is_compressed(archive) {
+            "tar.zst"
+        } else {
+            "tar"
+        };
+        self.cache_directory
+            .join_component(&format!("{hash}.{extension}"))
+            .create_with_contents(archive)?;
+
+        self.write_metadata(hash, duration, task)
+    }
+
+    fn write_metadata(
+        &self,
+        hash: &str,
+        duration: u64,
+        task: Option<&ArtifactTask>,
+    ) -> Result<(), CacheError> {
+        let metadata_path = self.metadata_path(hash);
 
         let meta = CacheMetadata {
             hash: hash.to_string(),
             duration,
+            task: task.cloned(),
         };
 
         let mut metadata_options = OpenOptions::new();
@@ -189,7 +383,10 @@ mod test {
     use turborepo_vercel_api_mock::start_test_server;
 
     use super::*;
-    use crate::test_cases::{get_test_cases, validate_analytics, TestCase};
+    use crate::{
+        cache_archive::DEFAULT_COMPRESSION_LEVEL,
+        test_cases::{get_test_cases, validate_analytics, TestCase},
+    };
 
     #[tokio::test]
     async fn test_fs_cache() -> Result<()> {
@@ -209,6 +406,68 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_evict() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let output = AnchoredSystemPathBuf::from_raw("output.txt")?;
+        repo_root_path
+            .resolve(&output)
+            .create_with_contents("x".repeat(1024))?;
+
+        let cache = FSCache::new(
+            Utf8Path::new("cache"),
+            repo_root_path,
+            None,
+            DEFAULT_COMPRESSION_LEVEL,
+        )?;
+        // Write the artifacts an hour apart, oldest first
+        let now = SystemTime::now();
+        for (i, hash) in ["old", "mid", "new"].into_iter().enumerate() {
+            cache.put(repo_root_path, hash, &[output.clone()], 0, None)?;
+            let last_used = now - Duration::from_secs(3600 * (2 - i as u64));
+            for path in &cache
+                .entries()?
+                .into_iter()
+                .find(|e| e.hash == hash)
+                .unwrap()
+                .paths
+            {
+                File::options()
+                    .write(true)
+                    .open(path.as_std_path())?
+                    .set_modified(last_used)?;
+            }
+        }
+        let entry_size = cache.entries()?[0].size;
+
+        let summary = cache.evict(&EvictionPolicy {
+            max_age: Some(Duration::from_secs(5400)),
+            max_size: None,
+        })?;
+        assert_eq!(summary.evicted, 1);
+        assert!(cache.exists("old")?.is_none());
+
+        let summary = cache.evict(&EvictionPolicy {
+            max_age: None,
+            max_size: Some(entry_size),
+        })?;
+        assert_eq!(summary.evicted, 1);
+        assert_eq!(summary.retained, 1);
+        assert!(cache.exists("mid")?.is_none());
+        assert!(cache.exists("new")?.is_some());
+
+        // Restoring an artifact counts as using it
+        assert!(cache.fetch(repo_root_path, "new")?.is_some());
+        let summary = cache.evict(&EvictionPolicy {
+            max_age: Some(Duration::from_secs(60)),
+            max_size: None,
+        })?;
+        assert_eq!(summary.evicted, 0);
+
+        Ok(())
+    }
+
     async fn round_trip_test(test_case: &TestCase, port: u16) -> Result<()> {
         let repo_root = tempdir()?;
         let repo_root_path = AbsoluteSystemPath::from_std_path(repo_root.path())?;
@@ -233,6 +492,7 @@ mod test {
             Utf8Path::new(""),
             repo_root_path,
             Some(analytics_sender.clone()),
+            DEFAULT_COMPRESSION_LEVEL,
         )?;
 
         let expected_miss = cache.fetch(repo_root_path, test_case.hash)?;
@@ -243,7 +503,18 @@ mod test {
             .iter()
             .map(|f| f.path().to_owned())
             .collect();
-        cache.put(repo_root_path, test_case.hash, &files, test_case.duration)?;
+        let task = ArtifactTask {
+            package: "web".to_string(),
+            task: "build".to_string(),
+        };
+        cache.put(
+            repo_root_path,
+            test_case.hash,
+            &files,
+            test_case.duration,
+            Some(&task),
+        )?;
+        assert_eq!(cache.metadata(test_case.hash)?.unwrap().task, Some(task));
 
         let (status, files) = cache.fetch(repo_root_path, test_case.hash)?.unwrap();
 
@@ -267,6 +538,11 @@ mod test {
             }
         }
 
+        let extract_dir = tempdir()?;
+        let extract_dir_path = AbsoluteSystemPath::from_std_path(extract_dir.path())?;
+        let extracted = cache.extract(test_case.hash, extract_dir_path)?.unwrap();
+        assert_eq!(extracted, files);
+
         analytics_handle.close_with_timeout().await;
         Ok(())
     }
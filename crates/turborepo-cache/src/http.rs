@@ -1,35 +1,46 @@
 use std::{
     backtrace::Backtrace,
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io::{Cursor, Write},
     sync::{Arc, Mutex},
 };
 
-use tokio_stream::StreamExt;
-use tracing::debug;
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+use reqwest::header::HeaderMap;
+use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{
     analytics::{self, AnalyticsEvent},
-    APIAuth, APIClient, CacheClient, Response,
+    APIAuth, APIClient, CacheClient,
 };
 
 use crate::{
     cache_archive::{CacheReader, CacheWriter},
+    chunking::{self, Chunk, ChunkRef, Manifest},
+    retry::Download,
     signature_authentication::ArtifactSignatureAuthenticator,
     upload_progress::{UploadProgress, UploadProgressQuery},
-    CacheError, CacheHitMetadata, CacheOpts, CacheSource,
+    CacheError, CacheHitMetadata, CacheOpts, CacheSource, RetryPolicy, SignatureMismatchPolicy,
 };
 
 pub type UploadMap = HashMap<String, UploadProgressQuery<10, 100>>;
 
+// How many chunks of a single artifact are transferred at once
+const CHUNK_TRANSFERS: usize = 8;
+
 pub struct HTTPCache {
     client: APIClient,
     signer_verifier: Option<ArtifactSignatureAuthenticator>,
+    signature_mismatch: SignatureMismatchPolicy,
     repo_root: AbsoluteSystemPathBuf,
     api_auth: APIAuth,
     analytics_recorder: Option<AnalyticsSender>,
     uploads: Arc<Mutex<UploadMap>>,
+    compression_level: i32,
+    retry_policy: RetryPolicy,
+    chunking: bool,
 }
 
 impl HTTPCache {
@@ -54,18 +65,32 @@ impl HTTPCache {
                     .as_bytes()
                     .to_vec(),
                 secret_key_override: None,
+                previous_keys_override: None,
             })
         } else {
             None
         };
+        let signature_mismatch = opts
+            .remote_cache_opts
+            .as_ref()
+            .map(|remote_cache_opts| remote_cache_opts.signature_mismatch)
+            .unwrap_or_default();
+        let chunking = opts
+            .remote_cache_opts
+            .as_ref()
+            .map_or(false, |remote_cache_opts| remote_cache_opts.chunking);
 
         HTTPCache {
             client,
             signer_verifier,
+            signature_mismatch,
             repo_root,
             uploads: Arc::new(Mutex::new(HashMap::new())),
             api_auth,
             analytics_recorder,
+            compression_level: opts.compression_level,
+            retry_policy: opts.retry_policy,
+            chunking,
         }
     }
 
@@ -77,9 +102,27 @@ impl HTTPCache {
         files: &[AnchoredSystemPathBuf],
         duration: u64,
     ) -> Result<(), CacheError> {
-        let mut artifact_body = Vec::new();
-        self.write(&mut artifact_body, anchor, files).await?;
-        let bytes = artifact_body.len();
+        let artifact_body = if self.chunking {
+            // Chunk the uncompressed archive, since compression would spread an
+            // edit across everything after it
+            let mut archive = Vec::new();
+            self.write(&mut archive, anchor, files, None).await?;
+            if archive.len() > chunking::MAX_CHUNK_SIZE {
+                self.put_chunks(&archive).await?
+            } else {
+                zstd::encode_all(archive.as_slice(), self.compression_level)?
+            }
+        } else {
+            let mut artifact_body = Vec::new();
+            self.write(
+                &mut artifact_body,
+                anchor,
+                files,
+                Some(self.compression_level),
+            )
+            .await?;
+            artifact_body
+        };
 
         let tag = self
             .signer_verifier
@@ -87,6 +130,77 @@ impl HTTPCache {
             .map(|signer| signer.generate_tag(hash.as_bytes(), &artifact_body))
             .transpose()?;
 
+        self.upload_with_retries(hash, Bytes::from(artifact_body), duration, tag.as_deref())
+            .await
+    }
+
+    /// Uploads the chunks of `archive` that the remote cache doesn't already
+    /// have, returning the manifest to upload in place of the archive
+    async fn put_chunks(&self, archive: &[u8]) -> Result<Vec<u8>, CacheError> {
+        let chunks = chunking::split(archive);
+        let mut seen = HashSet::new();
+        let uploads: Vec<_> = chunks
+            .iter()
+            .filter(|chunk| seen.insert(&chunk.hash))
+            .map(|chunk| self.put_chunk(chunk))
+            .collect();
+        futures::stream::iter(uploads)
+            .buffer_unordered(CHUNK_TRANSFERS)
+            .try_collect::<()>()
+            .await?;
+
+        Manifest::new(&chunks).to_bytes()
+    }
+
+    async fn put_chunk(&self, chunk: &Chunk<'_>) -> Result<(), CacheError> {
+        let exists = self
+            .client
+            .artifact_exists(
+                &chunk.hash,
+                &self.api_auth.token,
+                self.api_auth.team_id.as_deref(),
+                self.api_auth.team_slug.as_deref(),
+            )
+            .await
+            .map_err(|err| Self::convert_api_error(&chunk.hash, err))?;
+        if exists.is_some() {
+            debug!("skipping upload of existing chunk {}", chunk.hash);
+            return Ok(());
+        }
+
+        // Chunks are verified against the signed manifest, so they aren't signed
+        let body = zstd::encode_all(chunk.data, self.compression_level)?;
+        self.upload_with_retries(&chunk.hash, Bytes::from(body), 0, None)
+            .await
+    }
+
+    async fn upload_with_retries(
+        &self,
+        hash: &str,
+        artifact_body: Bytes,
+        duration: u64,
+        tag: Option<&str>,
+    ) -> Result<(), CacheError> {
+        let mut retrier = self.retry_policy.start();
+        loop {
+            match self
+                .upload(hash, artifact_body.clone(), duration, tag)
+                .await
+            {
+                Err(err) if retrier.backoff(hash, &err).await => continue,
+                result => return result,
+            }
+        }
+    }
+
+    async fn upload(
+        &self,
+        hash: &str,
+        artifact_body: Bytes,
+        duration: u64,
+        tag: Option<&str>,
+    ) -> Result<(), CacheError> {
+        let bytes = artifact_body.len();
         let stream = tokio_util::codec::FramedRead::new(
             Cursor::new(artifact_body),
             tokio_util::codec::BytesCodec::new(),
@@ -111,7 +225,7 @@ impl HTTPCache {
                 progress,
                 bytes,
                 duration,
-                tag.as_deref(),
+                tag,
                 &self.api_auth.token,
                 self.api_auth.team_id.as_deref(),
                 self.api_auth.team_slug.as_deref(),
@@ -128,8 +242,9 @@ impl HTTPCache {
         writer: impl Write,
         anchor: &AbsoluteSystemPath,
         files: &[AnchoredSystemPathBuf],
+        compression_level: Option<i32>,
     ) -> Result<(), CacheError> {
-        let mut cache_archive = CacheWriter::from_writer(writer, true)?;
+        let mut cache_archive = CacheWriter::from_writer(writer, compression_level)?;
         for file in files {
             cache_archive.add_file(anchor, file)?;
         }
@@ -152,7 +267,7 @@ impl HTTPCache {
             return Ok(None);
         };
 
-        let duration = Self::get_duration_from_response(&response)?;
+        let duration = Self::get_duration(response.headers())?;
 
         Ok(Some(CacheHitMetadata {
             source: CacheSource::Remote,
@@ -160,8 +275,8 @@ impl HTTPCache {
         }))
     }
 
-    fn get_duration_from_response(response: &Response) -> Result<u64, CacheError> {
-        if let Some(duration_value) = response.headers().get("x-artifact-duration") {
+    fn get_duration(headers: &HeaderMap) -> Result<u64, CacheError> {
+        if let Some(duration_value) = headers.get("x-artifact-duration") {
             let duration = duration_value
                 .to_str()
                 .map_err(|_| CacheError::InvalidDuration(Backtrace::capture()))?;
@@ -194,67 +309,160 @@ impl HTTPCache {
         &self,
         hash: &str,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        let Some(response) = self
-            .client
-            .fetch_artifact(
-                hash,
-                &self.api_auth.token,
-                self.api_auth.team_id.as_deref(),
-                self.api_auth.team_slug.as_deref(),
-            )
-            .await?
-        else {
-            self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
+        let Some((metadata, body)) = self.fetch_archive(hash).await? else {
             return Ok(None);
         };
 
-        let duration = Self::get_duration_from_response(&response)?;
+        let files = Self::restore_tar(&self.repo_root, &body)?;
+
+        Ok(Some((metadata, files)))
+    }
 
-        let body = if let Some(signer_verifier) = &self.signer_verifier {
-            let expected_tag = response
-                .headers()
-                .get("x-artifact-tag")
-                .ok_or(CacheError::ArtifactTagMissing(Backtrace::capture()))?;
+    /// Downloads and verifies an artifact without restoring it.
+    #[tracing::instrument(skip_all)]
+    pub async fn fetch_archive(
+        &self,
+        hash: &str,
+    ) -> Result<Option<(CacheHitMetadata, Vec<u8>)>, CacheError> {
+        let Some(download) = self.download_with_retries(hash).await? else {
+            self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
+            return Ok(None);
+        };
 
-            let expected_tag = expected_tag
-                .to_str()
-                .map_err(|_| CacheError::InvalidTag(Backtrace::capture()))?
-                .to_string();
-
-            let body = response.bytes().await.map_err(|e| {
-                CacheError::ApiClientError(
-                    Box::new(turborepo_api_client::Error::ReqwestError(e)),
-                    Backtrace::capture(),
-                )
-            })?;
-            let is_valid = signer_verifier.validate(hash.as_bytes(), &body, &expected_tag)?;
-
-            if !is_valid {
-                return Err(CacheError::InvalidTag(Backtrace::capture()));
+        let duration = Self::get_duration(&download.headers)?;
+
+        let expected_tag = download
+            .headers
+            .get("x-artifact-tag")
+            .map(|tag| tag.to_str().map(|tag| tag.to_string()));
+        let body = download.body;
+
+        if let Some(signer_verifier) = &self.signer_verifier {
+            let verification = match expected_tag {
+                None => Err(CacheError::ArtifactTagMissing(Backtrace::capture())),
+                Some(Err(_)) => Err(CacheError::InvalidTag(Backtrace::capture())),
+                Some(Ok(expected_tag)) => signer_verifier
+                    .verify(hash.as_bytes(), &body, &expected_tag)
+                    .map_err(CacheError::from)
+                    .and_then(|verifying_key| {
+                        verifying_key.ok_or_else(|| CacheError::InvalidTag(Backtrace::capture()))
+                    }),
+            };
+
+            match verification {
+                Ok(verifying_key) => debug!("verified {hash} with {verifying_key}"),
+                Err(err) if self.signature_mismatch == SignatureMismatchPolicy::Warn => {
+                    warn!("restoring {hash} despite failed verification: {err}")
+                }
+                Err(err) => return Err(err),
             }
+        }
 
-            body
-        } else {
-            response.bytes().await.map_err(|e| {
-                CacheError::ApiClientError(
-                    Box::new(turborepo_api_client::Error::ReqwestError(e)),
-                    Backtrace::capture(),
-                )
-            })?
+        let body = match Manifest::from_bytes(&body)? {
+            Some(manifest) => self.fetch_chunks(&manifest).await?,
+            None => body,
         };
 
-        let files = Self::restore_tar(&self.repo_root, &body)?;
-
         self.log_fetch(analytics::CacheEvent::Hit, hash, duration);
         Ok(Some((
             CacheHitMetadata {
                 source: CacheSource::Remote,
                 time_saved: duration,
             },
-            files,
+            body,
         )))
     }
 
+    /// Downloads the chunks listed in a manifest and joins them back into
+    /// the original archive
+    async fn fetch_chunks(&self, manifest: &Manifest) -> Result<Vec<u8>, CacheError> {
+        let downloads: Vec<_> = manifest
+            .chunks
+            .iter()
+            .map(|chunk| self.fetch_chunk(chunk))
+            .collect();
+        let chunks: Vec<Vec<u8>> = futures::stream::iter(downloads)
+            .buffered(CHUNK_TRANSFERS)
+            .try_collect()
+            .await?;
+        debug!(
+            "downloaded {} chunks ({} bytes)",
+            chunks.len(),
+            manifest.size()
+        );
+
+        Ok(chunks.concat())
+    }
+
+    async fn fetch_chunk(&self, chunk: &ChunkRef) -> Result<Vec<u8>, CacheError> {
+        let Some(download) = self.download_with_retries(&chunk.hash).await? else {
+            return Err(CacheError::MissingChunk(
+                chunk.hash.clone(),
+                Backtrace::capture(),
+            ));
+        };
+        let data = if CacheReader::is_compressed(&download.body) {
+            zstd::decode_all(download.body.as_slice())?
+        } else {
+            download.body
+        };
+        chunk.verify(&data)?;
+
+        Ok(data)
+    }
+
+    async fn download_with_retries(&self, hash: &str) -> Result<Option<Download>, CacheError> {
+        let mut download = Download::default();
+        let mut retrier = self.retry_policy.start();
+        loop {
+            match self.download(hash, &mut download).await {
+                Err(err) if retrier.backoff(hash, &err).await => continue,
+                result => return Ok(result?.then_some(download)),
+            }
+        }
+    }
+
+    /// Downloads an artifact, resuming from whatever an earlier attempt
+    /// already received. Returns false if the artifact doesn't exist.
+    async fn download(&self, hash: &str, download: &mut Download) -> Result<bool, CacheError> {
+        let response = match download.offset() {
+            None => {
+                self.client
+                    .fetch_artifact(
+                        hash,
+                        &self.api_auth.token,
+                        self.api_auth.team_id.as_deref(),
+                        self.api_auth.team_slug.as_deref(),
+                    )
+                    .await?
+            }
+            Some(offset) => {
+                debug!("resuming download of {hash} from byte {offset}");
+                self.client
+                    .fetch_artifact_range(
+                        hash,
+                        offset,
+                        &self.api_auth.token,
+                        self.api_auth.team_id.as_deref(),
+                        self.api_auth.team_slug.as_deref(),
+                    )
+                    .await?
+            }
+        };
+        let Some(response) = response else {
+            return Ok(false);
+        };
+
+        download.read(response).await.map_err(|e| {
+            CacheError::ApiClientError(
+                Box::new(turborepo_api_client::Error::ReqwestError(e)),
+                Backtrace::capture(),
+            )
+        })?;
+
+        Ok(true)
+    }
+
     pub fn requests(&self) -> Arc<Mutex<UploadMap>> {
         self.uploads.clone()
     }
@@ -264,7 +472,7 @@ impl HTTPCache {
         root: &AbsoluteSystemPath,
         body: &[u8],
     ) -> Result<Vec<AnchoredSystemPathBuf>, CacheError> {
-        let mut cache_reader = CacheReader::from_reader(body, true)?;
+        let mut cache_reader = CacheReader::from_bytes(body)?;
         cache_reader.restore(root)
     }
 
@@ -292,7 +500,7 @@ mod test {
     use futures::future::try_join_all;
     use insta::assert_snapshot;
     use tempfile::tempdir;
-    use turbopath::AbsoluteSystemPathBuf;
+    use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
     use turborepo_analytics::start_analytics;
     use turborepo_api_client::{analytics, APIClient};
     use turborepo_vercel_api_mock::start_test_server;
@@ -300,7 +508,7 @@ mod test {
     use crate::{
         http::{APIAuth, HTTPCache},
         test_cases::{get_test_cases, validate_analytics, TestCase},
-        CacheOpts, CacheSource,
+        CacheOpts, CacheSource, RemoteCacheOpts, SignatureMismatchPolicy,
     };
 
     #[tokio::test]
@@ -387,6 +595,73 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_chunked_round_trip() -> Result<()> {
+        let port = port_scanner::request_open_port().unwrap();
+        let handle = tokio::spawn(start_test_server(port));
+
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        let output = repo_root_path.join_component("output.bin");
+        let mut state = 1u64;
+        let contents: Vec<u8> = (0..12 * 1024 * 1024)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        output.create_with_contents(&contents)?;
+        let files = [AnchoredSystemPathBuf::from_raw("output.bin")?];
+
+        let api_client = APIClient::new(
+            format!("http://localhost:{}", port),
+            Some(Duration::from_secs(200)),
+            None,
+            "2.0.0",
+            true,
+        )?;
+        let opts = CacheOpts {
+            remote_cache_opts: Some(RemoteCacheOpts::new(
+                None,
+                false,
+                SignatureMismatchPolicy::Error,
+                true,
+            )),
+            ..CacheOpts::default()
+        };
+        let api_auth = APIAuth {
+            team_id: Some("my-team".to_string()),
+            token: "my-token".to_string(),
+            team_slug: None,
+        };
+        let cache = HTTPCache::new(api_client, &opts, repo_root_path.clone(), api_auth, None);
+
+        cache.put(&repo_root_path, "chunked", &files, 10).await?;
+
+        // The mock server appends to existing artifacts, so re-uploading a
+        // shared chunk would corrupt it and break the first artifact
+        let mut edited = contents.clone();
+        edited.splice(edited.len() / 2..edited.len() / 2, *b"one-line change");
+        output.create_with_contents(&edited)?;
+        cache
+            .put(&repo_root_path, "chunked-edit", &files, 20)
+            .await?;
+
+        for (hash, expected, duration) in
+            [("chunked", &contents, 10), ("chunked-edit", &edited, 20)]
+        {
+            let (metadata, restored) = cache.fetch(hash).await?.unwrap();
+            assert_eq!(metadata.time_saved, duration);
+            assert_eq!(restored, files);
+            assert_eq!(&std::fs::read(&output)?, expected);
+        }
+
+        handle.abort();
+        Ok(())
+    }
+
     #[test]
     fn test_forbidden_error() {
         let err = HTTPCache::convert_api_error(
@@ -5,8 +5,13 @@
 
 /// A wrapper for the cache that uses a worker pool to perform cache operations
 mod async_cache;
+/// Remote cache backed by a cloud storage bucket
+pub mod bucket;
 /// The core cache creation and restoration logic.
 pub mod cache_archive;
+/// Splits large remote artifacts into chunks that can be shared between
+/// artifacts.
+mod chunking;
 /// File system cache
 pub mod fs;
 /// Remote cache
@@ -14,6 +19,8 @@ pub mod http;
 /// A wrapper that allows reads and writes from the file system and remote
 /// cache.
 mod multiplexer;
+/// Retries for remote cache transfers that fail for transient reasons
+mod retry;
 /// Cache signature authentication lets users provide a private key to sign
 /// their cache payloads.
 pub mod signature_authentication;
@@ -21,9 +28,10 @@ pub mod signature_authentication;
 mod test_cases;
 mod upload_progress;
 
-use std::{backtrace, backtrace::Backtrace};
+use std::{backtrace, backtrace::Backtrace, fmt, time::Duration};
 
 pub use async_cache::AsyncCache;
+use biome_deserialize_macros::Deserializable;
 use camino::Utf8PathBuf;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -85,6 +93,45 @@ pub enum CacheError {
     ConfigCacheError,
     #[error("Insufficient permissions to write to remote cache. Please verify that your role has write access for Remote Cache Artifact at https://vercel.com/docs/accounts/team-members-and-roles/access-roles/team-level-roles?resource=Remote+Cache+Artifact")]
     ForbiddenRemoteCacheWrite,
+    #[error(
+        "invalid remote cache storage URL {0}: expected gs://<bucket>[/<prefix>] or \
+         azblob://<account>/<container>[/<prefix>]"
+    )]
+    InvalidStorageUrl(String, #[backtrace] Backtrace),
+    #[error("unable to authenticate with remote cache storage: {0}")]
+    StorageCredentials(String, #[backtrace] Backtrace),
+    #[error("failed to contact remote cache storage: {0}")]
+    StorageRequest(reqwest::Error, #[backtrace] Backtrace),
+    #[error("remote cache storage request to {0} failed with status {1}")]
+    StorageStatus(String, reqwest::StatusCode, #[backtrace] Backtrace),
+    #[error("invalid chunked artifact manifest: {0}")]
+    InvalidManifest(serde_json::Error, #[backtrace] Backtrace),
+    #[error("chunk {0} is missing from the remote cache")]
+    MissingChunk(String, #[backtrace] Backtrace),
+    #[error("downloaded chunk does not match its hash {0}")]
+    InvalidChunk(String, #[backtrace] Backtrace),
+}
+
+impl CacheError {
+    pub(crate) fn storage_request(err: reqwest::Error) -> Self {
+        CacheError::StorageRequest(err, Backtrace::capture())
+    }
+
+    /// Whether the error could go away if the request is made again
+    pub(crate) fn is_transient(&self) -> bool {
+        match self {
+            CacheError::TimeoutError(_) | CacheError::ConnectError => true,
+            CacheError::StorageStatus(_, status, _) => retry::is_transient_status(*status),
+            CacheError::StorageRequest(err, _) => retry::is_transient_error(err),
+            CacheError::ApiClientError(box turborepo_api_client::Error::ReqwestError(err), _) => {
+                retry::is_transient_error(err)
+            }
+            CacheError::ApiClientError(box turborepo_api_client::Error::TooManyFailures(_), _) => {
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 impl From<turborepo_api_client::Error> for CacheError {
@@ -109,23 +156,94 @@ pub struct CacheHitMetadata {
 pub struct CacheOpts {
     pub cache_dir: Utf8PathBuf,
     pub remote_cache_read_only: bool,
+    pub remote_cache_write_only: bool,
     pub skip_remote: bool,
     pub skip_filesystem: bool,
     pub workers: u32,
+    pub storage_url: Option<String>,
+    pub compression_level: i32,
+    pub eviction_policy: EvictionPolicy,
+    pub retry_policy: RetryPolicy,
     pub remote_cache_opts: Option<RemoteCacheOpts>,
 }
 
+/// The task that produced an artifact, recorded alongside it in the local
+/// cache
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactTask {
+    pub package: String,
+    pub task: String,
+}
+
+/// Limits on the size of the local cache. Once a limit is exceeded, the
+/// least recently used artifacts are removed first.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EvictionPolicy {
+    /// The total number of bytes the cache directory may hold
+    pub max_size: Option<u64>,
+    /// How long an artifact may go without being written or restored
+    pub max_age: Option<Duration>,
+}
+
+impl EvictionPolicy {
+    pub fn is_unbounded(&self) -> bool {
+        self.max_size.is_none() && self.max_age.is_none()
+    }
+}
+
+/// How persistently remote cache transfers are retried. Each retry waits twice
+/// as long as the one before it, and interrupted downloads pick up where they
+/// left off when the server supports it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of times a failed upload or download is retried
+    pub retries: u32,
+}
+
+/// What to do when a downloaded artifact fails signature verification
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Deserializable, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SignatureMismatchPolicy {
+    /// Treat the artifact as a cache miss by failing the fetch
+    #[default]
+    Error,
+    /// Log a warning and restore the artifact anyway
+    Warn,
+}
+
+impl SignatureMismatchPolicy {
+    pub const VARIANTS: [Self; 2] = [Self::Error, Self::Warn];
+}
+
+impl fmt::Display for SignatureMismatchPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SignatureMismatchPolicy::Error => "error",
+            SignatureMismatchPolicy::Warn => "warn",
+        })
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct RemoteCacheOpts {
     unused_team_id: Option<String>,
     signature: bool,
+    signature_mismatch: SignatureMismatchPolicy,
+    chunking: bool,
 }
 
 impl RemoteCacheOpts {
-    pub fn new(unused_team_id: Option<String>, signature: bool) -> Self {
+    pub fn new(
+        unused_team_id: Option<String>,
+        signature: bool,
+        signature_mismatch: SignatureMismatchPolicy,
+        chunking: bool,
+    ) -> Self {
         Self {
             unused_team_id,
             signature,
+            signature_mismatch,
+            chunking,
         }
     }
 }
@@ -1,17 +1,19 @@
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicUsize, Ordering},
     Arc, Mutex,
 };
 
+use tokio::sync::Semaphore;
 use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
 use crate::{
+    bucket::{BucketCache, BucketLocation},
     fs::FSCache,
     http::{HTTPCache, UploadMap},
-    CacheError, CacheHitMetadata, CacheOpts,
+    ArtifactTask, CacheError, CacheHitMetadata, CacheOpts, EvictionPolicy,
 };
 
 pub struct CacheMultiplexer {
@@ -23,9 +25,18 @@ pub struct CacheMultiplexer {
     // Just for keeping track of whether we've already printed a warning about the remote cache
     // being read-only
     should_print_skipping_remote_put: AtomicBool,
+    // Same as above, but for the remote cache being write-only
+    should_print_skipping_remote_fetch: AtomicBool,
     remote_cache_read_only: bool,
+    remote_cache_write_only: bool,
+    // Failed requests to the remote cache. These don't fail the run, but callers
+    // may want to know about them once it's over.
+    remote_errors: AtomicUsize,
+    eviction_policy: EvictionPolicy,
+    downloads: Semaphore,
     fs: Option<FSCache>,
     http: Option<HTTPCache>,
+    bucket: Option<BucketCache>,
 }
 
 impl CacheMultiplexer {
@@ -38,20 +49,45 @@ impl CacheMultiplexer {
         analytics_recorder: Option<AnalyticsSender>,
     ) -> Result<Self, CacheError> {
         let use_fs_cache = !opts.skip_filesystem;
-        let use_http_cache = !opts.skip_remote;
+        let use_remote_cache = !opts.skip_remote;
 
         // Since the above two flags are not mutually exclusive it is possible to
         // configure yourself out of having a cache. We should tell you about it
         // but we shouldn't fail your build for that reason.
-        if !use_fs_cache && !use_http_cache {
+        if !use_fs_cache && !use_remote_cache {
             warn!("no caches are enabled");
         }
 
         let fs_cache = use_fs_cache
-            .then(|| FSCache::new(&opts.cache_dir, repo_root, analytics_recorder.clone()))
+            .then(|| {
+                FSCache::new(
+                    &opts.cache_dir,
+                    repo_root,
+                    analytics_recorder.clone(),
+                    opts.compression_level,
+                )
+            })
             .transpose()?;
 
-        let http_cache = use_http_cache
+        // A storage URL replaces the Remote Cache API entirely, so we don't need to
+        // be logged in to use it.
+        let bucket_cache = opts
+            .storage_url
+            .as_deref()
+            .filter(|_| use_remote_cache)
+            .map(|url| {
+                let location = url.parse::<BucketLocation>()?;
+                debug!("using {location} as the remote cache");
+                BucketCache::new(
+                    location,
+                    repo_root.to_owned(),
+                    opts.compression_level,
+                    opts.retry_policy,
+                )
+            })
+            .transpose()?;
+
+        let http_cache = (use_remote_cache && bucket_cache.is_none())
             .then_some(api_auth)
             .flatten()
             .map(|api_auth| {
@@ -66,10 +102,16 @@ impl CacheMultiplexer {
 
         Ok(CacheMultiplexer {
             should_print_skipping_remote_put: AtomicBool::new(true),
+            should_print_skipping_remote_fetch: AtomicBool::new(true),
             should_use_http_cache: AtomicBool::new(http_cache.is_some()),
             remote_cache_read_only: opts.remote_cache_read_only,
+            remote_cache_write_only: opts.remote_cache_write_only,
+            remote_errors: AtomicUsize::new(0),
+            eviction_policy: opts.eviction_policy,
+            downloads: Semaphore::new(opts.workers.max(1) as usize),
             fs: fs_cache,
             http: http_cache,
+            bucket: bucket_cache,
         })
     }
 
@@ -83,6 +125,65 @@ impl CacheMultiplexer {
         }
     }
 
+    // Cache is functional but running in read-only mode, so we don't want to try to
+    // write to it
+    fn skip_remote_put(&self) -> bool {
+        if self.remote_cache_read_only
+            && self
+                .should_print_skipping_remote_put
+                .swap(false, Ordering::Relaxed)
+        {
+            // Warn once per build, not per task
+            warn!("Remote cache is read-only, skipping upload");
+        }
+        self.remote_cache_read_only
+    }
+
+    // Cache is functional but running in write-only mode, so we only publish
+    // artifacts and never restore them from it
+    fn skip_remote_fetch(&self) -> bool {
+        if self.remote_cache_write_only
+            && self
+                .should_print_skipping_remote_fetch
+                .swap(false, Ordering::Relaxed)
+        {
+            // Warn once per build, not per task
+            warn!("Remote cache is write-only, skipping download");
+        }
+        self.remote_cache_write_only
+    }
+
+    /// The number of requests to the remote cache that have failed so far
+    pub fn remote_errors(&self) -> usize {
+        self.remote_errors.load(Ordering::Relaxed)
+    }
+
+    fn record_remote_error<T>(&self, result: Result<T, CacheError>) -> Result<T, CacheError> {
+        if result.is_err() {
+            self.remote_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    // Trims the filesystem cache down to the configured limits. Failing to do so
+    // shouldn't fail the run, so errors are only logged.
+    pub fn evict(&self) {
+        let Some(fs) = self
+            .fs
+            .as_ref()
+            .filter(|_| !self.eviction_policy.is_unbounded())
+        else {
+            return;
+        };
+        match fs.evict(&self.eviction_policy) {
+            Ok(summary) => debug!(
+                "evicted {} artifacts ({} bytes) from the local cache",
+                summary.evicted, summary.evicted_bytes
+            ),
+            Err(e) => warn!("failed to evict artifacts from the local cache: {e}"),
+        }
+    }
+
     pub fn requests(&self) -> Option<Arc<Mutex<UploadMap>>> {
         self.http.as_ref().map(|http| http.requests())
     }
@@ -94,32 +195,24 @@ impl CacheMultiplexer {
         key: &str,
         files: &[AnchoredSystemPathBuf],
         duration: u64,
+        task: Option<&ArtifactTask>,
     ) -> Result<(), CacheError> {
         self.fs
             .as_ref()
-            .map(|fs| fs.put(anchor, key, files, duration))
+            .map(|fs| fs.put(anchor, key, files, duration, task))
             .transpose()?;
 
+        if let Some(bucket) = &self.bucket {
+            if !self.skip_remote_put() {
+                self.record_remote_error(bucket.put(anchor, key, files, duration).await)?;
+            }
+            return Ok(());
+        }
+
         let http_result = match self.get_http_cache() {
+            Some(_) if self.skip_remote_put() => None,
             Some(http) => {
-                if self.remote_cache_read_only {
-                    if self
-                        .should_print_skipping_remote_put
-                        .load(Ordering::Relaxed)
-                    {
-                        // Warn once per build, not per task
-                        warn!("Remote cache is read-only, skipping upload");
-                        self.should_print_skipping_remote_put
-                            .store(false, Ordering::Relaxed);
-                    }
-                    // Cache is functional but running in read-only mode, so we don't want to try to
-                    // write to it
-                    None
-                } else {
-                    let http_result = http.put(anchor, key, files, duration).await;
-
-                    Some(http_result)
-                }
+                Some(self.record_remote_error(http.put(anchor, key, files, duration).await))
             }
             _ => None,
         };
@@ -143,6 +236,7 @@ impl CacheMultiplexer {
         &self,
         anchor: &AbsoluteSystemPath,
         key: &str,
+        task: Option<&ArtifactTask>,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
         if let Some(fs) = &self.fs {
             if let response @ Ok(Some(_)) = fs.fetch(anchor, key) {
@@ -150,25 +244,68 @@ impl CacheMultiplexer {
             }
         }
 
-        if let Some(http) = self.get_http_cache() {
-            if let Ok(Some((CacheHitMetadata { source, time_saved }, files))) =
-                http.fetch(key).await
-            {
-                // Store this into fs cache. We can ignore errors here because we know
-                // we have previously successfully stored in HTTP cache, and so the overall
-                // result is a success at fetching. Storing in lower-priority caches is an
-                // optimization.
-                if let Some(fs) = &self.fs {
-                    let _ = fs.put(anchor, key, &files, time_saved);
-                }
+        if self.skip_remote_fetch() {
+            return Ok(None);
+        }
+
+        // Downloads share the worker limit with uploads so that a wide task graph
+        // doesn't open a connection per task
+        let _permit = self.downloads.acquire().await;
+        let remote_result = match (&self.bucket, self.get_http_cache()) {
+            (Some(bucket), _) => Some(self.record_remote_error(bucket.fetch(key).await)),
+            (None, Some(http)) => Some(self.record_remote_error(http.fetch(key).await)),
+            (None, None) => None,
+        };
 
-                return Ok(Some((CacheHitMetadata { source, time_saved }, files)));
+        if let Some(Ok(Some((CacheHitMetadata { source, time_saved }, files)))) = remote_result {
+            // Store this into fs cache. We can ignore errors here because we know
+            // we have previously successfully stored in the remote cache, and so the
+            // overall result is a success at fetching. Storing in lower-priority caches
+            // is an optimization.
+            if let Some(fs) = &self.fs {
+                let _ = fs.put(anchor, key, &files, time_saved, task);
             }
+
+            return Ok(Some((CacheHitMetadata { source, time_saved }, files)));
         }
 
         Ok(None)
     }
 
+    /// Makes sure an artifact is in the filesystem cache, downloading it from
+    /// the remote cache if needed. The hit reports where the artifact was
+    /// found.
+    #[tracing::instrument(skip_all)]
+    pub async fn prefetch(
+        &self,
+        key: &str,
+        task: Option<&ArtifactTask>,
+    ) -> Result<Option<CacheHitMetadata>, CacheError> {
+        // Without a filesystem cache there is nowhere to keep the artifact
+        let Some(fs) = &self.fs else {
+            return Ok(None);
+        };
+        if let cache_hit @ Some(_) = fs.exists(key)? {
+            return Ok(cache_hit);
+        }
+        if self.skip_remote_fetch() {
+            return Ok(None);
+        }
+
+        let _permit = self.downloads.acquire().await;
+        let archive = match (&self.bucket, self.get_http_cache()) {
+            (Some(bucket), _) => self.record_remote_error(bucket.fetch_archive(key).await)?,
+            (None, Some(http)) => self.record_remote_error(http.fetch_archive(key).await)?,
+            (None, None) => None,
+        };
+        let Some((metadata, archive)) = archive else {
+            return Ok(None);
+        };
+        fs.put_archive(key, &archive, metadata.time_saved, task)?;
+
+        Ok(Some(metadata))
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn exists(&self, key: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
         if let Some(fs) = &self.fs {
@@ -181,8 +318,22 @@ impl CacheMultiplexer {
             }
         }
 
+        if self.remote_cache_write_only {
+            return Ok(None);
+        }
+
+        if let Some(bucket) = &self.bucket {
+            match self.record_remote_error(bucket.exists(key).await) {
+                cache_hit @ Ok(Some(_)) => {
+                    return cache_hit;
+                }
+                Ok(None) => {}
+                Err(err) => debug!("failed to check remote cache storage: {:?}", err),
+            }
+        }
+
         if let Some(http) = self.get_http_cache() {
-            match http.exists(key).await {
+            match self.record_remote_error(http.exists(key).await) {
                 cache_hit @ Ok(Some(_)) => {
                     return cache_hit;
                 }
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use reqwest::{
+    header::{HeaderMap, CONTENT_RANGE},
+    Response, StatusCode,
+};
+use tracing::debug;
+
+use crate::{CacheError, RetryPolicy};
+
+const MIN_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(10);
+
+/// Tracks the attempts made for a single transfer
+pub(crate) struct Retrier {
+    policy: RetryPolicy,
+    attempt: u32,
+}
+
+impl RetryPolicy {
+    pub(crate) fn start(&self) -> Retrier {
+        Retrier {
+            policy: *self,
+            attempt: 0,
+        }
+    }
+}
+
+impl Retrier {
+    /// Waits out the backoff for the next attempt. Returns false if the
+    /// transfer should be abandoned, either because the error isn't one that
+    /// retrying can fix or because we are out of retries.
+    pub(crate) async fn backoff(&mut self, hash: &str, err: &CacheError) -> bool {
+        if self.attempt >= self.policy.retries || !err.is_transient() {
+            return false;
+        }
+
+        let delay = Self::delay(self.attempt);
+        self.attempt += 1;
+        debug!(
+            "retrying {hash} in {delay:?} ({}/{}): {err}",
+            self.attempt, self.policy.retries
+        );
+        tokio::time::sleep(delay).await;
+        true
+    }
+
+    fn delay(attempt: u32) -> Duration {
+        MIN_BACKOFF
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF)
+    }
+}
+
+/// An artifact download that can pick up where an interrupted attempt left
+/// off.
+#[derive(Default)]
+pub(crate) struct Download {
+    pub body: Vec<u8>,
+    /// The headers of the response that started the download
+    pub headers: HeaderMap,
+}
+
+impl Download {
+    /// The offset to resume the download from, if anything was received
+    pub(crate) fn offset(&self) -> Option<u64> {
+        (!self.body.is_empty()).then_some(self.body.len() as u64)
+    }
+
+    /// Reads a response into the download. If the server honored the range
+    /// we asked for, the new bytes are appended onto what was already
+    /// received, otherwise the download starts over.
+    pub(crate) async fn read(&mut self, mut response: Response) -> Result<(), reqwest::Error> {
+        if !continues_from(&response, self.body.len()) {
+            self.body.clear();
+            self.headers = response.headers().clone();
+        }
+        while let Some(chunk) = response.chunk().await? {
+            self.body.extend_from_slice(&chunk);
+        }
+
+        Ok(())
+    }
+}
+
+fn continues_from(response: &Response, offset: usize) -> bool {
+    response.status() == StatusCode::PARTIAL_CONTENT
+        && response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|range| range.to_str().ok())
+            .and_then(|range| range.strip_prefix("bytes "))
+            .and_then(|range| range.split_once('-'))
+            .and_then(|(start, _)| start.parse::<usize>().ok())
+            == Some(offset)
+}
+
+pub(crate) fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status.is_server_error() && status != StatusCode::NOT_IMPLEMENTED)
+}
+
+pub(crate) fn is_transient_error(err: &reqwest::Error) -> bool {
+    err.is_timeout()
+        || err.is_connect()
+        || err.is_body()
+        || err.is_decode()
+        || err.status().map_or(false, is_transient_status)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use reqwest::{header::CONTENT_RANGE, Response, StatusCode};
+    use test_case::test_case;
+
+    use super::{Download, Retrier};
+
+    fn response(status: StatusCode, range: Option<&str>, body: &'static str) -> Response {
+        let mut response = http::Response::builder()
+            .status(status)
+            .header("x-artifact-duration", body.len().to_string());
+        if let Some(range) = range {
+            response = response.header(CONTENT_RANGE, range);
+        }
+        response.body(body).unwrap().into()
+    }
+
+    #[test_case(0, Duration::from_millis(500) ; "first")]
+    #[test_case(1, Duration::from_secs(1) ; "second")]
+    #[test_case(3, Duration::from_secs(4) ; "fourth")]
+    #[test_case(10, Duration::from_secs(10) ; "capped")]
+    #[test_case(100, Duration::from_secs(10) ; "overflow")]
+    fn test_delay(attempt: u32, expected: Duration) {
+        assert_eq!(Retrier::delay(attempt), expected);
+    }
+
+    #[tokio::test]
+    async fn test_resume_download() {
+        let mut download = Download::default();
+        assert_eq!(download.offset(), None);
+
+        download
+            .read(response(StatusCode::OK, None, "abc"))
+            .await
+            .unwrap();
+        assert_eq!(download.offset(), Some(3));
+
+        download
+            .read(response(
+                StatusCode::PARTIAL_CONTENT,
+                Some("bytes 3-7/8"),
+                "defgh",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(download.body, b"abcdefgh");
+        // The headers of the response that started the download are kept
+        assert_eq!(download.headers["x-artifact-duration"], "3");
+    }
+
+    #[test_case(StatusCode::OK, None ; "range ignored")]
+    #[test_case(StatusCode::PARTIAL_CONTENT, Some("bytes 1-3/4") ; "wrong offset")]
+    #[test_case(StatusCode::PARTIAL_CONTENT, None ; "missing range")]
+    #[tokio::test]
+    async fn test_restart_download(status: StatusCode, range: Option<&str>) {
+        let mut download = Download::default();
+        download
+            .read(response(StatusCode::OK, None, "abc"))
+            .await
+            .unwrap();
+
+        download
+            .read(response(status, range, "wxyz"))
+            .await
+            .unwrap();
+        assert_eq!(download.body, b"wxyz");
+        assert_eq!(download.headers["x-artifact-duration"], "4");
+    }
+}
@@ -1,4 +1,4 @@
-use std::env;
+use std::{env, fmt, iter};
 
 use base64::{prelude::BASE64_STANDARD, Engine};
 use hmac::{Hmac, Mac};
@@ -23,11 +23,34 @@ pub enum SignatureError {
     Hmac(#[from] hmac::digest::InvalidLength),
 }
 
+/// The key that an artifact's signature was verified with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VerifyingKey {
+    /// The key in `TURBO_REMOTE_CACHE_SIGNATURE_KEY`
+    Current,
+    /// A key from `TURBO_REMOTE_CACHE_SIGNATURE_PREVIOUS_KEYS`, by position
+    Previous(usize),
+}
+
+impl fmt::Display for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyingKey::Current => write!(f, "TURBO_REMOTE_CACHE_SIGNATURE_KEY"),
+            VerifyingKey::Previous(idx) => write!(
+                f,
+                "key {} of TURBO_REMOTE_CACHE_SIGNATURE_PREVIOUS_KEYS",
+                idx + 1
+            ),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct ArtifactSignatureAuthenticator {
     pub(crate) team_id: Vec<u8>,
     // An override for testing purposes (to avoid env var race conditions)
     pub(crate) secret_key_override: Option<Vec<u8>>,
+    pub(crate) previous_keys_override: Option<Vec<Vec<u8>>>,
 }
 
 impl ArtifactSignatureAuthenticator {
@@ -35,6 +58,7 @@ impl ArtifactSignatureAuthenticator {
         Self {
             team_id,
             secret_key_override,
+            previous_keys_override: None,
         }
     }
 
@@ -52,6 +76,25 @@ impl ArtifactSignatureAuthenticator {
             .into_raw_vec())
     }
 
+    // Keys that were rotated out are still accepted when verifying so that
+    // artifacts written before the rotation remain usable. They are never used
+    // for signing.
+    fn previous_keys(&self) -> Vec<Vec<u8>> {
+        if let Some(previous_keys) = &self.previous_keys_override {
+            return previous_keys.clone();
+        }
+
+        env::var("TURBO_REMOTE_CACHE_SIGNATURE_PREVIOUS_KEYS")
+            .map(|keys| {
+                keys.split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty())
+                    .map(|key| key.as_bytes().to_vec())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
     fn construct_metadata(&self, hash: &[u8]) -> Result<Vec<u8>, SignatureError> {
         let mut metadata = hash.to_vec();
         metadata.extend_from_slice(&self.team_id);
@@ -101,13 +144,37 @@ impl ArtifactSignatureAuthenticator {
         artifact_body: &[u8],
         expected_tag: &str,
     ) -> Result<bool, SignatureError> {
-        let mut mac = HmacSha256::new_from_slice(&self.secret_key()?)?;
-        let message = self.construct_metadata(hash)?;
-        mac.update(&message);
-        mac.update(artifact_body);
+        Ok(self.verify(hash, artifact_body, expected_tag)?.is_some())
+    }
 
+    /// Checks the tag against the current key and then any previous keys,
+    /// returning the key that matched.
+    #[tracing::instrument(skip_all)]
+    pub fn verify(
+        &self,
+        hash: &[u8],
+        artifact_body: &[u8],
+        expected_tag: &str,
+    ) -> Result<Option<VerifyingKey>, SignatureError> {
         let expected_bytes = BASE64_STANDARD.decode(expected_tag)?;
-        Ok(mac.verify_slice(&expected_bytes).is_ok())
+        let message = self.construct_metadata(hash)?;
+
+        let keys = iter::once((VerifyingKey::Current, self.secret_key()?)).chain(
+            self.previous_keys()
+                .into_iter()
+                .enumerate()
+                .map(|(idx, key)| (VerifyingKey::Previous(idx), key)),
+        );
+        for (verifying_key, key) in keys {
+            let mut mac = HmacSha256::new_from_slice(&key)?;
+            mac.update(&message);
+            mac.update(artifact_body);
+            if mac.verify_slice(&expected_bytes).is_ok() {
+                return Ok(Some(verifying_key));
+            }
+        }
+
+        Ok(None)
     }
 }
 
@@ -248,6 +315,7 @@ mod tests {
         let signature = ArtifactSignatureAuthenticator {
             team_id: test_case.team_id.to_vec(),
             secret_key_override: None,
+            previous_keys_override: Some(Vec::new()),
         };
 
         let hash = test_case.artifact_hash;
@@ -273,4 +341,37 @@ mod tests {
         assert!(signature.validate(hash, artifact_body, &tag)?);
         Ok(())
     }
+
+    #[test]
+    fn test_previous_keys() -> Result<()> {
+        let old_signature = ArtifactSignatureAuthenticator {
+            team_id: b"my-team".to_vec(),
+            secret_key_override: Some(b"old key".to_vec()),
+            previous_keys_override: Some(Vec::new()),
+        };
+        let tag = old_signature.generate_tag(b"hash", b"body")?;
+
+        let rotated_signature = ArtifactSignatureAuthenticator {
+            team_id: b"my-team".to_vec(),
+            secret_key_override: Some(b"new key".to_vec()),
+            previous_keys_override: Some(vec![b"older key".to_vec(), b"old key".to_vec()]),
+        };
+        assert_eq!(
+            rotated_signature.verify(b"hash", b"body", &tag)?,
+            Some(VerifyingKey::Previous(1))
+        );
+        assert_eq!(
+            rotated_signature.verify(b"hash", b"tampered body", &tag)?,
+            None
+        );
+
+        let new_tag = rotated_signature.generate_tag(b"hash", b"body")?;
+        assert_eq!(
+            rotated_signature.verify(b"hash", b"body", &new_tag)?,
+            Some(VerifyingKey::Current)
+        );
+        assert_eq!(old_signature.verify(b"hash", b"body", &new_tag)?, None);
+
+        Ok(())
+    }
 }
@@ -5,21 +5,45 @@ use std::{
 };
 
 use biome_deserialize::{Deserializable, DeserializableValue, DeserializationDiagnostic};
-use miette::{Diagnostic, NamedSource, SourceSpan};
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceSpan};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 pub const TURBO_SITE: &str = "https://turbo.build";
 
 /// A little helper to convert from biome's syntax errors to miette.
-#[derive(Debug, Error, Diagnostic)]
+///
+/// Along with biome's message, we point at the JSON path of the offending key
+/// and suggest the closest known key or value when biome tells us what it
+/// expected.
+#[derive(Debug, Error)]
 #[error("{message}")]
 pub struct ParseDiagnostic {
     message: String,
-    #[source_code]
     source_code: NamedSource,
-    #[label]
     label: Option<SourceSpan>,
+    path: Option<String>,
+    help: Option<String>,
+}
+
+impl Diagnostic for ParseDiagnostic {
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let label = self.label?;
+        let text = self.path.as_ref().map(|path| format!("at `{path}`"));
+        Some(Box::new(std::iter::once(LabeledSpan::new_with_span(
+            text, label,
+        ))))
+    }
+
+    fn help(&self) -> Option<Box<dyn Display + '_>> {
+        self.help
+            .as_ref()
+            .map(|help| Box::new(help) as Box<dyn Display>)
+    }
 }
 
 struct BiomeMessage<'a>(&'a biome_diagnostics::Error);
@@ -30,6 +54,60 @@ impl Display for BiomeMessage<'_> {
     }
 }
 
+/// Collects the list of known keys or accepted values that biome attaches to
+/// unknown key and value diagnostics
+#[derive(Default)]
+struct BiomeAdvice {
+    heading: Option<String>,
+    list: Vec<String>,
+}
+
+impl biome_diagnostics::Visit for BiomeAdvice {
+    fn record_log(
+        &mut self,
+        _category: biome_diagnostics::LogCategory,
+        text: &dyn biome_console::fmt::Display,
+    ) -> std::io::Result<()> {
+        self.heading = Some(markup_text(text));
+        Ok(())
+    }
+
+    fn record_list(&mut self, list: &[&dyn biome_console::fmt::Display]) -> std::io::Result<()> {
+        self.list = list.iter().map(|item| markup_text(*item)).collect();
+        Ok(())
+    }
+}
+
+fn markup_text(markup: &dyn biome_console::fmt::Display) -> String {
+    biome_console::markup!({ markup })
+        .to_owned()
+        .0
+        .into_iter()
+        .map(|node| node.content)
+        .collect()
+}
+
+impl BiomeAdvice {
+    fn help(&self, name: &str) -> Option<String> {
+        if self.list.is_empty() {
+            return None;
+        }
+        if let Some(suggestion) = closest_match(name, self.list.iter().map(String::as_str)) {
+            return Some(format!("did you mean `{suggestion}`?"));
+        }
+        let list = self
+            .list
+            .iter()
+            .map(|item| format!("`{item}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(match &self.heading {
+            Some(heading) => format!("{heading} {list}"),
+            None => list,
+        })
+    }
+}
+
 impl From<biome_diagnostics::Error> for ParseDiagnostic {
     fn from(diagnostic: biome_diagnostics::Error) -> Self {
         let location = diagnostic.location();
@@ -38,22 +116,171 @@ impl From<biome_diagnostics::Error> for ParseDiagnostic {
             .resource
             .and_then(|r| r.as_file().map(|p| p.to_string()))
             .unwrap_or_default();
+        let text = location
+            .source_code
+            .map(|s| s.text.to_string())
+            .unwrap_or_default();
+        let range = location.span.map(|span| {
+            let start: usize = span.start().into();
+            let end: usize = span.end().into();
+            start..end
+        });
+
+        let mut advice = BiomeAdvice::default();
+        // Our visitor never returns an error
+        let _ = diagnostic.advices(&mut advice);
+        let help = range.clone().and_then(|range| {
+            let name = text.get(range)?.trim_matches('"');
+            advice.help(name)
+        });
+        let json_path = range
+            .as_ref()
+            .and_then(|range| json_path(&text, range.start));
+
         Self {
             message,
-            source_code: NamedSource::new(
-                path,
-                location
-                    .source_code
-                    .map(|s| s.text.to_string())
-                    .unwrap_or_default(),
-            ),
-            label: location.span.map(|span| {
-                let start: usize = span.start().into();
-                let len: usize = span.len().into();
-                (start, len).into()
+            source_code: NamedSource::new(path, text),
+            label: range.map(|range| (range.start, range.end - range.start).into()),
+            path: json_path,
+            help,
+        }
+    }
+}
+
+/// Finds the candidate that's closest to `name`, if any is close enough that
+/// `name` is likely a typo of it.
+pub fn closest_match<'a>(
+    name: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Option<&'a str> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (strsim::damerau_levenshtein(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+enum PathSegment {
+    Object {
+        key: Option<String>,
+        expecting_key: bool,
+    },
+    Array {
+        index: usize,
+    },
+}
+
+/// Gets the path, e.g. `tasks.build.outputs[0]`, of the JSON key or value
+/// that starts at `offset`. Returns `None` for the root value.
+fn json_path(text: &str, offset: usize) -> Option<String> {
+    let mut stack = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        if index >= offset {
+            break;
+        }
+        match c {
+            '{' => stack.push(PathSegment::Object {
+                key: None,
+                expecting_key: true,
             }),
+            '[' => stack.push(PathSegment::Array { index: 0 }),
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => match stack.last_mut() {
+                Some(PathSegment::Object { key, expecting_key }) => {
+                    *key = None;
+                    *expecting_key = true;
+                }
+                Some(PathSegment::Array { index }) => *index += 1,
+                None => {}
+            },
+            '"' => {
+                let string = read_string(&mut chars);
+                if let Some(PathSegment::Object { key, expecting_key }) = stack.last_mut() {
+                    if *expecting_key {
+                        *key = Some(string);
+                        *expecting_key = false;
+                    }
+                }
+            }
+            // turbo.json allows comments
+            '/' => match chars.peek() {
+                Some((_, '/')) => {
+                    for (_, c) in chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some((_, '*')) => {
+                    chars.next();
+                    let mut previous = None;
+                    for (_, c) in chars.by_ref() {
+                        if previous == Some('*') && c == '/' {
+                            break;
+                        }
+                        previous = Some(c);
+                    }
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    // If we're pointing at a key, it's the last segment of the path
+    if let Some(PathSegment::Object { key, expecting_key }) = stack.last_mut() {
+        if let Some(rest) = text.get(offset..).and_then(|rest| rest.strip_prefix('"')) {
+            if *expecting_key {
+                *key = Some(read_string(&mut rest.char_indices()));
+            }
+        }
+    }
+
+    let mut path = String::new();
+    for segment in &stack {
+        match segment {
+            PathSegment::Object { key: Some(key), .. } => {
+                let is_identifier = !key.is_empty()
+                    && key
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '$'));
+                if !is_identifier {
+                    path.push_str(&format!("[{key:?}]"));
+                } else if path.is_empty() {
+                    path.push_str(key);
+                } else {
+                    path.push('.');
+                    path.push_str(key);
+                }
+            }
+            PathSegment::Object { key: None, .. } => break,
+            PathSegment::Array { index } => path.push_str(&format!("[{index}]")),
+        }
+    }
+    (!path.is_empty()).then_some(path)
+}
+
+// Reads the rest of a string whose opening quote has already been consumed
+fn read_string(chars: &mut impl Iterator<Item = (usize, char)>) -> String {
+    let mut string = String::new();
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => {
+                if let Some((_, escaped)) = chars.next() {
+                    string.push(escaped);
+                }
+            }
+            _ => string.push(c),
         }
     }
+    string
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, Eq)]
@@ -241,7 +468,7 @@ mod tests {
     use serde_json::json;
     use test_case::test_case;
 
-    use crate::Spanned;
+    use crate::{closest_match, json_path, Spanned};
 
     #[test_case(Spanned { value: 10, range: Some(0..2), path: None, text: None }, "10")]
     #[test_case(Spanned { value: "hello world", range: None, path: None, text: Some(Arc::from("hello world")) }, "\"hello world\"")]
@@ -253,4 +480,31 @@ mod tests {
         let actual = serde_json::to_string(&spanned_value).unwrap();
         assert_eq!(actual, expected);
     }
+
+    const TURBO_JSON: &str = r#"{
+  // comments with "quotes" and [brackets] are skipped
+  "tasks": {
+    "build": { "outputs": ["dist/**", 1] },
+    "my-app#build": { "dependOn": [] }
+  }
+}"#;
+
+    #[test_case("{", None ; "root value")]
+    #[test_case("\"tasks\"", Some("tasks") ; "top level key")]
+    #[test_case("{ \"outputs\"", Some("tasks.build") ; "object value")]
+    #[test_case("1]", Some("tasks.build.outputs[1]") ; "array item")]
+    #[test_case("\"dependOn\"", Some("tasks[\"my-app#build\"].dependOn") ; "key")]
+    fn test_json_path(at: &str, expected: Option<&str>) {
+        let offset = TURBO_JSON.find(at).unwrap();
+        assert_eq!(json_path(TURBO_JSON, offset).as_deref(), expected);
+    }
+
+    #[test_case("dependOn", Some("dependsOn") ; "missing letter")]
+    #[test_case("biuld", Some("build") ; "transposed letters")]
+    #[test_case("build", None ; "exact match")]
+    #[test_case("deploy", None ; "nothing close")]
+    fn test_closest_match(name: &str, expected: Option<&str>) {
+        let candidates = ["build", "dependsOn", "outputs", "lint"];
+        assert_eq!(closest_match(name, candidates), expected);
+    }
 }
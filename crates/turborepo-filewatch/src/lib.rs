@@ -28,7 +28,7 @@ use std::{
     fmt::{Debug, Display},
     future::IntoFuture,
     path::Path,
-    sync::Arc,
+    sync::{Arc, OnceLock},
     time::Duration,
 };
 
@@ -37,25 +37,19 @@ use std::{
 // macos -> custom watcher impl in fsevents, no recursive watch, no watching ancestors
 #[cfg(target_os = "macos")]
 use fsevent::FsEventWatcher;
-#[cfg(any(feature = "manual_recursive_watch", feature = "watch_ancestors"))]
-use notify::event::EventKind;
+use notify::{
+    event::{CreateKind, EventAttributes},
+    Event, EventHandler, EventKind, PollWatcher, RecursiveMode, Watcher,
+};
 #[cfg(not(target_os = "macos"))]
 use notify::{Config, RecommendedWatcher};
-use notify::{Event, EventHandler, RecursiveMode, Watcher};
 use thiserror::Error;
 use tokio::sync::{broadcast, mpsc, watch::error::RecvError};
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathRelation};
+use walkdir::WalkDir;
 #[cfg(feature = "manual_recursive_watch")]
-use {
-    notify::{
-        event::{CreateKind, EventAttributes},
-        ErrorKind,
-    },
-    std::io,
-    tracing::trace,
-    walkdir::WalkDir,
-};
+use {notify::ErrorKind, std::io, tracing::trace};
 
 pub mod cookies;
 mod debouncer;
@@ -63,8 +57,11 @@ mod debouncer;
 mod fsevent;
 pub mod globwatcher;
 pub mod hash_watcher;
+mod network_fs;
 mod optional_watch;
 pub mod package_watcher;
+#[cfg(unix)]
+mod watchman;
 
 pub use optional_watch::OptionalWatch;
 
@@ -73,6 +70,71 @@ type Backend = RecommendedWatcher;
 #[cfg(target_os = "macos")]
 type Backend = FsEventWatcher;
 
+/// The name of the platform API that delivers file events
+#[cfg(target_os = "macos")]
+pub const BACKEND_NAME: &str = "fsevents";
+#[cfg(target_os = "linux")]
+pub const BACKEND_NAME: &str = "inotify";
+#[cfg(target_os = "windows")]
+pub const BACKEND_NAME: &str = "ReadDirectoryChangesW";
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub const BACKEND_NAME: &str = "notify";
+
+/// How often the polling backend scans the repository by default. Scans
+/// only stat files, but on a network filesystem every stat is a round trip,
+/// so this trades latency for load on the file server.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// Directories the polling backend doesn't scan. They hold most of the files
+// in a repository, and tasks don't depend on changes inside of them.
+const POLL_EXCLUDED_DIRS: &[&str] = &["node_modules", ".git", ".turbo"];
+
+// A scan of a large repository over the network can take a while, so the
+// first cookie gets more time to show up when polling
+const COOKIE_TIMEOUT: Duration = Duration::from_millis(2000);
+const POLL_COOKIE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Where file events come from
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum WatcherBackend {
+    /// The native backend, unless the root is on a network filesystem, in
+    /// which case the polling backend
+    #[default]
+    Auto,
+    /// The platform API, see [`BACKEND_NAME`]
+    Native,
+    /// A running Watchman server. Falls back to the native backend if
+    /// Watchman isn't available.
+    Watchman,
+    /// Periodically scans the root for changes, skipping dependencies and
+    /// git and turbo's own state
+    Poll,
+}
+
+impl WatcherBackend {
+    // Picks a concrete backend for watching `root`
+    fn resolve(self, root: &AbsoluteSystemPath) -> Self {
+        if self != WatcherBackend::Auto {
+            return self;
+        }
+        match network_fs::network_filesystem(root) {
+            Some(fs_type) => {
+                info!("{root} is on a {fs_type} filesystem, polling for file changes");
+                WatcherBackend::Poll
+            }
+            None => WatcherBackend::Native,
+        }
+    }
+}
+
+// The watcher we ended up with. Holding onto it keeps events flowing.
+enum ActiveBackend {
+    Native(Backend),
+    Poll(PollWatcher),
+    #[cfg(unix)]
+    Watchman(tokio::task::JoinHandle<()>),
+}
+
 type EventResult = Result<Event, notify::Error>;
 
 #[derive(Debug, Error)]
@@ -113,6 +175,7 @@ pub struct FileSystemWatcher {
     // to be notified of a close.
     _exit_ch: tokio::sync::oneshot::Sender<()>,
     cookie_dir: AbsoluteSystemPathBuf,
+    backend_name: Arc<OnceLock<&'static str>>,
 }
 
 impl FileSystemWatcher {
@@ -120,14 +183,26 @@ impl FileSystemWatcher {
         // We already store logs in .turbo and recommend it be gitignore'd.
         // Watchman uses .git, but we can't guarantee that git is present _or_
         // that the turbo root is the same as the git root.
-        Self::new(root, root.join_components(&[".turbo", "cookies"]))
+        Self::new(root, default_cookie_dir(root))
     }
 
     pub fn new(
         root: &AbsoluteSystemPath,
         cookie_dir: AbsoluteSystemPathBuf,
     ) -> Result<Self, WatchError> {
-        tracing::debug!("initing file-system watcher");
+        Self::new_with_backend(root, cookie_dir, WatcherBackend::Native, POLL_INTERVAL)
+    }
+
+    /// Creates a watcher that gets its events from `backend`. The polling
+    /// backend scans for changes every `poll_interval`.
+    pub fn new_with_backend(
+        root: &AbsoluteSystemPath,
+        cookie_dir: AbsoluteSystemPathBuf,
+        backend: WatcherBackend,
+        poll_interval: Duration,
+    ) -> Result<Self, WatchError> {
+        let backend = backend.resolve(root);
+        tracing::debug!("initing file-system watcher with {:?} backend", backend);
 
         if root.relation_to_path(&cookie_dir) != PathRelation::Parent {
             return Err(WatchError::Setup(format!(
@@ -139,28 +214,43 @@ impl FileSystemWatcher {
         let (file_events_receiver_tx, file_events_receiver_lazy) = OptionalWatch::new();
         let (send_file_events, mut recv_file_events) = mpsc::channel(1024);
         let (exit_ch, exit_signal) = tokio::sync::oneshot::channel();
+        let backend_name = Arc::new(OnceLock::new());
 
         tokio::task::spawn({
             let cookie_dir = cookie_dir.clone();
             let watch_root = root.to_owned();
+            let backend_name = backend_name.clone();
             async move {
-                // this task never yields, so run it in the blocking threadpool
-                let watch_root_task = watch_root.clone();
-                let cookie_dir_task = cookie_dir.clone();
-                let task = tokio::task::spawn_blocking(move || {
-                    setup_cookie_dir(&cookie_dir_task)?;
-                    run_watcher(&watch_root_task, send_file_events)
-                });
-
-                let Ok(Ok(watcher)) = task.await else {
+                let Ok(watcher) = start_backend(
+                    backend,
+                    poll_interval,
+                    None,
+                    &watch_root,
+                    &cookie_dir,
+                    send_file_events,
+                )
+                .await
+                else {
                     // if the watcher fails, just return. we don't set the event sender, and other
                     // services will never start
                     return;
                 };
+                let _ = backend_name.set(match watcher {
+                    ActiveBackend::Native(_) => BACKEND_NAME,
+                    ActiveBackend::Poll(_) => "poll",
+                    #[cfg(unix)]
+                    ActiveBackend::Watchman(_) => "watchman",
+                });
 
                 // Ensure we are ready to receive new events, not events for existing state
                 debug!("waiting for initial filesystem cookie");
-                if let Err(e) = wait_for_cookie(&cookie_dir, &mut recv_file_events).await {
+                let cookie_timeout = match watcher {
+                    ActiveBackend::Poll(_) => POLL_COOKIE_TIMEOUT,
+                    _ => COOKIE_TIMEOUT,
+                };
+                if let Err(e) =
+                    wait_for_cookie(&cookie_dir, &mut recv_file_events, cookie_timeout).await
+                {
                     // if we can't get a cookie here, we should not make the file
                     // watching available to downstream services
                     warn!("failed to wait for initial filesystem cookie: {}", e);
@@ -178,7 +268,22 @@ impl FileSystemWatcher {
                     return;
                 }
 
-                watch_events(watcher, watch_root, recv_file_events, exit_signal, sender).await;
+                match watcher {
+                    ActiveBackend::Native(watcher) => {
+                        watch_events(watcher, watch_root, recv_file_events, exit_signal, sender)
+                            .await
+                    }
+                    ActiveBackend::Poll(watcher) => {
+                        watch_poll_events(watcher, recv_file_events, exit_signal, sender).await
+                    }
+                    // Watchman does the recursive watching for us, so events
+                    // only need forwarding
+                    #[cfg(unix)]
+                    ActiveBackend::Watchman(subscription) => {
+                        forward_events(recv_file_events, exit_signal, sender).await;
+                        subscription.abort();
+                    }
+                }
             }
         });
 
@@ -186,6 +291,7 @@ impl FileSystemWatcher {
             receiver: file_events_receiver_lazy,
             _exit_ch: exit_ch,
             cookie_dir,
+            backend_name,
         })
     }
 
@@ -205,6 +311,64 @@ impl FileSystemWatcher {
     pub fn cookie_dir(&self) -> &AbsoluteSystemPath {
         &self.cookie_dir
     }
+
+    /// The name of the backend delivering events, once file watching has
+    /// started
+    pub fn backend_name(&self) -> Option<&'static str> {
+        self.backend_name.get().copied()
+    }
+}
+
+/// The cookie directory used by
+/// [`FileSystemWatcher::new_with_default_cookie_dir`]
+pub fn default_cookie_dir(root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
+    root.join_components(&[".turbo", "cookies"])
+}
+
+// `watchman_sock` overrides where the watchman server is found
+async fn start_backend(
+    backend: WatcherBackend,
+    poll_interval: Duration,
+    #[cfg_attr(not(unix), allow(unused_variables))] watchman_sock: Option<&Path>,
+    root: &AbsoluteSystemPath,
+    cookie_dir: &AbsoluteSystemPath,
+    sender: mpsc::Sender<EventResult>,
+) -> Result<ActiveBackend, WatchError> {
+    let cookie_dir_task = cookie_dir.to_owned();
+    tokio::task::spawn_blocking(move || setup_cookie_dir(&cookie_dir_task))
+        .await
+        .map_err(|e| WatchError::Setup(e.to_string()))??;
+
+    if backend == WatcherBackend::Watchman {
+        #[cfg(unix)]
+        match watchman::Subscription::new(root, watchman_sock).await {
+            Ok(subscription) => {
+                debug!("using watchman for file watching");
+                return Ok(ActiveBackend::Watchman(tokio::spawn(
+                    subscription.run(sender),
+                )));
+            }
+            Err(e) => warn!("watchman unavailable, falling back to {BACKEND_NAME}: {e}"),
+        }
+        #[cfg(not(unix))]
+        warn!("watchman is not supported on this platform, falling back to {BACKEND_NAME}");
+    }
+
+    // this task never yields, so run it in the blocking threadpool
+    let root = root.to_owned();
+    if backend == WatcherBackend::Poll {
+        let cookie_dir = cookie_dir.to_owned();
+        return tokio::task::spawn_blocking(move || {
+            run_poll_watcher(&root, &cookie_dir, poll_interval, sender)
+        })
+        .await
+        .map_err(|e| WatchError::Setup(e.to_string()))?
+        .map(ActiveBackend::Poll);
+    }
+    tokio::task::spawn_blocking(move || run_watcher(&root, sender))
+        .await
+        .map_err(|e| WatchError::Setup(e.to_string()))?
+        .map(ActiveBackend::Native)
 }
 
 fn setup_cookie_dir(cookie_dir: &AbsoluteSystemPath) -> Result<(), WatchError> {
@@ -227,6 +391,14 @@ fn setup_cookie_dir(cookie_dir: &AbsoluteSystemPath) -> Result<(), WatchError> {
 async fn watch_events(
     _watcher: Backend,
     _watch_root: AbsoluteSystemPathBuf,
+    recv_file_events: mpsc::Receiver<EventResult>,
+    exit_signal: tokio::sync::oneshot::Receiver<()>,
+    broadcast_sender: broadcast::Sender<Result<Event, NotifyError>>,
+) {
+    forward_events(recv_file_events, exit_signal, broadcast_sender).await
+}
+
+async fn forward_events(
     mut recv_file_events: mpsc::Receiver<EventResult>,
     exit_signal: tokio::sync::oneshot::Receiver<()>,
     broadcast_sender: broadcast::Sender<Result<Event, NotifyError>>,
@@ -243,6 +415,38 @@ async fn watch_events(
     }
 }
 
+// Polling watches every directory on its own, so new directories need
+// watches added as they show up
+async fn watch_poll_events(
+    mut watcher: PollWatcher,
+    mut recv_file_events: mpsc::Receiver<EventResult>,
+    exit_signal: tokio::sync::oneshot::Receiver<()>,
+    broadcast_sender: broadcast::Sender<Result<Event, NotifyError>>,
+) {
+    let mut exit_signal = exit_signal;
+    'outer: loop {
+        tokio::select! {
+            _ = &mut exit_signal => break 'outer,
+            Some(event) = recv_file_events.recv().into_future() => {
+                let new_dirs = match &event {
+                    Ok(event) if matches!(event.kind, EventKind::Create(_)) => event
+                        .paths
+                        .iter()
+                        .filter(|path| path.is_dir())
+                        .cloned()
+                        .collect(),
+                    _ => Vec::new(),
+                };
+                // we don't care if we fail to send, it just means no one is currently watching
+                let _ = broadcast_sender.send(event.map_err(NotifyError::from));
+                for dir in new_dirs {
+                    add_poll_watches(&dir, &mut watcher, Some(&broadcast_sender));
+                }
+            }
+        }
+    }
+}
+
 #[cfg(any(feature = "watch_ancestors", feature = "manual_recursive_watch"))]
 async fn watch_events(
     #[cfg(feature = "manual_recursive_watch")] mut watcher: Backend,
@@ -423,6 +627,69 @@ fn run_watcher(
     Ok(watcher)
 }
 
+fn run_poll_watcher(
+    root: &AbsoluteSystemPath,
+    cookie_dir: &AbsoluteSystemPath,
+    poll_interval: Duration,
+    sender: mpsc::Sender<EventResult>,
+) -> Result<PollWatcher, WatchError> {
+    let mut watcher = PollWatcher::new(
+        move |res| {
+            let _ = sender.blocking_send(res);
+        },
+        notify::Config::default().with_poll_interval(poll_interval),
+    )?;
+    // A recursive watch can't skip the excluded directories, so every other
+    // directory is watched on its own. Polling notices the root going away,
+    // so there's no need to watch ancestors.
+    add_poll_watches(root.as_std_path(), &mut watcher, None);
+    // Cookies are written to .turbo, which is otherwise skipped
+    watcher.watch(cookie_dir.as_std_path(), RecursiveMode::NonRecursive)?;
+    Ok(watcher)
+}
+
+// Watches `dir` and the directories below it, skipping the excluded ones. If
+// `sender` is given, creation events are sent for what's already inside of
+// `dir`, as it may have been filled in before we started watching it.
+fn add_poll_watches(
+    dir: &Path,
+    watcher: &mut PollWatcher,
+    sender: Option<&broadcast::Sender<Result<Event, NotifyError>>>,
+) {
+    let entries = WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .filter_entry(|entry| {
+            !(entry.file_type().is_dir()
+                && entry
+                    .file_name()
+                    .to_str()
+                    .map_or(false, |name| POLL_EXCLUDED_DIRS.contains(&name)))
+        })
+        // Entries can disappear while we walk, those are skipped
+        .filter_map(Result::ok);
+    for entry in entries {
+        if entry.file_type().is_dir() {
+            // Polling a path that doesn't exist isn't an error, so this can't fail
+            let _ = watcher.watch(entry.path(), RecursiveMode::NonRecursive);
+        }
+        if let Some(sender) = sender.filter(|_| entry.depth() > 0) {
+            let create_kind = if entry.file_type().is_dir() {
+                CreateKind::Folder
+            } else {
+                CreateKind::File
+            };
+            let event = Event {
+                paths: vec![entry.path().to_owned()],
+                kind: EventKind::Create(create_kind),
+                attrs: EventAttributes::default(),
+            };
+            // It's ok if we fail to send, it means we're shutting down
+            let _ = sender.send(Ok(event));
+        }
+    }
+}
+
 #[cfg(not(target_os = "macos"))]
 fn make_watcher<F: EventHandler>(event_handler: F) -> Result<Backend, notify::Error> {
     RecommendedWatcher::new(event_handler, Config::default())
@@ -439,6 +706,7 @@ fn make_watcher<F: EventHandler>(event_handler: F) -> Result<Backend, notify::Er
 async fn wait_for_cookie(
     cookie_dir: &AbsoluteSystemPath,
     recv: &mut mpsc::Receiver<EventResult>,
+    timeout: Duration,
 ) -> Result<(), WatchError> {
     // TODO: should this be passed in? Currently the caller guarantees that the
     // directory is empty, but it could be the responsibility of the
@@ -448,7 +716,7 @@ async fn wait_for_cookie(
         WatchError::Setup(format!("failed to write cookie to {}: {}", cookie_path, e))
     })?;
     loop {
-        let event = tokio::time::timeout(Duration::from_millis(2000), recv.recv())
+        let event = tokio::time::timeout(timeout, recv.recv())
             .await
             .map_err(|e| WatchError::Setup(format!("waiting for cookie timed out: {}", e)))?
             .ok_or_else(|| {
@@ -481,9 +749,15 @@ mod test {
     use notify::event::RenameMode;
     use notify::{event::ModifyKind, Event, EventKind};
     use tokio::sync::broadcast;
+    #[cfg(unix)]
+    use tokio::sync::mpsc;
     use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 
-    use crate::{FileSystemWatcher, NotifyError};
+    use crate::{
+        default_cookie_dir, FileSystemWatcher, NotifyError, WatcherBackend, POLL_INTERVAL,
+    };
+    #[cfg(unix)]
+    use crate::{start_backend, ActiveBackend};
 
     fn temp_dir() -> (AbsoluteSystemPathBuf, tempfile::TempDir) {
         let tmp = tempfile::tempdir().unwrap();
@@ -525,6 +799,87 @@ mod test {
         }
     }
 
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_watchman_falls_back_to_native() {
+        let (repo_root, _tmp_repo_root) = temp_dir();
+        let repo_root = repo_root.to_realpath().unwrap();
+        // Point at a socket that doesn't exist so that watchman is never found
+        let missing_sock = repo_root.join_component("missing.sock");
+        let (sender, _receiver) = mpsc::channel(1);
+
+        let backend = start_backend(
+            WatcherBackend::Watchman,
+            POLL_INTERVAL,
+            Some(missing_sock.as_std_path()),
+            &repo_root,
+            &default_cookie_dir(&repo_root),
+            sender,
+        )
+        .await
+        .unwrap();
+        assert!(matches!(backend, ActiveBackend::Native(_)));
+    }
+
+    #[tokio::test]
+    async fn test_poll_backend() {
+        let (repo_root, _tmp_repo_root) = temp_dir();
+        let repo_root = repo_root.to_realpath().unwrap();
+        let dependency = repo_root.join_components(&["node_modules", "some-dep"]);
+        dependency.create_dir_all().unwrap();
+
+        let watcher = FileSystemWatcher::new_with_backend(
+            &repo_root,
+            default_cookie_dir(&repo_root),
+            WatcherBackend::Poll,
+            Duration::from_millis(50),
+        )
+        .unwrap();
+        let mut recv = watcher.subscribe().await.unwrap();
+        assert_eq!(watcher.backend_name(), Some("poll"));
+
+        // Excluded directories aren't scanned
+        dependency
+            .join_component("index.js")
+            .create_with_contents("hello")
+            .unwrap();
+        repo_root.join_component(".git").create_dir_all().unwrap();
+        let git_file = repo_root.join_components(&[".git", "HEAD"]);
+        git_file.create_with_contents("hello").unwrap();
+
+        // A directory created along with its contents, and a file added to it
+        // afterwards
+        let parent_path = repo_root.join_component("parent");
+        let child_path = parent_path.join_component("child");
+        child_path.create_dir_all().unwrap();
+        let foo_path = child_path.join_component("foo");
+        foo_path.create_with_contents("hello").unwrap();
+        // Events from excluded directories would show up by the time the
+        // second file is seen, a scan after the first
+        let bar_path = child_path.join_component("bar");
+        let mut excluded_events = Vec::new();
+        for expected_path in [&foo_path, &bar_path] {
+            'outer: loop {
+                let event = tokio::time::timeout(Duration::from_millis(3000), recv.recv())
+                    .await
+                    .expect("timed out waiting for filesystem event")
+                    .expect("sender was dropped")
+                    .expect("filewatching error");
+                for path in event.paths {
+                    if path.starts_with(&dependency) || path.starts_with(&git_file) {
+                        excluded_events.push(path);
+                    } else if path == expected_path.as_std_path() {
+                        break 'outer;
+                    }
+                }
+            }
+            if expected_path == &foo_path {
+                bar_path.create_with_contents("hello").unwrap();
+            }
+        }
+        assert!(excluded_events.is_empty(), "{excluded_events:?}");
+    }
+
     #[tokio::test]
     async fn test_file_watching() {
         // Directory layout:
@@ -0,0 +1,159 @@
+//! Detection of network and virtualized filesystems. Changes made on the other
+//! side of these mounts, like a host editing files shared into a dev
+//! container, never produce native file events, so the only way to see them is
+//! to poll.
+
+use std::path::Path;
+
+use turbopath::AbsoluteSystemPath;
+
+// Filesystem types, as reported in /proc/self/mountinfo, that don't deliver
+// inotify events for changes made elsewhere
+#[cfg(target_os = "linux")]
+const NETWORK_FILESYSTEMS: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    // WSL2 mounts of Windows drives and some VM shared folders
+    "9p",
+    "virtiofs",
+    // Docker Desktop bind mounts
+    "fakeowner",
+    "fuse.grpcfuse",
+    "fuse.sshfs",
+];
+
+// Filesystem types, as reported by statfs(2), that don't deliver FSEvents for
+// changes made elsewhere
+#[cfg(target_os = "macos")]
+const NETWORK_FILESYSTEMS: &[&str] = &["nfs", "smbfs", "afpfs", "webdav"];
+
+/// Returns the type of the filesystem `path` is on if it's one that native
+/// file watching can't be trusted on
+pub(crate) fn network_filesystem(path: &AbsoluteSystemPath) -> Option<String> {
+    let path = path
+        .as_std_path()
+        .canonicalize()
+        .unwrap_or_else(|_| path.as_std_path().to_owned());
+    let fs_type = filesystem_type(&path)?;
+    is_network_filesystem(&fs_type).then_some(fs_type)
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_network_filesystem(fs_type: &str) -> bool {
+    NETWORK_FILESYSTEMS.contains(&fs_type)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn is_network_filesystem(_fs_type: &str) -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    let mountinfo = std::fs::read_to_string("/proc/self/mountinfo").ok()?;
+    filesystem_from_mountinfo(&mountinfo, path).map(str::to_owned)
+}
+
+#[cfg(target_os = "macos")]
+fn filesystem_type(path: &Path) -> Option<String> {
+    use std::{ffi::CStr, os::unix::ffi::OsStrExt};
+
+    let c_path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    // SAFETY: c_path is a valid C string and stat is a valid statfs to write to
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // SAFETY: statfs NUL terminates f_fstypename
+    let fs_type = unsafe { CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+    Some(fs_type.to_string_lossy().into_owned())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn filesystem_type(_path: &Path) -> Option<String> {
+    None
+}
+
+/// Finds the filesystem type of the mount containing `path`, which is the
+/// mount with the longest mount point that is an ancestor of `path`. See
+/// proc(5) for the format.
+#[cfg(any(target_os = "linux", test))]
+fn filesystem_from_mountinfo<'a>(mountinfo: &'a str, path: &Path) -> Option<&'a str> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            let (mount, filesystem) = line.split_once(" - ")?;
+            let mount_point = unescape_mount_point(mount.split(' ').nth(4)?);
+            let fs_type = filesystem.split(' ').next()?;
+            Some((mount_point, fs_type))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        // Later mounts shadow earlier ones at the same mount point
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .map(|(_, fs_type)| fs_type)
+}
+
+// Spaces, tabs, newlines and backslashes in mount points are escaped as octal
+#[cfg(any(target_os = "linux", test))]
+fn unescape_mount_point(escaped: &str) -> std::path::PathBuf {
+    let mut unescaped = String::with_capacity(escaped.len());
+    let mut rest = escaped;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        let code = rest
+            .get(index + 1..index + 4)
+            .and_then(|octal| u8::from_str_radix(octal, 8).ok());
+        match code {
+            Some(code) => {
+                unescaped.push(code as char);
+                rest = &rest[index + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped.into()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use test_case::test_case;
+
+    use super::filesystem_from_mountinfo;
+
+    const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+25 22 0:22 / /proc rw,nosuid,nodev,noexec,relatime shared:12 - proc proc rw
+40 22 0:35 / /workspaces rw,relatime - virtiofs workspaces rw
+41 40 0:36 / /workspaces/repo/node_modules rw,relatime - ext4 /dev/sdb1 rw
+42 22 0:37 / /mnt/my\\040share rw,relatime - nfs4 server:/share rw
+43 22 0:38 / /workspaces rw,relatime - fakeowner workspaces rw
+";
+
+    #[test_case("/home/user/repo", Some("ext4") ; "root filesystem")]
+    #[test_case("/workspaces-other", Some("ext4") ; "mount point prefix isn't an ancestor")]
+    #[test_case("/workspaces/repo/node_modules/a", Some("ext4") ; "nested mount")]
+    #[test_case("/mnt/my share/repo", Some("nfs4") ; "escaped mount point")]
+    fn test_filesystem_from_mountinfo(path: &str, expected: Option<&str>) {
+        assert_eq!(
+            filesystem_from_mountinfo(MOUNTINFO, Path::new(path)),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_later_mount_shadows_earlier() {
+        assert_eq!(
+            filesystem_from_mountinfo(MOUNTINFO, Path::new("/workspaces/repo")),
+            Some("fakeowner")
+        );
+    }
+}
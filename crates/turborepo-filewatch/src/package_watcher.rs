@@ -156,6 +156,8 @@ enum PackageState {
     ValidWorkspaces {
         package_manager: PackageManager,
         filter: Box<WorkspaceGlobs>,
+        // Workspaces that declare workspaces of their own, with their globs
+        nested_filters: HashMap<AbsoluteSystemPathBuf, WorkspaceGlobs>,
         workspaces: HashMap<AbsoluteSystemPathBuf, WorkspaceData>,
     },
 }
@@ -400,14 +402,47 @@ impl Subscriber {
         // If we don't have a valid package manager and workspace globs, nothing to be
         // done here
         let PackageState::ValidWorkspaces {
+            package_manager,
             ref filter,
+            ref nested_filters,
             ref mut workspaces,
-            ..
         } = **package_state
         else {
             return;
         };
 
+        // Changes to a nested workspace root can add or remove any number of
+        // packages below it, so those are handled like changes to the root
+        let changes_nested_root = file_event
+            .paths
+            .iter()
+            .filter_map(|p| p.as_os_str().to_str())
+            .any(|path| {
+                let path = AbsoluteSystemPathBuf::new(path).expect("watched paths are absolute");
+                if nested_filters.contains_key(&path) {
+                    return true;
+                }
+                let Some(parent) = path.parent() else {
+                    return false;
+                };
+                INVALIDATION_PATHS.contains(&path.file_name().unwrap_or_default())
+                    && workspaces.contains_key(parent)
+                    && (nested_filters.contains_key(parent)
+                        || package_manager.get_workspace_globs(parent).is_ok())
+            });
+        if changes_nested_root {
+            self.bump_or_queue_rediscovery(state, package_state_tx);
+            return;
+        }
+        let is_workspace = |path: &AbsoluteSystemPath| {
+            filter
+                .target_is_workspace(&self.repo_root, path)
+                .unwrap_or(false)
+                || nested_filters.iter().any(|(root, globs)| {
+                    &**root != path && globs.target_is_workspace(root, path).unwrap_or(false)
+                })
+        };
+
         // here, we can only update if we have a valid package state
         let mut changed = false;
         // if a path is not a valid utf8 string, it is not a valid path, so ignore
@@ -423,20 +458,14 @@ impl Subscriber {
                     let path_parent = path_file
                         .parent()
                         .expect("watched paths will not be at the root");
-                    if filter
-                        .target_is_workspace(&self.repo_root, path_parent)
-                        .unwrap_or(false)
-                    {
+                    if is_workspace(path_parent) {
                         path_parent
                     } else {
                         // irrelevant package.json file update, it's not in a directory
                         // matching workspace globs
                         continue;
                     }
-                } else if filter
-                    .target_is_workspace(&self.repo_root, &path_file)
-                    .unwrap_or(false)
-                {
+                } else if is_workspace(&path_file) {
                     // The file event is for a workspace directory itself
                     &path_file
                 } else {
@@ -550,9 +579,20 @@ async fn discover_packages(repo_root: AbsoluteSystemPathBuf) -> PackageState {
         .into_iter()
         .map(|p| (p.package_json.parent().expect("non-root").to_owned(), p))
         .collect::<HashMap<_, _>>();
+    let nested_filters = workspaces
+        .keys()
+        .filter_map(|workspace| {
+            let globs = initial_discovery
+                .package_manager
+                .get_workspace_globs(workspace)
+                .ok()?;
+            Some((workspace.clone(), globs))
+        })
+        .collect();
     PackageState::ValidWorkspaces {
         package_manager: initial_discovery.package_manager,
         filter: Box::new(filter),
+        nested_filters,
         workspaces,
     }
 }
@@ -562,7 +602,10 @@ mod test {
     use std::time::Duration;
 
     use turbopath::AbsoluteSystemPathBuf;
-    use turborepo_repository::{discovery::WorkspaceData, package_manager::PackageManager};
+    use turborepo_repository::{
+        discovery::{DiscoveryResponse, WorkspaceData},
+        package_manager::PackageManager,
+    };
 
     use crate::{cookies::CookieWriter, package_watcher::PackageWatcher, FileSystemWatcher};
 
@@ -798,6 +841,109 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn subscriber_nested_workspaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        let write = |path: &[&str], contents: &str| {
+            let file = repo_root.join_components(path);
+            file.ensure_dir().unwrap();
+            file.create_with_contents(contents).unwrap();
+        };
+        let package_jsons = |data: DiscoveryResponse| {
+            let mut package_jsons = data
+                .workspaces
+                .into_iter()
+                .map(|workspace| {
+                    repo_root
+                        .anchor(workspace.package_json)
+                        .unwrap()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            package_jsons.sort();
+            package_jsons
+        };
+
+        write(&["package-lock.json"], "");
+        write(
+            &["package.json"],
+            r#"{"workspaces":["packages/*"], "packageManager": "npm@10.0.0"}"#,
+        );
+        write(&["packages", "foo", "package.json"], r#"{"name": "foo"}"#);
+        write(
+            &["packages", "acquired", "package.json"],
+            r#"{"name": "acquired", "workspaces": ["apps/*"]}"#,
+        );
+        write(
+            &["packages", "acquired", "apps", "b", "package.json"],
+            r#"{"name": "b"}"#,
+        );
+
+        let watcher = FileSystemWatcher::new_with_default_cookie_dir(&repo_root).unwrap();
+        let recv = watcher.watch();
+        let cookie_writer = CookieWriter::new(
+            watcher.cookie_dir(),
+            Duration::from_millis(100),
+            recv.clone(),
+        );
+        let package_watcher = PackageWatcher::new(repo_root.clone(), recv, cookie_writer).unwrap();
+
+        let data = package_watcher.discover_packages_blocking().await.unwrap();
+        assert_eq!(
+            package_jsons(data),
+            [
+                "packages/acquired/apps/b/package.json",
+                "packages/acquired/package.json",
+                "packages/foo/package.json",
+            ]
+        );
+
+        // a package added to the nested workspace
+        write(
+            &["packages", "acquired", "apps", "c", "package.json"],
+            r#"{"name": "c"}"#,
+        );
+        let data = package_watcher.discover_packages_blocking().await.unwrap();
+        assert_eq!(
+            package_jsons(data),
+            [
+                "packages/acquired/apps/b/package.json",
+                "packages/acquired/apps/c/package.json",
+                "packages/acquired/package.json",
+                "packages/foo/package.json",
+            ]
+        );
+
+        // the nested workspace no longer declares workspaces, and another
+        // package starts to
+        write(
+            &["packages", "acquired", "package.json"],
+            r#"{"name": "acquired"}"#,
+        );
+        write(
+            &["packages", "foo", "libs", "d", "package.json"],
+            r#"{"name": "d"}"#,
+        );
+        write(
+            &["packages", "foo", "package.json"],
+            r#"{"name": "foo", "workspaces": ["libs/*"]}"#,
+        );
+        let data = package_watcher.discover_packages_blocking().await.unwrap();
+        assert_eq!(
+            package_jsons(data),
+            [
+                "packages/acquired/package.json",
+                "packages/foo/libs/d/package.json",
+                "packages/foo/package.json",
+            ]
+        );
+    }
+
     #[tokio::test]
     #[tracing_test::traced_test]
     async fn pnpm_invalid_states_test() {
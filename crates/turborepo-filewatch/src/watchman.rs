@@ -0,0 +1,440 @@
+//! File events from a running Watchman server. Large repositories often run
+//! Watchman already, and subscribing to it avoids the OS watch limits that the
+//! native watcher runs into on them.
+//!
+//! We speak Watchman's JSON protocol directly: every request and response is a
+//! single line of JSON, and subscription updates arrive as unilateral PDUs
+//! interleaved with responses.
+
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use notify::{
+    event::{CreateKind, Flag, ModifyKind, RemoveKind},
+    Event, EventKind,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{
+        unix::{OwnedReadHalf, OwnedWriteHalf},
+        UnixStream,
+    },
+    sync::mpsc,
+};
+use tracing::{debug, warn};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use crate::EventResult;
+
+const SUBSCRIPTION_NAME: &str = "turbo";
+// How long we wait on watchman before giving up on it
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum WatchmanError {
+    #[error("failed to find the watchman socket: {0}")]
+    Sockname(String),
+    #[error("failed to talk to watchman: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid response from watchman: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+    #[error("watchman responded with an error: {0}")]
+    Server(String),
+    #[error("watchman closed the connection")]
+    Closed,
+    #[error("timed out waiting for watchman")]
+    Timeout,
+}
+
+/// A connection to the watchman server
+struct Client {
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
+    line: String,
+}
+
+impl Client {
+    async fn connect(sockname: &Path) -> Result<Self, WatchmanError> {
+        let stream = UnixStream::connect(sockname).await?;
+        let (reader, writer) = stream.into_split();
+        Ok(Self {
+            reader: BufReader::new(reader),
+            writer,
+            line: String::new(),
+        })
+    }
+
+    /// Sends a command and waits for its response, skipping any unilateral
+    /// PDUs that arrive in the meantime
+    async fn command(&mut self, command: Value) -> Result<Value, WatchmanError> {
+        let mut request = serde_json::to_vec(&command)?;
+        request.push(b'\n');
+        self.writer.write_all(&request).await?;
+
+        loop {
+            let pdu = tokio::time::timeout(RESPONSE_TIMEOUT, self.read_pdu())
+                .await
+                .map_err(|_| WatchmanError::Timeout)??;
+            if is_unilateral(&pdu) {
+                continue;
+            }
+            return match pdu.get("error").and_then(Value::as_str) {
+                Some(error) => Err(WatchmanError::Server(error.to_string())),
+                None => Ok(pdu),
+            };
+        }
+    }
+
+    async fn read_pdu(&mut self) -> Result<Value, WatchmanError> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line).await? == 0 {
+            return Err(WatchmanError::Closed);
+        }
+        Ok(serde_json::from_str(&self.line)?)
+    }
+}
+
+fn is_unilateral(pdu: &Value) -> bool {
+    pdu.get("unilateral").is_some() || pdu.get("subscription").is_some()
+}
+
+#[derive(Debug, Deserialize)]
+struct WatchProjectResponse {
+    watch: PathBuf,
+    relative_path: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubscribeResponse {
+    clock: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct SubscriptionPdu {
+    #[serde(default)]
+    files: Vec<FileChange>,
+    clock: Option<String>,
+    #[serde(default)]
+    is_fresh_instance: bool,
+    #[serde(default)]
+    canceled: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileChange {
+    name: String,
+    exists: bool,
+    #[serde(default)]
+    new: bool,
+}
+
+/// A subscription to the changes under a root
+pub struct Subscription {
+    client: Client,
+    root: AbsoluteSystemPathBuf,
+    // The last clock we've seen, used to resume without missing events
+    // when we have to reconnect
+    clock: Option<String>,
+    // Watchman reports a fresh instance for the very first update of every
+    // subscription, which doesn't indicate that we missed anything
+    seen_first_update: bool,
+}
+
+impl Subscription {
+    /// Connects to the watchman server and subscribes to changes under
+    /// `root`. The server is found through `WATCHMAN_SOCK` or the `watchman`
+    /// binary unless `sockname` is given.
+    pub async fn new(
+        root: &AbsoluteSystemPath,
+        sockname: Option<&Path>,
+    ) -> Result<Self, WatchmanError> {
+        let sockname = match sockname {
+            Some(sockname) => sockname.to_owned(),
+            None => self::sockname().await?,
+        };
+        let client = Client::connect(&sockname).await?;
+        let mut subscription = Self {
+            client,
+            root: root.to_owned(),
+            clock: None,
+            seen_first_update: false,
+        };
+        subscription.subscribe().await?;
+        Ok(subscription)
+    }
+
+    async fn subscribe(&mut self) -> Result<(), WatchmanError> {
+        let watch: WatchProjectResponse = serde_json::from_value(
+            self.client
+                .command(json!(["watch-project", self.root.as_str()]))
+                .await?,
+        )?;
+
+        let mut query = json!({
+            "fields": ["name", "exists", "new"],
+            "empty_on_fresh_instance": true,
+        });
+        if let Some(relative_path) = watch.relative_path {
+            query["relative_root"] = relative_path.into();
+        }
+        if let Some(clock) = &self.clock {
+            query["since"] = clock.as_str().into();
+        }
+        let response: SubscribeResponse = serde_json::from_value(
+            self.client
+                .command(json!(["subscribe", watch.watch, SUBSCRIPTION_NAME, query]))
+                .await?,
+        )?;
+        if let Some(clock) = response.clock {
+            self.clock = Some(clock);
+        }
+        debug!("subscribed to watchman changes under {}", self.root);
+        Ok(())
+    }
+
+    /// Forwards file events to `sender` until the subscription ends or
+    /// nobody is listening anymore
+    pub async fn run(mut self, sender: mpsc::Sender<EventResult>) {
+        loop {
+            let pdu = match self.client.read_pdu().await {
+                Ok(pdu) => pdu,
+                Err(err) => {
+                    warn!("lost connection to watchman: {err}, reconnecting");
+                    if let Err(err) = self.reconnect().await {
+                        let _ = sender
+                            .send(Err(notify::Error::generic(&format!(
+                                "watchman unavailable: {err}"
+                            ))))
+                            .await;
+                        return;
+                    }
+                    continue;
+                }
+            };
+            if pdu.get("subscription").and_then(Value::as_str) != Some(SUBSCRIPTION_NAME) {
+                continue;
+            }
+            let pdu: SubscriptionPdu = match serde_json::from_value(pdu) {
+                Ok(pdu) => pdu,
+                Err(err) => {
+                    warn!("ignoring invalid watchman update: {err}");
+                    continue;
+                }
+            };
+            if pdu.canceled {
+                let _ = sender
+                    .send(Err(notify::Error::generic(
+                        "watchman canceled the subscription",
+                    )))
+                    .await;
+                return;
+            }
+            if pdu.clock.is_some() {
+                self.clock.clone_from(&pdu.clock);
+            }
+            let events = to_events(&self.root, pdu, !self.seen_first_update);
+            self.seen_first_update = true;
+            for event in events {
+                if sender.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn reconnect(&mut self) -> Result<(), WatchmanError> {
+        self.client = Client::connect(&sockname().await?).await?;
+        self.subscribe().await
+    }
+}
+
+/// Converts a subscription update into file events. A fresh instance after
+/// the first update means watchman lost track of changes, so downstream
+/// consumers are told to rescan.
+fn to_events(root: &AbsoluteSystemPath, pdu: SubscriptionPdu, first_update: bool) -> Vec<Event> {
+    if pdu.is_fresh_instance && !first_update {
+        return vec![Event::new(EventKind::Any)
+            .add_path(root.as_std_path().to_owned())
+            .set_flag(Flag::Rescan)];
+    }
+    pdu.files
+        .into_iter()
+        .map(|file| {
+            let kind = if !file.exists {
+                EventKind::Remove(RemoveKind::Any)
+            } else if file.new {
+                EventKind::Create(CreateKind::Any)
+            } else {
+                EventKind::Modify(ModifyKind::Any)
+            };
+            Event::new(kind).add_path(root.as_std_path().join(file.name))
+        })
+        .collect()
+}
+
+async fn sockname() -> Result<PathBuf, WatchmanError> {
+    if let Some(sock) = std::env::var_os("WATCHMAN_SOCK") {
+        return Ok(sock.into());
+    }
+
+    #[derive(Deserialize)]
+    struct GetSocknameResponse {
+        sockname: PathBuf,
+    }
+
+    let output = tokio::time::timeout(
+        RESPONSE_TIMEOUT,
+        tokio::process::Command::new("watchman")
+            .args(["--output-encoding=json", "--no-pretty", "get-sockname"])
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| WatchmanError::Timeout)?
+    .map_err(|err| WatchmanError::Sockname(err.to_string()))?;
+    if !output.status.success() {
+        return Err(WatchmanError::Sockname(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let response: GetSocknameResponse = serde_json::from_slice(&output.stdout)?;
+    Ok(response.sockname)
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::net::UnixListener;
+
+    use super::*;
+
+    fn update(value: Value) -> SubscriptionPdu {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_to_events() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { "C:\\repo" } else { "/repo" }).unwrap();
+        let events = to_events(
+            &root,
+            update(json!({
+                "files": [
+                    { "name": "a.js", "exists": true, "new": true },
+                    { "name": "lib/b.js", "exists": true, "new": false },
+                    { "name": "c.js", "exists": false, "new": false },
+                ],
+            })),
+            false,
+        );
+        let kinds = events
+            .iter()
+            .map(|event| (event.kind, event.paths[0].clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (
+                    EventKind::Create(CreateKind::Any),
+                    root.as_std_path().join("a.js")
+                ),
+                (
+                    EventKind::Modify(ModifyKind::Any),
+                    root.as_std_path().join("lib/b.js")
+                ),
+                (
+                    EventKind::Remove(RemoveKind::Any),
+                    root.as_std_path().join("c.js")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fresh_instance_requests_rescan() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { "C:\\repo" } else { "/repo" }).unwrap();
+        let fresh = || update(json!({ "is_fresh_instance": true, "files": [] }));
+
+        assert!(to_events(&root, fresh(), true).is_empty());
+        let events = to_events(&root, fresh(), false);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].flag(), Some(Flag::Rescan));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_against_server() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let sockname = tmp.path().join("watchman.sock");
+        let listener = UnixListener::bind(&sockname).unwrap();
+
+        let server = tokio::spawn({
+            let root = root.clone();
+            async move {
+                let (stream, _) = listener.accept().await.unwrap();
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+
+                let watch_project: Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                assert_eq!(watch_project, json!(["watch-project", root.as_str()]));
+                writer
+                    .write_all(format!("{}\n", json!({ "watch": root.as_str() })).as_bytes())
+                    .await
+                    .unwrap();
+
+                let subscribe: Value =
+                    serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+                assert_eq!(subscribe[0], "subscribe");
+                assert_eq!(subscribe[2], SUBSCRIPTION_NAME);
+                let responses = [
+                    json!({ "subscribe": SUBSCRIPTION_NAME, "clock": "c:1" }),
+                    json!({ "unilateral": true, "log": "ignored" }),
+                    json!({
+                        "subscription": SUBSCRIPTION_NAME,
+                        "clock": "c:2",
+                        "is_fresh_instance": true,
+                        "files": [],
+                    }),
+                    json!({
+                        "subscription": SUBSCRIPTION_NAME,
+                        "clock": "c:3",
+                        "files": [{ "name": "a.js", "exists": true, "new": true }],
+                    }),
+                    json!({ "subscription": SUBSCRIPTION_NAME, "canceled": true }),
+                ];
+                for response in responses {
+                    writer
+                        .write_all(format!("{response}\n").as_bytes())
+                        .await
+                        .unwrap();
+                }
+            }
+        });
+
+        let client = Client::connect(&sockname).await.unwrap();
+        let mut subscription = Subscription {
+            client,
+            root: root.clone(),
+            clock: None,
+            seen_first_update: false,
+        };
+        subscription.subscribe().await.unwrap();
+        assert_eq!(subscription.clock.as_deref(), Some("c:1"));
+
+        let (tx, mut rx) = mpsc::channel(16);
+        subscription.run(tx).await;
+        server.await.unwrap();
+
+        let event = rx.recv().await.unwrap().unwrap();
+        assert_eq!(event.kind, EventKind::Create(CreateKind::Any));
+        assert_eq!(event.paths, vec![root.as_std_path().join("a.js")]);
+        assert!(rx.recv().await.unwrap().is_err());
+        assert!(rx.recv().await.is_none());
+    }
+}
@@ -1,6 +1,22 @@
 #![feature(assert_matches)]
 #![deny(clippy::all)]
 
+//! Glob matching for task inputs, outputs, and anything else `turbo` selects
+//! files with. [`globwalk`] finds the files on disk and [`GlobMatcher`]
+//! checks paths that are already known, and both follow the same rules:
+//!
+//! - Globs are relative to a base directory and use `/` as the separator. `.`
+//!   and `..` segments are collapsed before matching.
+//! - A glob that names a directory selects everything inside of it, so `dist`
+//!   is the same as `dist/**`.
+//! - Wildcards match dotfiles, `*` doesn't need a leading `.` to match `.env`.
+//!   Dotfiles that aren't wanted have to be excluded.
+//! - Exclusions always win over inclusions, the order they're given in doesn't
+//!   matter. An exclusion also excludes everything below what it matches.
+//! - Matching follows the platform's filesystem, it's case sensitive on Unix
+//!   and case insensitive on Windows. `(?i)` makes a glob case insensitive
+//!   everywhere.
+
 use std::{
     borrow::Cow,
     collections::HashSet,
@@ -19,6 +35,10 @@ use tracing::debug;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathError, RelativeUnixPath};
 use wax::{walk::FileIterator, BuildError, Glob};
 
+mod matcher;
+
+pub use matcher::GlobMatcher;
+
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum WalkType {
     Files,
@@ -46,6 +66,8 @@ pub enum WalkError {
     InternalError { glob: String, error: String },
     #[error("IO Error: {0}")]
     IO(#[from] std::io::Error),
+    #[error(transparent)]
+    Glob(#[from] GlobError),
 }
 
 fn join_unix_like_paths(a: &str, b: &str) -> String {
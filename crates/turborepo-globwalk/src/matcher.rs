@@ -0,0 +1,133 @@
+use std::str::FromStr;
+
+use wax::{Glob, Program};
+
+use crate::{
+    add_trailing_double_star, collapse_path, fix_glob_pattern, glob_literals,
+    glob_with_contextual_error, ValidatedGlob, WalkError,
+};
+
+/// Matches relative unix paths against globs the same way [`globwalk`]
+/// selects files, for callers that already have a list of paths instead of
+/// a directory to walk.
+///
+/// Since there's no filesystem to check, a literal path, such as `dist`,
+/// matches both itself and everything below it. `globwalk` only does the
+/// latter when the path is a directory, which is the only case where there
+/// is anything below it.
+///
+/// [`globwalk`]: crate::globwalk
+#[derive(Debug, Clone)]
+pub struct GlobMatcher {
+    include: Vec<Glob<'static>>,
+    exclude: Vec<Glob<'static>>,
+}
+
+impl GlobMatcher {
+    pub fn new(include: &[ValidatedGlob], exclude: &[ValidatedGlob]) -> Result<Self, WalkError> {
+        let include = include
+            .iter()
+            .flat_map(|glob| include_patterns(glob.as_str()))
+            .map(glob_with_contextual_error)
+            .collect::<Result<_, _>>()?;
+        let exclude = exclude
+            .iter()
+            .flat_map(|glob| exclude_patterns(glob.as_str()))
+            .map(glob_with_contextual_error)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { include, exclude })
+    }
+
+    /// Builds a matcher from globs written the way `inputs` and `outputs` are
+    /// in `turbo.json`, where a leading `!` marks an exclusion
+    pub fn from_patterns<S: AsRef<str>>(patterns: &[S]) -> Result<Self, WalkError> {
+        let mut include = Vec::new();
+        let mut exclude = Vec::new();
+        for pattern in patterns {
+            match pattern.as_ref().strip_prefix('!') {
+                Some(exclusion) => exclude.push(ValidatedGlob::from_str(exclusion)?),
+                None => include.push(ValidatedGlob::from_str(pattern.as_ref())?),
+            }
+        }
+        Self::new(&include, &exclude)
+    }
+
+    pub fn has_inclusions(&self) -> bool {
+        !self.include.is_empty()
+    }
+
+    pub fn is_included(&self, path: &str) -> bool {
+        self.include.iter().any(|glob| glob.is_match(path))
+    }
+
+    pub fn is_excluded(&self, path: &str) -> bool {
+        self.exclude.iter().any(|glob| glob.is_match(path))
+    }
+
+    /// Whether `path` is selected, exclusions win over inclusions regardless
+    /// of the order they were given in
+    pub fn is_match(&self, path: &str) -> bool {
+        self.is_included(path) && !self.is_excluded(path)
+    }
+}
+
+fn include_patterns(glob: &str) -> Vec<String> {
+    let glob = fix_glob_pattern(glob);
+    let Some((glob, _)) = collapse_path(&glob) else {
+        // The glob points outside of the directory it's relative to
+        return Vec::new();
+    };
+    if glob_literals().is_match(&glob) {
+        vec![glob.into_owned()]
+    } else {
+        let contents = format!("{}/**", glob.trim_end_matches('/'));
+        vec![glob.into_owned(), contents]
+    }
+}
+
+fn exclude_patterns(glob: &str) -> Vec<String> {
+    let glob = fix_glob_pattern(glob);
+    let mut patterns = Vec::new();
+    if let Some((glob, _)) = collapse_path(&glob) {
+        add_trailing_double_star(&mut patterns, &glob);
+    }
+    patterns
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::GlobMatcher;
+
+    #[test_case(&["src/**"], "src/index.ts", true ; "doublestar")]
+    #[test_case(&["src/*.ts"], "src/nested/index.ts", false ; "star stays in its segment")]
+    #[test_case(&["src"], "src/nested/index.ts", true ; "literal directory")]
+    #[test_case(&["src/"], "src/index.ts", true ; "literal directory with trailing slash")]
+    #[test_case(&["./src/../lib/*.ts"], "lib/index.ts", true ; "dot segments are collapsed")]
+    #[test_case(&["../other/**"], "other/index.ts", false ; "outside of the directory")]
+    #[test_case(&["**"], ".env", true ; "wildcards match dotfiles")]
+    #[test_case(&["src/*"], "src/.eslintrc", true ; "star matches dotfiles")]
+    #[test_case(&["**", "!dist"], "dist/index.js", false ; "literal exclusion excludes contents")]
+    #[test_case(&["**", "!dist/"], "dist/index.js", false ; "exclusion with trailing slash")]
+    #[test_case(&["**", "!**/*.test.ts"], "src/a.test.ts", false ; "glob exclusion")]
+    #[test_case(&["!src/a.ts", "src/**"], "src/a.ts", false ; "exclusions win regardless of order")]
+    #[test_case(&["!dist"], "src/index.ts", false ; "only exclusions select nothing")]
+    fn test_is_match(patterns: &[&str], path: &str, expected: bool) {
+        let matcher = GlobMatcher::from_patterns(patterns).unwrap();
+        assert_eq!(matcher.is_match(path), expected);
+    }
+
+    #[test]
+    fn test_case_sensitivity() {
+        let matcher = GlobMatcher::from_patterns(&["README.md"]).unwrap();
+        assert_eq!(matcher.is_match("readme.md"), cfg!(windows));
+        let matcher = GlobMatcher::from_patterns(&["(?i)README.md"]).unwrap();
+        assert!(matcher.is_match("readme.md"));
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        assert!(GlobMatcher::from_patterns(&["src/[a"]).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! An interface for running tasks from other Rust programs without spawning
+//! the `turbo` binary.
+//!
+//! ```no_run
+//! # async fn build() -> Result<(), turborepo_lib::api::Error> {
+//! use turbopath::AbsoluteSystemPathBuf;
+//! use turborepo_lib::api::{RunBuilder, TaskEvent};
+//!
+//! let (events, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+//! tokio::spawn(async move {
+//!     while let Some(event) = receiver.recv().await {
+//!         if let TaskEvent::Finished { task_id, status, .. } = event {
+//!             println!("{task_id}: {status:?}");
+//!         }
+//!     }
+//! });
+//!
+//! let repo_root = AbsoluteSystemPathBuf::new("/path/to/repo").unwrap();
+//! let result = RunBuilder::new(repo_root)
+//!     .with_tasks(["build"])
+//!     .with_filter("web...")
+//!     .with_args(["--concurrency=2"])
+//!     .with_task_events(events)
+//!     .run()
+//!     .await?;
+//!
+//! for task in result.failed_tasks() {
+//!     eprintln!("{} failed with {:?}", task.task_id, task.exit_code);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{future, time::Duration};
+
+use clap::Parser;
+use itertools::Itertools;
+use miette::Diagnostic;
+use thiserror::Error;
+use tokio::sync::mpsc;
+use turbopath::AbsoluteSystemPathBuf;
+use turborepo_repository::inference::{RepoMode, RepoState};
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::ColorConfig;
+
+use crate::{
+    cli::{Args, Command},
+    commands::{run::get_signal, CommandBase},
+    get_version, run,
+    signal::SignalHandler,
+    turbo_json::UIMode,
+};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("invalid run arguments: {0}")]
+    Args(#[from] clap::Error),
+    #[error("`--{0}` is not supported when running through the API")]
+    UnsupportedArg(&'static str),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Run(#[from] Box<run::Error>),
+}
+
+impl From<run::Error> for Error {
+    fn from(err: run::Error) -> Self {
+        Error::Run(Box::new(err))
+    }
+}
+
+/// Configures and executes a run of tasks in a repository, the same way
+/// `turbo run` would.
+///
+/// Task output is written to stdout and stderr as it is with `--ui=stream`.
+pub struct RunBuilder {
+    repo_root: AbsoluteSystemPathBuf,
+    tasks: Vec<String>,
+    filters: Vec<String>,
+    args: Vec<String>,
+    color_config: ColorConfig,
+    task_events: Option<mpsc::UnboundedSender<TaskEvent>>,
+}
+
+impl RunBuilder {
+    pub fn new(repo_root: AbsoluteSystemPathBuf) -> Self {
+        Self {
+            repo_root,
+            tasks: Vec::new(),
+            filters: Vec::new(),
+            args: Vec::new(),
+            color_config: ColorConfig::new(true),
+            task_events: None,
+        }
+    }
+
+    /// Adds tasks to run
+    pub fn with_tasks(mut self, tasks: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.tasks.extend(tasks.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds a package filter, as with `--filter`
+    pub fn with_filter(mut self, filter: impl Into<String>) -> Self {
+        self.filters.push(filter.into());
+        self
+    }
+
+    /// Adds any other `turbo run` flags, e.g. `--concurrency=2` or
+    /// `--summarize`. Arguments after `--` are passed to the tasks. Dry runs
+    /// aren't printed, the tasks they would run are in the result instead.
+    pub fn with_args(mut self, args: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets whether output is colored. Colors are off by default.
+    pub fn with_color_config(mut self, color_config: ColorConfig) -> Self {
+        self.color_config = color_config;
+        self
+    }
+
+    /// Sends an event as each task starts and finishes
+    pub fn with_task_events(mut self, task_events: mpsc::UnboundedSender<TaskEvent>) -> Self {
+        self.task_events = Some(task_events);
+        self
+    }
+
+    fn args(&self) -> Result<Args, Error> {
+        let mut args = Args::try_parse_from(
+            ["turbo", "run"]
+                .into_iter()
+                .map(String::from)
+                .chain(self.tasks.iter().cloned())
+                .chain(
+                    self.filters
+                        .iter()
+                        .map(|filter| format!("--filter={filter}")),
+                )
+                .chain(self.args.iter().cloned()),
+        )?;
+        let Some(Command::Run {
+            run_args,
+            execution_args,
+        }) = &mut args.command
+        else {
+            unreachable!("arguments always start with the run command")
+        };
+        // These take over the terminal, which isn't ours to take
+        if run_args.interactive {
+            return Err(Error::UnsupportedArg("interactive"));
+        }
+        match args.ui {
+            Some(UIMode::Tui) => return Err(Error::UnsupportedArg("ui=tui")),
+            Some(UIMode::Web) => return Err(Error::UnsupportedArg("ui=web")),
+            _ => args.ui = Some(UIMode::Stream),
+        }
+        execution_args.single_package = RepoState::infer(&self.repo_root)
+            .is_ok_and(|repo_state| matches!(repo_state.mode, RepoMode::SinglePackage));
+        args.cwd = Some(self.repo_root.as_path().to_owned());
+        Ok(args)
+    }
+
+    /// Runs the tasks, returning once they have all finished or the process
+    /// receives a SIGINT or SIGTERM
+    pub async fn run(self) -> Result<RunResult, Error> {
+        let signal = get_signal()?;
+        self.execute(SignalHandler::new(signal)).await
+    }
+
+    /// Computes the hashes of the tasks that would run, as `--dry` would,
+    /// without running them. Nothing is printed.
+    pub async fn dry_run(mut self) -> Result<RunResult, Error> {
+        self.args.push("--dry=json".to_string());
+        // Nothing gets spawned, so there's nothing to stop on a signal
+        self.execute(SignalHandler::new(future::pending())).await
+    }
+
+    /// Returns the names of the packages matched by the filters, resolved the
+    /// same way `turbo run` resolves them
+    pub async fn resolve_filters(self) -> Result<Vec<String>, Error> {
+        let base = self.command_base()?;
+        let handler = SignalHandler::new(future::pending());
+        let run = run::builder::RunBuilder::new(base)?
+            .hide_prelude()
+            .build(&handler, CommandEventBuilder::new("run"))
+            .await?;
+        handler.close().await;
+        Ok(run
+            .filtered_pkgs()
+            .iter()
+            .map(ToString::to_string)
+            .sorted()
+            .collect())
+    }
+
+    fn command_base(&self) -> Result<CommandBase, Error> {
+        Ok(CommandBase::new(
+            self.args()?,
+            self.repo_root.clone(),
+            get_version(),
+            self.color_config,
+        ))
+    }
+
+    async fn execute(self, handler: SignalHandler) -> Result<RunResult, Error> {
+        let mut base = self.command_base()?;
+        base.authenticate_with_oidc()
+            .await
+            .map_err(run::Error::from)?;
+        let telemetry = CommandEventBuilder::new("run");
+
+        let (run_events, mut receiver) = mpsc::unbounded_channel();
+        let task_events = self.task_events;
+        let forwarder = tokio::spawn(async move {
+            let mut result = None;
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    RunEvent::Task(event) => {
+                        if let Some(task_events) = &task_events {
+                            // The receiver going away only means nobody is listening
+                            let _ = task_events.send(event);
+                        }
+                    }
+                    RunEvent::Finished(run_result) => result = Some(run_result),
+                }
+            }
+            result
+        });
+
+        let run_fut = async {
+            let run = run::builder::RunBuilder::new(base)?
+                .with_run_events(run_events)
+                .build(&handler, telemetry)
+                .await?;
+            run.run(None, false).await
+        };
+
+        let exit_code = tokio::select! {
+            biased;
+            _ = handler.done() => 1,
+            exit_code = run_fut => {
+                handler.close().await;
+                exit_code?
+            }
+        };
+
+        // The run and all of its event senders have been dropped, so this finishes
+        // once the last events have been forwarded
+        let result = forwarder.await.ok().flatten();
+        Ok(match result {
+            Some(result) => RunResult {
+                exit_code,
+                ..result
+            },
+            // Runs that don't execute tasks, like `--graph`, don't produce a summary
+            None => RunResult {
+                exit_code,
+                tasks: Vec::new(),
+                duration: Duration::ZERO,
+                summary: None,
+            },
+        })
+    }
+}
+
+/// The outcome of a run
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct RunResult {
+    /// The exit code `turbo run` would have exited with
+    pub exit_code: i32,
+    pub tasks: Vec<TaskResult>,
+    pub duration: Duration,
+    /// The summary `--dry=json` would have printed, only set for dry runs
+    pub(crate) summary: Option<serde_json::Value>,
+}
+
+impl RunResult {
+    pub fn failed_tasks(&self) -> impl Iterator<Item = &TaskResult> {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == TaskStatus::Failed)
+    }
+}
+
+/// The outcome of a single task in a run
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct TaskResult {
+    /// The task's ID, e.g. `web#build`
+    pub task_id: String,
+    pub package: String,
+    pub task: String,
+    pub hash: String,
+    pub status: TaskStatus,
+    /// Not present if the task didn't run or failed to start
+    pub exit_code: Option<i32>,
+    /// Not present if the task didn't run
+    pub duration: Option<Duration>,
+    pub log_file: Option<AbsoluteSystemPathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskStatus {
+    Succeeded,
+    Cached,
+    Failed,
+    /// The task wasn't run, because of a dry run or an earlier failure
+    NotRun,
+}
+
+/// Sent as tasks start and finish during a run
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskEvent {
+    Started {
+        task_id: String,
+    },
+    Finished {
+        task_id: String,
+        status: TaskStatus,
+        exit_code: Option<i32>,
+        duration: Duration,
+    },
+}
+
+/// Events sent from a run back to an API caller
+#[derive(Debug)]
+pub(crate) enum RunEvent {
+    Task(TaskEvent),
+    Finished(RunResult),
+}
+
+pub(crate) type RunEventSender = mpsc::UnboundedSender<RunEvent>;
+
+#[cfg(test)]
+mod test {
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::*;
+
+    fn repo_root() -> AbsoluteSystemPathBuf {
+        AbsoluteSystemPathBuf::new(if cfg!(windows) { "C:\\repo" } else { "/repo" }).unwrap()
+    }
+
+    #[test]
+    fn test_args() {
+        let args = RunBuilder::new(repo_root())
+            .with_tasks(["build", "test"])
+            .with_filter("web...")
+            .with_args(["--concurrency=2", "--", "--watch=false"])
+            .args()
+            .unwrap();
+
+        let execution_args = args.execution_args().unwrap();
+        assert_eq!(execution_args.tasks, ["build", "test"]);
+        assert_eq!(execution_args.filter, ["web..."]);
+        assert_eq!(execution_args.concurrency.as_deref(), Some("2"));
+        assert_eq!(execution_args.pass_through_args, ["--watch=false"]);
+        assert_eq!(args.cwd.as_deref(), Some(repo_root().as_path()));
+    }
+
+    #[test]
+    fn test_unsupported_args() {
+        let result = RunBuilder::new(repo_root())
+            .with_tasks(["build"])
+            .with_args(["--ui=tui"])
+            .args();
+        assert!(matches!(result, Err(Error::UnsupportedArg("ui=tui"))));
+
+        let result = RunBuilder::new(repo_root())
+            .with_args(["--not-a-flag"])
+            .args();
+        assert!(matches!(result, Err(Error::Args(_))));
+    }
+}
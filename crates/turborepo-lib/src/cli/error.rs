@@ -8,7 +8,10 @@ use turborepo_telemetry::events::command::CommandEventBuilder;
 use turborepo_ui::{color, BOLD, GREY};
 
 use crate::{
-    commands::{bin, generate, ls, prune, run::get_signal, CommandBase},
+    commands::{
+        bin, cache, check_globs, completion, explain, generate, graph, ls, pick, prune,
+        run::get_signal, CommandBase,
+    },
     daemon::DaemonError,
     query,
     rewrite_json::RewriteError,
@@ -27,6 +30,18 @@ pub enum Error {
     Path(#[from] turbopath::PathError),
     #[error(transparent)]
     #[diagnostic(transparent)]
+    Cache(#[from] cache::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    CheckGlobs(#[from] check_globs::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Explain(#[from] explain::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Completion(#[from] completion::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
     Config(#[from] crate::config::Error),
     #[error(transparent)]
     ChromeTracing(#[from] crate::tracing::Error),
@@ -43,9 +58,15 @@ pub enum Error {
     Generate(#[from] generate::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
+    Graph(#[from] graph::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
     Ls(#[from] ls::Error),
     #[error(transparent)]
     #[diagnostic(transparent)]
+    Pick(#[from] pick::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
     Prune(#[from] prune::Error),
     #[error(transparent)]
     PackageJson(#[from] turborepo_repository::package_json::Error),
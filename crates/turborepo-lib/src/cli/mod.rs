@@ -6,8 +6,9 @@ use clap::{
     builder::NonEmptyStringValueParser, ArgAction, ArgGroup, CommandFactory, Parser, Subcommand,
     ValueEnum,
 };
-use clap_complete::{generate, Shell};
+use clap_complete::Shell;
 pub use error::Error;
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error, log::warn};
 use turbopath::AbsoluteSystemPathBuf;
@@ -22,8 +23,8 @@ use turborepo_ui::{ColorConfig, GREY};
 use crate::{
     cli::error::print_potential_tasks,
     commands::{
-        bin, config, daemon, generate, link, login, logout, ls, prune, query, run, scan, telemetry,
-        unlink, CommandBase,
+        bin, cache, check_globs, completion, config, daemon, explain, generate, graph, link, login,
+        logout, ls, pick, prefetch, prune, query, run, scan, telemetry, unlink, CommandBase,
     },
     get_version,
     run::watch::WatchClient,
@@ -87,7 +88,7 @@ impl From<OutputLogsMode> for turborepo_ui::tui::event::OutputLogs {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Serialize, ValueEnum, Deserialize, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, ValueEnum, Deserialize, Eq, Deserializable)]
 pub enum LogOrder {
     #[serde(rename = "auto")]
     Auto,
@@ -121,6 +122,25 @@ impl LogOrder {
     }
 }
 
+/// How a run should proceed once a task has failed
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ContinueMode {
+    /// Stop scheduling new tasks after the first failure
+    #[default]
+    Never,
+    /// Keep running all remaining tasks regardless of failures
+    Always,
+}
+
+impl Display for ContinueMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ContinueMode::Never => "never",
+            ContinueMode::Always => "always",
+        })
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
 pub enum DryRunMode {
     Text,
@@ -136,6 +156,23 @@ impl Display for DryRunMode {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub enum RunOutputFormat {
+    /// A JUnit XML report with a test case for each task
+    Junit,
+    /// An HTML report with a timeline, cache breakdown and logs of the run
+    Html,
+}
+
+impl Display for RunOutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RunOutputFormat::Junit => "junit",
+            RunOutputFormat::Html => "html",
+        })
+    }
+}
+
 #[derive(
     Copy, Clone, Debug, Default, PartialEq, Serialize, ValueEnum, Deserialize, Eq, Deserializable,
 )]
@@ -228,6 +265,10 @@ pub struct Args {
     /// root of the repository.
     #[clap(long, global = true)]
     pub root_turbo_json: Option<Utf8PathBuf>,
+    /// Merge the `turbo.<NAME>.json` overlay next to the root `turbo.json`
+    /// over it.
+    #[clap(long, global = true, value_name = "NAME")]
+    pub config_overlay: Option<String>,
     #[clap(flatten, next_help_heading = "Run Arguments")]
     // DO NOT MAKE THIS VISIBLE
     // This is explicitly set to None in `run`
@@ -269,7 +310,7 @@ impl From<Verbosity> for u8 {
     }
 }
 
-#[derive(Subcommand, Copy, Clone, Debug, PartialEq)]
+#[derive(Subcommand, Clone, Debug, PartialEq)]
 pub enum DaemonCommand {
     /// Restarts the turbo daemon
     Restart,
@@ -292,6 +333,16 @@ pub enum DaemonCommand {
     },
     /// Shows the daemon logs
     Logs,
+    /// Reports the packages whose inputs changed since the point identified
+    /// by a hash from a previous call
+    Changed {
+        /// The hash reported by a previous call. Without it, every package is
+        /// reported as changed
+        since: Option<String>,
+        /// Pass --json to report the changed packages in JSON format
+        #[clap(long)]
+        json: bool,
+    },
 }
 
 #[derive(Copy, Clone, Debug, Default, ValueEnum, Serialize, Eq, PartialEq)]
@@ -313,6 +364,25 @@ impl fmt::Display for OutputFormat {
     }
 }
 
+#[derive(Copy, Clone, Debug, Default, ValueEnum, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// Output in JSON format for direct parsing
+    #[default]
+    Json,
+    /// Output in Graphviz DOT format
+    Dot,
+}
+
+impl fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GraphFormat::Json => "json",
+            GraphFormat::Dot => "dot",
+        })
+    }
+}
+
 #[derive(Subcommand, Copy, Clone, Debug, PartialEq)]
 pub enum TelemetryCommand {
     /// Enables anonymous telemetry
@@ -323,6 +393,44 @@ pub enum TelemetryCommand {
     Status,
 }
 
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum CacheCommand {
+    /// Evict artifacts from the local cache using the configured `cacheMaxSize`
+    /// and `cacheMaxAge` limits. Removes every artifact if no limits are set.
+    Clean {
+        /// Evict the least recently used artifacts until the cache is at most
+        /// this size, e.g. `10GB`
+        #[clap(long)]
+        max_size: Option<String>,
+        /// Evict artifacts that haven't been written or restored within this
+        /// duration, e.g. `7d`
+        #[clap(long)]
+        max_age: Option<String>,
+    },
+    /// List the artifacts in the local cache, newest first
+    Ls {
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Show the task, package, size, and creation time of an artifact
+    Info {
+        /// The hash of the artifact
+        hash: String,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Unpack an artifact into a directory for inspection
+    Extract {
+        /// The hash of the artifact
+        hash: String,
+        /// The directory to unpack into. Files are placed at their paths
+        /// relative to the repository root.
+        dir: Utf8PathBuf,
+    },
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
 pub enum LinkTarget {
     RemoteCache,
@@ -482,10 +590,36 @@ impl Args {
 pub enum Command {
     /// Get the path to the Turbo binary
     Bin,
+    /// Manage the local cache
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommand,
+    },
+    /// Print the files that globs select, matched the same way as task
+    /// `inputs` and `outputs`
+    CheckGlobs {
+        /// Globs to match, relative to the directory. Globs starting with `!`
+        /// are exclusions
+        #[clap(required = true)]
+        patterns: Vec<String>,
+        /// The directory to match in (default: current directory)
+        #[clap(long)]
+        dir: Option<Utf8PathBuf>,
+        /// Match the way `outputs` are, which selects directories as well as
+        /// files
+        #[clap(long)]
+        outputs: bool,
+    },
     /// Generate the autocompletion script for the specified shell
     Completion {
         shell: Shell,
     },
+    /// Lists package or task names for completion scripts
+    #[clap(name = "_complete", hide = true)]
+    Complete {
+        #[clap(value_enum)]
+        kind: completion::CompletionKind,
+    },
     /// Runs the Turborepo background daemon
     Daemon {
         /// Set the idle timeout for turbod
@@ -494,6 +628,20 @@ pub enum Command {
         #[clap(subcommand)]
         command: Option<DaemonCommand>,
     },
+    /// Show what went into a task's hash, or what changed since a previous
+    /// run
+    Explain {
+        /// The task to explain, as `<package>#<task>`. Tasks without a
+        /// package are root tasks
+        task: String,
+        /// Compare with a run summary written by `--summarize`, given as a run
+        /// ID or a path. Without a value, the latest run with the task is used
+        #[clap(long, num_args = 0..=1, default_missing_value = explain::LATEST_RUN, value_name = "RUN")]
+        diff: Option<String>,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
     /// Generate a new app / package
     #[clap(aliases = ["g", "gen"])]
     Generate {
@@ -544,6 +692,25 @@ pub enum Command {
         #[clap(long, value_enum)]
         output: Option<OutputFormat>,
     },
+    /// Output the package graph of your monorepo, and optionally the task
+    /// graph for the given tasks, without running anything
+    Graph {
+        /// Tasks to include in the output as a task graph
+        tasks: Vec<String>,
+        /// Show only packages that are affected by changes between
+        /// the current branch and `main`
+        #[clap(long, group = "scope-filter-group")]
+        affected: bool,
+        /// Use the given selector to specify package(s) to act as
+        /// entry points. The syntax mirrors pnpm's syntax, and
+        /// additional documentation and examples can be found in
+        /// turbo's documentation https://turbo.build/repo/docs/reference/command-line-reference/run#--filter
+        #[clap(short = 'F', long, group = "scope-filter-group")]
+        filter: Vec<String>,
+        /// Output format
+        #[clap(long, value_enum, default_value_t = GraphFormat::Json)]
+        format: GraphFormat,
+    },
     /// Link your local directory to a Vercel organization and enable remote
     /// caching.
     Link {
@@ -570,6 +737,9 @@ pub enum Command {
         #[clap(long)]
         invalidate: bool,
     },
+    /// Download the remote cache artifacts for the selected tasks into the
+    /// local cache without running them
+    Prefetch(Box<ExecutionArgs>),
     /// Prepare a subset of your monorepo.
     Prune {
         #[clap(hide = true, long)]
@@ -585,6 +755,10 @@ pub enum Command {
         docker: bool,
         #[clap(long = "out-dir", default_value_t = String::from(prune::DEFAULT_OUTPUT_DIR), value_parser)]
         output_dir: String,
+        /// Write the pruned monorepo to a reproducible .tar.gz file instead of
+        /// a directory
+        #[clap(long, value_name = "FILE", conflicts_with = "output_dir")]
+        tar: Option<Utf8PathBuf>,
     },
 
     /// Run tasks across projects in your monorepo
@@ -718,9 +892,17 @@ pub struct ExecutionArgs {
     #[clap(long)]
     pub concurrency: Option<String>,
     /// Continue execution even if a task exits with an error or non-zero
-    /// exit code. The default behavior is to bail
-    #[clap(long = "continue")]
-    pub continue_execution: bool,
+    /// exit code. The default behavior is to bail. Passing the flag without
+    /// a value is the same as `--continue=always`
+    #[clap(
+        long = "continue",
+        value_name = "CONTINUE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_value_t = ContinueMode::Never,
+        default_missing_value = "always"
+    )]
+    pub continue_execution: ContinueMode,
     /// Run turbo in single-package mode
     #[clap(long)]
     pub single_package: bool,
@@ -747,9 +929,18 @@ pub struct ExecutionArgs {
     pub filter: Vec<String>,
 
     /// Run only tasks that are affected by changes between
-    /// the current branch and `main`
-    #[clap(long, group = "scope-filter-group", conflicts_with = "filter")]
-    pub affected: bool,
+    /// the current branch and `main`. An explicit git range can be
+    /// provided as `--affected=<base>...<head>`, where either side
+    /// may be omitted to fall back to `TURBO_SCM_BASE`/`TURBO_SCM_HEAD`.
+    #[clap(
+        long,
+        group = "scope-filter-group",
+        conflicts_with = "filter",
+        value_name = "BASE...HEAD",
+        num_args = 0..=1,
+        require_equals = true
+    )]
+    pub affected: Option<Option<String>>,
 
     /// Set type of process output logging. Use "full" to show
     /// all output. Use "hash-only" to show only turbo-computed
@@ -780,8 +971,13 @@ pub struct ExecutionArgs {
     /// logs, so removing prefixes can make it difficult to associate logs
     /// with tasks. Use --log-order=grouped to prevent interleaving. (default
     /// auto)
-    #[clap(long, value_enum, default_value_t = LogPrefix::Auto)]
-    pub log_prefix: LogPrefix,
+    #[clap(long, value_enum)]
+    pub log_prefix: Option<LogPrefix>,
+    /// Use "ndjson" to write each line of output from turbo and from tasks as
+    /// a JSON object with a timestamp, package, task, stream and sequence
+    /// number, for log aggregation pipelines. (default text)
+    #[clap(long, value_enum)]
+    pub log_format: Option<LogFormat>,
     // NOTE: The following two are hidden because clap displays them in the help text incorrectly:
     // > Usage: turbo [OPTIONS] [TASKS]... [-- <FORWARDED_ARGS>...] [COMMAND]
     #[clap(hide = true)]
@@ -800,7 +996,8 @@ impl ExecutionArgs {
         // default to false
         track_usage!(telemetry, self.framework_inference, |val: bool| !val);
 
-        track_usage!(telemetry, self.continue_execution, |val| val);
+        track_usage!(telemetry, self.continue_execution, |val| val
+            == ContinueMode::Always);
         track_usage!(telemetry, self.single_package, |val| val);
         track_usage!(telemetry, self.only, |val| val);
         track_usage!(telemetry, self.remote_only().unwrap_or_default(), |val| val);
@@ -832,8 +1029,12 @@ impl ExecutionArgs {
             telemetry.track_arg_value("log-order", log_order, EventType::NonSensitive);
         }
 
-        if self.log_prefix != LogPrefix::default() {
-            telemetry.track_arg_value("log-prefix", self.log_prefix, EventType::NonSensitive);
+        if let Some(log_prefix) = self.log_prefix {
+            telemetry.track_arg_value("log-prefix", log_prefix, EventType::NonSensitive);
+        }
+
+        if let Some(log_format) = self.log_format {
+            telemetry.track_arg_value("log-format", log_format, EventType::NonSensitive);
         }
 
         // track sizes
@@ -856,7 +1057,8 @@ pub struct RunArgs {
     /// Generate a graph of the task execution and output to a file when a
     /// filename is specified (.svg, .png, .jpg, .pdf, .json,
     /// .html, .mermaid, .dot). Outputs dot graph to stdout when if no filename
-    /// is provided
+    /// is provided. An .html graph is written after the tasks run and shows
+    /// how each of them ran
     #[clap(long, num_args = 0..=1, default_missing_value = "", value_parser = validate_graph_extension)]
     pub graph: Option<String>,
 
@@ -890,9 +1092,20 @@ pub struct RunArgs {
     /// Treat remote cache as read only
     #[clap(long, default_missing_value = "true")]
     pub remote_cache_read_only: Option<Option<bool>>,
+    /// Treat remote cache as write only
+    #[clap(
+        long,
+        default_missing_value = "true",
+        conflicts_with = "remote_cache_read_only"
+    )]
+    pub remote_cache_write_only: Option<Option<bool>>,
     /// Generate a summary of the turbo run
     #[clap(long, default_missing_value = "true")]
     pub summarize: Option<Option<bool>>,
+    /// Write reports of how each task ran to `.turbo/`. Separate multiple
+    /// formats with commas
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub output_format: Vec<RunOutputFormat>,
 
     // Pass a string to enable posting Run Summaries to Vercel
     #[clap(long, hide = true)]
@@ -901,6 +1114,10 @@ pub struct RunArgs {
     /// Execute all tasks in parallel.
     #[clap(long)]
     pub parallel: bool,
+
+    /// Pick the task and package to run from a searchable list
+    #[clap(long)]
+    pub interactive: bool,
 }
 
 impl Default for RunArgs {
@@ -915,9 +1132,12 @@ impl Default for RunArgs {
             profile: None,
             anon_profile: None,
             remote_cache_read_only: None,
+            remote_cache_write_only: None,
             summarize: None,
+            output_format: Vec::new(),
             experimental_space_id: None,
             parallel: false,
+            interactive: false,
         }
     }
 }
@@ -949,6 +1169,11 @@ impl RunArgs {
         Some(remote_cache_read_only.unwrap_or(true))
     }
 
+    pub fn remote_cache_write_only(&self) -> Option<bool> {
+        let remote_cache_write_only = self.remote_cache_write_only?;
+        Some(remote_cache_write_only.unwrap_or(true))
+    }
+
     pub fn summarize(&self) -> Option<bool> {
         let summarize = self.summarize?;
         Some(summarize.unwrap_or(true))
@@ -960,11 +1185,17 @@ impl RunArgs {
         track_usage!(telemetry, self.daemon, |val| val);
         track_usage!(telemetry, self.no_daemon, |val| val);
         track_usage!(telemetry, self.parallel, |val| val);
+        track_usage!(telemetry, self.interactive, |val| val);
         track_usage!(
             telemetry,
             self.remote_cache_read_only().unwrap_or_default(),
             |val| val
         );
+        track_usage!(
+            telemetry,
+            self.remote_cache_write_only().unwrap_or_default(),
+            |val| val
+        );
 
         // default to None
         track_usage!(telemetry, &self.profile, Option::is_some);
@@ -977,6 +1208,10 @@ impl RunArgs {
             telemetry.track_arg_value("dry-run", dry_run, EventType::NonSensitive);
         }
 
+        for output_format in &self.output_format {
+            telemetry.track_arg_value("output-format", output_format, EventType::NonSensitive);
+        }
+
         if self.cache_workers != DEFAULT_NUM_WORKERS {
             telemetry.track_arg_value("cache-workers", self.cache_workers, EventType::NonSensitive);
         }
@@ -989,7 +1224,7 @@ impl RunArgs {
     }
 }
 
-#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Serialize)]
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Deserializable)]
 pub enum LogPrefix {
     #[serde(rename = "auto")]
     Auto,
@@ -1015,6 +1250,24 @@ impl Display for LogPrefix {
     }
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LogFormat {
+    #[default]
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "ndjson")]
+    Ndjson,
+}
+
+impl Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => write!(f, "text"),
+            LogFormat::Ndjson => write!(f, "ndjson"),
+        }
+    }
+}
+
 /// Runs the CLI by parsing arguments with clap, then either calling Rust code
 /// directly or returning a payload for the Go code to use.
 ///
@@ -1084,7 +1337,7 @@ pub async fn run(
             .clone()
             .ok_or_else(|| Error::NoCommand(Backtrace::capture()))?;
 
-        if execution_args.tasks.is_empty() {
+        if execution_args.tasks.is_empty() && !run_args.interactive {
             let mut cmd = <Args as CommandFactory>::command();
             let _ = cmd.print_help();
             process::exit(1);
@@ -1170,6 +1423,44 @@ pub async fn run(
 
             Ok(0)
         }
+        Command::CheckGlobs {
+            patterns,
+            dir,
+            outputs,
+        } => {
+            CommandEventBuilder::new("check-globs")
+                .with_parent(&root_telemetry)
+                .track_call();
+            check_globs::run(dir.as_deref(), patterns, *outputs)?;
+
+            Ok(0)
+        }
+        Command::Explain { task, diff, output } => {
+            CommandEventBuilder::new("explain")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config);
+            explain::run(&base, task, diff.as_deref(), *output).await?;
+
+            Ok(0)
+        }
+        Command::Cache { command } => {
+            CommandEventBuilder::new("cache")
+                .with_parent(&root_telemetry)
+                .track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config);
+
+            match command {
+                CacheCommand::Clean { max_size, max_age } => {
+                    cache::clean(&base, max_size.as_deref(), max_age.as_deref())?
+                }
+                CacheCommand::Ls { output } => cache::ls(&base, *output)?,
+                CacheCommand::Info { hash, output } => cache::info(&base, hash, *output)?,
+                CacheCommand::Extract { hash, dir } => cache::extract(&base, hash, dir)?,
+            }
+
+            Ok(0)
+        }
         #[allow(unused_variables)]
         Command::Daemon { command, idle_time } => {
             CommandEventBuilder::new("daemon")
@@ -1246,6 +1537,24 @@ pub async fn run(
 
             Ok(0)
         }
+        Command::Graph {
+            tasks,
+            affected,
+            filter,
+            format,
+        } => {
+            let event = CommandEventBuilder::new("graph").with_parent(&root_telemetry);
+            event.track_call();
+            let tasks = tasks.clone();
+            let affected = *affected;
+            let filter = filter.clone();
+            let format = *format;
+            let base = CommandBase::new(cli_args, repo_root, version, color_config);
+
+            graph::run(base, tasks, filter, affected, format, event).await?;
+
+            Ok(0)
+        }
         Command::Link {
             no_gitignore,
             target,
@@ -1325,9 +1634,20 @@ pub async fn run(
             let event = CommandEventBuilder::new("run").with_parent(&root_telemetry);
             event.track_call();
 
-            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config);
+            let mut cli_args = cli_args.clone();
+            if run_args.interactive {
+                let base =
+                    CommandBase::new(cli_args.clone(), repo_root.clone(), version, color_config);
+                let selection = pick::pick(base, event.clone()).await?;
+                if let Some(Command::Run { execution_args, .. }) = &mut cli_args.command {
+                    execution_args.tasks = vec![selection.task];
+                    execution_args.filter.extend(selection.package);
+                }
+            }
 
-            if execution_args.tasks.is_empty() {
+            let base = CommandBase::new(cli_args, repo_root, version, color_config);
+
+            if execution_args.tasks.is_empty() && !run_args.interactive {
                 print_potential_tasks(base, event).await?;
                 return Ok(1);
             }
@@ -1368,31 +1688,62 @@ pub async fn run(
             // We only exit if we get a signal, so we return a non-zero exit code
             return Ok(1);
         }
+        Command::Prefetch(_) => {
+            let event = CommandEventBuilder::new("prefetch").with_parent(&root_telemetry);
+            event.track_call();
+            let base = CommandBase::new(cli_args, repo_root, version, color_config);
+
+            Ok(prefetch::run(base, event).await?)
+        }
         Command::Prune {
             scope,
             scope_arg,
             docker,
             output_dir,
+            tar,
         } => {
             let event = CommandEventBuilder::new("prune").with_parent(&root_telemetry);
             event.track_call();
+            // A workspace listed more than once is only pruned once
             let scope = scope_arg
                 .as_ref()
                 .or(scope.as_ref())
+                .into_iter()
+                .flatten()
+                .unique()
                 .cloned()
-                .unwrap_or_default();
+                .collect::<Vec<_>>();
             let docker = *docker;
             let output_dir = output_dir.clone();
+            let tar = tar.clone();
             let base = CommandBase::new(cli_args, repo_root, version, color_config);
             let event_child = event.child();
-            prune::prune(&base, &scope, docker, &output_dir, event_child).await?;
+            prune::prune(
+                &base,
+                &scope,
+                docker,
+                &output_dir,
+                tar.as_deref(),
+                event_child,
+            )
+            .await?;
             Ok(0)
         }
         Command::Completion { shell } => {
             CommandEventBuilder::new("completion")
                 .with_parent(&root_telemetry)
                 .track_call();
-            generate(*shell, &mut Args::command(), "turbo", &mut io::stdout());
+            completion::generate(*shell, &mut io::stdout())?;
+            Ok(0)
+        }
+        Command::Complete { kind } => {
+            let event = CommandEventBuilder::new("complete").with_parent(&root_telemetry);
+            event.track_call();
+            let kind = *kind;
+            let base = CommandBase::new(cli_args, repo_root, version, color_config);
+
+            completion::candidates(base, kind, event).await?;
+
             Ok(0)
         }
     };
@@ -1415,7 +1766,7 @@ mod test {
     use itertools::Itertools;
     use pretty_assertions::assert_eq;
 
-    use crate::cli::{ExecutionArgs, RunArgs};
+    use crate::cli::{ExecutionArgs, GraphFormat, OutputFormat, RunArgs};
 
     struct CommandTestCase {
         command: &'static str,
@@ -1472,7 +1823,9 @@ mod test {
         }
     }
 
-    use crate::cli::{Args, Command, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode};
+    use crate::cli::{
+        Args, Command, ContinueMode, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode,
+    };
 
     #[test_case::test_case(
         &["turbo", "run", "build"],
@@ -1661,7 +2014,7 @@ mod test {
             command: Some(Command::Run {
                 execution_args: Box::new(ExecutionArgs {
                     tasks: vec!["build".to_string()],
-                    continue_execution: true,
+                    continue_execution: ContinueMode::Always,
                     ..get_default_execution_args()
                 }),
                 run_args: Box::new(get_default_run_args())
@@ -1670,6 +2023,51 @@ mod test {
         } ;
         "continue flag"
 	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--continue=always"],
+        Args {
+            command: Some(Command::Run {
+                execution_args: Box::new(ExecutionArgs {
+                    tasks: vec!["build".to_string()],
+                    continue_execution: ContinueMode::Always,
+                    ..get_default_execution_args()
+                }),
+                run_args: Box::new(get_default_run_args())
+            }),
+            ..Args::default()
+        } ;
+        "continue always"
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "--continue", "build"],
+        Args {
+            command: Some(Command::Run {
+                execution_args: Box::new(ExecutionArgs {
+                    tasks: vec!["build".to_string()],
+                    continue_execution: ContinueMode::Always,
+                    ..get_default_execution_args()
+                }),
+                run_args: Box::new(get_default_run_args())
+            }),
+            ..Args::default()
+        } ;
+        "continue flag before task"
+	)]
+    #[test_case::test_case(
+		&["turbo", "run", "build", "--continue=never"],
+        Args {
+            command: Some(Command::Run {
+                execution_args: Box::new(ExecutionArgs {
+                    tasks: vec!["build".to_string()],
+                    continue_execution: ContinueMode::Never,
+                    ..get_default_execution_args()
+                }),
+                run_args: Box::new(get_default_run_args())
+            }),
+            ..Args::default()
+        } ;
+        "continue never"
+	)]
     #[test_case::test_case(
 		&["turbo", "run", "build", "--dry-run"],
         Args {
@@ -1975,7 +2373,7 @@ mod test {
             command: Some(Command::Run {
                 execution_args: Box::new(ExecutionArgs {
                     tasks: vec!["build".to_string()],
-                    log_prefix: LogPrefix::Auto,
+                    log_prefix: Some(LogPrefix::Auto),
                     ..get_default_execution_args()
                 }),
                 run_args: Box::new(get_default_run_args())
@@ -1990,7 +2388,7 @@ mod test {
             command: Some(Command::Run {
                 execution_args: Box::new(ExecutionArgs {
                     tasks: vec!["build".to_string()],
-                    log_prefix: LogPrefix::None,
+                    log_prefix: Some(LogPrefix::None),
                     ..get_default_execution_args()
                 }),
                 run_args: Box::new(get_default_run_args())
@@ -2005,7 +2403,7 @@ mod test {
             command: Some(Command::Run {
                 execution_args: Box::new(ExecutionArgs {
                      tasks: vec!["build".to_string()],
-                     log_prefix: LogPrefix::Task,
+                     log_prefix: Some(LogPrefix::Task),
                      ..get_default_execution_args()
                 }),
                 run_args: Box::new(get_default_run_args())
@@ -2187,6 +2585,33 @@ mod test {
         assert_eq!(Args::try_parse_from(args).unwrap(), expected);
     }
 
+    #[test_case::test_case(
+        &["turbo", "prefetch", "build"],
+        Args {
+            command: Some(Command::Prefetch(Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                ..get_default_execution_args()
+            }))),
+            ..Args::default()
+        };
+        "default prefetch"
+    )]
+    #[test_case::test_case(
+        &["turbo", "prefetch", "build", "--filter", "web..."],
+        Args {
+            command: Some(Command::Prefetch(Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                filter: vec!["web...".to_string()],
+                ..get_default_execution_args()
+            }))),
+            ..Args::default()
+        };
+        "with filter"
+    )]
+    fn test_parse_prefetch(args: &[&str], expected: Args) {
+        assert_eq!(Args::try_parse_from(args).unwrap(), expected);
+    }
+
     #[test_case::test_case(
         &["turbo", "run", "build", "--daemon", "--no-daemon"],
         "cannot be used with '--no-daemon'" ;
@@ -2331,6 +2756,43 @@ mod test {
         .test();
     }
 
+    #[test]
+    fn test_parse_explain() {
+        let explain = |diff: Option<&str>, output| Args {
+            command: Some(Command::Explain {
+                task: "web#build".to_string(),
+                diff: diff.map(str::to_string),
+                output,
+            }),
+            ..Args::default()
+        };
+
+        assert_eq!(
+            Args::try_parse_from(["turbo", "explain", "web#build"]).unwrap(),
+            explain(None, None)
+        );
+        assert_eq!(
+            Args::try_parse_from(["turbo", "explain", "web#build", "--diff"]).unwrap(),
+            explain(Some("latest"), None)
+        );
+        assert_eq!(
+            Args::try_parse_from([
+                "turbo",
+                "explain",
+                "--diff",
+                "2fdA8yvPZYDNLZpJkfDPMkCXpZf",
+                "web#build",
+                "--output",
+                "json"
+            ])
+            .unwrap(),
+            explain(
+                Some("2fdA8yvPZYDNLZpJkfDPMkCXpZf"),
+                Some(OutputFormat::Json)
+            )
+        );
+    }
+
     #[test]
     fn test_parse_prune() {
         let default_prune = Command::Prune {
@@ -2338,6 +2800,7 @@ mod test {
             scope_arg: Some(vec!["foo".into()]),
             docker: false,
             output_dir: "out".to_string(),
+            tar: None,
         };
 
         assert_eq!(
@@ -2368,6 +2831,7 @@ mod test {
                     scope_arg: None,
                     docker: false,
                     output_dir: "out".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             }
@@ -2381,6 +2845,7 @@ mod test {
                     scope_arg: Some(vec!["foo".to_string(), "bar".to_string()]),
                     docker: false,
                     output_dir: "out".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             }
@@ -2394,11 +2859,36 @@ mod test {
                     scope_arg: Some(vec!["foo".into()]),
                     docker: true,
                     output_dir: "out".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             }
         );
 
+        assert_eq!(
+            Args::try_parse_from(["turbo", "prune", "--tar", "out.tar.gz", "foo"]).unwrap(),
+            Args {
+                command: Some(Command::Prune {
+                    scope: None,
+                    scope_arg: Some(vec!["foo".into()]),
+                    docker: false,
+                    output_dir: "out".to_string(),
+                    tar: Some(Utf8PathBuf::from("out.tar.gz")),
+                }),
+                ..Args::default()
+            }
+        );
+        assert!(Args::try_parse_from([
+            "turbo",
+            "prune",
+            "--tar",
+            "out.tar.gz",
+            "--out-dir",
+            "dist",
+            "foo"
+        ])
+        .is_err());
+
         assert_eq!(
             Args::try_parse_from(["turbo", "prune", "--out-dir", "dist", "foo"]).unwrap(),
             Args {
@@ -2407,6 +2897,7 @@ mod test {
                     scope_arg: Some(vec!["foo".into()]),
                     docker: false,
                     output_dir: "dist".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             }
@@ -2422,6 +2913,7 @@ mod test {
                     scope_arg: Some(vec!["foo".into()]),
                     docker: true,
                     output_dir: "dist".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             },
@@ -2438,6 +2930,7 @@ mod test {
                     scope_arg: Some(vec!["foo".into()]),
                     docker: true,
                     output_dir: "dist".to_string(),
+                    tar: None,
                 }),
                 cwd: Some(Utf8PathBuf::from("../examples/with-yarn")),
                 ..Args::default()
@@ -2459,6 +2952,7 @@ mod test {
                     scope_arg: None,
                     docker: true,
                     output_dir: "dist".to_string(),
+                    tar: None,
                 }),
                 ..Args::default()
             },
@@ -2652,6 +3146,59 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_affected_range() {
+        let affected = |args: &[&str]| match Args::try_parse_from(args).unwrap().command {
+            Some(Command::Run { execution_args, .. }) => execution_args.affected,
+            _ => panic!("expected run command"),
+        };
+        assert_eq!(affected(&["turbo", "run", "build"]), None);
+        assert_eq!(
+            affected(&["turbo", "run", "build", "--affected"]),
+            Some(None)
+        );
+        assert_eq!(
+            affected(&["turbo", "run", "--affected", "build"]),
+            Some(None)
+        );
+        assert_eq!(
+            affected(&["turbo", "run", "build", "--affected=main...HEAD"]),
+            Some(Some("main...HEAD".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_remote_cache_read_and_write_only() {
+        let run_args = |args: &[&str]| {
+            Args::try_parse_from(args)
+                .unwrap()
+                .run_args()
+                .map(|run_args| {
+                    (
+                        run_args.remote_cache_read_only(),
+                        run_args.remote_cache_write_only(),
+                    )
+                })
+        };
+        assert_eq!(run_args(&["turbo", "run", "build"]), Some((None, None)));
+        assert_eq!(
+            run_args(&["turbo", "run", "build", "--remote-cache-write-only"]),
+            Some((None, Some(true)))
+        );
+        assert_eq!(
+            run_args(&["turbo", "run", "build", "--remote-cache-write-only=false"]),
+            Some((None, Some(false)))
+        );
+        assert!(Args::try_parse_from([
+            "turbo",
+            "run",
+            "build",
+            "--remote-cache-read-only",
+            "--remote-cache-write-only"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn test_prevent_affected_and_filter() {
         assert!(
@@ -2661,5 +3208,30 @@ mod test {
         assert!(Args::try_parse_from(["turbo", "build", "--affected", "--filter", "foo"]).is_err(),);
         assert!(Args::try_parse_from(["turbo", "build", "--filter", "foo", "--affected"]).is_err(),);
         assert!(Args::try_parse_from(["turbo", "ls", "--filter", "foo", "--affected"]).is_err(),);
+        assert!(Args::try_parse_from(["turbo", "graph", "--filter", "foo", "--affected"]).is_err(),);
+    }
+
+    #[test]
+    fn test_graph() {
+        assert_eq!(
+            Args::try_parse_from(["turbo", "graph"]).unwrap().command,
+            Some(Command::Graph {
+                tasks: vec![],
+                affected: false,
+                filter: vec![],
+                format: GraphFormat::Json,
+            })
+        );
+        assert_eq!(
+            Args::try_parse_from(["turbo", "graph", "build", "--format", "dot", "-F", "web"])
+                .unwrap()
+                .command,
+            Some(Command::Graph {
+                tasks: vec!["build".to_string()],
+                affected: false,
+                filter: vec!["web".to_string()],
+                format: GraphFormat::Dot,
+            })
+        );
     }
 }
@@ -0,0 +1,300 @@
+//! Commands for managing and inspecting the local cache.
+
+use std::time::{Duration, SystemTime};
+
+use camino::Utf8Path;
+use miette::Diagnostic;
+use serde::Serialize;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPathBuf, PathError};
+use turborepo_cache::{
+    fs::{CacheEntry, FSCache},
+    CacheError,
+};
+use turborepo_ui::{color, cprintln, BOLD, GREY};
+
+use crate::{
+    cli::OutputFormat,
+    commands::CommandBase,
+    config,
+    config::{parse_cache_max_age, parse_cache_max_size},
+};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Config(#[from] config::Error),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Path(#[from] PathError),
+    #[error("no artifact with hash `{hash}` in the local cache")]
+    #[diagnostic(help("run `turbo cache ls` to see the available artifacts"))]
+    ArtifactNotFound { hash: String },
+    #[error("`{hash}` isn't a valid hash")]
+    #[diagnostic(help("hashes are hexadecimal, like the ones `turbo cache ls` shows"))]
+    InvalidHash { hash: String },
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactDetails {
+    hash: String,
+    package: Option<String>,
+    task: Option<String>,
+    size: u64,
+    created: String,
+    last_used: String,
+    /// How long the task took to run in milliseconds
+    duration: Option<u64>,
+}
+
+impl ArtifactDetails {
+    fn new(cache: &FSCache, entry: CacheEntry) -> Result<Self, Error> {
+        let metadata = cache.metadata(&entry.hash)?;
+        let (package, task) = metadata
+            .as_ref()
+            .and_then(|metadata| metadata.task.clone())
+            .map(|task| (Some(task.package), Some(task.task)))
+            .unwrap_or_default();
+
+        Ok(Self {
+            hash: entry.hash,
+            package,
+            task,
+            size: entry.size,
+            created: format_time(entry.created),
+            last_used: format_time(entry.last_used),
+            duration: metadata.map(|metadata| metadata.duration),
+        })
+    }
+
+    fn task_id(&self) -> Option<String> {
+        let (package, task) = self.package.as_ref().zip(self.task.as_ref())?;
+        Some(format!("{package}#{task}"))
+    }
+}
+
+fn local_cache(base: &CommandBase) -> Result<FSCache, Error> {
+    let config = base.config()?;
+    Ok(FSCache::new(
+        config.cache_dir(),
+        &base.repo_root,
+        None,
+        config.cache_compression_level(),
+    )?)
+}
+
+// Hashes become file names in the cache directory, so anything else could
+// point outside of it
+fn validate_hash(hash: &str) -> Result<(), Error> {
+    if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(Error::InvalidHash {
+            hash: hash.to_string(),
+        });
+    }
+    Ok(())
+}
+
+fn find_entry(cache: &FSCache, hash: &str) -> Result<CacheEntry, Error> {
+    validate_hash(hash)?;
+    cache
+        .entries()?
+        .into_iter()
+        .find(|entry| entry.hash == hash)
+        .ok_or_else(|| Error::ArtifactNotFound {
+            hash: hash.to_string(),
+        })
+}
+
+pub fn clean(
+    base: &CommandBase,
+    max_size: Option<&str>,
+    max_age: Option<&str>,
+) -> Result<(), Error> {
+    let config = base.config()?;
+    let mut policy = config.cache_eviction_policy();
+    if let Some(max_size) = max_size {
+        policy.max_size = Some(parse_cache_max_size(max_size)?);
+    }
+    if let Some(max_age) = max_age {
+        policy.max_age = Some(Duration::from_secs(parse_cache_max_age(max_age)?));
+    }
+    // Without any limits to enforce, cleaning means emptying the cache
+    if policy.is_unbounded() {
+        policy.max_size = Some(0);
+    }
+
+    let cache = local_cache(base)?;
+    let summary = cache.evict(&policy)?;
+
+    println!(
+        "Removed {} ({}) from {}",
+        color!(
+            base.color_config,
+            BOLD,
+            "{}",
+            pluralize(summary.evicted, "artifact")
+        ),
+        format_bytes(summary.evicted_bytes),
+        config.cache_dir()
+    );
+    if summary.retained > 0 {
+        println!(
+            "{} ({}) remaining",
+            pluralize(summary.retained, "artifact"),
+            format_bytes(summary.retained_bytes)
+        );
+    }
+
+    Ok(())
+}
+
+pub fn ls(base: &CommandBase, output: Option<OutputFormat>) -> Result<(), Error> {
+    let cache = local_cache(base)?;
+    let mut entries = cache.entries()?;
+    entries.sort_by(|a, b| b.created.cmp(&a.created));
+    let artifacts = entries
+        .into_iter()
+        .map(|entry| ArtifactDetails::new(&cache, entry))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if matches!(output, Some(OutputFormat::Json)) {
+        println!("{}", serde_json::to_string_pretty(&artifacts)?);
+        return Ok(());
+    }
+
+    if artifacts.is_empty() {
+        println!("No artifacts in {}", base.config()?.cache_dir());
+        return Ok(());
+    }
+
+    let rows = artifacts
+        .iter()
+        .map(|artifact| {
+            [
+                artifact.hash.clone(),
+                artifact.task_id().unwrap_or_else(|| "-".to_string()),
+                format_bytes(artifact.size),
+                artifact.created.clone(),
+            ]
+        })
+        .collect::<Vec<_>>();
+    let headers = ["HASH", "TASK", "SIZE", "CREATED"];
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |cells: [&str; 4]| {
+        cells
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+    cprintln!(base.color_config, GREY, "{}", format_row(headers));
+    for row in &rows {
+        println!("{}", format_row(row.each_ref().map(String::as_str)));
+    }
+
+    Ok(())
+}
+
+pub fn info(base: &CommandBase, hash: &str, output: Option<OutputFormat>) -> Result<(), Error> {
+    let cache = local_cache(base)?;
+    let artifact = ArtifactDetails::new(&cache, find_entry(&cache, hash)?)?;
+
+    if matches!(output, Some(OutputFormat::Json)) {
+        println!("{}", serde_json::to_string_pretty(&artifact)?);
+        return Ok(());
+    }
+
+    let unknown = || "unknown".to_string();
+    let fields = [
+        ("Hash", artifact.hash.clone()),
+        ("Package", artifact.package.clone().unwrap_or_else(unknown)),
+        ("Task", artifact.task.clone().unwrap_or_else(unknown)),
+        ("Size", format_bytes(artifact.size)),
+        ("Created", artifact.created.clone()),
+        ("Last used", artifact.last_used.clone()),
+        (
+            "Duration",
+            artifact
+                .duration
+                .map(|duration| humantime::format_duration(Duration::from_millis(duration)))
+                .map_or_else(unknown, |duration| duration.to_string()),
+        ),
+    ];
+    for (label, value) in fields {
+        println!(
+            "{} {value}",
+            color!(base.color_config, BOLD, "{:<10}", label)
+        );
+    }
+
+    Ok(())
+}
+
+pub fn extract(base: &CommandBase, hash: &str, dir: &Utf8Path) -> Result<(), Error> {
+    validate_hash(hash)?;
+    let cache = local_cache(base)?;
+    let dir = AbsoluteSystemPathBuf::from_cwd(dir)?;
+    let files = cache
+        .extract(hash, &dir)?
+        .ok_or_else(|| Error::ArtifactNotFound {
+            hash: hash.to_string(),
+        })?;
+
+    println!(
+        "Extracted {} from {} to {}",
+        pluralize(files.len(), "file"),
+        color!(base.color_config, BOLD, "{}", hash),
+        dir
+    );
+
+    Ok(())
+}
+
+fn pluralize(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {noun}"),
+        n => format!("{n} {noun}s"),
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    human_format::Formatter::new()
+        .with_decimals(2)
+        .with_separator("")
+        .with_units("B")
+        .format(bytes as f64)
+}
+
+fn format_time(time: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(time).to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::validate_hash;
+
+    #[test_case("a1b2c3d4e5f60718", true ; "hash")]
+    #[test_case("", false ; "empty")]
+    #[test_case("../a1b2c3d4e5f60718", false ; "parent directory")]
+    #[test_case("a1b2/c3d4", false ; "separator")]
+    #[test_case("a1b2\\c3d4", false ; "windows separator")]
+    fn test_validate_hash(hash: &str, valid: bool) {
+        assert_eq!(validate_hash(hash).is_ok(), valid);
+    }
+}
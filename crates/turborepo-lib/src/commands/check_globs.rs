@@ -0,0 +1,116 @@
+//! A command for trying out globs before putting them in `inputs` or
+//! `outputs`, using the same matching `turbo` does when hashing and caching.
+
+use std::{env, str::FromStr};
+
+use camino::Utf8Path;
+use globwalk::{ValidatedGlob, WalkType};
+use miette::Diagnostic;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathError};
+
+use crate::cli::INVOCATION_DIR_ENV_VAR;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error(transparent)]
+    Path(#[from] PathError),
+    #[error(transparent)]
+    Glob(#[from] globwalk::GlobError),
+    #[error(transparent)]
+    Walk(#[from] globwalk::WalkError),
+}
+
+/// Prints the paths that `patterns` select in `dir`, which defaults to the
+/// directory `turbo` was invoked from. Patterns starting with `!` are
+/// exclusions. With `outputs`, directories are selected as well, the way
+/// they are when caching a task's outputs.
+pub fn run(dir: Option<&Utf8Path>, patterns: &[String], outputs: bool) -> Result<(), Error> {
+    let invocation_dir = match env::var(INVOCATION_DIR_ENV_VAR) {
+        Ok(dir) => AbsoluteSystemPathBuf::new(dir)?,
+        Err(_) => AbsoluteSystemPathBuf::cwd()?,
+    };
+    let base = match dir {
+        Some(dir) => AbsoluteSystemPathBuf::from_unknown(&invocation_dir, dir),
+        None => invocation_dir,
+    };
+    let walk_type = if outputs {
+        WalkType::All
+    } else {
+        WalkType::Files
+    };
+
+    for path in matches(&base, patterns, walk_type)? {
+        println!("{path}");
+    }
+    Ok(())
+}
+
+fn matches(
+    base: &AbsoluteSystemPath,
+    patterns: &[String],
+    walk_type: WalkType,
+) -> Result<Vec<String>, Error> {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(exclusion) => exclude.push(ValidatedGlob::from_str(exclusion)?),
+            None => include.push(ValidatedGlob::from_str(pattern)?),
+        }
+    }
+
+    let mut paths = globwalk::globwalk(base, &include, &exclude, walk_type)?
+        .into_iter()
+        .filter(|path| path.as_path() != base.as_path())
+        .map(|path| {
+            let relative = base.anchor(&path)?.to_unix();
+            Ok(if path.as_std_path().is_dir() {
+                format!("{relative}/")
+            } else {
+                relative.to_string()
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod test {
+    use globwalk::WalkType;
+    use turbopath::AbsoluteSystemPathBuf;
+
+    use super::matches;
+
+    #[test]
+    fn test_matches() {
+        let tmp = tempfile::tempdir().unwrap();
+        let base = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        for file in ["src/index.ts", "src/index.test.ts", "dist/index.js", ".env"] {
+            let path = base.join_unix_path(turbopath::RelativeUnixPath::new(file).unwrap());
+            path.ensure_dir().unwrap();
+            path.create_with_contents("").unwrap();
+        }
+        let patterns = |patterns: &[&str]| {
+            patterns
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            matches(
+                &base,
+                &patterns(&["**", "!dist", "!**/*.test.ts"]),
+                WalkType::Files
+            )
+            .unwrap(),
+            vec![".env", "src/index.ts"]
+        );
+        assert_eq!(
+            matches(&base, &patterns(&["dist"]), WalkType::All).unwrap(),
+            vec!["dist/", "dist/index.js"]
+        );
+    }
+}
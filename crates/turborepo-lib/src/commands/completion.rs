@@ -0,0 +1,285 @@
+//! Shell completion scripts. On top of the completions clap generates from our
+//! flags, bash, zsh and fish complete `--filter` with package names and tasks
+//! with the tasks in `turbo.json` by calling back into `turbo _complete`.
+
+use std::io::{self, Write};
+
+use clap::{CommandFactory, ValueEnum};
+use clap_complete::{generate as generate_static, Shell};
+use itertools::Itertools;
+use miette::Diagnostic;
+use regex::Regex;
+use thiserror::Error;
+use turborepo_repository::package_graph::PackageName;
+use turborepo_telemetry::events::command::CommandEventBuilder;
+
+use crate::{
+    cli::{Args, Command},
+    commands::{run::get_signal, CommandBase},
+    run,
+    run::builder::RunBuilder,
+    signal::SignalHandler,
+};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("failed to write completions: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Run(#[from] run::Error),
+}
+
+/// The names `turbo _complete` can list
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CompletionKind {
+    Packages,
+    Tasks,
+}
+
+const BASH_FUNCTIONS: &str = r#"
+_turbo_packages() {
+    turbo _complete packages 2>/dev/null
+}
+
+_turbo_tasks() {
+    turbo _complete tasks 2>/dev/null
+}
+
+_turbo_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    # With the default COMP_WORDBREAKS, `--filter=web` is split into `--filter`,
+    # `=` and `web`
+    if [[ "${cur}" == "=" ]]; then
+        cur=""
+    elif [[ "${prev}" == "=" && ${COMP_CWORD} -ge 2 ]]; then
+        prev="${COMP_WORDS[COMP_CWORD-2]}"
+    fi
+    case "${cur}" in
+        --filter=*)
+            COMPREPLY=($(compgen -P "--filter=" -W "$(_turbo_packages)" -- "${cur#--filter=}"))
+            return 0
+            ;;
+    esac
+    case "${prev}" in
+        --filter|-F)
+            COMPREPLY=($(compgen -W "$(_turbo_packages)" -- "${cur}"))
+            return 0
+            ;;
+    esac
+    _turbo "$@"
+}
+"#;
+
+const ZSH_FUNCTIONS: &str = r#"(( $+functions[_turbo_packages] )) ||
+_turbo_packages() {
+    local -a packages
+    packages=(${(f)"$(_call_program packages turbo _complete packages 2>/dev/null)"})
+    _wanted packages expl 'package' compadd -a packages
+}
+(( $+functions[_turbo_tasks] )) ||
+_turbo_tasks() {
+    local -a tasks
+    tasks=(${(f)"$(_call_program tasks turbo _complete tasks 2>/dev/null)"})
+    _wanted tasks expl 'task' compadd -a tasks
+}
+
+"#;
+
+const FISH_FUNCTIONS: &str = r#"
+function __fish_turbo_packages
+	turbo _complete packages 2>/dev/null
+end
+
+function __fish_turbo_tasks
+	turbo _complete tasks 2>/dev/null
+end
+"#;
+
+// Clap's zsh script calls `_turbo` as soon as it's loaded when it's autoloaded
+// as a completion function, so our functions need to be defined before this
+const ZSH_TRAILER: &str = "if [ \"$funcstack[1]\" = \"_turbo\" ]; then";
+
+/// Writes the completion script for a shell
+pub fn generate(shell: Shell, out: &mut impl Write) -> Result<(), Error> {
+    let mut command = Args::command();
+    let mut script = Vec::new();
+    generate_static(shell, &mut command, "turbo", &mut script);
+    let script = String::from_utf8_lossy(&script);
+
+    let script = match shell {
+        Shell::Bash => bash(&script),
+        Shell::Zsh => zsh(&script),
+        Shell::Fish => fish(&script, &command),
+        // Other shells only get completions for flags and subcommands
+        _ => script.into_owned(),
+    };
+    out.write_all(script.as_bytes())?;
+    Ok(())
+}
+
+fn bash(script: &str) -> String {
+    // Positional arguments are listed as `[TASKS]...` alongside the flags
+    let script = script
+        .replace("[TASKS]...", "$([[ ${cur} != -* ]] && _turbo_tasks)")
+        .replace("[PACKAGES]...", "$([[ ${cur} != -* ]] && _turbo_packages)")
+        .replace("complete -F _turbo ", "complete -F _turbo_dynamic ")
+        .replace(" _complete ", " ");
+    format!("{script}{BASH_FUNCTIONS}")
+}
+
+fn zsh(script: &str) -> String {
+    let filter = Regex::new(r":FILTER: '").expect("regex is valid");
+    let positional = Regex::new(r"::(tasks|packages)( -- [^:]*)?:'").expect("regex is valid");
+    let script = filter.replace_all(script, ":FILTER:_turbo_packages'");
+    let script = positional.replace_all(&script, "::$1$2:_turbo_$1'");
+    let script = without_lines(&script, |line| line.starts_with("'_complete:"));
+    match script.find(ZSH_TRAILER) {
+        Some(index) => format!("{}{ZSH_FUNCTIONS}{}", &script[..index], &script[index..]),
+        None => format!("{script}\n{ZSH_FUNCTIONS}"),
+    }
+}
+
+fn fish(script: &str, command: &clap::Command) -> String {
+    let mut completions = String::new();
+    let commands = std::iter::once(("__fish_turbo_needs_command".to_string(), command)).chain(
+        command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| {
+                (
+                    format!("__fish_turbo_using_subcommand {}", subcommand.get_name()),
+                    subcommand,
+                )
+            }),
+    );
+    for (condition, command) in commands {
+        for arg in command.get_arguments() {
+            let function = match arg.get_id().as_str() {
+                "tasks" if arg.is_positional() => "__fish_turbo_tasks",
+                "packages" if arg.is_positional() => "__fish_turbo_packages",
+                "filter" => "__fish_turbo_packages",
+                _ => continue,
+            };
+            let flag = match (arg.get_short(), arg.get_long()) {
+                (Some(short), Some(long)) => format!(" -s {short} -l {long}"),
+                (None, Some(long)) => format!(" -l {long}"),
+                _ => String::new(),
+            };
+            completions.push_str(&format!(
+                "complete -c turbo -n \"{condition}\"{flag} -f -a \"({function})\"\n"
+            ));
+        }
+    }
+    let script = without_lines(script, |line| line.contains("-a \"_complete\""));
+    format!("{script}{FISH_FUNCTIONS}\n{completions}")
+}
+
+// Clap lists hidden subcommands in completion scripts, which would offer
+// `_complete` alongside the real subcommands
+fn without_lines(script: &str, hidden: impl Fn(&str) -> bool) -> String {
+    script
+        .split_inclusive('\n')
+        .filter(|line| !hidden(line))
+        .collect()
+}
+
+/// Prints the names of the packages in the repository or the tasks in its
+/// `turbo.json`, one per line, for completion scripts
+pub async fn candidates(
+    mut base: CommandBase,
+    kind: CompletionKind,
+    telemetry: CommandEventBuilder,
+) -> Result<(), Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    // We fake a run command, so we can construct a `Run` type
+    base.args_mut().command = Some(Command::Run {
+        run_args: Box::default(),
+        execution_args: Box::default(),
+    });
+
+    let run = RunBuilder::new(base)?
+        .hide_prelude()
+        .build(&handler, telemetry)
+        .await?;
+
+    let names = match kind {
+        CompletionKind::Packages => run
+            .pkg_dep_graph()
+            .packages()
+            .map(|(name, _)| name)
+            .filter(|name| !matches!(name, PackageName::Root))
+            .map(ToString::to_string)
+            .sorted()
+            .collect::<Vec<_>>(),
+        CompletionKind::Tasks => run
+            .root_turbo_json()
+            .tasks
+            .keys()
+            .map(ToString::to_string)
+            .sorted()
+            .dedup()
+            .collect(),
+    };
+
+    let mut stdout = io::stdout().lock();
+    for name in names {
+        writeln!(stdout, "{name}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn script(shell: Shell) -> String {
+        let mut out = Vec::new();
+        generate(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_bash_completions() {
+        let script = script(Shell::Bash);
+        assert!(!script.contains("[TASKS]..."));
+        assert!(!script.contains("complete -F _turbo "));
+        assert!(script.contains("complete -F _turbo_dynamic "));
+        assert!(script.contains("_turbo_dynamic() {"));
+        assert!(script.contains(" completion daemon "));
+    }
+
+    #[test]
+    fn test_zsh_completions() {
+        let script = script(Shell::Zsh);
+        assert!(script.contains("--filter=[Use the given selector"));
+        assert!(!script.contains(":FILTER: '"));
+        assert!(script.contains(":FILTER:_turbo_packages'"));
+        assert!(script.contains("'*::tasks:_turbo_tasks'"));
+        assert!(script.contains("'*::packages -- "));
+        assert!(!script.contains("'_complete:"));
+        // The functions need to exist before `_turbo` is called
+        let functions = script.find("_turbo_packages() {").unwrap();
+        assert!(functions < script.find(ZSH_TRAILER).unwrap());
+    }
+
+    #[test]
+    fn test_fish_completions() {
+        let script = script(Shell::Fish);
+        assert!(script.contains(
+            "complete -c turbo -n \"__fish_turbo_using_subcommand run\" -s F -l filter -f -a \
+             \"(__fish_turbo_packages)\""
+        ));
+        assert!(script.contains(
+            "complete -c turbo -n \"__fish_turbo_using_subcommand run\" -f -a \
+             \"(__fish_turbo_tasks)\""
+        ));
+        assert!(script.contains(
+            "complete -c turbo -n \"__fish_turbo_needs_command\" -f -a \"(__fish_turbo_tasks)\""
+        ));
+        assert!(!script.contains("-a \"_complete\""));
+    }
+}
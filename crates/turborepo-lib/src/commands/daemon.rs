@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, UNIX_EPOCH};
 
 use camino::Utf8PathBuf;
 use futures::FutureExt;
@@ -8,12 +8,14 @@ use time::{format_description, OffsetDateTime};
 use tokio::signal::ctrl_c;
 use tracing::{trace, warn};
 use turbopath::AbsoluteSystemPath;
+use turborepo_filewatch::{WatcherBackend, POLL_INTERVAL};
 use turborepo_ui::{color, BOLD_GREEN, BOLD_RED, GREY};
 use which::which;
 
 use super::CommandBase;
 use crate::{
     cli::DaemonCommand,
+    config::Watcher,
     daemon::{
         endpoint::SocketOpenError, CloseReason, DaemonConnector, DaemonConnectorError, DaemonError,
         Paths,
@@ -29,7 +31,9 @@ pub async fn daemon_client(command: &DaemonCommand, base: &CommandBase) -> Resul
     let (can_start_server, can_kill_server) = match command {
         DaemonCommand::Status { .. } | DaemonCommand::Logs => (false, false),
         DaemonCommand::Stop => (false, true),
-        DaemonCommand::Restart | DaemonCommand::Start => (true, true),
+        DaemonCommand::Restart | DaemonCommand::Start | DaemonCommand::Changed { .. } => {
+            (true, true)
+        }
         DaemonCommand::Clean { .. } => (false, true),
     };
 
@@ -111,6 +115,20 @@ pub async fn daemon_client(command: &DaemonCommand, base: &CommandBase) -> Resul
                 log_file: log_file.into(),
                 pid_file: paths.pid_file.to_owned(),
                 sock_file: paths.sock_file.to_owned(),
+                watched_roots: status.watched_roots,
+                watcher_backend: status.watcher_backend,
+                file_watching_ready: status.file_watching_ready,
+                file_events: status.file_events,
+                file_events_per_sec: events_per_sec(status.file_events, status.uptime_msec),
+                memory_bytes: status.memory_bytes,
+                recent_errors: status
+                    .recent_errors
+                    .into_iter()
+                    .map(|error| DaemonStatusError {
+                        timestamp_ms: error.timestamp_msec,
+                        message: error.message,
+                    })
+                    .collect(),
             };
 
             if *json {
@@ -141,6 +159,91 @@ pub async fn daemon_client(command: &DaemonCommand, base: &CommandBase) -> Resul
                     "socket file: {}",
                     color!(base.color_config, GREY, "{}", status.sock_file)
                 );
+                println!(
+                    "watcher: {}",
+                    color!(
+                        base.color_config,
+                        GREY,
+                        "{} ({})",
+                        status.watcher_backend,
+                        if status.file_watching_ready {
+                            "ready"
+                        } else {
+                            "not ready"
+                        }
+                    )
+                );
+                println!(
+                    "watched roots: {}",
+                    color!(
+                        base.color_config,
+                        GREY,
+                        "{}",
+                        status.watched_roots.join(", ")
+                    )
+                );
+                println!(
+                    "file events: {}",
+                    color!(
+                        base.color_config,
+                        GREY,
+                        "{} ({:.2}/s)",
+                        status.file_events,
+                        status.file_events_per_sec
+                    )
+                );
+                println!(
+                    "memory: {}",
+                    color!(
+                        base.color_config,
+                        GREY,
+                        "{:.1} MiB",
+                        status.memory_bytes as f64 / (1024.0 * 1024.0)
+                    )
+                );
+                if status.recent_errors.is_empty() {
+                    println!("recent errors: {}", color!(base.color_config, GREY, "none"));
+                } else {
+                    println!("recent errors:");
+                    for error in &status.recent_errors {
+                        let time = UNIX_EPOCH + Duration::from_millis(error.timestamp_ms);
+                        println!(
+                            "  {} {}",
+                            color!(
+                                base.color_config,
+                                GREY,
+                                "{}",
+                                humantime::format_rfc3339_seconds(time)
+                            ),
+                            error.message
+                        );
+                    }
+                }
+            }
+        }
+        DaemonCommand::Changed { since, json } => {
+            let mut client = connector.connect().await?;
+            let response = client.get_changed_packages(since.clone()).await?;
+            let changed = ChangedPackages {
+                hash: response.hash,
+                all_changed: response.all_changed,
+                packages: response.package_names,
+            };
+
+            if *json {
+                println!("{}", serde_json::to_string_pretty(&changed)?);
+            } else {
+                if changed.all_changed {
+                    println!("all packages changed");
+                } else {
+                    for package in &changed.packages {
+                        println!("{package}");
+                    }
+                }
+                println!(
+                    "hash: {}",
+                    color!(base.color_config, GREY, "{}", changed.hash)
+                );
             }
         }
         DaemonCommand::Logs => {
@@ -261,6 +364,13 @@ fn clean_logs(log_folder: &AbsoluteSystemPath) -> Result<(), DaemonError> {
     })
 }
 
+fn events_per_sec(events: u64, uptime_ms: u64) -> f64 {
+    if uptime_ms == 0 {
+        return 0.0;
+    }
+    events as f64 / (uptime_ms as f64 / 1000.0)
+}
+
 // log_filename matches the algorithm used by tracing_appender::Rotation::DAILY
 // to generate the log filename. This is kind of a hack, but there didn't appear
 // to be a simple way to grab the generated filename.
@@ -298,8 +408,28 @@ pub async fn daemon_server(
         }
         CloseReason::Interrupt
     });
+    let (watcher_backend, poll_interval) = match base.config() {
+        Ok(config) => {
+            let backend = match config.watcher() {
+                Watcher::Auto => WatcherBackend::Auto,
+                Watcher::Native => WatcherBackend::Native,
+                Watcher::Watchman => WatcherBackend::Watchman,
+                Watcher::Poll => WatcherBackend::Poll,
+            };
+            (backend, config.poll_interval())
+        }
+        Err(e) => {
+            warn!(
+                "failed to read config, picking a file watcher automatically: {}",
+                e
+            );
+            (WatcherBackend::Auto, POLL_INTERVAL)
+        }
+    };
     let server =
-        crate::daemon::TurboGrpcService::new(base.repo_root.clone(), paths, timeout, exit_signal);
+        crate::daemon::TurboGrpcService::new(base.repo_root.clone(), paths, timeout, exit_signal)
+            .with_watcher_backend(watcher_backend)
+            .with_poll_interval(poll_interval);
 
     let reason = server.serve().await?;
 
@@ -329,4 +459,27 @@ pub struct DaemonStatus {
     pub log_file: Utf8PathBuf,
     pub pid_file: turbopath::AbsoluteSystemPathBuf,
     pub sock_file: turbopath::AbsoluteSystemPathBuf,
+    pub watched_roots: Vec<String>,
+    pub watcher_backend: String,
+    pub file_watching_ready: bool,
+    pub file_events: u64,
+    // averaged over the daemon's uptime
+    pub file_events_per_sec: f64,
+    pub memory_bytes: u64,
+    pub recent_errors: Vec<DaemonStatusError>,
+}
+
+#[derive(serde::Serialize)]
+pub struct DaemonStatusError {
+    // milliseconds since the unix epoch
+    pub timestamp_ms: u64,
+    pub message: String,
+}
+
+#[derive(serde::Serialize)]
+pub struct ChangedPackages {
+    pub hash: String,
+    // set when the daemon can't tell which packages changed since the hash
+    pub all_changed: bool,
+    pub packages: Vec<String>,
 }
@@ -0,0 +1,593 @@
+//! `turbo explain` shows everything that went into a task's hash, and what
+//! changed since a previous run, so a cache miss can be traced back to the
+//! input that caused it.
+//!
+//! The hash is computed with a dry run, and previous runs are read from the
+//! summaries `--summarize` writes to `.turbo/runs`. Both are handled as JSON
+//! so summaries from older versions of `turbo` can still be compared.
+
+use std::collections::BTreeMap;
+
+use miette::Diagnostic;
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_ui::{color, cprintln, ColorConfig, BOLD, GREEN, GREY, RED, YELLOW};
+
+use crate::{api, cli::OutputFormat, commands::CommandBase};
+
+/// Value of `--diff` without a run, selects the most recent run with the task
+pub const LATEST_RUN: &str = "latest";
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Run(#[from] api::Error),
+    #[error("`{0}` wasn't part of the dry run")]
+    TaskNotFound(String),
+    #[error("no run summary in {dir} includes `{task_id}`")]
+    #[diagnostic(help("run the task with `--summarize` to record its hash composition"))]
+    NoPreviousRun {
+        task_id: String,
+        dir: AbsoluteSystemPathBuf,
+    },
+    #[error("`{task_id}` isn't in the run summary at {path}")]
+    TaskNotInRun {
+        task_id: String,
+        path: AbsoluteSystemPathBuf,
+    },
+    #[error("failed to read run summary at {path}: {error}")]
+    ReadSummary {
+        path: AbsoluteSystemPathBuf,
+        #[source]
+        error: std::io::Error,
+    },
+    #[error("failed to parse run summary at {path}: {error}")]
+    ParseSummary {
+        path: AbsoluteSystemPathBuf,
+        #[source]
+        error: serde_json::Error,
+    },
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+}
+
+/// The parts of a task summary that make up its hash. Fields are kept as
+/// JSON so that summaries missing some of them can still be compared.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Explanation {
+    task_id: String,
+    hash: Value,
+    global_hash: Value,
+    hash_of_files: Value,
+    hash_of_external_dependencies: Value,
+    /// Dependency task IDs mapped to their hashes
+    dependencies: BTreeMap<String, Value>,
+    inputs: Value,
+    env_mode: Value,
+    /// Values are hashed, so secrets aren't exposed
+    environment_variables: Value,
+    cli_arguments: Value,
+    resolved_task_definition: Value,
+    global_cache_inputs: Value,
+}
+
+impl Explanation {
+    fn from_summary(summary: &Value, task_id: &str) -> Option<Self> {
+        let tasks = summary["tasks"].as_array()?;
+        let task = find_task(tasks, task_id)?;
+        let dependencies = task["dependencies"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .map(|dependency| {
+                let hash = find_task(tasks, dependency)
+                    .map_or(Value::Null, |dependency| dependency["hash"].clone());
+                (dependency.to_string(), hash)
+            })
+            .collect();
+
+        Some(Self {
+            task_id: task_id.to_string(),
+            hash: task["hash"].clone(),
+            global_hash: task["hashInputs"]["globalHash"].clone(),
+            hash_of_files: task["hashInputs"]["hashOfFiles"].clone(),
+            hash_of_external_dependencies: task["hashOfExternalDependencies"].clone(),
+            dependencies,
+            inputs: task["inputs"].clone(),
+            env_mode: task["envMode"].clone(),
+            environment_variables: task["environmentVariables"].clone(),
+            cli_arguments: task["cliArguments"].clone(),
+            resolved_task_definition: task["resolvedTaskDefinition"].clone(),
+            global_cache_inputs: summary["globalCacheInputs"].clone(),
+        })
+    }
+}
+
+// Summaries of single package repos leave the `//#` off of task IDs
+fn find_task<'a>(tasks: &'a [Value], task_id: &str) -> Option<&'a Value> {
+    let root_task = task_id.strip_prefix("//#");
+    tasks.iter().find(|task| {
+        let id = task["taskId"].as_str();
+        id == Some(task_id) || (id.is_some() && id == root_task)
+    })
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+enum Change {
+    Added {
+        path: String,
+        value: Value,
+    },
+    Removed {
+        path: String,
+        value: Value,
+    },
+    Changed {
+        path: String,
+        before: Value,
+        after: Value,
+    },
+}
+
+/// Collects the differences between two JSON values. Arrays of strings, like
+/// lists of environment variables, are compared as sets.
+fn diff(path: &str, before: &Value, after: &Value, changes: &mut Vec<Change>) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, before_value) in before {
+                let path = join_path(path, key);
+                match after.get(key) {
+                    Some(after_value) => diff(&path, before_value, after_value, changes),
+                    None => changes.push(Change::Removed {
+                        path,
+                        value: before_value.clone(),
+                    }),
+                }
+            }
+            for (key, after_value) in after {
+                if !before.contains_key(key) {
+                    changes.push(Change::Added {
+                        path: join_path(path, key),
+                        value: after_value.clone(),
+                    });
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after))
+            if before.iter().chain(after).all(Value::is_string) =>
+        {
+            let path = format!("{path}[]");
+            for value in before.iter().filter(|value| !after.contains(value)) {
+                changes.push(Change::Removed {
+                    path: path.clone(),
+                    value: value.clone(),
+                });
+            }
+            for value in after.iter().filter(|value| !before.contains(value)) {
+                changes.push(Change::Added {
+                    path: path.clone(),
+                    value: value.clone(),
+                });
+            }
+        }
+        (before, after) if before != after => changes.push(Change::Changed {
+            path: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
+
+// File paths and task IDs are used as keys, so anything that isn't a plain
+// identifier is quoted
+fn join_path(path: &str, key: &str) -> String {
+    let is_identifier = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+    match (path.is_empty(), is_identifier) {
+        (true, true) => key.to_string(),
+        (true, false) => format!("{key:?}"),
+        (false, true) => format!("{path}.{key}"),
+        (false, false) => format!("{path}[{key:?}]"),
+    }
+}
+
+pub async fn run(
+    base: &CommandBase,
+    task: &str,
+    diff_against: Option<&str>,
+    output: Option<OutputFormat>,
+) -> Result<(), Error> {
+    let task_id = if task.contains('#') {
+        task.to_string()
+    } else {
+        format!("//#{task}")
+    };
+
+    let result = api::RunBuilder::new(base.repo_root.clone())
+        .with_tasks([task])
+        .dry_run()
+        .await?;
+    let explanation = result
+        .summary
+        .as_ref()
+        .and_then(|summary| Explanation::from_summary(summary, &task_id))
+        .ok_or_else(|| Error::TaskNotFound(task_id.clone()))?;
+
+    let Some(diff_against) = diff_against else {
+        if matches!(output, Some(OutputFormat::Json)) {
+            println!("{}", serde_json::to_string_pretty(&explanation)?);
+        } else {
+            print_explanation(&explanation, base.color_config);
+        }
+        return Ok(());
+    };
+
+    let (path, previous) = previous_explanation(&base.repo_root, diff_against, &task_id)?;
+    let mut changes = Vec::new();
+    diff(
+        "",
+        &serde_json::to_value(&previous)?,
+        &serde_json::to_value(&explanation)?,
+        &mut changes,
+    );
+    // The hash changing is the result, not a cause
+    changes.retain(|change| !matches!(change, Change::Changed { path, .. } if path == "hash"));
+
+    if matches!(output, Some(OutputFormat::Json)) {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Diff<'a> {
+            task_id: &'a str,
+            hash: &'a Value,
+            previous_hash: &'a Value,
+            previous_run: &'a str,
+            changes: &'a [Change],
+        }
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Diff {
+                task_id: &task_id,
+                hash: &explanation.hash,
+                previous_hash: &previous.hash,
+                previous_run: path.as_str(),
+                changes: &changes,
+            })?
+        );
+    } else {
+        print_diff(&explanation, &previous, &path, &changes, base.color_config);
+    }
+    Ok(())
+}
+
+/// Finds the run summary to compare against, `run` is either a path to a
+/// summary, the ID of a run in `.turbo/runs`, or [`LATEST_RUN`]
+fn previous_explanation(
+    repo_root: &AbsoluteSystemPath,
+    run: &str,
+    task_id: &str,
+) -> Result<(AbsoluteSystemPathBuf, Explanation), Error> {
+    let runs_dir = repo_root.join_components(&[".turbo", "runs"]);
+
+    if run != LATEST_RUN {
+        let path = if run.ends_with(".json") {
+            AbsoluteSystemPathBuf::from_unknown(repo_root, run)
+        } else {
+            runs_dir.join_component(&format!("{run}.json"))
+        };
+        let explanation =
+            Explanation::from_summary(&read_summary(&path)?, task_id).ok_or_else(|| {
+                Error::TaskNotInRun {
+                    task_id: task_id.to_string(),
+                    path: path.clone(),
+                }
+            })?;
+        return Ok((path, explanation));
+    }
+
+    // Run IDs are KSUIDs, which sort by the time they were created
+    let mut summaries = std::fs::read_dir(&runs_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let path = AbsoluteSystemPathBuf::try_from(entry.path()).ok()?;
+            (path.extension() == Some("json")).then_some(path)
+        })
+        .collect::<Vec<_>>();
+    summaries.sort();
+
+    for path in summaries.into_iter().rev() {
+        if let Some(explanation) = Explanation::from_summary(&read_summary(&path)?, task_id) {
+            return Ok((path, explanation));
+        }
+    }
+    Err(Error::NoPreviousRun {
+        task_id: task_id.to_string(),
+        dir: runs_dir,
+    })
+}
+
+fn read_summary(path: &AbsoluteSystemPath) -> Result<Value, Error> {
+    let contents = path.read_to_string().map_err(|error| Error::ReadSummary {
+        path: path.to_owned(),
+        error,
+    })?;
+    serde_json::from_str(&contents).map_err(|error| Error::ParseSummary {
+        path: path.to_owned(),
+        error,
+    })
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(value) => value.clone(),
+        Value::Null => "none".to_string(),
+        value => value.to_string(),
+    }
+}
+
+fn print_explanation(explanation: &Explanation, color_config: ColorConfig) {
+    println!(
+        "{} {}",
+        color!(color_config, BOLD, "{}", explanation.task_id),
+        display(&explanation.hash)
+    );
+
+    let heading = |heading: &str| cprintln!(color_config, BOLD, "\n{heading}");
+    let rows = |rows: Vec<(String, String)>| {
+        let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+        for (key, value) in rows {
+            println!("  {key:<width$}  {value}");
+        }
+    };
+
+    heading("Hash inputs");
+    rows(vec![
+        ("Global hash".into(), display(&explanation.global_hash)),
+        ("Files".into(), display(&explanation.hash_of_files)),
+        (
+            "External dependencies".into(),
+            display(&explanation.hash_of_external_dependencies),
+        ),
+        ("Environment mode".into(), display(&explanation.env_mode)),
+        ("Arguments".into(), display(&explanation.cli_arguments)),
+    ]);
+
+    heading("Dependencies");
+    if explanation.dependencies.is_empty() {
+        cprintln!(color_config, GREY, "  none");
+    }
+    rows(
+        explanation
+            .dependencies
+            .iter()
+            .map(|(task_id, hash)| (task_id.clone(), display(hash)))
+            .collect(),
+    );
+
+    heading("Inputs");
+    let inputs = explanation.inputs.as_object();
+    if inputs.map_or(true, |inputs| inputs.is_empty()) {
+        cprintln!(color_config, GREY, "  none");
+    }
+    rows(
+        inputs
+            .into_iter()
+            .flatten()
+            .map(|(file, hash)| (file.clone(), display(hash)))
+            .collect(),
+    );
+
+    heading("Environment variables");
+    cprintln!(color_config, GREY, "  values are hashed");
+    let env = &explanation.environment_variables;
+    let mut env_rows = Vec::new();
+    for (kind, key) in [
+        ("configured", "configured"),
+        ("inferred", "inferred"),
+        ("passthrough, not hashed", "passthrough"),
+    ] {
+        for var in env[key].as_array().into_iter().flatten() {
+            env_rows.push((kind.to_string(), display(var)));
+        }
+    }
+    if env_rows.is_empty() {
+        cprintln!(color_config, GREY, "  none");
+    }
+    rows(env_rows);
+
+    heading("Task definition");
+    let definition =
+        serde_json::to_string_pretty(&explanation.resolved_task_definition).unwrap_or_default();
+    for line in definition.lines() {
+        println!("  {line}");
+    }
+
+    heading("Global hash inputs");
+    let global = &explanation.global_cache_inputs;
+    let global_files = global["files"].as_object();
+    let global_env = global["environmentVariables"]["configured"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .chain(
+            global["environmentVariables"]["inferred"]
+                .as_array()
+                .into_iter()
+                .flatten(),
+        )
+        .map(display)
+        .collect::<Vec<_>>();
+    rows(vec![
+        (
+            "Files".into(),
+            global_files.map_or(0, |files| files.len()).to_string(),
+        ),
+        (
+            "External dependencies".into(),
+            display(&global["hashOfExternalDependencies"]),
+        ),
+        (
+            "Internal dependencies".into(),
+            display(&global["hashOfInternalDependencies"]),
+        ),
+        (
+            "Environment variables".into(),
+            if global_env.is_empty() {
+                "none".to_string()
+            } else {
+                global_env.join(", ")
+            },
+        ),
+    ]);
+}
+
+fn print_diff(
+    explanation: &Explanation,
+    previous: &Explanation,
+    previous_run: &AbsoluteSystemPath,
+    changes: &[Change],
+    color_config: ColorConfig,
+) {
+    cprintln!(color_config, GREY, "Comparing with {previous_run}");
+    if explanation.hash == previous.hash {
+        println!(
+            "{} hash is unchanged: {}",
+            color!(color_config, BOLD, "{}", explanation.task_id),
+            display(&explanation.hash)
+        );
+    } else {
+        println!(
+            "{} hash changed: {} -> {}",
+            color!(color_config, BOLD, "{}", explanation.task_id),
+            display(&previous.hash),
+            display(&explanation.hash)
+        );
+    }
+
+    if changes.is_empty() {
+        return;
+    }
+    println!();
+    for change in changes {
+        match change {
+            Change::Added { path, value } => {
+                cprintln!(color_config, GREEN, "  + {path}: {}", display(value))
+            }
+            Change::Removed { path, value } => {
+                cprintln!(color_config, RED, "  - {path}: {}", display(value))
+            }
+            Change::Changed {
+                path,
+                before,
+                after,
+            } => cprintln!(
+                color_config,
+                YELLOW,
+                "  ~ {path}: {} -> {}",
+                display(before),
+                display(after)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{diff, Change, Explanation};
+
+    #[test]
+    fn test_diff() {
+        let before = json!({
+            "hash": "a",
+            "inputs": { "src/index.ts": "1", "src/old.ts": "2" },
+            "environmentVariables": { "configured": ["API_URL=3", "DEBUG=4"] },
+            "resolvedTaskDefinition": { "outputs": ["dist/**"], "cache": true },
+        });
+        let after = json!({
+            "hash": "b",
+            "inputs": { "src/index.ts": "5", "src/new.ts": "6" },
+            "environmentVariables": { "configured": ["API_URL=7", "DEBUG=4"] },
+            "resolvedTaskDefinition": { "outputs": ["dist/**"], "cache": false },
+        });
+
+        let mut changes = Vec::new();
+        diff("", &before, &after, &mut changes);
+        assert_eq!(
+            changes,
+            vec![
+                Change::Changed {
+                    path: "hash".into(),
+                    before: json!("a"),
+                    after: json!("b")
+                },
+                Change::Changed {
+                    path: r#"inputs["src/index.ts"]"#.into(),
+                    before: json!("1"),
+                    after: json!("5")
+                },
+                Change::Removed {
+                    path: r#"inputs["src/old.ts"]"#.into(),
+                    value: json!("2")
+                },
+                Change::Added {
+                    path: r#"inputs["src/new.ts"]"#.into(),
+                    value: json!("6")
+                },
+                Change::Removed {
+                    path: "environmentVariables.configured[]".into(),
+                    value: json!("API_URL=3")
+                },
+                Change::Added {
+                    path: "environmentVariables.configured[]".into(),
+                    value: json!("API_URL=7")
+                },
+                Change::Changed {
+                    path: "resolvedTaskDefinition.cache".into(),
+                    before: json!(true),
+                    after: json!(false)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_explanation_from_summary() {
+        let summary = json!({
+            "globalCacheInputs": { "rootKey": "key" },
+            "tasks": [
+                {
+                    "taskId": "web#build",
+                    "hash": "web-hash",
+                    "hashInputs": { "globalHash": "global", "hashOfFiles": "files" },
+                    "dependencies": ["ui#build"],
+                    "inputs": { "src/index.ts": "1" },
+                },
+                { "taskId": "ui#build", "hash": "ui-hash", "dependencies": [] },
+            ]
+        });
+
+        let explanation = Explanation::from_summary(&summary, "web#build").unwrap();
+        assert_eq!(explanation.hash, json!("web-hash"));
+        assert_eq!(explanation.global_hash, json!("global"));
+        assert_eq!(explanation.dependencies["ui#build"], json!("ui-hash"));
+        assert_eq!(explanation.global_cache_inputs["rootKey"], json!("key"));
+        assert!(Explanation::from_summary(&summary, "docs#build").is_none());
+
+        // Single package summaries don't include the root package in task IDs
+        let summary = json!({ "tasks": [{ "taskId": "build", "hash": "hash" }] });
+        let explanation = Explanation::from_summary(&summary, "//#build").unwrap();
+        assert_eq!(explanation.hash, json!("hash"));
+    }
+}
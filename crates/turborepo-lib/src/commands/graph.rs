@@ -0,0 +1,286 @@
+//! A command for exporting the package graph, and optionally the task graph,
+//! of a turborepo without running anything.
+
+use std::io::{self, Write};
+
+use miette::Diagnostic;
+use serde::Serialize;
+use thiserror::Error;
+use turborepo_repository::package_graph::{PackageName, PackageNode};
+use turborepo_telemetry::events::{command::CommandEventBuilder, EventType};
+
+use crate::{
+    cli,
+    cli::{Command, ExecutionArgs, GraphFormat},
+    commands::{run::get_signal, CommandBase},
+    engine::TaskNode,
+    run::{builder::RunBuilder, Run},
+    signal::SignalHandler,
+};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("failed to write graph: {0}")]
+    Output(#[from] io::Error),
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GraphExport {
+    packages: Vec<PackageNodeExport>,
+    dependencies: Vec<Edge>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tasks: Option<TaskGraphExport>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct PackageNodeExport {
+    name: String,
+    path: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskNodeExport {
+    task_id: String,
+    package: String,
+    task: String,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct TaskGraphExport {
+    nodes: Vec<TaskNodeExport>,
+    dependencies: Vec<Edge>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+struct Edge {
+    from: String,
+    to: String,
+}
+
+pub async fn run(
+    mut base: CommandBase,
+    tasks: Vec<String>,
+    filter: Vec<String>,
+    affected: bool,
+    format: GraphFormat,
+    telemetry: CommandEventBuilder,
+) -> Result<(), cli::Error> {
+    telemetry.track_arg_value("format", format, EventType::NonSensitive);
+    telemetry.track_arg_usage("tasks", !tasks.is_empty());
+
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    // Like `ls`, we fake a run command so the filter and task graph are
+    // resolved the same way `turbo run` would resolve them
+    let include_tasks = !tasks.is_empty();
+    base.args_mut().command = Some(Command::Run {
+        run_args: Box::default(),
+        execution_args: Box::new(ExecutionArgs {
+            tasks,
+            filter,
+            affected: affected.then_some(None),
+            ..Default::default()
+        }),
+    });
+
+    let run = RunBuilder::new(base)?.build(&handler, telemetry).await?;
+    let export = GraphExport::new(&run, include_tasks);
+
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    match format {
+        GraphFormat::Json => {
+            serde_json::to_writer_pretty(&mut stdout, &export)?;
+            writeln!(stdout).map_err(Error::from)?;
+        }
+        GraphFormat::Dot => export.write_dot(&mut stdout).map_err(Error::from)?,
+    }
+
+    Ok(())
+}
+
+impl GraphExport {
+    fn new(run: &Run, include_tasks: bool) -> Self {
+        let package_graph = run.pkg_dep_graph();
+        let filtered_pkgs = run.filtered_pkgs();
+        let is_included =
+            |name: &PackageName| !matches!(name, PackageName::Root) && filtered_pkgs.contains(name);
+
+        let mut packages = Vec::new();
+        let mut dependencies = Vec::new();
+        for (name, info) in package_graph.packages() {
+            if !is_included(name) {
+                continue;
+            }
+            packages.push(PackageNodeExport {
+                name: name.to_string(),
+                path: info.package_path().to_unix().to_string(),
+            });
+
+            let node = PackageNode::Workspace(name.clone());
+            for dependency in package_graph
+                .immediate_dependencies(&node)
+                .into_iter()
+                .flatten()
+            {
+                match dependency {
+                    PackageNode::Workspace(dependency) if is_included(dependency) => dependencies
+                        .push(Edge {
+                            from: name.to_string(),
+                            to: dependency.to_string(),
+                        }),
+                    _ => (),
+                }
+            }
+        }
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+        dependencies.sort();
+
+        let tasks = include_tasks.then(|| Self::task_graph(run));
+
+        Self {
+            packages,
+            dependencies,
+            tasks,
+        }
+    }
+
+    fn task_graph(run: &Run) -> TaskGraphExport {
+        let engine = run.engine();
+        let mut nodes = Vec::new();
+        let mut dependencies = Vec::new();
+        for node in engine.tasks() {
+            let TaskNode::Task(task_id) = node else {
+                continue;
+            };
+            nodes.push(TaskNodeExport {
+                task_id: task_id.to_string(),
+                package: task_id.package().to_string(),
+                task: task_id.task().to_string(),
+            });
+            for dependency in engine.dependencies(task_id).into_iter().flatten() {
+                if let TaskNode::Task(dependency) = dependency {
+                    dependencies.push(Edge {
+                        from: task_id.to_string(),
+                        to: dependency.to_string(),
+                    });
+                }
+            }
+        }
+        nodes.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        dependencies.sort();
+
+        TaskGraphExport {
+            nodes,
+            dependencies,
+        }
+    }
+
+    fn write_dot(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "digraph {{")?;
+        writeln!(writer, "\tcompound = \"true\"")?;
+        writeln!(writer, "\tnewrank = \"true\"")?;
+        writeln!(writer, "\tsubgraph \"packages\" {{")?;
+        for package in &self.packages {
+            writeln!(writer, "\t\t\"{}\"", package.name)?;
+        }
+        for Edge { from, to } in &self.dependencies {
+            writeln!(writer, "\t\t\"{from}\" -> \"{to}\"")?;
+        }
+        writeln!(writer, "\t}}")?;
+        if let Some(tasks) = &self.tasks {
+            writeln!(writer, "\tsubgraph \"tasks\" {{")?;
+            for task in &tasks.nodes {
+                writeln!(writer, "\t\t\"{}\"", task.task_id)?;
+            }
+            for Edge { from, to } in &tasks.dependencies {
+                writeln!(writer, "\t\t\"{from}\" -> \"{to}\"")?;
+            }
+            writeln!(writer, "\t}}")?;
+        }
+        writeln!(writer, "}}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn edge(from: &str, to: &str) -> Edge {
+        Edge {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    fn package(name: &str, path: &str) -> PackageNodeExport {
+        PackageNodeExport {
+            name: name.into(),
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn test_dot_output() {
+        let export = GraphExport {
+            packages: vec![package("ui", "packages/ui"), package("web", "apps/web")],
+            dependencies: vec![edge("web", "ui")],
+            tasks: Some(TaskGraphExport {
+                nodes: vec![
+                    TaskNodeExport {
+                        task_id: "ui#build".into(),
+                        package: "ui".into(),
+                        task: "build".into(),
+                    },
+                    TaskNodeExport {
+                        task_id: "web#build".into(),
+                        package: "web".into(),
+                        task: "build".into(),
+                    },
+                ],
+                dependencies: vec![edge("web#build", "ui#build")],
+            }),
+        };
+        let mut out = Vec::new();
+        export.write_dot(&mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "digraph {
+\tcompound = \"true\"
+\tnewrank = \"true\"
+\tsubgraph \"packages\" {
+\t\t\"ui\"
+\t\t\"web\"
+\t\t\"web\" -> \"ui\"
+\t}
+\tsubgraph \"tasks\" {
+\t\t\"ui#build\"
+\t\t\"web#build\"
+\t\t\"web#build\" -> \"ui#build\"
+\t}
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_json_output_omits_tasks() {
+        let export = GraphExport {
+            packages: vec![package("web", "apps/web")],
+            dependencies: vec![],
+            tasks: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&export).unwrap(),
+            serde_json::json!({
+                "packages": [{ "name": "web", "path": "apps/web" }],
+                "dependencies": [],
+            })
+        );
+    }
+}
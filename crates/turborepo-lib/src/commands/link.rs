@@ -17,7 +17,8 @@ use dirs_next::home_dir;
 #[cfg(test)]
 use rand::Rng;
 use thiserror::Error;
-use turborepo_api_client::{CacheClient, Client};
+use turborepo_api_client::{APIClient, CacheClient, Client};
+use turborepo_auth::{login, AuthFlow, DefaultLoginServer, LoginOptions, Token};
 #[cfg(not(test))]
 use turborepo_ui::CYAN;
 use turborepo_ui::{DialoguerTheme, BOLD, GREY};
@@ -76,6 +77,12 @@ pub enum Error {
     },
     #[error(transparent)]
     Rewrite(#[from] rewrite_json::RewriteError),
+    #[error(transparent)]
+    Auth(#[from] turborepo_auth::Error),
+    #[error("Remote Caching is disabled for this team")]
+    CachingDisabled,
+    #[error("Spaces aren't available with a custom `auth` flow")]
+    SpacesWithAuthFlow,
 }
 
 #[derive(Clone)]
@@ -170,6 +177,21 @@ pub async fn link(
     let homedir = homedir_path.to_string_lossy();
     let repo_root_with_tilde = base.repo_root.to_string().replacen(&*homedir, "~", 1);
     let api_client = base.api_client()?;
+    if let Some(auth_flow) = base.config()?.auth_flow().cloned() {
+        return match target {
+            LinkTarget::RemoteCache => {
+                link_self_hosted(
+                    base,
+                    &api_client,
+                    &auth_flow,
+                    modify_gitignore,
+                    &repo_root_with_tilde,
+                )
+                .await
+            }
+            LinkTarget::Spaces => Err(Error::SpacesWithAuthFlow),
+        };
+    }
     let token = base.config()?.token().ok_or_else(|| Error::TokenNotFound {
         command: base.color_config.apply(BOLD.apply_to("`npx turbo login`")),
     })?;
@@ -365,6 +387,102 @@ pub async fn link(
     }
 }
 
+/// Links to a self-hosted Remote Cache. These don't have Vercel's user and
+/// team endpoints, so the team is whatever's already configured, and if
+/// there's no token yet we log in with the configured `auth` flow and store
+/// the token in `.turbo/config.json`.
+async fn link_self_hosted(
+    base: &mut CommandBase,
+    api_client: &APIClient,
+    auth_flow: &AuthFlow,
+    modify_gitignore: bool,
+    repo_root_with_tilde: &str,
+) -> Result<(), Error> {
+    println!(
+        "\n{}\n\n{}\n\nFor more information, visit: {}\n",
+        base.color_config.rainbow(">>> Remote Caching"),
+        REMOTE_CACHING_INFO,
+        REMOTE_CACHING_URL
+    );
+
+    if !should_link_remote_cache(base, repo_root_with_tilde)? {
+        return Err(Error::NotLinking);
+    }
+
+    let config = base.config()?;
+    let token = match config.token() {
+        Some(token) => Token::existing(token.to_string()),
+        None => {
+            let options = LoginOptions {
+                auth_flow: Some(auth_flow),
+                ..LoginOptions::new(
+                    &base.color_config,
+                    config.login_url(),
+                    api_client,
+                    &DefaultLoginServer,
+                )
+            };
+            login(&options).await?
+        }
+    };
+
+    let response = api_client
+        .get_caching_status(token.into_inner(), config.team_id(), config.team_slug())
+        .await
+        .map_err(Error::CachingStatusNotFound)?;
+    match response.status {
+        CachingStatus::Enabled => {}
+        CachingStatus::Disabled => return Err(Error::CachingDisabled),
+        CachingStatus::OverLimit => return Err(Error::UsageLimit),
+        CachingStatus::Paused => return Err(Error::SpendingPaused),
+    }
+
+    if let Token::New(token) = &token {
+        let local_config_path = base.local_config_path();
+        let before = local_config_path
+            .read_existing_to_string()
+            .map_err(|error| config::Error::FailedToReadConfig {
+                config_path: local_config_path.clone(),
+                error,
+            })?
+            .unwrap_or_else(|| String::from("{}"));
+        let after = set_path(&before, &["token"], &format!("\"{}\"", token))?;
+        local_config_path
+            .ensure_dir()
+            .map_err(|error| config::Error::FailedToSetConfig {
+                config_path: local_config_path.clone(),
+                error,
+            })?;
+        local_config_path
+            .create_with_contents(after)
+            .map_err(|error| config::Error::FailedToSetConfig {
+                config_path: local_config_path.clone(),
+                error,
+            })?;
+    }
+
+    if modify_gitignore {
+        ensure_turbo_is_gitignored(&base.repo_root).map_err(|error| {
+            config::Error::FailedToSetConfig {
+                config_path: base.repo_root.join_component(".gitignore"),
+                error,
+            }
+        })?;
+    }
+
+    println!(
+        "
+    {}  Turborepo CLI authorized for {}
+
+    {}
+        ",
+        base.color_config.rainbow(">>> Success!"),
+        base.color_config.apply(BOLD.apply_to(config.api_url())),
+        GREY.apply_to("To disable Remote Caching, run `npx turbo unlink`")
+    );
+    Ok(())
+}
+
 fn should_enable_caching() -> Result<bool, Error> {
     let theme = DialoguerTheme::default();
 
@@ -467,11 +585,17 @@ fn should_link_remote_cache(_: &CommandBase, _: &str) -> Result<bool, Error> {
 
 #[cfg(not(test))]
 fn should_link_remote_cache(base: &CommandBase, location: &str) -> Result<bool, Error> {
+    let is_self_hosted = base
+        .config()
+        .is_ok_and(|config| config.auth_flow().is_some());
     let prompt = format!(
         "{}{} {}{}",
         base.color_config.apply(BOLD.apply_to(GREY.apply_to("? "))),
-        base.color_config
-            .apply(BOLD.apply_to("Enable Vercel Remote Cache for")),
+        base.color_config.apply(BOLD.apply_to(if is_self_hosted {
+            "Enable Remote Cache for"
+        } else {
+            "Enable Vercel Remote Cache for"
+        })),
         base.color_config
             .apply(BOLD.apply_to(CYAN.apply_to(location))),
         base.color_config.apply(BOLD.apply_to(" ?"))
@@ -650,6 +774,73 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_link_self_hosted_remote_cache() -> Result<()> {
+        let user_config_file = NamedTempFile::new().unwrap();
+        fs::write(user_config_file.path(), "{}").unwrap();
+
+        let repo_root_tmp_dir = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp_dir.path()).unwrap();
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents("{}")
+            .unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents("{}")
+            .unwrap();
+
+        let port = port_scanner::request_open_port().unwrap();
+        let handle = tokio::spawn(start_test_server(port));
+        let repo_config_path = repo_root.join_components(&[".turbo", "config.json"]);
+        repo_config_path.ensure_dir().unwrap();
+        repo_config_path
+            .create_with_contents(
+                serde_json::json!({
+                    "apiUrl": format!("http://localhost:{port}"),
+                    "teamSlug": "my-team",
+                    "token": "token",
+                    "auth": {
+                        "type": "device",
+                        "deviceAuthorizationUrl": format!("http://localhost:{port}/device"),
+                        "tokenUrl": format!("http://localhost:{port}/token"),
+                        "clientId": "turbo"
+                    }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+        let mut base = CommandBase {
+            override_global_config_path: Some(
+                AbsoluteSystemPathBuf::try_from(user_config_file.path().to_path_buf()).unwrap(),
+            ),
+            repo_root: repo_root.clone(),
+            color_config: ColorConfig::new(false),
+            config: OnceCell::new(),
+            args: Args::default(),
+            version: "",
+        };
+
+        // The existing token is used and the configured team is left as is,
+        // instead of picking a team from the Vercel API
+        link::link(&mut base, false, LinkTarget::RemoteCache)
+            .await
+            .unwrap();
+        handle.abort();
+
+        let updated_config = TurborepoConfigBuilder::new(&base).build().unwrap();
+        assert_eq!(updated_config.team_slug(), Some("my-team"));
+        assert_eq!(updated_config.team_id(), None);
+
+        assert!(matches!(
+            link::link(&mut base, false, LinkTarget::Spaces).await,
+            Err(link::Error::SpacesWithAuthFlow)
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_link_spaces() {
         // user config
@@ -1,10 +1,14 @@
+use turbopath::AbsoluteSystemPath;
 use turborepo_api_client::APIClient;
 use turborepo_auth::{
     login as auth_login, sso_login as auth_sso_login, DefaultLoginServer, LoginOptions, Token,
 };
 use turborepo_telemetry::events::command::{CommandEventBuilder, LoginMethod};
 
-use crate::{cli::Error, commands::CommandBase, config, rewrite_json::set_path};
+use crate::{
+    cli::Error, commands::CommandBase, config, gitignore::ensure_turbo_is_gitignored,
+    rewrite_json::set_path,
+};
 
 pub async fn sso_login(
     base: &mut CommandBase,
@@ -16,10 +20,12 @@ pub async fn sso_login(
     let api_client: APIClient = base.api_client()?;
     let color_config = base.color_config;
     let login_url_config = base.config()?.login_url().to_string();
+    let auth_flow = base.config()?.auth_flow().cloned();
     let options = LoginOptions {
         existing_token: base.config()?.token(),
         sso_team: Some(sso_team),
         force,
+        auth_flow: auth_flow.as_ref(),
         ..LoginOptions::new(
             &color_config,
             &login_url_config,
@@ -35,30 +41,7 @@ pub async fn sso_login(
         return Ok(());
     }
 
-    let global_config_path = base.global_config_path()?;
-    let before = global_config_path
-        .read_existing_to_string()
-        .map_err(|e| config::Error::FailedToReadConfig {
-            config_path: global_config_path.clone(),
-            error: e,
-        })?
-        .unwrap_or_else(|| String::from("{}"));
-
-    let after = set_path(&before, &["token"], &format!("\"{}\"", token.into_inner()))?;
-
-    global_config_path
-        .ensure_dir()
-        .map_err(|e| config::Error::FailedToSetConfig {
-            config_path: global_config_path.clone(),
-            error: e,
-        })?;
-
-    global_config_path
-        .create_with_contents(after)
-        .map_err(|e| config::Error::FailedToSetConfig {
-            config_path: global_config_path.clone(),
-            error: e,
-        })?;
+    write_token(&base.global_config_path()?, token.into_inner())?;
 
     Ok(())
 }
@@ -73,9 +56,11 @@ pub async fn login(
     let api_client: APIClient = base.api_client()?;
     let color_config = base.color_config;
     let login_url_config = base.config()?.login_url().to_string();
+    let auth_flow = base.config()?.auth_flow().cloned();
     let options = LoginOptions {
         existing_token: base.config()?.token(),
         force,
+        auth_flow: auth_flow.as_ref(),
         ..LoginOptions::new(
             &color_config,
             &login_url_config,
@@ -91,31 +76,48 @@ pub async fn login(
         return Ok(());
     }
 
-    let global_config_path = base.global_config_path()?;
-    let before = global_config_path
+    if auth_flow.is_some() {
+        // A token for a self-hosted Remote Cache is kept with the repository's
+        // cache configuration instead of replacing the global token
+        write_token(&base.local_config_path(), token.into_inner())?;
+        ensure_turbo_is_gitignored(&base.repo_root).map_err(|error| {
+            config::Error::FailedToSetConfig {
+                config_path: base.repo_root.join_component(".gitignore"),
+                error,
+            }
+        })?;
+    } else {
+        write_token(&base.global_config_path()?, token.into_inner())?;
+    }
+
+    login_telemetry.set_success(true);
+    Ok(())
+}
+
+fn write_token(config_path: &AbsoluteSystemPath, token: &str) -> Result<(), Error> {
+    let before = config_path
         .read_existing_to_string()
         .map_err(|e| config::Error::FailedToReadConfig {
-            config_path: global_config_path.clone(),
+            config_path: config_path.to_owned(),
             error: e,
         })?
         .unwrap_or_else(|| String::from("{}"));
-    let after = set_path(&before, &["token"], &format!("\"{}\"", token.into_inner()))?;
+    let after = set_path(&before, &["token"], &format!("\"{}\"", token))?;
 
-    global_config_path
+    config_path
         .ensure_dir()
         .map_err(|e| config::Error::FailedToSetConfig {
-            config_path: global_config_path.clone(),
+            config_path: config_path.to_owned(),
             error: e,
         })?;
 
-    global_config_path
+    config_path
         .create_with_contents(after)
         .map_err(|e| config::Error::FailedToSetConfig {
-            config_path: global_config_path.clone(),
+            config_path: config_path.to_owned(),
             error: e,
         })?;
 
-    login_telemetry.set_success(true);
     Ok(())
 }
 
@@ -1,5 +1,8 @@
 //! A command for outputting info about packages and tasks in a turborepo.
 
+use std::collections::HashSet;
+
+use itertools::Itertools;
 use miette::Diagnostic;
 use serde::Serialize;
 use thiserror::Error;
@@ -83,8 +86,10 @@ struct PackageDetails<'a> {
     #[serde(skip)]
     color_config: ColorConfig,
     name: &'a str,
+    path: &'a AnchoredSystemPath,
     tasks: Vec<PackageTask<'a>>,
     dependencies: Vec<&'a str>,
+    dependents: Vec<&'a str>,
 }
 
 #[derive(Clone, Serialize)]
@@ -95,15 +100,19 @@ struct PackageDetailsList<'a> {
 #[derive(Serialize)]
 struct PackageDetailsDisplay<'a> {
     name: &'a str,
+    path: String,
     tasks: ItemsWithCount<PackageTask<'a>>,
     dependencies: Vec<&'a str>,
+    dependents: Vec<&'a str>,
 }
 
 impl<'a> From<PackageDetails<'a>> for PackageDetailsDisplay<'a> {
     fn from(val: PackageDetails<'a>) -> Self {
         PackageDetailsDisplay {
             name: val.name,
+            path: val.path.to_string(),
             dependencies: val.dependencies,
+            dependents: val.dependents,
             tasks: ItemsWithCount {
                 count: val.tasks.len(),
                 items: val.tasks,
@@ -128,7 +137,7 @@ pub async fn run(
         run_args: Box::default(),
         execution_args: Box::new(ExecutionArgs {
             filter,
-            affected,
+            affected: affected.then_some(None),
             ..Default::default()
         }),
     });
@@ -240,28 +249,22 @@ impl<'a> PackageDetails<'a> {
             name => PackageNode::Workspace(PackageName::Other(name.to_string())),
         };
 
-        let package_json = package_graph
-            .package_json(package_node.as_package_name())
+        let package_info = package_graph
+            .package_info(package_node.as_package_name())
             .ok_or_else(|| Error::PackageNotFound {
                 package: package.to_string(),
             })?;
+        let package_json = &package_info.package_json;
 
-        let transitive_dependencies = package_graph.transitive_closure(Some(&package_node));
-
-        let mut package_dep_names: Vec<&str> = transitive_dependencies
-            .into_iter()
-            .filter_map(|dependency| match dependency {
-                PackageNode::Root | PackageNode::Workspace(PackageName::Root) => None,
-                PackageNode::Workspace(PackageName::Other(dep_name)) if dep_name == package => None,
-                PackageNode::Workspace(PackageName::Other(dep_name)) => Some(dep_name.as_str()),
-            })
-            .collect();
-        package_dep_names.sort();
+        let dependencies = package_graph.transitive_closure(Some(&package_node));
+        let dependents = package_graph.ancestors(&package_node);
 
         Ok(Self {
             color_config,
             name: package,
-            dependencies: package_dep_names,
+            path: package_info.package_path(),
+            dependencies: package_names(dependencies, package),
+            dependents: package_names(dependents, package),
             tasks: package_json
                 .scripts
                 .iter()
@@ -273,16 +276,17 @@ impl<'a> PackageDetails<'a> {
     fn print(&self) {
         let name = color!(self.color_config, BOLD_GREEN, "{}", self.name);
         let depends_on = color!(self.color_config, BOLD, "depends on");
-        let dependencies = if self.dependencies.is_empty() {
-            "<no packages>".to_string()
-        } else {
-            self.dependencies.join(", ")
-        };
+        let depended_on_by = color!(self.color_config, BOLD, "depended on by");
+        println!("{} {}", name, GREY.apply_to(self.path));
         println!(
-            "{} {}: {}",
-            name,
+            "{}: {}",
             depends_on,
-            color!(self.color_config, GREY, "{}", dependencies)
+            color!(self.color_config, GREY, "{}", list(&self.dependencies))
+        );
+        println!(
+            "{}: {}",
+            depended_on_by,
+            color!(self.color_config, GREY, "{}", list(&self.dependents))
         );
         println!();
 
@@ -302,3 +306,24 @@ impl<'a> PackageDetails<'a> {
         println!();
     }
 }
+
+// The names of the workspace packages among `nodes`, other than `package`
+fn package_names<'a>(nodes: HashSet<&'a PackageNode>, package: &str) -> Vec<&'a str> {
+    nodes
+        .into_iter()
+        .filter_map(|node| match node {
+            PackageNode::Root | PackageNode::Workspace(PackageName::Root) => None,
+            PackageNode::Workspace(PackageName::Other(name)) if name == package => None,
+            PackageNode::Workspace(PackageName::Other(name)) => Some(name.as_str()),
+        })
+        .sorted()
+        .collect()
+}
+
+fn list(names: &[&str]) -> String {
+    if names.is_empty() {
+        "<no packages>".to_string()
+    } else {
+        names.join(", ")
+    }
+}
@@ -1,8 +1,12 @@
 use std::{cell::OnceCell, time::Duration};
 
+use tracing::warn;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_api_client::{APIAuth, APIClient};
-use turborepo_auth::{TURBO_TOKEN_DIR, TURBO_TOKEN_FILE};
+use turborepo_auth::{
+    oidc::{exchange_oidc_token, OidcOptions},
+    TURBO_TOKEN_DIR, TURBO_TOKEN_FILE,
+};
 use turborepo_dirs::config_dir;
 use turborepo_ui::ColorConfig;
 
@@ -12,13 +16,20 @@ use crate::{
 };
 
 pub(crate) mod bin;
+pub(crate) mod cache;
+pub(crate) mod check_globs;
+pub(crate) mod completion;
 pub(crate) mod config;
 pub(crate) mod daemon;
+pub(crate) mod explain;
 pub(crate) mod generate;
+pub(crate) mod graph;
 pub(crate) mod link;
 pub(crate) mod login;
 pub(crate) mod logout;
 pub(crate) mod ls;
+pub(crate) mod pick;
+pub(crate) mod prefetch;
 pub(crate) mod prune;
 pub(crate) mod query;
 pub(crate) mod run;
@@ -91,12 +102,15 @@ impl CommandBase {
                     .map(AbsoluteSystemPathBuf::from_cwd)
                     .transpose()?,
             )
+            .with_config_overlay(self.args.config_overlay.clone())
             .with_force(
                 self.args
                     .execution_args()
                     .and_then(|args| args.force.map(|value| value.unwrap_or(true))),
             )
             .with_log_order(self.args.execution_args().and_then(|args| args.log_order))
+            .with_log_prefix(self.args.execution_args().and_then(|args| args.log_prefix))
+            .with_log_format(self.args.execution_args().and_then(|args| args.log_format))
             .with_remote_only(
                 self.args
                     .execution_args()
@@ -107,6 +121,11 @@ impl CommandBase {
                     .run_args()
                     .and_then(|args| args.remote_cache_read_only()),
             )
+            .with_remote_cache_write_only(
+                self.args
+                    .run_args()
+                    .and_then(|args| args.remote_cache_write_only()),
+            )
             .with_run_summary(self.args.run_args().and_then(|args| args.summarize()))
             .with_allow_no_turbo_json(self.args.allow_no_turbo_json.then_some(true))
             .build()
@@ -153,6 +172,35 @@ impl CommandBase {
         }))
     }
 
+    /// Exchanges the CI provider's OIDC token for a Remote Cache token when
+    /// OIDC is configured and no token was given. If the exchange fails, the
+    /// command runs without the Remote Cache.
+    pub async fn authenticate_with_oidc(&mut self) -> Result<(), ConfigError> {
+        let config = self.config()?;
+        let Some(audience) = config.oidc_audience() else {
+            return Ok(());
+        };
+        if config.token().is_some() || !config.enabled() {
+            return Ok(());
+        }
+
+        let options = OidcOptions {
+            audience,
+            issuer: config.oidc_issuer(),
+        };
+        let api_client = self.api_client()?;
+        match exchange_oidc_token(&api_client, &options, config.team_id(), config.team_slug()).await
+        {
+            Ok(token) => {
+                self.args.token = Some(token);
+                // The token is read into the config when it's built
+                self.config = OnceCell::new();
+            }
+            Err(err) => warn!("Remote Cache disabled, failed to authenticate with OIDC: {err}"),
+        }
+        Ok(())
+    }
+
     pub fn args(&self) -> &Args {
         &self.args
     }
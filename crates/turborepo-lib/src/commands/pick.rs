@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, io};
+
+use console::Style;
+use dialoguer::FuzzySelect;
+use itertools::Itertools;
+use miette::Diagnostic;
+use thiserror::Error;
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::{ColorConfig, DialoguerTheme, BOLD, CYAN};
+
+use crate::{
+    commands::{run::get_signal, CommandBase},
+    run,
+    run::builder::RunBuilder,
+    signal::SignalHandler,
+};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("no tasks found to pick from")]
+    NoTasks,
+    #[error("canceled")]
+    UserCanceled(#[source] io::Error),
+    #[error("unable to show the task picker: {0}")]
+    Prompt(#[source] io::Error),
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    Run(#[from] run::Error),
+}
+
+/// The selection made in the picker. A package of `None` means the task
+/// should run in every package that defines it.
+#[derive(Debug, PartialEq)]
+pub struct Selection {
+    pub task: String,
+    pub package: Option<String>,
+}
+
+/// Present a fuzzy-searchable list of the tasks defined in the repository
+/// followed by the packages that define the chosen one.
+///
+/// Any filters passed on the command line narrow down the packages offered.
+pub async fn pick(base: CommandBase, telemetry: CommandEventBuilder) -> Result<Selection, Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+    let color_config = base.color_config;
+
+    let run_builder = RunBuilder::new(base)?;
+    let run = run_builder.build(&handler, telemetry).await?;
+    let potential_tasks = run.get_potential_tasks()?;
+    if potential_tasks.is_empty() {
+        return Err(Error::NoTasks);
+    }
+
+    let (tasks, task_items) = task_items(potential_tasks);
+    let task_idx = select(color_config, "Which task do you want to run?", &task_items)?;
+    let (task, packages) = &tasks[task_idx];
+
+    let package = if packages.len() > 1 && !run.opts().run_opts.single_package {
+        let mut package_items = vec!["All packages".to_string()];
+        package_items.extend(packages.iter().sorted().cloned());
+        let package_idx = select(
+            color_config,
+            &format!("Which package do you want to run {task} in?"),
+            &package_items,
+        )?;
+        (package_idx != 0).then(|| package_items.swap_remove(package_idx))
+    } else {
+        None
+    };
+
+    Ok(Selection {
+        task: task.clone(),
+        package,
+    })
+}
+
+/// Orders the tasks so those defined in the most packages come first, and
+/// labels each with the number of packages that define it
+fn task_items(
+    potential_tasks: BTreeMap<String, Vec<String>>,
+) -> (Vec<(String, Vec<String>)>, Vec<String>) {
+    let tasks = potential_tasks
+        .into_iter()
+        .sorted_by(|(a_task, a), (b_task, b)| b.len().cmp(&a.len()).then(a_task.cmp(b_task)))
+        .collect::<Vec<_>>();
+
+    let items = tasks
+        .iter()
+        .map(|(task, packages)| {
+            let count = match packages.len() {
+                1 => "1 package".to_string(),
+                n => format!("{n} packages"),
+            };
+            format!("{task} ({count})")
+        })
+        .collect();
+    (tasks, items)
+}
+
+fn select(color_config: ColorConfig, prompt: &str, items: &[String]) -> Result<usize, Error> {
+    let theme = DialoguerTheme {
+        active_item_style: Style::new().cyan().bold(),
+        active_item_prefix: Style::new().cyan().bold().apply_to(">".to_string()),
+        prompt_prefix: Style::new().dim().bold().apply_to("?".to_string()),
+        values_style: Style::new().cyan(),
+        ..DialoguerTheme::default()
+    };
+
+    let prompt = format!(
+        "{}\n  {}",
+        color_config.apply(BOLD.apply_to(prompt)),
+        color_config.apply(CYAN.apply_to("[Use arrows to move, type to filter]"))
+    );
+
+    FuzzySelect::with_theme(&theme)
+        .with_prompt(prompt)
+        .items(items)
+        .default(0)
+        .interact()
+        .map_err(|e| match e.kind() {
+            // Ctrl-C while the picker is open
+            io::ErrorKind::Interrupted => Error::UserCanceled(e),
+            _ => Error::Prompt(e),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::task_items;
+
+    #[test]
+    fn test_task_items() {
+        let potential_tasks = BTreeMap::from([
+            ("lint".to_string(), vec!["web".to_string()]),
+            (
+                "build".to_string(),
+                vec!["docs".to_string(), "web".to_string()],
+            ),
+            ("dev".to_string(), vec!["docs".to_string()]),
+        ]);
+
+        let (tasks, items) = task_items(potential_tasks);
+        assert_eq!(
+            tasks
+                .iter()
+                .map(|(task, _)| task.as_str())
+                .collect::<Vec<_>>(),
+            ["build", "dev", "lint"]
+        );
+        assert_eq!(
+            items,
+            ["build (2 packages)", "dev (1 package)", "lint (1 package)"]
+        );
+    }
+}
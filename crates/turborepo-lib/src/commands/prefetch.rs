@@ -0,0 +1,47 @@
+use turborepo_telemetry::events::command::CommandEventBuilder;
+
+use crate::{
+    cli::Command,
+    commands::{run::get_signal, CommandBase},
+    run,
+    run::builder::RunBuilder,
+    signal::SignalHandler,
+};
+
+/// Hashes the selected tasks and downloads any matching remote artifacts into
+/// the local cache so a later `turbo run` can restore them without the network
+pub async fn run(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, run::Error> {
+    let signal = get_signal()?;
+    let handler = SignalHandler::new(signal);
+
+    let Some(Command::Prefetch(execution_args)) = &base.args().command else {
+        unreachable!()
+    };
+
+    let mut new_base = base.clone();
+    new_base.args_mut().command = Some(Command::Run {
+        run_args: Box::default(),
+        execution_args: execution_args.clone(),
+    });
+
+    let run_fut = async {
+        let run = RunBuilder::new(new_base)?
+            .hide_prelude()
+            .prefetch()
+            .build(&handler, telemetry)
+            .await?;
+        run.run(None, false).await
+    };
+
+    tokio::select! {
+        biased;
+        _ = handler.done() => {
+            // We caught a signal, which already notified the subscribers
+            Ok(1)
+        }
+        result = run_fut => {
+            handler.close().await;
+            result
+        },
+    }
+}
@@ -1,12 +1,16 @@
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use std::sync::OnceLock;
+use std::{fs, sync::OnceLock};
 
+use camino::Utf8Path;
+use flate2::{Compression, GzBuilder};
 use lazy_static::lazy_static;
 use miette::Diagnostic;
+use tar::HeaderMode;
 use tracing::trace;
 use turbopath::{
-    AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf, RelativeUnixPath,
+    AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
+    RelativeUnixPath,
 };
 use turborepo_repository::{
     package_graph::{self, PackageGraph, PackageName, PackageNode},
@@ -47,8 +51,11 @@ pub enum Error {
     MissingWorkspace(PackageName),
     #[error("Cannot prune without parsed lockfile")]
     MissingLockfile,
-    #[error("Prune is not supported for Bun")]
-    BunUnsupported,
+    #[error(
+        "Prune is not supported for Bun's binary lockfile. Run `bun install --save-text-lockfile` \
+         to switch to bun.lock."
+    )]
+    BunBinaryLockfile,
     #[error("Unable to read config: {0}")]
     Config(#[from] crate::config::Error),
 }
@@ -93,24 +100,58 @@ pub async fn prune(
     scope: &[String],
     docker: bool,
     output_dir: &str,
+    tar: Option<&Utf8Path>,
     telemetry: CommandEventBuilder,
 ) -> Result<(), Error> {
     telemetry.track_arg_usage("docker", docker);
     telemetry.track_arg_usage("out-dir", output_dir != DEFAULT_OUTPUT_DIR);
+    telemetry.track_arg_usage("tar", tar.is_some());
+
+    // A tarball is written from a pruned copy in a temporary directory
+    let Some(tar) = tar else {
+        return prune_to_directory(base, scope, docker, output_dir, None, telemetry).await;
+    };
+    let tar_path = AbsoluteSystemPathBuf::from_unknown(&base.repo_root, tar);
+    let tmp_dir = tempfile::tempdir()?;
+    let tmp_path = AbsoluteSystemPathBuf::try_from(tmp_dir.path())?;
+    prune_to_directory(
+        base,
+        scope,
+        docker,
+        tmp_path.as_str(),
+        Some(&tar_path),
+        telemetry,
+    )
+    .await?;
+    write_tarball(&tmp_path, &tar_path)
+}
 
+async fn prune_to_directory(
+    base: &CommandBase,
+    scope: &[String],
+    docker: bool,
+    output_dir: &str,
+    display_path: Option<&AbsoluteSystemPath>,
+    telemetry: CommandEventBuilder,
+) -> Result<(), Error> {
     let prune = Prune::new(base, scope, docker, output_dir, telemetry).await?;
 
+    // Turbo can only write Bun's text lockfile
+    let package_manager = prune.package_graph.package_manager();
     if matches!(
-        prune.package_graph.package_manager(),
+        package_manager,
         turborepo_repository::package_manager::PackageManager::Bun
-    ) {
-        return Err(Error::BunUnsupported);
+    ) && package_manager.lockfile_path(&prune.root).file_name()
+        != Some(package_manager.lockfile_name())
+    {
+        return Err(Error::BunBinaryLockfile);
     }
 
     println!(
         "Generating pruned monorepo for {} in {}",
         base.color_config.apply(BOLD.apply_to(scope.join(", "))),
-        base.color_config.apply(BOLD.apply_to(&prune.out_directory)),
+        base.color_config
+            .apply(BOLD.apply_to(display_path.unwrap_or(&prune.out_directory))),
     );
 
     if let Some(workspace_config_path) = prune
@@ -237,6 +278,43 @@ pub async fn prune(
     Ok(())
 }
 
+/// Writes the contents of `dir` to a gzipped tarball at `path`. Entries are
+/// sorted and their timestamps, owners, and permissions are normalized, so
+/// pruning the same sources always produces the same bytes.
+fn write_tarball(dir: &AbsoluteSystemPath, path: &AbsoluteSystemPath) -> Result<(), Error> {
+    path.ensure_dir()?;
+    let encoder = GzBuilder::new()
+        .mtime(0)
+        .write(path.create()?, Compression::default());
+    let mut archive = tar::Builder::new(encoder);
+    archive.mode(HeaderMode::Deterministic);
+    archive.follow_symlinks(false);
+    append_directory(&mut archive, dir, dir)?;
+    archive.into_inner()?.finish()?;
+    Ok(())
+}
+
+fn append_directory<W: std::io::Write>(
+    archive: &mut tar::Builder<W>,
+    root: &AbsoluteSystemPath,
+    dir: &AbsoluteSystemPath,
+) -> Result<(), Error> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| Ok(AbsoluteSystemPathBuf::try_from(entry?.path())?))
+        .collect::<Result<Vec<_>, Error>>()?;
+    entries.sort();
+    for entry in entries {
+        let name = root.anchor(&entry)?.to_unix();
+        if entry.symlink_metadata()?.is_dir() {
+            archive.append_path_with_name(&entry, format!("{name}/"))?;
+            append_directory(archive, root, &entry)?;
+        } else {
+            archive.append_path_with_name(&entry, name.as_str())?;
+        }
+    }
+    Ok(())
+}
+
 struct Prune<'a> {
     package_graph: PackageGraph,
     root: AbsoluteSystemPathBuf,
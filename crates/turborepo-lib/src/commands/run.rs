@@ -32,10 +32,12 @@ pub fn get_signal() -> Result<impl Future<Output = Option<()>>, run::Error> {
     })
 }
 
-pub async fn run(base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, run::Error> {
+pub async fn run(mut base: CommandBase, telemetry: CommandEventBuilder) -> Result<i32, run::Error> {
     let signal = get_signal()?;
     let handler = SignalHandler::new(signal);
 
+    base.authenticate_with_oidc().await?;
+
     let run_builder = RunBuilder::new(base)?;
 
     let run_fut = async {
@@ -6,11 +6,16 @@ use std::{
 use clap::ValueEnum;
 use itertools::Itertools;
 use turbopath::AbsoluteSystemPathBuf;
+use turborepo_cache::SignatureMismatchPolicy;
 
-use super::{ConfigurationOptions, Error, ResolvedConfigurationOptions};
+use super::{
+    parse_cache_max_age, parse_cache_max_size, parse_poll_interval,
+    validate_cache_compression_level, validate_run_summary_upload_url, ConfigurationOptions, Error,
+    ResolvedConfigurationOptions,
+};
 use crate::{
-    cli::{EnvMode, LogOrder},
-    turbo_json::UIMode,
+    cli::{EnvMode, LogFormat, LogOrder, LogPrefix},
+    turbo_json::{DependencyCycles, HashMode, UIMode, Watcher},
 };
 
 const TURBO_MAPPING: &[(&str, &str)] = [
@@ -21,6 +26,11 @@ const TURBO_MAPPING: &[(&str, &str)] = [
     ("turbo_token", "token"),
     ("turbo_remote_cache_timeout", "timeout"),
     ("turbo_remote_cache_upload_timeout", "upload_timeout"),
+    ("turbo_remote_cache_retries", "retries"),
+    ("turbo_remote_cache_chunking", "chunking"),
+    ("turbo_remote_cache_storage_url", "storage_url"),
+    ("turbo_oidc_audience", "oidc_audience"),
+    ("turbo_oidc_issuer", "oidc_issuer"),
     ("turbo_ui", "ui"),
     (
         "turbo_dangerously_disable_package_manager_check",
@@ -29,15 +39,31 @@ const TURBO_MAPPING: &[(&str, &str)] = [
     ("turbo_daemon", "daemon"),
     ("turbo_env_mode", "env_mode"),
     ("turbo_cache_dir", "cache_dir"),
+    ("turbo_cache_compression_level", "cache_compression_level"),
+    ("turbo_cache_max_size", "cache_max_size"),
+    ("turbo_cache_max_age", "cache_max_age"),
     ("turbo_preflight", "preflight"),
     ("turbo_scm_base", "scm_base"),
     ("turbo_scm_head", "scm_head"),
     ("turbo_root_turbo_json", "root_turbo_json_path"),
+    ("turbo_config_overlay", "config_overlay"),
     ("turbo_force", "force"),
     ("turbo_log_order", "log_order"),
+    ("turbo_log_prefix", "log_prefix"),
+    ("turbo_log_format", "log_format"),
+    ("turbo_dependency_cycles", "dependency_cycles"),
+    ("turbo_hash_mode", "hash_mode"),
+    ("turbo_watcher", "watcher"),
+    ("turbo_poll_interval", "poll_interval"),
     ("turbo_remote_only", "remote_only"),
     ("turbo_remote_cache_read_only", "remote_cache_read_only"),
+    ("turbo_remote_cache_write_only", "remote_cache_write_only"),
+    (
+        "turbo_remote_cache_signature_mismatch",
+        "signature_mismatch",
+    ),
     ("turbo_run_summary", "run_summary"),
+    ("turbo_run_summary_upload_url", "run_summary_upload_url"),
     ("turbo_allow_no_turbo_json", "allow_no_turbo_json"),
 ]
 .as_slice();
@@ -86,6 +112,8 @@ impl ResolvedConfigurationOptions for EnvVars {
         let force = self.truthy_value("force").flatten();
         let remote_only = self.truthy_value("remote_only").flatten();
         let remote_cache_read_only = self.truthy_value("remote_cache_read_only").flatten();
+        let remote_cache_write_only = self.truthy_value("remote_cache_write_only").flatten();
+        let chunking = self.truthy_value("chunking").flatten();
         let run_summary = self.truthy_value("run_summary").flatten();
         let allow_no_turbo_json = self.truthy_value("allow_no_turbo_json").flatten();
 
@@ -104,6 +132,45 @@ impl ResolvedConfigurationOptions for EnvVars {
             .transpose()
             .map_err(Error::InvalidUploadTimeout)?;
 
+        let retries = self
+            .output_map
+            .get("retries")
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(Error::InvalidRemoteCacheRetries)?;
+
+        let cache_compression_level = self
+            .output_map
+            .get("cache_compression_level")
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse()
+                    .map_err(Error::InvalidCacheCompressionLevel)
+                    .and_then(validate_cache_compression_level)
+            })
+            .transpose()?;
+
+        let cache_max_size = self
+            .output_map
+            .get("cache_max_size")
+            .filter(|s| !s.is_empty())
+            .map(|s| parse_cache_max_size(s))
+            .transpose()?;
+
+        let cache_max_age = self
+            .output_map
+            .get("cache_max_age")
+            .filter(|s| !s.is_empty())
+            .map(|s| parse_cache_max_age(s))
+            .transpose()?;
+
+        let run_summary_upload_url = self
+            .output_map
+            .get("run_summary_upload_url")
+            .filter(|s| !s.is_empty())
+            .map(|s| validate_run_summary_upload_url(s))
+            .transpose()?;
+
         // Process experimentalUI
         let ui =
             self.truthy_value("ui")
@@ -149,6 +216,104 @@ impl ResolvedConfigurationOptions for EnvVars {
                 )
             })?;
 
+        let signature_mismatch = self
+            .output_map
+            .get("signature_mismatch")
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                SignatureMismatchPolicy::VARIANTS
+                    .into_iter()
+                    .find(|policy| policy.to_string().eq_ignore_ascii_case(s))
+                    .ok_or_else(|| {
+                        Error::InvalidSignatureMismatch(
+                            SignatureMismatchPolicy::VARIANTS.iter().join(", "),
+                        )
+                    })
+            })
+            .transpose()?;
+
+        let log_prefix = self
+            .output_map
+            .get("log_prefix")
+            .filter(|s| !s.is_empty())
+            .map(|s| LogPrefix::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidLogPrefix(
+                    LogPrefix::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let log_format = self
+            .output_map
+            .get("log_format")
+            .filter(|s| !s.is_empty())
+            .map(|s| LogFormat::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidLogFormat(
+                    LogFormat::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let dependency_cycles = self
+            .output_map
+            .get("dependency_cycles")
+            .filter(|s| !s.is_empty())
+            .map(|s| DependencyCycles::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidDependencyCycles(
+                    DependencyCycles::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let hash_mode = self
+            .output_map
+            .get("hash_mode")
+            .filter(|s| !s.is_empty())
+            .map(|s| HashMode::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidHashMode(
+                    HashMode::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let watcher = self
+            .output_map
+            .get("watcher")
+            .filter(|s| !s.is_empty())
+            .map(|s| Watcher::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidWatcher(
+                    Watcher::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let poll_interval = self
+            .output_map
+            .get("poll_interval")
+            .filter(|s| !s.is_empty())
+            .map(|s| parse_poll_interval(s))
+            .transpose()?;
+
         // We currently don't pick up a Spaces ID via env var, we likely won't
         // continue using the Spaces name, we can add an env var when we have the
         // name we want to stick with.
@@ -160,10 +325,21 @@ impl ResolvedConfigurationOptions for EnvVars {
             team_slug: self.output_map.get("team_slug").cloned(),
             team_id: self.output_map.get("team_id").cloned(),
             token: self.output_map.get("token").cloned(),
+            storage_url: self.output_map.get("storage_url").cloned(),
+            oidc_audience: self.output_map.get("oidc_audience").cloned(),
+            oidc_issuer: self.output_map.get("oidc_issuer").cloned(),
+            auth: None,
             scm_base: self.output_map.get("scm_base").cloned(),
             scm_head: self.output_map.get("scm_head").cloned(),
+            config_overlay: self.output_map.get("config_overlay").cloned(),
+            run_summary_upload_url,
+            // Headers can only be set in turbo.json, where their values can refer to
+            // environment variables
+            run_summary_upload_headers: None,
+            exit_codes: None,
             // Processed booleans
             signature,
+            signature_mismatch,
             preflight,
             enabled,
             ui,
@@ -172,17 +348,29 @@ impl ResolvedConfigurationOptions for EnvVars {
             force,
             remote_only,
             remote_cache_read_only,
+            remote_cache_write_only,
+            chunking,
             run_summary,
             allow_no_turbo_json,
 
             // Processed numbers
             timeout,
             upload_timeout,
+            retries,
+            cache_compression_level,
+            cache_max_size,
+            cache_max_age,
+            poll_interval,
             spaces_id,
             env_mode,
             cache_dir,
             root_turbo_json_path,
             log_order,
+            log_prefix,
+            log_format,
+            dependency_cycles,
+            hash_mode,
+            watcher,
         };
 
         Ok(output)
@@ -224,7 +412,10 @@ fn map_environment<'a>(
 
 #[cfg(test)]
 mod test {
+    use std::time::Duration;
+
     use camino::Utf8PathBuf;
+    use turborepo_cache::EvictionPolicy;
 
     use super::*;
     use crate::{
@@ -258,6 +449,8 @@ mod test {
             "turbo_remote_cache_timeout".into(),
             turbo_remote_cache_timeout.to_string().into(),
         );
+        env.insert("turbo_remote_cache_retries".into(), "5".into());
+        env.insert("turbo_remote_cache_chunking".into(), "1".into());
         env.insert("turbo_ui".into(), "true".into());
         env.insert(
             "turbo_dangerously_disable_package_manager_check".into(),
@@ -267,12 +460,39 @@ mod test {
         env.insert("turbo_preflight".into(), "true".into());
         env.insert("turbo_env_mode".into(), "strict".into());
         env.insert("turbo_cache_dir".into(), cache_dir.clone().into());
+        env.insert("turbo_cache_compression_level".into(), "19".into());
+        env.insert("turbo_cache_max_size".into(), "10GB".into());
+        env.insert("turbo_cache_max_age".into(), "7d".into());
         env.insert("turbo_root_turbo_json".into(), root_turbo_json.into());
+        env.insert("turbo_config_overlay".into(), "ci".into());
         env.insert("turbo_force".into(), "1".into());
         env.insert("turbo_log_order".into(), "grouped".into());
+        env.insert("turbo_log_prefix".into(), "none".into());
+        env.insert("turbo_log_format".into(), "ndjson".into());
+        env.insert("turbo_dependency_cycles".into(), "warn".into());
+        env.insert("turbo_hash_mode".into(), "filesystem".into());
+        env.insert("turbo_watcher".into(), "watchman".into());
+        env.insert("turbo_poll_interval".into(), "500ms".into());
         env.insert("turbo_remote_only".into(), "1".into());
         env.insert("turbo_remote_cache_read_only".into(), "1".into());
+        env.insert("turbo_remote_cache_write_only".into(), "1".into());
+        env.insert(
+            "turbo_remote_cache_signature_mismatch".into(),
+            "warn".into(),
+        );
+        env.insert(
+            "turbo_remote_cache_storage_url".into(),
+            "gs://my-bucket/turbo".into(),
+        );
+        env.insert(
+            "turbo_oidc_audience".into(),
+            "https://cache.example.com".into(),
+        );
         env.insert("turbo_run_summary".into(), "true".into());
+        env.insert(
+            "turbo_run_summary_upload_url".into(),
+            "https://example.com/runs".into(),
+        );
         env.insert("turbo_allow_no_turbo_json".into(), "true".into());
 
         let config = EnvVars::new(&env)
@@ -282,10 +502,35 @@ mod test {
         assert!(config.preflight());
         assert!(config.force());
         assert_eq!(config.log_order(), LogOrder::Grouped);
+        assert_eq!(config.log_prefix(), LogPrefix::None);
+        assert_eq!(config.log_format(), LogFormat::Ndjson);
+        assert_eq!(config.dependency_cycles(), DependencyCycles::Warn);
+        assert_eq!(config.hash_mode(), HashMode::Filesystem);
+        assert_eq!(config.watcher(), Watcher::Watchman);
+        assert_eq!(config.poll_interval(), Duration::from_millis(500));
         assert!(config.remote_only());
         assert!(config.remote_cache_read_only());
+        assert!(config.remote_cache_write_only());
+        assert!(config.chunking());
+        assert_eq!(config.signature_mismatch(), SignatureMismatchPolicy::Warn);
+        assert_eq!(config.storage_url(), Some("gs://my-bucket/turbo"));
+        assert_eq!(config.oidc_audience(), Some("https://cache.example.com"));
         assert!(config.run_summary());
+        assert_eq!(
+            config.run_summary_upload_url(),
+            Some("https://example.com/runs")
+        );
         assert!(config.allow_no_turbo_json());
+        assert_eq!(config.config_overlay(), Some("ci"));
+        assert_eq!(config.cache_compression_level(), 19);
+        assert_eq!(config.retries(), 5);
+        assert_eq!(
+            config.cache_eviction_policy(),
+            EvictionPolicy {
+                max_size: Some(10_000_000_000),
+                max_age: Some(Duration::from_secs(7 * 24 * 60 * 60)),
+            }
+        );
         assert_eq!(turbo_api, config.api_url.unwrap());
         assert_eq!(turbo_login, config.login_url.unwrap());
         assert_eq!(turbo_team, config.team_slug.unwrap());
@@ -318,10 +563,12 @@ mod test {
         env.insert("turbo_scm_head".into(), "".into());
         env.insert("turbo_scm_base".into(), "".into());
         env.insert("turbo_root_turbo_json".into(), "".into());
+        env.insert("turbo_config_overlay".into(), "".into());
         env.insert("turbo_force".into(), "".into());
         env.insert("turbo_log_order".into(), "".into());
         env.insert("turbo_remote_only".into(), "".into());
         env.insert("turbo_remote_cache_read_only".into(), "".into());
+        env.insert("turbo_remote_cache_write_only".into(), "".into());
         env.insert("turbo_run_summary".into(), "".into());
         env.insert("turbo_allow_no_turbo_json".into(), "".into());
 
@@ -341,10 +588,12 @@ mod test {
         assert_eq!(config.scm_base(), None);
         assert_eq!(config.scm_head(), None);
         assert_eq!(config.root_turbo_json_path, None);
+        assert_eq!(config.config_overlay(), None);
         assert!(!config.force());
         assert_eq!(config.log_order(), LogOrder::Auto);
         assert!(!config.remote_only());
         assert!(!config.remote_cache_read_only());
+        assert!(!config.remote_cache_write_only());
         assert!(!config.run_summary());
         assert!(!config.allow_no_turbo_json());
     }
@@ -3,7 +3,13 @@ mod file;
 mod override_env;
 mod turbo_json;
 
-use std::{collections::HashMap, ffi::OsString, io};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::OsString,
+    io,
+    net::IpAddr,
+    time::Duration,
+};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use convert_case::{Case, Casing};
@@ -19,12 +25,14 @@ use thiserror::Error;
 use tracing::debug;
 use turbo_json::TurboJsonReader;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_auth::AuthFlow;
+use turborepo_cache::{EvictionPolicy, SignatureMismatchPolicy};
 use turborepo_errors::TURBO_SITE;
 use turborepo_repository::package_graph::PackageName;
 
-pub use crate::turbo_json::{RawTurboJson, UIMode};
+pub use crate::turbo_json::{DependencyCycles, ExitCodes, HashMode, RawTurboJson, UIMode, Watcher};
 use crate::{
-    cli::{EnvMode, LogOrder},
+    cli::{EnvMode, LogFormat, LogOrder, LogPrefix},
     commands::CommandBase,
     turbo_json::CONFIG_FILE,
 };
@@ -141,6 +149,15 @@ pub enum Error {
         #[source_code]
         text: NamedSource,
     },
+    #[error(
+        "`$TURBO_ROOT$` can only be used at the start of an input, like `$TURBO_ROOT$/dir/**`"
+    )]
+    InvalidTurboRootUse {
+        #[label("`$TURBO_ROOT$` used here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
     #[error("No \"extends\" key found")]
     NoExtends {
         #[label("add extends key here")]
@@ -148,6 +165,69 @@ pub enum Error {
         #[source_code]
         text: NamedSource,
     },
+    #[error(
+        "\"{preset}\" is not a package name. The root turbo.json can only extend presets from \
+         packages"
+    )]
+    InvalidPreset {
+        preset: String,
+        #[label("preset referenced here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("Unable to find preset \"{preset}\" at {path}")]
+    MissingPreset {
+        preset: String,
+        path: String,
+        #[label("preset referenced here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error("Presets can't extend other configuration")]
+    PresetExtends {
+        #[label("extends found here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error(
+        "Invalid config overlay \"{0}\". Overlay names may only contain letters, numbers, '-' and \
+         '_'"
+    )]
+    InvalidConfigOverlay(String),
+    #[error("Unable to find config overlay at {0}")]
+    MissingConfigOverlay(String),
+    #[error("Config overlays can't extend other configuration")]
+    OverlayExtends {
+        #[label("extends found here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error(
+        "Invalid matrix value \"{value}\". Matrix values must be unique and may only contain \
+         letters, numbers, '-', '_' and '.'"
+    )]
+    InvalidMatrixValue {
+        value: String,
+        #[label("matrix value defined here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
+    #[error(
+        "Invalid inputsFrom entry \"{task}\". Tasks can only take inputs from other tasks in the \
+         same package"
+    )]
+    InvalidInputsFrom {
+        task: String,
+        #[label("task referenced here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+    },
     #[error("Tasks cannot be marked as interactive and cacheable")]
     InteractiveNoCacheable {
         #[label("marked interactive here")]
@@ -175,10 +255,47 @@ pub enum Error {
     InvalidRemoteCacheTimeout(#[source] std::num::ParseIntError),
     #[error("TURBO_REMOTE_CACHE_UPLOAD_TIMEOUT: error parsing timeout.")]
     InvalidUploadTimeout(#[source] std::num::ParseIntError),
+    #[error("TURBO_REMOTE_CACHE_RETRIES: error parsing retries.")]
+    InvalidRemoteCacheRetries(#[source] std::num::ParseIntError),
     #[error("TURBO_PREFLIGHT should be either 1 or 0.")]
     InvalidPreflight,
+    #[error("TURBO_CACHE_COMPRESSION_LEVEL: error parsing compression level.")]
+    InvalidCacheCompressionLevel(#[source] std::num::ParseIntError),
+    #[error(
+        "cache compression level must be between {} and {}, got {0}",
+        MIN_CACHE_COMPRESSION_LEVEL,
+        MAX_CACHE_COMPRESSION_LEVEL
+    )]
+    CacheCompressionLevelOutOfRange(i32),
+    #[error(
+        "invalid cache size `{0}`: expected a number of bytes with an optional unit, like `500MB` \
+         or `10GB`"
+    )]
+    InvalidCacheMaxSize(String),
+    #[error("invalid cache age `{0}`: expected a duration, like `12h` or `7d`")]
+    InvalidCacheMaxAge(String, #[source] humantime::DurationError),
+    #[error("invalid run summary upload URL `{0}`: expected an https URL, or http for localhost")]
+    InvalidRunSummaryUploadUrl(String),
+    #[error("invalid exit code `{0}`: exit codes must be between 0 and 255")]
+    InvalidExitCode(i32),
+    #[error("TURBO_REMOTE_CACHE_SIGNATURE_MISMATCH should be one of: {0}")]
+    InvalidSignatureMismatch(String),
     #[error("TURBO_LOG_ORDER should be one of: {0}")]
     InvalidLogOrder(String),
+    #[error("TURBO_LOG_PREFIX should be one of: {0}")]
+    InvalidLogPrefix(String),
+    #[error("TURBO_LOG_FORMAT should be one of: {0}")]
+    InvalidLogFormat(String),
+    #[error("TURBO_DEPENDENCY_CYCLES should be one of: {0}")]
+    InvalidDependencyCycles(String),
+    #[error("TURBO_HASH_MODE should be one of: {0}")]
+    InvalidHashMode(String),
+    #[error("TURBO_WATCHER should be one of: {0}")]
+    InvalidWatcher(String),
+    #[error(
+        "invalid poll interval `{0}`: expected a duration greater than zero, like `500ms` or `5s`"
+    )]
+    InvalidPollInterval(String),
     #[error(transparent)]
     #[diagnostic(transparent)]
     TurboJsonParseError(#[from] crate::turbo_json::parser::Error),
@@ -198,6 +315,11 @@ const DEFAULT_API_URL: &str = "https://vercel.com/api";
 const DEFAULT_LOGIN_URL: &str = "https://vercel.com";
 const DEFAULT_TIMEOUT: u64 = 30;
 const DEFAULT_UPLOAD_TIMEOUT: u64 = 60;
+const DEFAULT_RETRIES: u32 = 3;
+// zstd accepts levels up to 22, but anything past 19 needs a larger window
+// than older clients are willing to decompress.
+const MIN_CACHE_COMPRESSION_LEVEL: i32 = 1;
+const MAX_CACHE_COMPRESSION_LEVEL: i32 = 19;
 
 // We intentionally don't derive Serialize so that different parts
 // of the code that want to display the config can tune how they
@@ -231,10 +353,22 @@ pub struct ConfigurationOptions {
     /// corresponds to env var TURBO_TOKEN
     pub(crate) token: Option<String>,
     pub(crate) signature: Option<bool>,
+    pub(crate) signature_mismatch: Option<SignatureMismatchPolicy>,
     pub(crate) preflight: Option<bool>,
     pub(crate) timeout: Option<u64>,
     pub(crate) upload_timeout: Option<u64>,
+    pub(crate) retries: Option<u32>,
+    pub(crate) chunking: Option<bool>,
     pub(crate) enabled: Option<bool>,
+    /// corresponds to env var TURBO_REMOTE_CACHE_STORAGE_URL
+    pub(crate) storage_url: Option<String>,
+    /// corresponds to env var TURBO_OIDC_AUDIENCE
+    pub(crate) oidc_audience: Option<String>,
+    /// corresponds to env var TURBO_OIDC_ISSUER
+    pub(crate) oidc_issuer: Option<String>,
+    /// Login flow for a self-hosted Remote Cache, only read from
+    /// `.turbo/config.json` and the global config
+    pub(crate) auth: Option<AuthFlow>,
     pub(crate) spaces_id: Option<String>,
     #[serde(rename = "ui")]
     pub(crate) ui: Option<UIMode>,
@@ -247,14 +381,38 @@ pub struct ConfigurationOptions {
     pub(crate) scm_head: Option<String>,
     #[serde(rename = "cacheDir")]
     pub(crate) cache_dir: Option<Utf8PathBuf>,
+    pub(crate) cache_compression_level: Option<i32>,
+    /// Maximum size of the local cache in bytes
+    pub(crate) cache_max_size: Option<u64>,
+    /// Maximum time in seconds a local cache artifact may go unused
+    pub(crate) cache_max_age: Option<u64>,
     // This is skipped as we never want this to be stored in a file
     #[serde(skip)]
     pub(crate) root_turbo_json_path: Option<AbsoluteSystemPathBuf>,
+    /// Name of the `turbo.<name>.json` overlay to merge over the root
+    /// turbo.json, corresponds to env var TURBO_CONFIG_OVERLAY
+    #[serde(skip)]
+    pub(crate) config_overlay: Option<String>,
     pub(crate) force: Option<bool>,
     pub(crate) log_order: Option<LogOrder>,
+    pub(crate) log_prefix: Option<LogPrefix>,
+    pub(crate) log_format: Option<LogFormat>,
+    pub(crate) dependency_cycles: Option<DependencyCycles>,
+    pub(crate) hash_mode: Option<HashMode>,
+    pub(crate) watcher: Option<Watcher>,
+    /// How often the polling file watcher scans for changes, in milliseconds
+    pub(crate) poll_interval: Option<u64>,
     pub(crate) remote_only: Option<bool>,
     pub(crate) remote_cache_read_only: Option<bool>,
+    pub(crate) remote_cache_write_only: Option<bool>,
     pub(crate) run_summary: Option<bool>,
+    /// Endpoint run summaries are sent to, corresponds to env var
+    /// TURBO_RUN_SUMMARY_UPLOAD_URL
+    pub(crate) run_summary_upload_url: Option<String>,
+    /// Headers sent along with run summaries, `${VAR}` in a value is replaced
+    /// with the environment variable `VAR`
+    pub(crate) run_summary_upload_headers: Option<BTreeMap<String, String>>,
+    pub(crate) exit_codes: Option<ExitCodes>,
     pub(crate) allow_no_turbo_json: Option<bool>,
 }
 
@@ -266,6 +424,88 @@ pub struct TurborepoConfigBuilder {
     environment: Option<HashMap<OsString, OsString>>,
 }
 
+fn validate_cache_compression_level(level: i32) -> Result<i32, Error> {
+    (MIN_CACHE_COMPRESSION_LEVEL..=MAX_CACHE_COMPRESSION_LEVEL)
+        .contains(&level)
+        .then_some(level)
+        .ok_or(Error::CacheCompressionLevelOutOfRange(level))
+}
+
+pub(crate) fn parse_cache_max_size(size: &str) -> Result<u64, Error> {
+    let invalid = || Error::InvalidCacheMaxSize(size.to_string());
+    let trimmed = size.trim();
+    let unit_start = trimmed
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(trimmed.len());
+    let (value, unit) = trimmed.split_at(unit_start);
+    let value: f64 = value.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1000,
+        "m" | "mb" => 1000_u64.pow(2),
+        "g" | "gb" => 1000_u64.pow(3),
+        "t" | "tb" => 1000_u64.pow(4),
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        "tib" => 1 << 40,
+        _ => return Err(invalid()),
+    };
+    Ok((value * multiplier as f64).round() as u64)
+}
+
+pub(crate) fn parse_cache_max_age(age: &str) -> Result<u64, Error> {
+    humantime::parse_duration(age)
+        .map(|duration| duration.as_secs())
+        .map_err(|e| Error::InvalidCacheMaxAge(age.to_string(), e))
+}
+
+pub(crate) fn parse_poll_interval(interval: &str) -> Result<u64, Error> {
+    humantime::parse_duration(interval)
+        .ok()
+        .map(|duration| duration.as_millis() as u64)
+        .filter(|millis| *millis > 0)
+        .ok_or_else(|| Error::InvalidPollInterval(interval.to_string()))
+}
+
+pub(crate) fn validate_exit_codes(exit_codes: ExitCodes) -> Result<ExitCodes, Error> {
+    let ExitCodes {
+        task_failure,
+        no_tasks,
+        remote_cache_error,
+        warnings,
+    } = exit_codes;
+    for code in [task_failure, no_tasks, remote_cache_error, warnings]
+        .into_iter()
+        .flatten()
+    {
+        if !(0..=255).contains(&code) {
+            return Err(Error::InvalidExitCode(code));
+        }
+    }
+    Ok(exit_codes)
+}
+
+// Summaries carry commands, environment variable names and SCM details, so
+// they shouldn't travel unencrypted anywhere but the local machine
+pub(crate) fn validate_run_summary_upload_url(url: &str) -> Result<String, Error> {
+    let invalid = || Error::InvalidRunSummaryUploadUrl(url.to_string());
+    let parsed = reqwest::Url::parse(url).map_err(|_| invalid())?;
+    let is_local = parsed.host_str().is_some_and(|host| {
+        host == "localhost"
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .is_ok_and(|ip| ip.is_loopback())
+    });
+    match parsed.scheme() {
+        "https" => Ok(url.to_string()),
+        "http" if is_local => Ok(url.to_string()),
+        _ => Err(invalid()),
+    }
+}
+
 // Getters
 impl ConfigurationOptions {
     pub fn api_url(&self) -> &str {
@@ -294,14 +534,41 @@ impl ConfigurationOptions {
         self.signature.unwrap_or_default()
     }
 
+    pub fn signature_mismatch(&self) -> SignatureMismatchPolicy {
+        self.signature_mismatch.unwrap_or_default()
+    }
+
     pub fn enabled(&self) -> bool {
         self.enabled.unwrap_or(true)
     }
 
+    pub fn storage_url(&self) -> Option<&str> {
+        non_empty_str(self.storage_url.as_deref())
+    }
+
+    /// OIDC authentication is used when an audience is configured
+    pub fn oidc_audience(&self) -> Option<&str> {
+        non_empty_str(self.oidc_audience.as_deref())
+    }
+
+    pub fn oidc_issuer(&self) -> Option<&str> {
+        non_empty_str(self.oidc_issuer.as_deref())
+    }
+
+    pub fn auth_flow(&self) -> Option<&AuthFlow> {
+        self.auth.as_ref()
+    }
+
     pub fn preflight(&self) -> bool {
         self.preflight.unwrap_or_default()
     }
 
+    /// Whether large artifacts are uploaded to the remote cache in
+    /// content-defined chunks
+    pub fn chunking(&self) -> bool {
+        self.chunking.unwrap_or_default()
+    }
+
     /// Note: 0 implies no timeout
     pub fn timeout(&self) -> u64 {
         self.timeout.unwrap_or(DEFAULT_TIMEOUT)
@@ -312,6 +579,11 @@ impl ConfigurationOptions {
         self.upload_timeout.unwrap_or(DEFAULT_UPLOAD_TIMEOUT)
     }
 
+    /// Note: 0 disables retries
+    pub fn retries(&self) -> u32 {
+        self.retries.unwrap_or(DEFAULT_RETRIES)
+    }
+
     pub fn spaces_id(&self) -> Option<&str> {
         self.spaces_id.as_deref()
     }
@@ -322,6 +594,11 @@ impl ConfigurationOptions {
             return UIMode::Stream;
         }
 
+        // Structured logs need every line written out as it's produced
+        if self.log_format() == LogFormat::Ndjson {
+            return UIMode::Stream;
+        }
+
         self.log_order()
             .compatible_with_tui()
             .then_some(self.ui)
@@ -358,6 +635,18 @@ impl ConfigurationOptions {
         self.env_mode.unwrap_or_default()
     }
 
+    pub fn cache_compression_level(&self) -> i32 {
+        self.cache_compression_level
+            .unwrap_or(turborepo_cache::cache_archive::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    pub fn cache_eviction_policy(&self) -> EvictionPolicy {
+        EvictionPolicy {
+            max_size: self.cache_max_size,
+            max_age: self.cache_max_age.map(Duration::from_secs),
+        }
+    }
+
     pub fn cache_dir(&self) -> &Utf8Path {
         self.cache_dir.as_deref().unwrap_or_else(|| {
             Utf8Path::new(if cfg!(windows) {
@@ -376,6 +665,31 @@ impl ConfigurationOptions {
         self.log_order.unwrap_or_default()
     }
 
+    pub fn log_prefix(&self) -> LogPrefix {
+        self.log_prefix.unwrap_or_default()
+    }
+
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format.unwrap_or_default()
+    }
+
+    pub fn dependency_cycles(&self) -> DependencyCycles {
+        self.dependency_cycles.unwrap_or_default()
+    }
+
+    pub fn hash_mode(&self) -> HashMode {
+        self.hash_mode.unwrap_or_default()
+    }
+
+    pub fn watcher(&self) -> Watcher {
+        self.watcher.unwrap_or_default()
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+            .map_or(turborepo_filewatch::POLL_INTERVAL, Duration::from_millis)
+    }
+
     pub fn remote_only(&self) -> bool {
         self.remote_only.unwrap_or_default()
     }
@@ -384,16 +698,59 @@ impl ConfigurationOptions {
         self.remote_cache_read_only.unwrap_or_default()
     }
 
+    pub fn remote_cache_write_only(&self) -> bool {
+        self.remote_cache_write_only.unwrap_or_default()
+    }
+
     pub fn run_summary(&self) -> bool {
         self.run_summary.unwrap_or_default()
     }
 
+    pub fn exit_codes(&self) -> ExitCodes {
+        self.exit_codes.unwrap_or_default()
+    }
+
+    pub fn run_summary_upload_url(&self) -> Option<&str> {
+        non_empty_str(self.run_summary_upload_url.as_deref())
+    }
+
+    pub fn run_summary_upload_headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.run_summary_upload_headers
+            .iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
     pub fn root_turbo_json_path(&self, repo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
         self.root_turbo_json_path
             .clone()
             .unwrap_or_else(|| repo_root.join_component(CONFIG_FILE))
     }
 
+    pub fn config_overlay(&self) -> Option<&str> {
+        non_empty_str(self.config_overlay.as_deref())
+    }
+
+    /// The overlay selected with `--config-overlay`, which lives next to the
+    /// root turbo.json
+    pub fn config_overlay_path(
+        &self,
+        repo_root: &AbsoluteSystemPath,
+    ) -> Result<Option<AbsoluteSystemPathBuf>, Error> {
+        let Some(overlay) = self.config_overlay() else {
+            return Ok(None);
+        };
+        if !overlay
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(Error::InvalidConfigOverlay(overlay.to_owned()));
+        }
+        let root_turbo_json_path = self.root_turbo_json_path(repo_root);
+        let dir = root_turbo_json_path.parent().unwrap_or(repo_root);
+        Ok(Some(dir.join_component(&format!("turbo.{overlay}.json"))))
+    }
+
     pub fn allow_no_turbo_json(&self) -> bool {
         self.allow_no_turbo_json.unwrap_or_default()
     }
@@ -502,11 +859,15 @@ mod test {
     use std::{collections::HashMap, ffi::OsString};
 
     use tempfile::TempDir;
+    use test_case::test_case;
     use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+    use turborepo_auth::AuthFlow;
+    use turborepo_cache::SignatureMismatchPolicy;
 
     use crate::config::{
+        parse_cache_max_size, parse_poll_interval, validate_run_summary_upload_url,
         ConfigurationOptions, TurborepoConfigBuilder, DEFAULT_API_URL, DEFAULT_LOGIN_URL,
-        DEFAULT_TIMEOUT,
+        DEFAULT_RETRIES, DEFAULT_TIMEOUT,
     };
 
     #[test]
@@ -520,7 +881,9 @@ mod test {
         assert!(!defaults.signature());
         assert!(defaults.enabled());
         assert!(!defaults.preflight());
+        assert!(!defaults.chunking());
         assert_eq!(defaults.timeout(), DEFAULT_TIMEOUT);
+        assert_eq!(defaults.retries(), DEFAULT_RETRIES);
         assert_eq!(defaults.spaces_id(), None);
         assert!(!defaults.allow_no_package_manager());
         let repo_root = AbsoluteSystemPath::new(if cfg!(windows) {
@@ -596,8 +959,15 @@ mod test {
                 "teamSlug": team_slug,
                 "teamId": team_id,
                 "signature": true,
+                "signatureMismatch": "warn",
                 "preflight": false,
-                "timeout": 123
+                "timeout": 123,
+                "retries": 0,
+                "chunking": true,
+                "oidc": {
+                    "audience": "https://cache.example.com",
+                    "issuer": "https://token.actions.githubusercontent.com"
+                }
             }
         }))
         .unwrap();
@@ -620,8 +990,82 @@ mod test {
         assert_eq!(config.login_url(), login_url);
         assert_eq!(config.team_slug(), Some(team_slug));
         assert_eq!(config.team_id(), Some(team_id));
+        assert_eq!(config.oidc_audience(), Some("https://cache.example.com"));
+        assert_eq!(
+            config.oidc_issuer(),
+            Some("https://token.actions.githubusercontent.com")
+        );
         assert!(config.signature());
+        assert_eq!(config.signature_mismatch(), SignatureMismatchPolicy::Warn);
         assert!(!config.preflight());
         assert_eq!(config.timeout(), 123);
+        assert_eq!(config.retries(), 0);
+        assert!(config.chunking());
+    }
+
+    #[test]
+    fn test_local_config_auth_flow() {
+        let tmp_dir = TempDir::new().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp_dir.path()).unwrap();
+        let local_config_path = repo_root.join_components(&[".turbo", "config.json"]);
+        local_config_path.ensure_dir().unwrap();
+        local_config_path
+            .create_with_contents(
+                serde_json::json!({
+                    "apiUrl": "https://cache.example.com",
+                    "auth": {
+                        "type": "browser",
+                        "url": "https://cache.example.com/cli/login"
+                    }
+                })
+                .to_string(),
+            )
+            .unwrap();
+
+        let builder = TurborepoConfigBuilder {
+            repo_root,
+            override_config: ConfigurationOptions::default(),
+            global_config_path: None,
+            environment: Some(HashMap::default()),
+        };
+
+        let config = builder.build().unwrap();
+        assert_eq!(config.api_url(), "https://cache.example.com");
+        assert_eq!(
+            config.auth_flow(),
+            Some(&AuthFlow::Browser {
+                url: "https://cache.example.com/cli/login".to_string(),
+                success_url: None,
+            })
+        );
+    }
+
+    #[test_case("1024", Some(1024) ; "bytes")]
+    #[test_case("500MB", Some(500_000_000) ; "megabytes")]
+    #[test_case("1.5 gb", Some(1_500_000_000) ; "fractional with space")]
+    #[test_case("2GiB", Some(2 << 30) ; "binary unit")]
+    #[test_case("10 parsecs", None ; "unknown unit")]
+    #[test_case("GB", None ; "missing number")]
+    fn test_parse_cache_max_size(size: &str, expected: Option<u64>) {
+        assert_eq!(parse_cache_max_size(size).ok(), expected);
+    }
+
+    #[test_case("500ms", Some(500) ; "milliseconds")]
+    #[test_case("5s", Some(5000) ; "seconds")]
+    #[test_case("0s", None ; "zero")]
+    #[test_case("often", None ; "not a duration")]
+    fn test_parse_poll_interval(interval: &str, expected: Option<u64>) {
+        assert_eq!(parse_poll_interval(interval).ok(), expected);
+    }
+
+    #[test_case("https://example.com/runs", true ; "https")]
+    #[test_case("http://localhost:8080/runs", true ; "http localhost")]
+    #[test_case("http://127.0.0.1/runs", true ; "http loopback")]
+    #[test_case("http://[::1]/runs", true ; "http ipv6 loopback")]
+    #[test_case("http://example.com/runs", false ; "http remote")]
+    #[test_case("ftp://example.com/runs", false ; "other scheme")]
+    #[test_case("example.com/runs", false ; "no scheme")]
+    fn test_validate_run_summary_upload_url(url: &str, valid: bool) {
+        assert_eq!(validate_run_summary_upload_url(url).is_ok(), valid);
     }
 }
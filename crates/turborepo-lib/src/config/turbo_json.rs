@@ -1,7 +1,12 @@
 use camino::Utf8PathBuf;
+use merge::Merge;
 use turbopath::{AbsoluteSystemPath, RelativeUnixPath};
 
-use super::{ConfigurationOptions, Error, ResolvedConfigurationOptions};
+use super::{
+    parse_cache_max_age, parse_cache_max_size, parse_poll_interval,
+    validate_cache_compression_level, validate_exit_codes, validate_run_summary_upload_url,
+    ConfigurationOptions, Error, ResolvedConfigurationOptions,
+};
 use crate::turbo_json::RawTurboJson;
 
 pub struct TurboJsonReader<'a> {
@@ -29,6 +34,25 @@ impl<'a> ResolvedConfigurationOptions for TurboJsonReader<'a> {
 
             Err(e)
         })?;
+        let mut opts = ConfigurationOptions::default();
+        // Settings from the overlay take precedence over the ones in turbo.json
+        if let Some(overlay_path) = existing_config.config_overlay_path(self.repo_root)? {
+            let overlay =
+                RawTurboJson::read(self.repo_root, &overlay_path).map_err(|e| match e {
+                    Error::Io(e) if matches!(e.kind(), std::io::ErrorKind::NotFound) => {
+                        Error::MissingConfigOverlay(overlay_path.to_string())
+                    }
+                    e => e,
+                })?;
+            opts = Self::options_from_turbo_json(overlay)?;
+        }
+        opts.merge(Self::options_from_turbo_json(turbo_json)?);
+        Ok(opts)
+    }
+}
+
+impl<'a> TurboJsonReader<'a> {
+    fn options_from_turbo_json(turbo_json: RawTurboJson) -> Result<ConfigurationOptions, Error> {
         let mut opts = if let Some(remote_cache_options) = &turbo_json.remote_cache {
             remote_cache_options.into()
         } else {
@@ -60,6 +84,39 @@ impl<'a> ResolvedConfigurationOptions for TurboJsonReader<'a> {
         opts.daemon = turbo_json.daemon.map(|daemon| *daemon.as_inner());
         opts.env_mode = turbo_json.env_mode;
         opts.cache_dir = cache_dir;
+        opts.cache_compression_level = turbo_json
+            .cache_compression_level
+            .map(validate_cache_compression_level)
+            .transpose()?;
+        opts.cache_max_size = turbo_json
+            .cache_max_size
+            .as_deref()
+            .map(parse_cache_max_size)
+            .transpose()?;
+        opts.cache_max_age = turbo_json
+            .cache_max_age
+            .as_deref()
+            .map(parse_cache_max_age)
+            .transpose()?;
+        opts.log_order = turbo_json.log_order;
+        opts.log_prefix = turbo_json.log_prefix;
+        opts.dependency_cycles = turbo_json.dependency_cycles;
+        opts.hash_mode = turbo_json.hash_mode;
+        opts.watcher = turbo_json.watcher;
+        opts.poll_interval = turbo_json
+            .poll_interval
+            .as_deref()
+            .map(parse_poll_interval)
+            .transpose()?;
+        opts.exit_codes = turbo_json.exit_codes.map(validate_exit_codes).transpose()?;
+        if let Some(upload) = turbo_json.run_summary_upload {
+            opts.run_summary_upload_url = upload
+                .url
+                .as_deref()
+                .map(validate_run_summary_upload_url)
+                .transpose()?;
+            opts.run_summary_upload_headers = upload.headers;
+        }
         Ok(opts)
     }
 }
@@ -69,6 +126,10 @@ mod test {
     use tempfile::tempdir;
 
     use super::*;
+    use crate::{
+        cli::{LogOrder, LogPrefix},
+        turbo_json::{DependencyCycles, ExitCodes},
+    };
 
     #[test]
     fn test_reads_from_default() {
@@ -123,4 +184,176 @@ mod test {
         // Make sure we read the correct turbo.json
         assert_eq!(config.daemon(), Some(false));
     }
+
+    #[test]
+    fn test_reads_log_modes() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "logOrder": "grouped",
+                    "logPrefix": "none"
+                }))
+                .unwrap(),
+            )
+            .unwrap();
+
+        let reader = TurboJsonReader::new(repo_root);
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+        assert_eq!(config.log_order(), LogOrder::Grouped);
+        assert_eq!(config.log_prefix(), LogPrefix::None);
+    }
+
+    #[test]
+    fn test_reads_dependency_cycles() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(r#"{ "dependencyCycles": "warn" }"#)
+            .unwrap();
+
+        let reader = TurboJsonReader::new(repo_root);
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+        assert_eq!(config.dependency_cycles(), DependencyCycles::Warn);
+    }
+
+    #[test]
+    fn test_reads_run_summary_upload() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        let turbo_json = repo_root.join_component("turbo.json");
+        turbo_json
+            .create_with_contents(
+                serde_json::to_string_pretty(&serde_json::json!({
+                    "runSummaryUpload": {
+                        "url": "https://example.com/runs",
+                        "headers": { "Authorization": "Bearer ${TURBO_RUN_SUMMARY_TOKEN}" }
+                    }
+                }))
+                .unwrap(),
+            )
+            .unwrap();
+
+        let reader = TurboJsonReader::new(repo_root);
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+        assert_eq!(
+            config.run_summary_upload_url(),
+            Some("https://example.com/runs")
+        );
+        assert_eq!(
+            config.run_summary_upload_headers().collect::<Vec<_>>(),
+            [("Authorization", "Bearer ${TURBO_RUN_SUMMARY_TOKEN}")]
+        );
+
+        turbo_json
+            .create_with_contents(r#"{ "runSummaryUpload": { "url": "http://example.com" } }"#)
+            .unwrap();
+        assert!(matches!(
+            reader.get_configuration_options(&ConfigurationOptions::default()),
+            Err(Error::InvalidRunSummaryUploadUrl(_))
+        ));
+    }
+
+    #[test]
+    fn test_reads_exit_codes() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        let turbo_json = repo_root.join_component("turbo.json");
+        turbo_json
+            .create_with_contents(r#"{ "exitCodes": { "noTasks": 3, "warnings": 0 } }"#)
+            .unwrap();
+
+        let reader = TurboJsonReader::new(repo_root);
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+        assert_eq!(
+            config.exit_codes(),
+            ExitCodes {
+                no_tasks: Some(3),
+                warnings: Some(0),
+                ..Default::default()
+            }
+        );
+
+        turbo_json
+            .create_with_contents(r#"{ "exitCodes": { "taskFailure": 256 } }"#)
+            .unwrap();
+        assert!(matches!(
+            reader.get_configuration_options(&ConfigurationOptions::default()),
+            Err(Error::InvalidExitCode(256))
+        ));
+    }
+
+    #[test]
+    fn test_config_overlay() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        repo_root
+            .join_component("turbo.json")
+            .create_with_contents(r#"{ "daemon": true, "logOrder": "grouped" }"#)
+            .unwrap();
+        repo_root
+            .join_component("turbo.ci.json")
+            .create_with_contents(r#"{ "daemon": false }"#)
+            .unwrap();
+
+        let reader = TurboJsonReader::new(repo_root);
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions {
+                config_overlay: Some("ci".into()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(config.daemon(), Some(false));
+        assert_eq!(config.log_order(), LogOrder::Grouped);
+
+        assert!(matches!(
+            reader.get_configuration_options(&ConfigurationOptions {
+                config_overlay: Some("release".into()),
+                ..Default::default()
+            }),
+            Err(Error::MissingConfigOverlay(_))
+        ));
+        assert!(matches!(
+            reader.get_configuration_options(&ConfigurationOptions {
+                config_overlay: Some("../ci".into()),
+                ..Default::default()
+            }),
+            Err(Error::InvalidConfigOverlay(_))
+        ));
+    }
+
+    #[test]
+    fn test_cache_compression_level_range() {
+        let tmpdir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmpdir.path().to_str().unwrap()).unwrap();
+        let turbo_json = repo_root.join_component("turbo.json");
+        let reader = TurboJsonReader::new(repo_root);
+
+        turbo_json
+            .create_with_contents(r#"{"cacheCompressionLevel": 12}"#)
+            .unwrap();
+        let config = reader
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+        assert_eq!(config.cache_compression_level(), 12);
+
+        turbo_json
+            .create_with_contents(r#"{"cacheCompressionLevel": 30}"#)
+            .unwrap();
+        assert!(matches!(
+            reader.get_configuration_options(&ConfigurationOptions::default()),
+            Err(Error::CacheCompressionLevelOutOfRange(30))
+        ));
+    }
 }
@@ -10,7 +10,7 @@ use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
 use super::{
     connector::{DaemonConnector, DaemonConnectorError},
     endpoint::SocketOpenError,
-    proto::{DiscoverPackagesResponse, GetFileHashesResponse},
+    proto::{DiscoverPackagesResponse, GetChangedPackagesResponse, GetFileHashesResponse},
     Paths,
 };
 use crate::{
@@ -74,6 +74,7 @@ impl<T> DaemonClient<T> {
         Ok(self.connect_settings)
     }
 
+    #[tracing::instrument(skip(self, output_globs))]
     pub async fn get_changed_outputs(
         &mut self,
         hash: String,
@@ -91,6 +92,27 @@ impl<T> DaemonClient<T> {
             .changed_output_globs)
     }
 
+    /// Whether a task with `hash` succeeded while the daemon was running and
+    /// none of its outputs have changed since
+    #[tracing::instrument(skip(self, output_globs))]
+    pub async fn is_unchanged_since_success(
+        &mut self,
+        hash: String,
+        output_globs: &[ValidatedGlob],
+    ) -> Result<bool, DaemonError> {
+        let output_globs = output_globs
+            .iter()
+            .map(|validated_glob| validated_glob.as_str().to_string())
+            .collect();
+        let response = self
+            .client
+            .get_changed_outputs(proto::GetChangedOutputsRequest { hash, output_globs })
+            .await?
+            .into_inner();
+        Ok(response.recorded && response.changed_output_globs.is_empty())
+    }
+
+    #[tracing::instrument(skip(self, output_globs, output_exclusion_globs))]
     pub async fn notify_outputs_written(
         &mut self,
         hash: String,
@@ -119,6 +141,7 @@ impl<T> DaemonClient<T> {
     }
 
     /// Get the status of the daemon.
+    #[tracing::instrument(skip(self))]
     pub async fn status(&mut self) -> Result<proto::DaemonStatus, DaemonError> {
         self.client
             .status(proto::StatusRequest {})
@@ -128,6 +151,7 @@ impl<T> DaemonClient<T> {
             .ok_or(DaemonError::MalformedResponse)
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn discover_packages(&mut self) -> Result<DiscoverPackagesResponse, DaemonError> {
         let req = proto::DiscoverPackagesRequest {};
         let mut req = req.into_request();
@@ -137,6 +161,7 @@ impl<T> DaemonClient<T> {
         Ok(response)
     }
 
+    #[tracing::instrument(skip(self))]
     pub async fn discover_packages_blocking(
         &mut self,
     ) -> Result<DiscoverPackagesResponse, DaemonError> {
@@ -160,6 +185,7 @@ impl<T> DaemonClient<T> {
         Ok(response)
     }
 
+    #[tracing::instrument(skip(self, inputs))]
     pub async fn get_file_hashes(
         &mut self,
         package_path: &AnchoredSystemPath,
@@ -175,6 +201,23 @@ impl<T> DaemonClient<T> {
             .into_inner();
         Ok(response)
     }
+
+    /// Get the packages whose inputs changed since the point identified by
+    /// `since`, a hash returned by a previous call.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_changed_packages(
+        &mut self,
+        since: Option<String>,
+    ) -> Result<GetChangedPackagesResponse, DaemonError> {
+        let response = self
+            .client
+            .get_changed_packages(proto::GetChangedPackagesRequest {
+                since: since.unwrap_or_default(),
+            })
+            .await?
+            .into_inner();
+        Ok(response)
+    }
 }
 
 impl DaemonClient<DaemonConnector> {
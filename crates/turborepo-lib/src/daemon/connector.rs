@@ -655,6 +655,13 @@ mod test {
         ) -> Result<tonic::Response<proto::GetFileHashesResponse>, tonic::Status> {
             unimplemented!()
         }
+
+        async fn get_changed_packages(
+            &self,
+            _req: tonic::Request<proto::GetChangedPackagesRequest>,
+        ) -> Result<tonic::Response<proto::GetChangedPackagesResponse>, tonic::Status> {
+            unimplemented!()
+        }
     }
 
     #[tokio::test]
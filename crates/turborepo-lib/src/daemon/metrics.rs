@@ -0,0 +1,168 @@
+//! Runtime metrics reported by `turbo daemon status`. These exist so that a
+//! file watcher that has stopped delivering events can be noticed without
+//! digging through the daemon logs.
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use notify::Event;
+use sysinfo::{ProcessExt, ProcessRefreshKind, SystemExt};
+use tokio::sync::broadcast;
+use turborepo_filewatch::{NotifyError, OptionalWatch};
+
+use super::proto;
+
+// Number of errors kept around for the status output
+const MAX_RECENT_ERRORS: usize = 10;
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    file_watching_ready: bool,
+    file_events: u64,
+    recent_errors: VecDeque<proto::DaemonStatusError>,
+}
+
+impl MetricsState {
+    fn record_error(&mut self, message: String) {
+        if self.recent_errors.len() == MAX_RECENT_ERRORS {
+            self.recent_errors.pop_front();
+        }
+        let timestamp_msec = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        self.recent_errors.push_back(proto::DaemonStatusError {
+            timestamp_msec,
+            message,
+        });
+    }
+}
+
+/// Counts file events and keeps the most recent file watching errors
+#[derive(Clone, Debug, Default)]
+pub struct DaemonMetrics {
+    state: Arc<Mutex<MetricsState>>,
+}
+
+/// A point in time view of the daemon's metrics
+#[derive(Debug)]
+pub struct MetricsSnapshot {
+    pub file_watching_ready: bool,
+    pub file_events: u64,
+    pub memory_bytes: u64,
+    pub recent_errors: Vec<proto::DaemonStatusError>,
+}
+
+impl DaemonMetrics {
+    /// Starts recording the events delivered by the file watcher. Recording
+    /// stops once the watcher's channel closes.
+    pub fn new(
+        mut file_events_lazy: OptionalWatch<broadcast::Receiver<Result<Event, NotifyError>>>,
+    ) -> Self {
+        let metrics = Self::default();
+        tokio::spawn({
+            let metrics = metrics.clone();
+            async move {
+                let Ok(file_events) = file_events_lazy.get().await.map(|r| r.resubscribe()) else {
+                    metrics.record_error("file watching failed to start".to_string());
+                    return;
+                };
+                metrics.record(file_events).await;
+            }
+        });
+        metrics
+    }
+
+    async fn record(&self, mut file_events: broadcast::Receiver<Result<Event, NotifyError>>) {
+        self.state().file_watching_ready = true;
+        loop {
+            match file_events.recv().await {
+                Ok(Ok(_)) => self.state().file_events += 1,
+                Ok(Err(err)) => self.record_error(format!("file event error: {err}")),
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    let mut state = self.state();
+                    state.file_events += missed;
+                    state.record_error(format!("fell behind and skipped {missed} file events"));
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    let mut state = self.state();
+                    state.file_watching_ready = false;
+                    state.record_error("file watching stopped".to_string());
+                    return;
+                }
+            }
+        }
+    }
+
+    pub fn record_error(&self, message: String) {
+        self.state().record_error(message);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let state = self.state();
+        MetricsSnapshot {
+            file_watching_ready: state.file_watching_ready,
+            file_events: state.file_events,
+            memory_bytes: memory_usage().unwrap_or_default(),
+            recent_errors: state.recent_errors.iter().cloned().collect(),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MetricsState> {
+        self.state.lock().expect("metrics lock poisoned")
+    }
+}
+
+/// Resident memory of the current process in bytes
+fn memory_usage() -> Option<u64> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = sysinfo::System::new();
+    system.refresh_process_specifics(pid, ProcessRefreshKind::new());
+    system.process(pid).map(|process| process.memory())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_records_events_and_errors() {
+        let metrics = DaemonMetrics::default();
+        assert!(!metrics.snapshot().file_watching_ready);
+
+        let (events_tx, events_rx) = broadcast::channel(16);
+        events_tx.send(Ok(Event::default())).unwrap();
+        events_tx.send(Ok(Event::default())).unwrap();
+        events_tx
+            .send(Err(NotifyError::from(notify::Error::generic("boom"))))
+            .unwrap();
+        drop(events_tx);
+        metrics.record(events_rx).await;
+
+        let snapshot = metrics.snapshot();
+        assert!(!snapshot.file_watching_ready);
+        assert_eq!(snapshot.file_events, 2);
+        let messages = snapshot
+            .recent_errors
+            .iter()
+            .map(|error| error.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec!["file event error: boom", "file watching stopped"]
+        );
+    }
+
+    #[test]
+    fn test_keeps_most_recent_errors() {
+        let mut state = MetricsState::default();
+        for i in 0..MAX_RECENT_ERRORS + 2 {
+            state.record_error(i.to_string());
+        }
+        assert_eq!(state.recent_errors.len(), MAX_RECENT_ERRORS);
+        assert_eq!(state.recent_errors.front().unwrap().message, "2");
+    }
+}
@@ -26,10 +26,13 @@ mod client;
 mod connector;
 mod default_timeout_layer;
 pub(crate) mod endpoint;
+mod metrics;
 mod server;
 
 pub use client::{DaemonClient, DaemonError};
 pub use connector::{DaemonConnector, DaemonConnectorError};
+#[cfg(test)]
+pub(crate) use server::start_test_daemon;
 pub use server::{CloseReason, TurboGrpcService};
 use sha2::{Digest, Sha256};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
@@ -28,21 +28,22 @@ use tracing::{error, info, trace, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf, PathError};
 use turborepo_filewatch::{
     cookies::CookieWriter,
+    default_cookie_dir,
     globwatcher::{Error as GlobWatcherError, GlobError, GlobSet, GlobWatcher},
     hash_watcher::{Error as HashWatcherError, HashSpec, HashWatcher, InputGlobs},
     package_watcher::{PackageWatchError, PackageWatcher},
-    FileSystemWatcher, WatchError,
+    FileSystemWatcher, WatchError, WatcherBackend, BACKEND_NAME, POLL_INTERVAL,
 };
 use turborepo_repository::package_manager;
 use turborepo_scm::SCM;
 
-use super::{bump_timeout::BumpTimeout, endpoint::SocketOpenError, proto};
+use super::{bump_timeout::BumpTimeout, endpoint::SocketOpenError, metrics::DaemonMetrics, proto};
 use crate::{
     daemon::{
         bump_timeout_layer::BumpTimeoutLayer, default_timeout_layer::DefaultTimeoutLayer,
         endpoint::listen_socket, Paths,
     },
-    package_changes_watcher::{PackageChangeEvent, PackageChangesWatcher},
+    package_changes_watcher::{ChangedPackages, PackageChangeEvent, PackageChangesWatcher},
 };
 
 #[derive(Debug)]
@@ -110,8 +111,17 @@ impl FileWatching {
     /// waiting for the filewatcher to be ready. Using `OptionalWatch`,
     /// dependent services can wait for resources they need to become
     /// available, and the server can start up without waiting for them.
-    pub fn new(repo_root: AbsoluteSystemPathBuf) -> Result<FileWatching, WatchError> {
-        let watcher = Arc::new(FileSystemWatcher::new_with_default_cookie_dir(&repo_root)?);
+    pub fn new(
+        repo_root: AbsoluteSystemPathBuf,
+        backend: WatcherBackend,
+        poll_interval: Duration,
+    ) -> Result<FileWatching, WatchError> {
+        let watcher = Arc::new(FileSystemWatcher::new_with_backend(
+            &repo_root,
+            default_cookie_dir(&repo_root),
+            backend,
+            poll_interval,
+        )?);
         let recv = watcher.watch();
 
         let cookie_writer = CookieWriter::new(
@@ -160,6 +170,8 @@ pub struct TurboGrpcService<S> {
     paths: Paths,
     timeout: Duration,
     external_shutdown: S,
+    watcher_backend: WatcherBackend,
+    poll_interval: Duration,
 }
 
 impl<S> TurboGrpcService<S>
@@ -186,15 +198,31 @@ where
             paths,
             timeout,
             external_shutdown,
+            watcher_backend: WatcherBackend::default(),
+            poll_interval: POLL_INTERVAL,
         }
     }
 
+    /// Sets where the server's file watcher gets its events from
+    pub fn with_watcher_backend(mut self, watcher_backend: WatcherBackend) -> Self {
+        self.watcher_backend = watcher_backend;
+        self
+    }
+
+    /// Sets how often the file watcher scans for changes when it polls
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
     pub async fn serve(self) -> Result<CloseReason, package_manager::Error> {
         let Self {
             external_shutdown,
             paths,
             repo_root,
             timeout,
+            watcher_backend,
+            poll_interval,
         } = self;
 
         // A channel to trigger the shutdown of the gRPC server. This is handed out
@@ -202,8 +230,13 @@ where
         // well as available to the gRPC server itself to handle the shutdown RPC.
         let (trigger_shutdown, mut shutdown_signal) = mpsc::channel::<()>(1);
 
-        let (service, exit_root_watch, watch_root_handle) =
-            TurboGrpcServiceInner::new(repo_root.clone(), trigger_shutdown, paths.log_file);
+        let (service, exit_root_watch, watch_root_handle) = TurboGrpcServiceInner::new(
+            repo_root.clone(),
+            watcher_backend,
+            poll_interval,
+            trigger_shutdown,
+            paths.log_file,
+        );
 
         let running = Arc::new(AtomicBool::new(true));
         let (_pid_lock, stream) =
@@ -267,10 +300,14 @@ where
 struct TurboGrpcServiceInner {
     shutdown: mpsc::Sender<()>,
     file_watching: FileWatching,
+    // Every hash that outputs were written for, which is only done once a task
+    // succeeds
     times_saved: Arc<Mutex<HashMap<String, u64>>>,
     start_time: Instant,
     log_file: AbsoluteSystemPathBuf,
     package_watcher: Arc<PackageWatcher>,
+    repo_root: AbsoluteSystemPathBuf,
+    metrics: DaemonMetrics,
 }
 
 // we have a grpc service that uses watching package discovery, and where the
@@ -279,6 +316,8 @@ struct TurboGrpcServiceInner {
 impl TurboGrpcServiceInner {
     pub fn new(
         repo_root: AbsoluteSystemPathBuf,
+        watcher_backend: WatcherBackend,
+        poll_interval: Duration,
         trigger_shutdown: mpsc::Sender<()>,
         log_file: AbsoluteSystemPathBuf,
     ) -> (
@@ -286,11 +325,13 @@ impl TurboGrpcServiceInner {
         oneshot::Sender<()>,
         JoinHandle<Result<(), WatchError>>,
     ) {
-        let file_watching = FileWatching::new(repo_root.clone()).unwrap();
+        let file_watching =
+            FileWatching::new(repo_root.clone(), watcher_backend, poll_interval).unwrap();
 
         tracing::debug!("initing package discovery");
         // Note that we're cloning the Arc, not the package watcher itself
         let package_watcher = Arc::clone(&file_watching.package_watcher);
+        let metrics = DaemonMetrics::new(file_watching.watcher.watch());
 
         // exit_root_watch delivers a signal to the root watch loop to exit.
         // In the event that the server shuts down via some other mechanism, this
@@ -311,6 +352,8 @@ impl TurboGrpcServiceInner {
                 times_saved: Arc::new(Mutex::new(HashMap::new())),
                 start_time: Instant::now(),
                 log_file,
+                repo_root,
+                metrics,
             },
             exit_root_watch,
             watch_root_handle,
@@ -345,10 +388,10 @@ impl TurboGrpcServiceInner {
         &self,
         hash: String,
         candidates: HashSet<String>,
-    ) -> Result<(HashSet<String>, u64), RpcError> {
+    ) -> Result<(HashSet<String>, Option<u64>), RpcError> {
         let time_saved = {
             let times_saved = self.times_saved.lock().expect("times saved lock poisoned");
-            times_saved.get(hash.as_str()).copied().unwrap_or_default()
+            times_saved.get(hash.as_str()).copied()
         };
         let changed_globs = self
             .file_watching
@@ -479,10 +522,22 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
         &self,
         _request: tonic::Request<proto::StatusRequest>,
     ) -> Result<tonic::Response<proto::StatusResponse>, tonic::Status> {
+        let metrics = self.metrics.snapshot();
         Ok(tonic::Response::new(proto::StatusResponse {
             daemon_status: Some(proto::DaemonStatus {
                 uptime_msec: self.start_time.elapsed().as_millis() as u64,
                 log_file: self.log_file.to_string(),
+                watched_roots: vec![self.repo_root.to_string()],
+                watcher_backend: self
+                    .file_watching
+                    .watcher
+                    .backend_name()
+                    .unwrap_or(BACKEND_NAME)
+                    .to_string(),
+                file_watching_ready: metrics.file_watching_ready,
+                file_events: metrics.file_events,
+                memory_bytes: metrics.memory_bytes,
+                recent_errors: metrics.recent_errors,
             }),
         }))
     }
@@ -513,7 +568,8 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
             .await?;
         Ok(tonic::Response::new(proto::GetChangedOutputsResponse {
             changed_output_globs: changed.into_iter().collect(),
-            time_saved,
+            time_saved: time_saved.unwrap_or_default(),
+            recorded: time_saved.is_some(),
         }))
     }
 
@@ -646,6 +702,37 @@ impl proto::turbod_server::Turbod for TurboGrpcServiceInner {
 
         Ok(tonic::Response::new(ReceiverStream::new(rx)))
     }
+
+    async fn get_changed_packages(
+        &self,
+        request: tonic::Request<proto::GetChangedPackagesRequest>,
+    ) -> Result<tonic::Response<proto::GetChangedPackagesResponse>, tonic::Status> {
+        let inner = request.into_inner();
+        let (changed, hash) = self
+            .file_watching
+            .package_changes_watcher
+            .changed_packages_since(&inner.since);
+        let response = match changed {
+            ChangedPackages::All => proto::GetChangedPackagesResponse {
+                package_names: vec![],
+                all_changed: true,
+                hash,
+            },
+            ChangedPackages::Some(packages) => {
+                let mut package_names = packages
+                    .into_iter()
+                    .map(|name| name.to_string())
+                    .collect::<Vec<_>>();
+                package_names.sort();
+                proto::GetChangedPackagesResponse {
+                    package_names,
+                    all_changed: false,
+                    hash,
+                }
+            }
+        };
+        Ok(tonic::Response::new(response))
+    }
 }
 
 /// Determine whether a server can serve a client's request based on its
@@ -675,6 +762,52 @@ impl NamedService for TurboGrpcServiceInner {
     const NAME: &'static str = "turborepo.Daemon";
 }
 
+/// Serves a daemon for `repo_root` in the background and connects to it once
+/// it's watching files. Sending on the returned channel stops it.
+#[cfg(test)]
+pub(crate) async fn start_test_daemon(
+    repo_root: &AbsoluteSystemPath,
+) -> (
+    super::DaemonClient<super::DaemonConnector>,
+    oneshot::Sender<CloseReason>,
+    JoinHandle<Result<CloseReason, package_manager::Error>>,
+) {
+    use futures::FutureExt;
+
+    let (stop_tx, stop_rx) = oneshot::channel::<CloseReason>();
+    let handle = tokio::task::spawn(
+        TurboGrpcService::new(
+            repo_root.to_owned(),
+            Paths::from_repo_root(repo_root),
+            Duration::from_secs(60 * 60),
+            stop_rx.map(|_| CloseReason::Interrupt),
+        )
+        .serve(),
+    );
+
+    let mut client = None;
+    for _ in 0..50 {
+        if let Ok(connected) = super::DaemonConnector::new(false, false, repo_root)
+            .connect()
+            .await
+        {
+            client = Some(connected);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let mut client = client.expect("failed to connect to daemon");
+    // Until it's watching files, the daemon can't answer queries about them
+    for _ in 0..50 {
+        if client.get_changed_outputs(String::new(), &[]).await.is_ok() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    (client, stop_tx, handle)
+}
+
 #[cfg(test)]
 mod test {
     use std::{
@@ -898,4 +1031,44 @@ mod test {
             .expect("server exited");
         assert_matches!(close_reason, Ok(CloseReason::Shutdown));
     }
+
+    // the windows runner starts a new thread to accept uds requests,
+    // so we need a multi-threaded runtime
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_unchanged_since_success() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        repo_root.create_dir_all().unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(r#"{"workspaces": ["packages/*"]}"#)
+            .unwrap();
+        repo_root
+            .join_component("package-lock.json")
+            .create_with_contents("")
+            .unwrap();
+        let (mut client, stop, handle) = super::start_test_daemon(&repo_root).await;
+
+        // Without outputs there's nothing that could have changed, so only a
+        // recorded success counts
+        assert!(!client
+            .is_unchanged_since_success("never-ran".to_string(), &[])
+            .await
+            .unwrap());
+        client
+            .notify_outputs_written("succeeded".to_string(), &[], &[], 0)
+            .await
+            .unwrap();
+        assert!(client
+            .is_unchanged_since_success("succeeded".to_string(), &[])
+            .await
+            .unwrap());
+
+        stop.send(CloseReason::Interrupt).unwrap();
+        handle.await.unwrap().unwrap();
+    }
 }
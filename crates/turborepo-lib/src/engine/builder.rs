@@ -1,10 +1,10 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 
 use convert_case::{Case, Casing};
 use itertools::Itertools;
 use miette::{Diagnostic, NamedSource, SourceSpan};
 use turbopath::AbsoluteSystemPath;
-use turborepo_errors::{Spanned, TURBO_SITE};
+use turborepo_errors::{closest_match, Spanned, TURBO_SITE};
 use turborepo_graph_utils as graph;
 use turborepo_repository::package_graph::{PackageGraph, PackageName, PackageNode, ROOT_PKG_NAME};
 
@@ -26,6 +26,21 @@ pub struct MissingTaskError {
     span: Option<SourceSpan>,
     #[source_code]
     text: NamedSource,
+    #[help]
+    help: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error, Diagnostic)]
+#[error("Could not find \"{task_id}\" in root turbo.json or \"{task_name}\" in package")]
+pub struct MissingPackageTaskError {
+    #[label]
+    span: Option<SourceSpan>,
+    #[source_code]
+    text: NamedSource,
+    task_id: String,
+    task_name: String,
+    #[help]
+    help: Option<String>,
 }
 
 #[derive(Debug, thiserror::Error, Diagnostic)]
@@ -61,9 +76,24 @@ pub enum Error {
         package: String,
         task_id: String,
     },
-    #[error("Could not find \"{task_id}\" in root turbo.json or \"{task_name}\" in package")]
-    MissingPackageTask {
-        #[label]
+    #[error(transparent)]
+    #[diagnostic(transparent)]
+    MissingPackageTask(Box<MissingPackageTaskError>),
+    #[error(
+        "\"{task_id}\" takes inputs from \"{task_name}\", which isn't a task in the same package"
+    )]
+    MissingInputsFromTask {
+        #[label("task referenced here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource,
+        task_id: String,
+        task_name: String,
+    },
+    #[error("\"{task_id}\" takes inputs from \"{task_name}\", which has no outputs")]
+    #[diagnostic(help("add the files that \"{task_name}\" creates to its outputs"))]
+    InputsFromWithoutOutputs {
+        #[label("task referenced here")]
         span: Option<SourceSpan>,
         #[source_code]
         text: NamedSource,
@@ -235,7 +265,11 @@ impl<'a> EngineBuilder<'a> {
                 .task_id()
                 .unwrap_or_else(|| TaskId::new(workspace.as_ref(), task.task()));
 
-            if Self::has_task_definition(&mut turbo_json_loader, workspace, task, &task_id)? {
+            if Self::has_task_definition(&mut turbo_json_loader, workspace, task, &task_id)?
+                || self
+                    .matrix_variant(&mut turbo_json_loader, &task.to(task_id.clone()), task)?
+                    .is_some()
+            {
                 missing_tasks.remove(task.as_inner());
 
                 // Even if a task definition was found, we _only_ want to add it as an entry
@@ -244,8 +278,7 @@ impl<'a> EngineBuilder<'a> {
                 // - A task from the non-root workspace (i.e. tasks from every other workspace)
                 // - A task that we *know* is rootEnabled task (in which case, the root
                 //   workspace is acceptable)
-                if !matches!(workspace, PackageName::Root) || self.root_enabled_tasks.contains(task)
-                {
+                if !matches!(workspace, PackageName::Root) || self.is_root_enabled(task) {
                     let task_id = task.to(task_id);
                     traversal_queue.push_back(task_id);
                 }
@@ -259,11 +292,18 @@ impl<'a> EngineBuilder<'a> {
                 .collect::<Vec<_>>();
             // We sort the tasks mostly to keep it deterministic for our tests
             missing_tasks.sort_by(|a, b| a.0.cmp(&b.0));
+            let known_tasks = self.known_task_names(&mut turbo_json_loader);
             let errors = missing_tasks
                 .into_iter()
                 .map(|(name, span)| {
                     let (span, text) = span.span_and_text("turbo.json");
-                    MissingTaskError { name, span, text }
+                    let help = suggest_task(&TaskName::from(name.as_str()), &known_tasks);
+                    MissingTaskError {
+                        name,
+                        span,
+                        text,
+                        help,
+                    }
                 })
                 .collect();
 
@@ -282,9 +322,7 @@ impl<'a> EngineBuilder<'a> {
             }
 
             if task_id.package() == ROOT_PKG_NAME
-                && !self
-                    .root_enabled_tasks
-                    .contains(&task_id.as_non_workspace_task_name())
+                && !self.is_root_enabled(&task_id.as_non_workspace_task_name())
             {
                 let (span, text) = task_id.span_and_text("turbo.json");
                 return Err(Error::MissingRootTaskInTurboJson {
@@ -315,7 +353,7 @@ impl<'a> EngineBuilder<'a> {
                 });
             }
 
-            let task_definition = self.task_definition(
+            let mut task_definition = self.task_definition(
                 &mut turbo_json_loader,
                 &task_id,
                 &task_id.as_non_workspace_task_name(),
@@ -395,6 +433,47 @@ impl<'a> EngineBuilder<'a> {
                 traversal_queue.push_back(from_task_id);
             }
 
+            // A task with a matrix runs through its variants, which are always
+            // included, even with `--only`
+            for value in &task_definition.matrix {
+                let variant = format!("{}:{value}", to_task_id.task());
+                let from_task_id = TaskId::new(to_task_id.package(), &variant).into_owned();
+                has_deps = true;
+                let from_task_index = engine.get_index(&from_task_id);
+                engine
+                    .task_graph
+                    .add_edge(to_task_index, from_task_index, ());
+                traversal_queue.push_back(task_id.to(from_task_id));
+            }
+
+            // A task depends on the tasks it takes inputs from. When `--only`
+            // leaves them out, their outputs are hashed as its inputs instead.
+            for task_name in task_definition.inputs_from.clone() {
+                let inputs_from_definition =
+                    self.inputs_from_definition(&mut turbo_json_loader, &to_task_id, &task_name)?;
+                let from_task_id = TaskId::new(to_task_id.package(), task_name.task()).into_owned();
+                let runs_first = allowed_tasks
+                    .as_ref()
+                    .map_or(true, |allowed_tasks| allowed_tasks.contains(&from_task_id));
+                task_definition.add_inputs_from(&inputs_from_definition, runs_first);
+                if !runs_first {
+                    continue;
+                }
+                has_deps = true;
+                let from_task_index = engine.get_index(&from_task_id);
+                engine
+                    .task_graph
+                    .add_edge(to_task_index, from_task_index, ());
+                traversal_queue.push_back(task_name.to(from_task_id));
+            }
+
+            if let Some(package_info) = self
+                .package_graph
+                .package_info(&PackageName::from(to_task_id.package()))
+            {
+                task_definition.resolve_turbo_root(package_info.package_path());
+            }
+
             engine.add_definition(task_id.as_inner().clone().into_owned(), task_definition);
             if !has_deps && !has_topo_deps {
                 engine.connect_to_root(&to_task_id);
@@ -444,12 +523,95 @@ impl<'a> EngineBuilder<'a> {
         }
     }
 
+    // Root tasks are enabled when they're in the root turbo.json, which for a
+    // matrix variant means the task with the matrix is
+    fn is_root_enabled(&self, task_name: &TaskName) -> bool {
+        self.root_enabled_tasks.contains(task_name)
+            || task_name
+                .task()
+                .rsplit_once(':')
+                .is_some_and(|(task, _)| self.root_enabled_tasks.contains(&TaskName::from(task)))
+    }
+
+    // A task named `<task>:<value>` without a definition of its own is a variant
+    // of `<task>` when `<value>` is in that task's matrix
+    fn matrix_variant(
+        &self,
+        turbo_json_loader: &mut TurboJsonLoader,
+        task_id: &Spanned<TaskId>,
+        task_name: &TaskName,
+    ) -> Result<Option<TaskDefinition>, Error> {
+        let Some((task, value)) = task_name.task().rsplit_once(':') else {
+            return Ok(None);
+        };
+        let workspace = PackageName::from(task_id.package());
+        let own_name = TaskName::from(task_name.task().to_string());
+        if Self::has_task_definition(turbo_json_loader, &workspace, &own_name, task_id)? {
+            return Ok(None);
+        }
+
+        let base_name = TaskName::from(task.to_string());
+        let base_id = task_id.to(TaskId::new(task_id.package(), task));
+        if !Self::has_task_definition(turbo_json_loader, &workspace, &base_name, &base_id)? {
+            return Ok(None);
+        }
+        let base_definition = self.task_definition(turbo_json_loader, &base_id, &base_name)?;
+        Ok(base_definition
+            .matrix
+            .iter()
+            .any(|matrix_value| matrix_value == value)
+            .then(|| base_definition.for_matrix_variant(task, value)))
+    }
+
+    // The definition of a task that `task_id` takes inputs from, which has to be
+    // a task in the same package with outputs
+    fn inputs_from_definition(
+        &self,
+        turbo_json_loader: &mut TurboJsonLoader,
+        task_id: &TaskId,
+        task_name: &Spanned<TaskName<'static>>,
+    ) -> Result<TaskDefinition, Error> {
+        let workspace = PackageName::from(task_id.package());
+        let inputs_from_id = task_name.to(TaskId::new(task_id.package(), task_name.task()));
+        if !Self::has_task_definition(turbo_json_loader, &workspace, task_name, &inputs_from_id)?
+            && self
+                .matrix_variant(turbo_json_loader, &inputs_from_id, task_name)?
+                .is_none()
+        {
+            let (span, text) = task_name.span_and_text("turbo.json");
+            return Err(Error::MissingInputsFromTask {
+                span,
+                text,
+                task_id: task_id.to_string(),
+                task_name: task_name.to_string(),
+            });
+        }
+
+        let task_definition =
+            self.task_definition(turbo_json_loader, &inputs_from_id, task_name)?;
+        if task_definition.outputs.inclusions.is_empty() {
+            let (span, text) = task_name.span_and_text("turbo.json");
+            return Err(Error::InputsFromWithoutOutputs {
+                span,
+                text,
+                task_id: task_id.to_string(),
+                task_name: task_name.to_string(),
+            });
+        }
+
+        Ok(task_definition)
+    }
+
     fn task_definition(
         &self,
         turbo_json_loader: &mut TurboJsonLoader,
         task_id: &Spanned<TaskId>,
         task_name: &TaskName,
     ) -> Result<TaskDefinition, Error> {
+        if let Some(task_definition) = self.matrix_variant(turbo_json_loader, task_id, task_name)? {
+            return Ok(task_definition);
+        }
+
         let raw_task_definition = RawTaskDefinition::from_iter(self.task_definition_chain(
             turbo_json_loader,
             task_id,
@@ -511,16 +673,37 @@ impl<'a> EngineBuilder<'a> {
 
         if task_definitions.is_empty() && self.should_validate_engine {
             let (span, text) = task_id.span_and_text("turbo.json");
-            return Err(Error::MissingPackageTask {
-                span,
-                text,
-                task_id: task_id.to_string(),
-                task_name: task_name.to_string(),
-            });
+            let help = suggest_task(task_name, &self.known_task_names(turbo_json_loader));
+            return Err(Error::MissingPackageTask(Box::new(
+                MissingPackageTaskError {
+                    span,
+                    text,
+                    task_id: task_id.to_string(),
+                    task_name: task_name.to_string(),
+                    help,
+                },
+            )));
         }
 
         Ok(task_definitions)
     }
+
+    // The names of all tasks defined in any turbo.json, for suggesting what a
+    // reference to a missing task was meant to be
+    fn known_task_names(&self, turbo_json_loader: &mut TurboJsonLoader) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for package in std::iter::once(&PackageName::Root).chain(self.workspaces.iter()) {
+            if let Ok(turbo_json) = turbo_json_loader.load(package) {
+                names.extend(turbo_json.tasks.keys().map(|task| task.task().to_string()));
+            }
+        }
+        names
+    }
+}
+
+fn suggest_task(task_name: &TaskName, known_tasks: &BTreeSet<String>) -> Option<String> {
+    closest_match(task_name.task(), known_tasks.iter().map(String::as_str))
+        .map(|task| format!("did you mean `{task}`?"))
 }
 
 impl Error {
@@ -1229,6 +1412,200 @@ mod test {
         assert_eq!(all_dependencies(&engine), expected);
     }
 
+    fn matrix_engine(task: &str, tasks_only: bool) -> Engine {
+        let repo_root_dir = TempDir::with_prefix("repo").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::new(repo_root_dir.path().to_str().unwrap()).unwrap();
+        let package_graph = mock_package_graph(
+            &repo_root,
+            package_jsons! {
+                repo_root,
+                "app1" => ["libA"],
+                "libA" => []
+            },
+        );
+        let turbo_jsons = vec![(
+            PackageName::Root,
+            turbo_json(json!({
+                "tasks": {
+                    "build": { "dependsOn": ["^build"] },
+                    "test": {
+                        "dependsOn": ["^build"],
+                        "matrix": ["node18", "node20"],
+                        "outputs": ["coverage/$TURBO_MATRIX/**"]
+                    }
+                }
+            })),
+        )]
+        .into_iter()
+        .collect();
+        let loader = TurboJsonLoader::noop(turbo_jsons);
+        EngineBuilder::new(&repo_root, &package_graph, loader, false)
+            .with_tasks_only(tasks_only)
+            .with_tasks(Some(Spanned::new(TaskName::from(task.to_string()))))
+            .with_workspaces(vec![PackageName::from("app1")])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_matrix_expands_into_variants() {
+        let engine = matrix_engine("test", false);
+        let expected = deps! {
+            "app1#test" => ["libA#build", "app1#test:node18", "app1#test:node20"],
+            "app1#test:node18" => ["libA#build"],
+            "app1#test:node20" => ["libA#build"],
+            "libA#build" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+
+        let aggregate = TaskId::new("app1", "test");
+        let definition = engine.task_definition(&aggregate).unwrap();
+        assert_eq!(definition.script(&aggregate), None);
+
+        let variant = TaskId::new("app1", "test:node20");
+        let definition = engine.task_definition(&variant).unwrap();
+        assert_eq!(definition.script(&variant), Some("test"));
+        assert_eq!(
+            definition.matrix_variant.as_ref().map(|v| v.value.as_str()),
+            Some("node20")
+        );
+        assert_eq!(
+            definition.outputs.inclusions,
+            vec!["coverage/node20/**".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_matrix_single_variant() {
+        let engine = matrix_engine("test:node18", false);
+        let expected = deps! {
+            "app1#test:node18" => ["libA#build"],
+            "libA#build" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+    }
+
+    #[test]
+    fn test_matrix_tasks_only_keeps_variants() {
+        let engine = matrix_engine("test", true);
+        let expected = deps! {
+            "app1#test" => ["app1#test:node18", "app1#test:node20"],
+            "app1#test:node18" => ["___ROOT___"],
+            "app1#test:node20" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+    }
+
+    fn inputs_from_engine(tasks: serde_json::Value) -> Result<Engine, Error> {
+        inputs_from_engine_with_only(tasks, false)
+    }
+
+    fn inputs_from_engine_with_only(
+        tasks: serde_json::Value,
+        tasks_only: bool,
+    ) -> Result<Engine, Error> {
+        let repo_root_dir = TempDir::with_prefix("repo").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::new(repo_root_dir.path().to_str().unwrap()).unwrap();
+        let package_graph = mock_package_graph(
+            &repo_root,
+            package_jsons! {
+                repo_root,
+                "app1" => []
+            },
+        );
+        let turbo_jsons = vec![(PackageName::Root, turbo_json(json!({ "tasks": tasks })))]
+            .into_iter()
+            .collect();
+        let loader = TurboJsonLoader::noop(turbo_jsons);
+        EngineBuilder::new(&repo_root, &package_graph, loader, false)
+            .with_tasks(Some(Spanned::new(TaskName::from("build"))))
+            .with_tasks_only(tasks_only)
+            .with_workspaces(vec![PackageName::from("app1")])
+            .build()
+    }
+
+    #[test_case(json!([]), &["$TURBO_DEFAULT$", "!src/generated/**"] ; "default inputs")]
+    #[test_case(json!(["src/**"]), &["src/**", "!src/generated/**"] ; "explicit inputs")]
+    fn test_inputs_from(inputs: serde_json::Value, expected_inputs: &[&str]) {
+        let engine = inputs_from_engine(json!({
+            "codegen": { "outputs": ["src/generated/**", "!src/generated/tmp"] },
+            "build": { "inputs": inputs, "inputsFrom": ["codegen"], "outputs": ["dist/**"] }
+        }))
+        .unwrap();
+
+        let expected = deps! {
+            "app1#build" => ["app1#codegen"],
+            "app1#codegen" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+
+        let definition = engine
+            .task_definition(&TaskId::new("app1", "build"))
+            .unwrap();
+        assert_eq!(definition.inputs, expected_inputs);
+    }
+
+    #[test]
+    fn test_inputs_from_with_only() {
+        let engine = inputs_from_engine_with_only(
+            json!({
+                "codegen": { "outputs": ["src/generated/**", "!src/generated/tmp"] },
+                "build": { "inputs": ["src/**"], "inputsFrom": ["codegen"] }
+            }),
+            true,
+        )
+        .unwrap();
+
+        let expected = deps! {
+            "app1#build" => ["___ROOT___"]
+        };
+        assert_eq!(all_dependencies(&engine), expected);
+
+        // codegen won't run, so the files it left behind are hashed
+        let definition = engine
+            .task_definition(&TaskId::new("app1", "build"))
+            .unwrap();
+        assert_eq!(definition.inputs, &["src/**", "src/generated/**"]);
+    }
+
+    #[test]
+    fn test_inputs_from_missing_task() {
+        let engine = inputs_from_engine(json!({
+            "build": { "inputsFrom": ["codegen"] }
+        }));
+        assert_matches!(engine, Err(Error::MissingInputsFromTask { .. }));
+    }
+
+    #[test]
+    fn test_inputs_from_task_without_outputs() {
+        let engine = inputs_from_engine(json!({
+            "codegen": {},
+            "build": { "inputsFrom": ["codegen"] }
+        }));
+        assert_matches!(engine, Err(Error::InputsFromWithoutOutputs { .. }));
+    }
+
+    #[test]
+    fn test_missing_task_suggestion() {
+        let engine = inputs_from_engine(json!({ "biuld": {} }));
+        let Err(Error::MissingTasks(errors)) = engine else {
+            panic!("expected missing tasks error, got {engine:?}");
+        };
+        assert_eq!(errors[0].help.as_deref(), Some("did you mean `biuld`?"));
+    }
+
+    #[test]
+    fn test_missing_dependency_suggestion() {
+        let engine = inputs_from_engine(json!({
+            "codegen": {},
+            "build": { "dependsOn": ["codegn"] }
+        }));
+        let Err(Error::MissingPackageTask(error)) = engine else {
+            panic!("expected missing package task error, got {engine:?}");
+        };
+        assert_eq!(error.help.as_deref(), Some("did you mean `codegen`?"));
+    }
+
     #[allow(clippy::duplicated_attributes)]
     #[test_case("build", None)]
     #[test_case("build:prod", None)]
@@ -168,14 +168,18 @@ impl Engine<Built> {
             .flatten()
             .collect();
 
-        // We reverse the graph because we want the *dependents* of entrypoint tasks
-        let mut reversed_graph = self.task_graph.clone();
-        reversed_graph.reverse();
-
-        // This is `O(V^3)`, so in theory a bottleneck. Running dijkstra's
-        // algorithm for each entrypoint task could potentially be faster.
-        let node_distances = petgraph::algo::floyd_warshall::floyd_warshall(&reversed_graph, |_| 1)
-            .expect("no negative cycles");
+        // We traverse the reversed graph because we want the *dependents* of
+        // entrypoint tasks. The visited set is shared across entrypoints so each
+        // node is only visited once, keeping this linear in the size of the graph.
+        let reversed_graph = petgraph::visit::Reversed(&self.task_graph);
+        let mut dfs = petgraph::visit::Dfs::empty(reversed_graph);
+        let mut reachable = HashSet::new();
+        for idx in &entrypoint_indices {
+            dfs.move_to(**idx);
+            while let Some(node_idx) = dfs.next(reversed_graph) {
+                reachable.insert(node_idx);
+            }
+        }
 
         let new_graph = self.task_graph.filter_map(
             |node_idx, node| {
@@ -191,14 +195,7 @@ impl Engine<Built> {
                     }
                 }
                 // If the node is reachable from any of the entrypoint tasks, we include it
-                entrypoint_indices
-                    .iter()
-                    .any(|idx| {
-                        node_distances
-                            .get(&(**idx, node_idx))
-                            .map_or(false, |dist| *dist != i32::MAX)
-                    })
-                    .then_some(node.clone())
+                reachable.contains(&node_idx).then_some(node.clone())
             },
             |_, _| Some(()),
         );
@@ -433,6 +430,11 @@ impl Engine<Built> {
                     // No need to check the root node if that's where we are.
                     return Ok(false);
                 };
+                let task_definition = self.task_definitions.get(task_id);
+                // A task with a matrix doesn't run anything itself, so it can depend on
+                // persistent variants
+                let runs_variants =
+                    task_definition.map_or(false, |task_def| !task_def.matrix.is_empty());
 
                 for dep_index in self
                     .task_graph
@@ -460,7 +462,10 @@ impl Engine<Built> {
                             package: dep_id.package().to_string(),
                         })?;
                     if task_definition.persistent
-                        && package_json.scripts.contains_key(dep_id.task())
+                        && !runs_variants
+                        && task_definition
+                            .script(dep_id)
+                            .map_or(false, |script| package_json.scripts.contains_key(script))
                     {
                         let (span, text) = self
                             .task_locations
@@ -482,17 +487,14 @@ impl Engine<Built> {
                     .package_info(&PackageName::from(task_id.package().to_string()))
                     .expect("package graph should contain workspace info for task package");
 
-                let package_has_task = info
-                    .package_json
-                    .scripts
-                    .get(task_id.task())
+                let package_has_task = task_definition
+                    .and_then(|task_def| task_def.script(task_id))
+                    .and_then(|script| info.package_json.scripts.get(script))
                     // handle legacy behaviour from go where an empty string may appear
                     .map_or(false, |script| !script.is_empty());
 
-                let task_is_persistent = self
-                    .task_definitions
-                    .get(task_id)
-                    .map_or(false, |task_def| task_def.persistent);
+                let task_is_persistent =
+                    task_definition.map_or(false, |task_def| task_def.persistent);
 
                 Ok(task_is_persistent && package_has_task)
             })
@@ -813,4 +815,34 @@ mod test {
         assert!(tasks.contains(&&TaskNode::Task(a_dev_task_id)));
         assert!(tasks.contains(&&TaskNode::Task(b_build_task_id)));
     }
+
+    #[tokio::test]
+    async fn test_get_subgraph_includes_transitive_dependents() {
+        // `c#build` depends on `b#build` which depends on `a#build`, so a change in
+        // `a` should pull in the whole chain while leaving `d#build` out.
+        let mut engine = Engine::new();
+
+        let task_ids = ["a", "b", "c", "d"].map(|pkg| TaskId::new(pkg, "build"));
+        let indices = task_ids
+            .iter()
+            .map(|task_id| {
+                let idx = engine.get_index(task_id);
+                engine.add_definition(task_id.clone(), TaskDefinition::default());
+                idx
+            })
+            .collect::<Vec<_>>();
+        engine.task_graph.add_edge(indices[1], indices[0], ());
+        engine.task_graph.add_edge(indices[2], indices[1], ());
+
+        let engine = engine.seal();
+        let subgraph =
+            engine.create_engine_for_subgraph(&[PackageName::from("a")].into_iter().collect());
+
+        let tasks: HashSet<_> = subgraph.tasks().collect();
+        assert_eq!(tasks.len(), 3);
+        for task_id in &task_ids[..3] {
+            assert!(tasks.contains(&TaskNode::Task(task_id.clone())));
+        }
+        assert!(!tasks.contains(&TaskNode::Task(task_ids[3].clone())));
+    }
 }
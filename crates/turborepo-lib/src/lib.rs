@@ -11,6 +11,7 @@
 #![allow(clippy::needless_pass_by_ref_mut)]
 #![allow(dead_code)]
 
+pub mod api;
 mod child;
 mod cli;
 mod commands;
@@ -1,19 +1,20 @@
 use std::{backtrace, backtrace::Backtrace};
 
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use thiserror::Error;
 use turbopath::AnchoredSystemPathBuf;
 use turborepo_api_client::APIAuth;
-use turborepo_cache::{CacheOpts, RemoteCacheOpts};
+use turborepo_cache::{CacheOpts, RemoteCacheOpts, RetryPolicy};
 
 use crate::{
     cli::{
-        Command, DryRunMode, EnvMode, ExecutionArgs, LogOrder, LogPrefix, OutputLogsMode, RunArgs,
+        Command, ContinueMode, DryRunMode, EnvMode, ExecutionArgs, LogFormat, LogOrder, LogPrefix,
+        OutputLogsMode, RunArgs, RunOutputFormat,
     },
     commands::CommandBase,
     config::ConfigurationOptions,
     run::task_id::TaskId,
-    turbo_json::UIMode,
+    turbo_json::{ExitCodes, UIMode},
 };
 
 #[derive(Debug, Error)]
@@ -164,8 +165,12 @@ pub struct RunOpts {
     pub(crate) single_package: bool,
     pub log_prefix: ResolvedLogPrefix,
     pub log_order: ResolvedLogOrder,
+    pub(crate) log_format: LogFormat,
     pub summarize: bool,
+    pub(crate) output_format: Vec<RunOutputFormat>,
     pub(crate) experimental_space_id: Option<String>,
+    pub(crate) summary_upload: Option<SummaryUploadOpts>,
+    pub(crate) exit_codes: ExitCodes,
     pub is_github_actions: bool,
     pub ui_mode: UIMode,
 }
@@ -202,12 +207,31 @@ impl<'a> TaskArgs<'a> {
     }
 }
 
+/// Where run summaries get uploaded. Header values are templates that haven't
+/// been filled in from the environment yet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SummaryUploadOpts {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
 #[derive(Clone, Debug)]
 pub enum GraphOpts {
     Stdout,
     File(String),
 }
 
+impl GraphOpts {
+    /// The file to write an HTML graph to. HTML graphs are written once the run
+    /// finishes so they can show how each task ran.
+    pub fn html_file(&self) -> Option<&str> {
+        match self {
+            GraphOpts::File(file) if Utf8Path::new(file).extension() == Some("html") => Some(file),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ResolvedLogOrder {
     Stream,
@@ -243,7 +267,7 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             LogOrder::Auto if turborepo_ci::Vendor::get_constant() == Some("GITHUB_ACTIONS") => (
                 true,
                 ResolvedLogOrder::Grouped,
-                match inputs.execution_args.log_prefix {
+                match inputs.config.log_prefix() {
                     LogPrefix::Task => ResolvedLogPrefix::Task,
                     _ => ResolvedLogPrefix::None,
                 },
@@ -253,30 +277,54 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             LogOrder::Auto | LogOrder::Stream => (
                 false,
                 ResolvedLogOrder::Stream,
-                inputs.execution_args.log_prefix.into(),
+                inputs.config.log_prefix().into(),
             ),
             LogOrder::Grouped => (
                 false,
                 ResolvedLogOrder::Grouped,
-                inputs.execution_args.log_prefix.into(),
+                inputs.config.log_prefix().into(),
             ),
         };
 
+        // Structured logs carry the task in each record instead
+        let log_format = inputs.config.log_format();
+        let log_prefix = match log_format {
+            LogFormat::Text => log_prefix,
+            LogFormat::Ndjson => ResolvedLogPrefix::None,
+        };
+
         Ok(Self {
             tasks: inputs.execution_args.tasks.clone(),
             log_prefix,
             log_order,
+            log_format,
             summarize: inputs.config.run_summary(),
+            output_format: inputs.run_args.output_format.clone(),
             experimental_space_id: inputs
                 .run_args
                 .experimental_space_id
                 .clone()
                 .or(inputs.config.spaces_id().map(|s| s.to_owned())),
+            summary_upload: inputs
+                .config
+                .run_summary_upload_url()
+                .map(|url| SummaryUploadOpts {
+                    url: url.to_string(),
+                    headers: inputs
+                        .config
+                        .run_summary_upload_headers()
+                        .map(|(name, value)| (name.to_string(), value.to_string()))
+                        .collect(),
+                }),
+            exit_codes: inputs.config.exit_codes(),
             framework_inference: inputs.execution_args.framework_inference,
             concurrency,
             parallel: inputs.run_args.parallel,
             profile: inputs.run_args.profile.clone(),
-            continue_on_error: inputs.execution_args.continue_execution,
+            continue_on_error: matches!(
+                inputs.execution_args.continue_execution,
+                ContinueMode::Always
+            ),
             pass_through_args: inputs.execution_args.pass_through_args.clone(),
             only: inputs.execution_args.only,
             daemon: inputs.config.daemon(),
@@ -341,13 +389,14 @@ impl<'a> TryFrom<OptsInputs<'a>> for ScopeOpts {
             .map(AnchoredSystemPathBuf::from_raw)
             .transpose()?;
 
-        let affected_range = inputs.execution_args.affected.then(|| {
-            let scm_base = inputs.config.scm_base();
-            let scm_head = inputs.config.scm_head();
-            (
-                scm_base.map(|b| b.to_owned()),
-                scm_head.map(|h| h.to_string()),
-            )
+        let affected_range = inputs.execution_args.affected.as_ref().map(|range| {
+            let (base, head) = range
+                .as_deref()
+                .map(parse_affected_range)
+                .unwrap_or_default();
+            let scm_base = base.or_else(|| inputs.config.scm_base().map(|b| b.to_owned()));
+            let scm_head = head.or_else(|| inputs.config.scm_head().map(|h| h.to_owned()));
+            (scm_base, scm_head)
         });
 
         Ok(Self {
@@ -362,7 +411,10 @@ impl<'a> TryFrom<OptsInputs<'a>> for ScopeOpts {
 impl<'a> From<OptsInputs<'a>> for CacheOpts {
     fn from(inputs: OptsInputs<'a>) -> Self {
         let is_linked = turborepo_api_client::is_linked(inputs.api_auth);
-        let skip_remote = if !is_linked {
+        let storage_url = inputs.config.storage_url().map(|url| url.to_string());
+        // A storage bucket authenticates with its own credentials, so it doesn't
+        // require the repository to be linked.
+        let skip_remote = if !is_linked && storage_url.is_none() {
             true
         } else if let Some(enabled) = inputs.config.enabled {
             // We're linked, but if the user has explicitly enabled or disabled, use that
@@ -381,14 +433,23 @@ impl<'a> From<OptsInputs<'a>> for CacheOpts {
         let remote_cache_opts = Some(RemoteCacheOpts::new(
             unused_remote_cache_opts_team_id,
             signature,
+            inputs.config.signature_mismatch(),
+            inputs.config.chunking(),
         ));
 
         CacheOpts {
             cache_dir: inputs.config.cache_dir().into(),
             skip_filesystem: inputs.config.remote_only(),
             remote_cache_read_only: inputs.config.remote_cache_read_only(),
+            remote_cache_write_only: inputs.config.remote_cache_write_only(),
             workers: inputs.run_args.cache_workers,
             skip_remote,
+            storage_url,
+            compression_level: inputs.config.cache_compression_level(),
+            eviction_policy: inputs.config.cache_eviction_policy(),
+            retry_policy: RetryPolicy {
+                retries: inputs.config.retries(),
+            },
             remote_cache_opts,
         }
     }
@@ -408,12 +469,23 @@ impl ScopeOpts {
     }
 }
 
+/// Parses a `<base>...<head>` range as passed to `--affected`.
+/// Either side may be empty, in which case it is left unset so the
+/// configured SCM base or head can be used instead.
+fn parse_affected_range(range: &str) -> (Option<String>, Option<String>) {
+    let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+    match range.split_once("...") {
+        Some((base, head)) => (non_empty(base), non_empty(head)),
+        None => (non_empty(range), None),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use test_case::test_case;
     use turborepo_cache::CacheOpts;
 
-    use super::RunOpts;
+    use super::{parse_affected_range, RunOpts};
     use crate::{
         cli::DryRunMode,
         opts::{Opts, RunCacheOpts, ScopeOpts},
@@ -527,8 +599,12 @@ mod test {
             single_package: false,
             log_prefix: crate::opts::ResolvedLogPrefix::Task,
             log_order: crate::opts::ResolvedLogOrder::Stream,
+            log_format: crate::cli::LogFormat::Text,
             summarize: false,
+            output_format: Vec::new(),
             experimental_space_id: None,
+            summary_upload: None,
+            exit_codes: Default::default(),
             is_github_actions: false,
             daemon: None,
         };
@@ -551,4 +627,18 @@ mod test {
         let synthesized = opts.synthesize_command();
         assert_eq!(synthesized, expected);
     }
+
+    #[test_case("main...HEAD", (Some("main"), Some("HEAD")) ; "base and head")]
+    #[test_case("main", (Some("main"), None) ; "base only")]
+    #[test_case("main...", (Some("main"), None) ; "trailing separator")]
+    #[test_case("...my-branch", (None, Some("my-branch")) ; "head only")]
+    #[test_case("", (None, None) ; "empty")]
+    fn test_parse_affected_range(input: &str, expected: (Option<&str>, Option<&str>)) {
+        let (base, head) = parse_affected_range(input);
+        assert_eq!(
+            (base.as_deref(), head.as_deref()),
+            expected,
+            "unexpected range for {input}"
+        );
+    }
 }
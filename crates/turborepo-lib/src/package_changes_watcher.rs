@@ -34,6 +34,7 @@ pub struct PackageChangesWatcher {
     _exit_tx: oneshot::Sender<()>,
     _handle: tokio::task::JoinHandle<()>,
     package_change_events_rx: broadcast::Receiver<PackageChangeEvent>,
+    changes_log: PackageChangesLog,
 }
 
 /// The number of events that can be buffered in the channel.
@@ -49,6 +50,8 @@ impl PackageChangesWatcher {
         let (exit_tx, exit_rx) = oneshot::channel();
         let (package_change_events_tx, package_change_events_rx) =
             broadcast::channel(CHANGE_EVENT_CHANNEL_CAPACITY);
+        // Subscribe before the subscriber starts so the log sees every event
+        let changes_log = PackageChangesLog::new(package_change_events_rx.resubscribe());
         let subscriber = Subscriber::new(
             repo_root,
             file_events_lazy,
@@ -61,12 +64,137 @@ impl PackageChangesWatcher {
             _exit_tx: exit_tx,
             _handle,
             package_change_events_rx,
+            changes_log,
         }
     }
 
     pub async fn package_changes(&self) -> broadcast::Receiver<PackageChangeEvent> {
         self.package_change_events_rx.resubscribe()
     }
+
+    /// Returns the packages that have changed since the point identified by
+    /// `since`, along with a hash identifying the current point.
+    pub fn changed_packages_since(&self, since: &str) -> (ChangedPackages, String) {
+        self.changes_log.changed_since(since)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ChangedPackages {
+    All,
+    Some(HashSet<PackageName>),
+}
+
+/// Records the package change events seen by the daemon, so clients can ask
+/// which packages changed since an earlier point without holding a stream
+/// of events open.
+struct PackageChangesLog {
+    // Distinguishes the points recorded by this daemon from ones recorded by
+    // a previous daemon for the same repository
+    session: u64,
+    state: Arc<std::sync::Mutex<ChangesLogState>>,
+    _handle: tokio::task::JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct ChangesLogState {
+    // Incremented for every event, so each event is a new point in the log
+    sequence: u64,
+    // The point at which each package last changed
+    last_changed: HashMap<PackageName, u64>,
+    // The last point at which every package had to be considered changed.
+    // The watcher starts by asking for a rediscovery, so points handed out
+    // before then also report every package.
+    last_rediscover: Option<u64>,
+    closed: bool,
+}
+
+impl PackageChangesLog {
+    fn new(mut events: broadcast::Receiver<PackageChangeEvent>) -> Self {
+        let session = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos() as u64);
+        let state = Arc::new(std::sync::Mutex::new(ChangesLogState::default()));
+
+        let _handle = tokio::spawn({
+            let state = state.clone();
+            async move {
+                loop {
+                    let event = match events.recv().await {
+                        Ok(event) => event,
+                        // We can't tell which packages changed in the events we missed
+                        Err(broadcast::error::RecvError::Lagged(_)) => {
+                            PackageChangeEvent::Rediscover
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            state.lock().expect("changes log lock poisoned").closed = true;
+                            break;
+                        }
+                    };
+                    state
+                        .lock()
+                        .expect("changes log lock poisoned")
+                        .record(event);
+                }
+            }
+        });
+
+        Self {
+            session,
+            state,
+            _handle,
+        }
+    }
+
+    fn changed_since(&self, since: &str) -> (ChangedPackages, String) {
+        let state = self.state.lock().expect("changes log lock poisoned");
+        let hash = format!("{:016x}{:016x}", self.session, state.sequence);
+        let since = parse_point(since)
+            .filter(|(session, _)| *session == self.session)
+            .map(|(_, sequence)| sequence);
+        (state.changed_since(since), hash)
+    }
+}
+
+fn parse_point(hash: &str) -> Option<(u64, u64)> {
+    if hash.len() != 32 || !hash.is_ascii() {
+        return None;
+    }
+    let (session, sequence) = hash.split_at(16);
+    Some((
+        u64::from_str_radix(session, 16).ok()?,
+        u64::from_str_radix(sequence, 16).ok()?,
+    ))
+}
+
+impl ChangesLogState {
+    fn record(&mut self, event: PackageChangeEvent) {
+        self.sequence += 1;
+        match event {
+            PackageChangeEvent::Package { name } => {
+                self.last_changed.insert(name, self.sequence);
+            }
+            PackageChangeEvent::Rediscover => {
+                self.last_rediscover = Some(self.sequence);
+                self.last_changed.clear();
+            }
+        }
+    }
+
+    fn changed_since(&self, since: Option<u64>) -> ChangedPackages {
+        match (since, self.last_rediscover) {
+            (Some(since), Some(last_rediscover)) if !self.closed && since >= last_rediscover => {
+                ChangedPackages::Some(
+                    self.last_changed
+                        .iter()
+                        .filter(|(_, changed)| **changed > since)
+                        .map(|(name, _)| name.clone())
+                        .collect(),
+                )
+            }
+            _ => ChangedPackages::All,
+        }
+    }
 }
 
 enum ChangedFiles {
@@ -437,3 +565,59 @@ impl Subscriber {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn package(name: &str) -> PackageChangeEvent {
+        PackageChangeEvent::Package {
+            name: PackageName::from(name),
+        }
+    }
+
+    #[test]
+    fn test_changes_log() {
+        let mut state = ChangesLogState::default();
+        assert_eq!(state.changed_since(Some(0)), ChangedPackages::All);
+
+        state.record(PackageChangeEvent::Rediscover);
+        let start = state.sequence;
+        assert_eq!(state.changed_since(Some(0)), ChangedPackages::All);
+        assert_eq!(
+            state.changed_since(Some(start)),
+            ChangedPackages::Some(HashSet::new())
+        );
+
+        state.record(package("a"));
+        let after_a = state.sequence;
+        state.record(package("b"));
+        state.record(package("a"));
+        assert_eq!(
+            state.changed_since(Some(start)),
+            ChangedPackages::Some(["a", "b"].into_iter().map(PackageName::from).collect())
+        );
+        assert_eq!(
+            state.changed_since(Some(after_a)),
+            ChangedPackages::Some(["a", "b"].into_iter().map(PackageName::from).collect())
+        );
+        assert_eq!(
+            state.changed_since(Some(state.sequence)),
+            ChangedPackages::Some(HashSet::new())
+        );
+
+        state.record(PackageChangeEvent::Rediscover);
+        assert_eq!(state.changed_since(Some(after_a)), ChangedPackages::All);
+        assert_eq!(state.changed_since(None), ChangedPackages::All);
+    }
+
+    #[test]
+    fn test_parse_point() {
+        assert_eq!(
+            parse_point("000000000000000a0000000000000002"),
+            Some((10, 2))
+        );
+        assert_eq!(parse_point(""), None);
+        assert_eq!(parse_point("not a hash"), None);
+    }
+}
@@ -588,11 +588,25 @@ impl Child {
     pub async fn wait_with_piped_outputs<W: Write>(
         &mut self,
         stdout_pipe: W,
+    ) -> Result<Option<ChildExit>, std::io::Error> {
+        self.wait_with_split_piped_outputs(stdout_pipe, None::<std::io::Sink>)
+            .await
+    }
+
+    /// Wait for the `Child` to exit, piping stdout and stderr to their own
+    /// writers. Stderr goes to the stdout writer if there is no stderr writer
+    /// or the child is attached to a pty, which combines the two.
+    #[tracing::instrument(skip_all)]
+    pub async fn wait_with_split_piped_outputs<W1: Write, W2: Write>(
+        &mut self,
+        stdout_pipe: W1,
+        stderr_pipe: Option<W2>,
     ) -> Result<Option<ChildExit>, std::io::Error> {
         match self.outputs() {
             Some(ChildOutput::Std { stdout, stderr }) => {
                 self.wait_with_piped_async_outputs(
                     stdout_pipe,
+                    stderr_pipe,
                     Some(BufReader::new(stdout)),
                     Some(BufReader::new(stderr)),
                 )
@@ -664,6 +678,7 @@ impl Child {
     async fn wait_with_piped_async_outputs<R1: AsyncBufRead + Unpin, R2: AsyncBufRead + Unpin>(
         &mut self,
         mut stdout_pipe: impl Write,
+        mut stderr_pipe: Option<impl Write>,
         mut stdout_lines: Option<R1>,
         mut stderr_lines: Option<R2>,
     ) -> Result<Option<ChildExit>, std::io::Error> {
@@ -701,7 +716,10 @@ impl Child {
                     trace!("processing stderr line");
                     result?;
                     add_trailing_newline(&mut stderr_buffer);
-                    stdout_pipe.write_all(&stderr_buffer)?;
+                    match &mut stderr_pipe {
+                        Some(stderr_pipe) => stderr_pipe.write_all(&stderr_buffer)?,
+                        None => stdout_pipe.write_all(&stderr_buffer)?,
+                    }
                     stderr_buffer.clear();
                 }
                 status = self.wait(), if !is_exited => {
@@ -727,7 +745,10 @@ impl Child {
                     }
                     if !stderr_buffer.is_empty() {
                         add_trailing_newline(&mut stderr_buffer);
-                        stdout_pipe.write_all(&stderr_buffer)?;
+                        match &mut stderr_pipe {
+                            Some(stderr_pipe) => stderr_pipe.write_all(&stderr_buffer)?,
+                            None => stdout_pipe.write_all(&stderr_buffer)?,
+                        }
                         stderr_buffer.clear();
                     }
                     break;
@@ -1165,6 +1186,27 @@ mod test {
         assert_matches!(exit, Some(ChildExit::Finished(Some(0))));
     }
 
+    #[tokio::test]
+    async fn test_wait_with_split_outputs() {
+        let script = find_script_dir().join_component("hello_world_hello_moon.js");
+        let mut cmd = Command::new("node");
+        cmd.args([script.as_std_path()]);
+        cmd.open_stdin();
+        let mut child = Child::spawn(cmd, ShutdownStyle::Kill, None).unwrap();
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+
+        let exit = child
+            .wait_with_split_piped_outputs(&mut stdout, Some(&mut stderr))
+            .await
+            .unwrap();
+
+        assert_eq!(String::from_utf8(stdout).unwrap(), "hello world\n");
+        assert_eq!(String::from_utf8(stderr).unwrap(), "hello moon\n");
+        assert_matches!(exit, Some(ChildExit::Finished(Some(0))));
+    }
+
     #[test_case(false)]
     #[test_case(TEST_PTY)]
     #[tokio::test]
@@ -91,6 +91,10 @@ enum PackageFields {
     IndirectDependencyCount,
     AllDependentCount,
     AllDependencyCount,
+    DirectDependency,
+    DirectDependent,
+    AllDependency,
+    AllDependent,
 }
 
 #[derive(InputObject)]
@@ -249,6 +253,12 @@ impl PackagePredicate {
         match (field, &value.0) {
             (PackageFields::Name, Value::String(name)) => pkg.name.as_ref() == name,
             (PackageFields::TaskName, Value::String(name)) => pkg.get_tasks().contains_key(name),
+            (PackageFields::DirectDependency, Value::String(name)) => {
+                pkg.has_direct_dependency(name)
+            }
+            (PackageFields::DirectDependent, Value::String(name)) => pkg.has_direct_dependent(name),
+            (PackageFields::AllDependency, Value::String(name)) => pkg.has_dependency(name),
+            (PackageFields::AllDependent, Value::String(name)) => pkg.has_dependent(name),
             _ => false,
         }
     }
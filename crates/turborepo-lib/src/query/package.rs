@@ -69,6 +69,41 @@ impl Package {
             .dependencies(&PackageNode::Workspace(self.name.clone()))
             .len()
     }
+
+    pub fn has_direct_dependency(&self, name: &str) -> bool {
+        self.run
+            .pkg_dep_graph()
+            .immediate_dependencies(&PackageNode::Workspace(self.name.clone()))
+            .is_some_and(|pkgs| contains_package(pkgs, name))
+    }
+
+    pub fn has_direct_dependent(&self, name: &str) -> bool {
+        self.run
+            .pkg_dep_graph()
+            .immediate_ancestors(&PackageNode::Workspace(self.name.clone()))
+            .is_some_and(|pkgs| contains_package(pkgs, name))
+    }
+
+    pub fn has_dependency(&self, name: &str) -> bool {
+        let pkgs = self
+            .run
+            .pkg_dep_graph()
+            .dependencies(&PackageNode::Workspace(self.name.clone()));
+        contains_package(pkgs, name)
+    }
+
+    pub fn has_dependent(&self, name: &str) -> bool {
+        let pkgs = self
+            .run
+            .pkg_dep_graph()
+            .ancestors(&PackageNode::Workspace(self.name.clone()));
+        contains_package(pkgs, name)
+    }
+}
+
+fn contains_package<'a>(pkgs: impl IntoIterator<Item = &'a PackageNode>, name: &str) -> bool {
+    pkgs.into_iter()
+        .any(|pkg| pkg.as_package_name().as_ref() == name)
 }
 
 #[Object]
@@ -6,7 +6,8 @@ use std::{
 };
 
 use chrono::Local;
-use tracing::debug;
+use itertools::Itertools;
+use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_analytics::{start_analytics, AnalyticsHandle, AnalyticsSender};
 use turborepo_api_client::{APIAuth, APIClient};
@@ -15,7 +16,7 @@ use turborepo_env::EnvironmentVariableMap;
 use turborepo_errors::Spanned;
 use turborepo_repository::{
     change_mapper::PackageInclusionReason,
-    package_graph::{PackageGraph, PackageName},
+    package_graph::{self, PackageGraph, PackageInfo, PackageName, PackageNode},
     package_json,
     package_json::PackageJson,
 };
@@ -38,6 +39,7 @@ use {
 };
 
 use crate::{
+    api::RunEventSender,
     cli::DryRunMode,
     commands::CommandBase,
     engine::{Engine, EngineBuilder},
@@ -46,7 +48,7 @@ use crate::{
     run::{scope, task_access::TaskAccess, task_id::TaskName, Error, Run, RunCache},
     shim::TurboState,
     signal::{SignalHandler, SignalSubscriber},
-    turbo_json::{TurboJson, TurboJsonLoader, UIMode},
+    turbo_json::{DependencyCycles, HashMode, TurboJson, TurboJsonLoader, UIMode},
     DaemonConnector,
 };
 
@@ -56,17 +58,22 @@ pub struct RunBuilder {
     api_auth: Option<APIAuth>,
     repo_root: AbsoluteSystemPathBuf,
     root_turbo_json_path: AbsoluteSystemPathBuf,
+    config_overlay_path: Option<AbsoluteSystemPathBuf>,
     color_config: ColorConfig,
     version: &'static str,
     api_client: APIClient,
     analytics_sender: Option<AnalyticsSender>,
+    run_events: Option<RunEventSender>,
     // In watch mode, we can have a changed package that we want to serve as an entrypoint.
     // We will then prune away any tasks that do not depend on tasks inside
     // this package.
     entrypoint_packages: Option<HashSet<PackageName>>,
     should_print_prelude_override: Option<bool>,
+    prefetch: bool,
     allow_missing_package_manager: bool,
     allow_no_turbo_json: bool,
+    dependency_cycles: DependencyCycles,
+    hash_mode: HashMode,
     // In query, we don't want to validate the engine. Defaults to `true`
     should_validate_engine: bool,
     // If true, we will add all tasks to the graph, even if they are not specified
@@ -91,7 +98,10 @@ impl RunBuilder {
             (!cfg!(windows) || matches!(opts.run_opts.ui_mode, UIMode::Tui)),
         );
         let root_turbo_json_path = config.root_turbo_json_path(&base.repo_root);
+        let config_overlay_path = config.config_overlay_path(&base.repo_root)?;
         let allow_no_turbo_json = config.allow_no_turbo_json();
+        let dependency_cycles = config.dependency_cycles();
+        let hash_mode = config.hash_mode();
 
         let CommandBase {
             repo_root,
@@ -108,11 +118,16 @@ impl RunBuilder {
             version,
             api_auth,
             analytics_sender: None,
+            run_events: None,
             entrypoint_packages: None,
             should_print_prelude_override: None,
+            prefetch: false,
             allow_missing_package_manager,
             root_turbo_json_path,
+            config_overlay_path,
             allow_no_turbo_json,
+            dependency_cycles,
+            hash_mode,
             should_validate_engine: true,
             add_all_tasks: false,
         })
@@ -128,6 +143,13 @@ impl RunBuilder {
         self
     }
 
+    /// Download remote artifacts for the selected tasks instead of running
+    /// them
+    pub fn prefetch(mut self) -> Self {
+        self.prefetch = true;
+        self
+    }
+
     pub fn add_all_tasks(mut self) -> Self {
         self.add_all_tasks = true;
         self
@@ -151,6 +173,11 @@ impl RunBuilder {
         self
     }
 
+    pub fn with_run_events(mut self, run_events: RunEventSender) -> Self {
+        self.run_events = Some(run_events);
+        self
+    }
+
     pub fn calculate_filtered_packages(
         repo_root: &AbsoluteSystemPath,
         opts: &Opts,
@@ -164,7 +191,16 @@ impl RunBuilder {
             pkg_dep_graph,
             scm,
             root_turbo_json,
-        )?;
+        )
+        .map_err(|e| match e {
+            scope::ResolutionError::NoPackagesMatchedWithName(name) if scm.checkout().sparse => {
+                Error::NotInSparseCheckout(name)
+            }
+            e => e.into(),
+        })?;
+        if scm.checkout().sparse {
+            check_sparse_dependencies(repo_root, pkg_dep_graph, scm, filtered_pkgs.keys())?;
+        }
 
         if is_all_packages {
             for target in opts.run_opts.tasks.iter() {
@@ -223,7 +259,11 @@ impl RunBuilder {
 
         let scm = {
             let repo_root = self.repo_root.clone();
-            tokio::task::spawn_blocking(move || SCM::new(&repo_root))
+            let hash_mode = self.hash_mode;
+            tokio::task::spawn_blocking(move || match hash_mode {
+                HashMode::Git => SCM::new(&repo_root),
+                HashMode::Filesystem => SCM::Manual,
+            })
         };
         let package_json_path = self.repo_root.join_component("package.json");
         let root_package_json = PackageJson::load(&package_json_path)?;
@@ -384,7 +424,7 @@ impl RunBuilder {
         let task_access = TaskAccess::new(self.repo_root.clone(), async_cache.clone(), &scm);
         task_access.restore_config().await;
 
-        let mut turbo_json_loader = if task_access.is_enabled() {
+        let turbo_json_loader = if task_access.is_enabled() {
             TurboJsonLoader::task_access(
                 self.repo_root.clone(),
                 self.root_turbo_json_path.clone(),
@@ -408,10 +448,25 @@ impl RunBuilder {
                 pkg_dep_graph.packages(),
             )
         };
+        let mut turbo_json_loader =
+            turbo_json_loader.with_overlay(self.config_overlay_path.clone());
 
         let root_turbo_json = turbo_json_loader.load(&PackageName::Root)?.clone();
 
         pkg_dep_graph.validate()?;
+        let cycles = pkg_dep_graph.dependency_cycles();
+        if !cycles.is_empty() {
+            match self.dependency_cycles {
+                DependencyCycles::Error => {
+                    return Err(package_graph::Error::CyclicDependencies(cycles).into());
+                }
+                DependencyCycles::Warn => {
+                    for cycle in cycles {
+                        warn!("cyclic dependency detected: {cycle}");
+                    }
+                }
+            }
+        }
 
         let filtered_pkgs = Self::calculate_filtered_packages(
             &self.repo_root,
@@ -476,6 +531,8 @@ impl RunBuilder {
             signal_handler: signal_handler.clone(),
             daemon,
             should_print_prelude,
+            prefetch: self.prefetch,
+            run_events: self.run_events,
         })
     }
 
@@ -529,3 +586,62 @@ impl RunBuilder {
         Ok(engine)
     }
 }
+
+/// A workspace dependency that isn't checked out looks like an external
+/// package, so hashing would silently leave it out. Packages that will run
+/// need all of their workspace dependencies present.
+fn check_sparse_dependencies<'a>(
+    repo_root: &AbsoluteSystemPath,
+    pkg_dep_graph: &PackageGraph,
+    scm: &SCM,
+    filtered_pkgs: impl Iterator<Item = &'a PackageName>,
+) -> Result<(), Error> {
+    // Packages that aren't checked out are still committed, so HEAD tells us
+    // which ones the workspace is missing
+    let workspace_globs = pkg_dep_graph
+        .package_manager()
+        .get_workspace_globs(repo_root)?;
+    let mut missing_packages = HashMap::new();
+    for (package_json_path, contents) in scm.read_outside_checkout(repo_root, "package.json")? {
+        let package_dir = repo_root.resolve(&package_json_path);
+        let package_dir = package_dir.parent().unwrap_or(repo_root);
+        if !workspace_globs.target_is_workspace(repo_root, package_dir)? {
+            continue;
+        }
+        let package_json = PackageJson::load_from_str(&contents, package_json_path.as_str())?;
+        let Some(name) = package_json.name.clone() else {
+            continue;
+        };
+        missing_packages.insert(
+            PackageName::from(name),
+            PackageInfo {
+                package_json,
+                package_json_path,
+                ..Default::default()
+            },
+        );
+    }
+    if missing_packages.is_empty() {
+        return Ok(());
+    }
+
+    let nodes = filtered_pkgs
+        .map(|name| PackageNode::Workspace(name.clone()))
+        .collect::<Vec<_>>();
+    let dependents = pkg_dep_graph
+        .transitive_closure(&nodes)
+        .into_iter()
+        .map(|node| node.as_package_name())
+        .sorted();
+    let missing = pkg_dep_graph
+        .dependencies_outside_graph(dependents, &missing_packages)
+        .into_iter()
+        .next();
+    if let Some((package, dependency)) = missing {
+        return Err(Error::DependencyNotInSparseCheckout {
+            package: package.to_string(),
+            dependency: dependency.to_string(),
+        });
+    }
+    Ok(())
+}
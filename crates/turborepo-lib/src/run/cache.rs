@@ -10,7 +10,9 @@ use tracing::{debug, error, log::warn};
 use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
-use turborepo_cache::{http::UploadMap, AsyncCache, CacheError, CacheHitMetadata, CacheSource};
+use turborepo_cache::{
+    http::UploadMap, ArtifactTask, AsyncCache, CacheError, CacheHitMetadata, CacheSource,
+};
 use turborepo_repository::package_graph::PackageInfo;
 use turborepo_scm::SCM;
 use turborepo_telemetry::events::{task::PackageTaskEventBuilder, TrackedErrors};
@@ -112,7 +114,9 @@ impl RunCache {
             task_output_logs = task_output_logs_override;
         }
 
-        let caching_disabled = !task_definition.cache;
+        let caching_disabled = !task_definition.cache.is_enabled();
+        let reads_disabled = self.reads_disabled || !task_definition.cache.reads_enabled();
+        let writes_disabled = self.writes_disabled || !task_definition.cache.writes_enabled();
 
         TaskCache {
             expanded_outputs: Vec::new(),
@@ -122,6 +126,9 @@ impl RunCache {
             task_id,
             task_output_logs,
             caching_disabled,
+            reads_disabled,
+            writes_disabled,
+            skippable: task_definition.skippable,
             log_file_path,
             daemon_client: self.daemon_client.clone(),
             ui: self.ui,
@@ -129,6 +136,20 @@ impl RunCache {
         }
     }
 
+    pub fn has_warnings(&self) -> bool {
+        self.warnings
+            .lock()
+            .map_or(false, |warnings| !warnings.is_empty())
+    }
+
+    /// The number of failed requests to the remote cache, including those made
+    /// by uploads that were still in flight
+    pub async fn remote_errors(&self) -> usize {
+        // A failed upload is counted, so there's nothing more to do with the error
+        let _ = self.cache.wait().await;
+        self.cache.remote_errors()
+    }
+
     pub async fn shutdown_cache(
         &self,
     ) -> Result<(Arc<Mutex<UploadMap>>, oneshot::Receiver<()>), CacheError> {
@@ -149,6 +170,10 @@ pub struct TaskCache {
     hash: String,
     task_output_logs: OutputLogsMode,
     caching_disabled: bool,
+    // Combines the run-wide flags with the task's cache policy
+    reads_disabled: bool,
+    writes_disabled: bool,
+    skippable: bool,
     log_file_path: AbsoluteSystemPathBuf,
     daemon_client: Option<DaemonClient<DaemonConnector>>,
     ui: ColorConfig,
@@ -165,7 +190,19 @@ impl TaskCache {
         self.caching_disabled
     }
 
+    pub fn is_writing_disabled(&self) -> bool {
+        self.writes_disabled
+    }
+
+    fn artifact_task(&self) -> ArtifactTask {
+        ArtifactTask {
+            package: self.task_id.package().to_string(),
+            task: self.task_id.task().to_string(),
+        }
+    }
+
     /// Will read log file and write to output a line at a time
+    #[tracing::instrument(skip_all)]
     pub fn replay_log_file(&self, output: &mut impl CacheOutput) -> Result<(), Error> {
         if self.log_file_path.exists() {
             output.replay_logs(&self.log_file_path)?;
@@ -192,7 +229,7 @@ impl TaskCache {
     pub fn output_writer<W: Write>(&self, writer: W) -> Result<LogWriter<W>, Error> {
         let mut log_writer = LogWriter::default();
 
-        if self.caching_disabled || self.run_cache.writes_disabled {
+        if self.writes_disabled {
             log_writer.with_writer(writer);
             return Ok(log_writer);
         }
@@ -209,16 +246,52 @@ impl TaskCache {
         Ok(log_writer)
     }
 
+    /// Like `output_writer`, but keeps the task's stdout and stderr apart while
+    /// still logging both to the same file
+    pub fn split_output_writers<W: Write>(
+        &self,
+        stdout: W,
+        stderr: W,
+    ) -> Result<(LogWriter<W>, LogWriter<W>), Error> {
+        let stdout_writer = self.output_writer(stdout)?;
+        let mut stderr_writer = stdout_writer.share_log_file();
+        if stdout_writer.has_writer() {
+            stderr_writer.with_writer(stderr);
+        }
+        Ok((stdout_writer, stderr_writer))
+    }
+
     pub async fn exists(&self) -> Result<Option<CacheHitMetadata>, CacheError> {
         self.run_cache.cache.exists(&self.hash).await
     }
 
+    /// Downloads the task's remote artifact into the local cache without
+    /// restoring any outputs
+    pub async fn prefetch(&self) -> Result<Option<CacheHitMetadata>, CacheError> {
+        if self.caching_disabled || self.reads_disabled {
+            return Ok(None);
+        }
+        self.run_cache
+            .cache
+            .prefetch(&self.hash, Some(&self.artifact_task()))
+            .await
+    }
+
+    #[tracing::instrument(skip_all, fields(task = %self.task_id, hash = %self.hash))]
     pub async fn restore_outputs(
         &mut self,
         terminal_output: &mut impl CacheOutput,
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<Option<CacheHitMetadata>, Error> {
-        if self.caching_disabled || self.run_cache.reads_disabled {
+        // Skippable tasks that can't read from the cache are still skipped if they
+        // last succeeded with this hash, unless the whole run is forced
+        if self.reads_disabled && self.skippable && !self.run_cache.reads_disabled {
+            if let Some(status) = self.skip_unchanged(terminal_output, telemetry).await? {
+                return Ok(Some(status));
+            }
+        }
+
+        if self.reads_disabled {
             if !matches!(
                 self.task_output_logs,
                 OutputLogsMode::None | OutputLogsMode::ErrorsOnly
@@ -266,7 +339,11 @@ impl TaskCache {
             let cache_status = self
                 .run_cache
                 .cache
-                .fetch(&self.run_cache.repo_root, &self.hash)
+                .fetch(
+                    &self.run_cache.repo_root,
+                    &self.hash,
+                    Some(&self.artifact_task()),
+                )
                 .await?;
 
             let Some((cache_hit_metadata, restored_files)) = cache_status else {
@@ -354,12 +431,95 @@ impl TaskCache {
         Ok(cache_status)
     }
 
+    /// Checks with the daemon if this task last succeeded with the same hash
+    /// and its outputs haven't changed since, in which case it doesn't need to
+    /// run again
+    async fn skip_unchanged(
+        &mut self,
+        terminal_output: &mut impl CacheOutput,
+        telemetry: &PackageTaskEventBuilder,
+    ) -> Result<Option<CacheHitMetadata>, Error> {
+        let Some(daemon_client) = &mut self.daemon_client else {
+            return Ok(None);
+        };
+        let validated_inclusions = self.repo_relative_globs.validated_inclusions()?;
+        match daemon_client
+            .is_unchanged_since_success(self.hash.to_string(), &validated_inclusions)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => return Ok(None),
+            Err(err) => {
+                telemetry.track_error(TrackedErrors::DaemonSkipOutputRestoreCheckFailed);
+                debug!(
+                    "Failed to check if {} is unchanged since its last run: {}",
+                    self.task_id, err
+                );
+                return Ok(None);
+            }
+        }
+
+        if !matches!(
+            self.task_output_logs,
+            OutputLogsMode::None | OutputLogsMode::ErrorsOnly
+        ) {
+            terminal_output.status(
+                &format!(
+                    "unchanged since last run, skipping {}",
+                    color!(self.ui, GREY, "{}", self.hash)
+                ),
+                CacheResult::Hit,
+            );
+        }
+        if self.task_output_logs == OutputLogsMode::Full {
+            self.replay_log_file(terminal_output)?;
+        }
+
+        Ok(Some(CacheHitMetadata {
+            source: CacheSource::Local,
+            time_saved: 0,
+        }))
+    }
+
+    /// Records a successful run of a skippable task with the daemon, so that
+    /// it can be skipped as long as its hash and outputs stay the same
+    async fn record_run(
+        &mut self,
+        duration: Duration,
+        telemetry: &PackageTaskEventBuilder,
+    ) -> Result<(), Error> {
+        let Some(daemon_client) = self.daemon_client.as_mut() else {
+            return Ok(());
+        };
+        let validated_inclusions = self.repo_relative_globs.validated_inclusions()?;
+        let validated_exclusions = self.repo_relative_globs.validated_exclusions()?;
+        if let Err(err) = daemon_client
+            .notify_outputs_written(
+                self.hash.to_string(),
+                &validated_inclusions,
+                &validated_exclusions,
+                duration.as_millis() as u64,
+            )
+            .await
+        {
+            telemetry.track_error(TrackedErrors::DaemonFailedToMarkOutputsAsCached);
+            let task_id = &self.task_id;
+            debug!("failed to record run of {task_id}: {err}");
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(skip_all, fields(task = %self.task_id, hash = %self.hash))]
     pub async fn save_outputs(
         &mut self,
         duration: Duration,
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<(), Error> {
-        if self.caching_disabled || self.run_cache.writes_disabled {
+        if self.writes_disabled {
+            if self.skippable {
+                self.record_run(duration, telemetry).await?;
+            }
             return Ok(());
         }
 
@@ -400,6 +560,7 @@ impl TaskCache {
                 self.hash.clone(),
                 relative_paths.clone(),
                 duration.as_millis() as u64,
+                Some(self.artifact_task()),
             )
             .await?;
 
@@ -468,7 +629,7 @@ impl ConfigCache {
     pub async fn restore(
         &self,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        self.cache.fetch(&self.repo_root, &self.hash).await
+        self.cache.fetch(&self.repo_root, &self.hash, None).await
     }
 
     pub async fn save(&self) -> Result<(), CacheError> {
@@ -481,6 +642,7 @@ impl ConfigCache {
                         self.hash.clone(),
                         vec![self.anchored_path.clone()],
                         0,
+                        None,
                     )
                     .await
             }
@@ -522,3 +684,143 @@ fn fallible_write(mut writer: impl Write, message: &str) {
         error!("cannot write to logs: {:?}", err);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+    use turborepo_api_client::APIClient;
+    use turborepo_cache::{AsyncCache, CacheOpts};
+    use turborepo_repository::package_graph::PackageInfo;
+    use turborepo_telemetry::events::task::PackageTaskEventBuilder;
+    use turborepo_ui::{tui::event::CacheResult, ColorConfig, ColorSelector};
+
+    use super::{CacheOutput, RunCache};
+    use crate::{
+        cli::OutputLogsMode,
+        daemon::{start_test_daemon, CloseReason},
+        opts::RunCacheOpts,
+        run::task_id::TaskId,
+        task_graph::{CachePolicy, TaskDefinition},
+    };
+
+    #[derive(Default)]
+    struct Statuses(Vec<String>);
+
+    impl CacheOutput for Statuses {
+        fn status(&mut self, message: &str, _result: CacheResult) {
+            self.0.push(message.to_string());
+        }
+
+        fn error(&mut self, _message: &str) {}
+
+        fn replay_logs(
+            &mut self,
+            _log_file: &turbopath::AbsoluteSystemPath,
+        ) -> Result<(), turborepo_ui::Error> {
+            Ok(())
+        }
+    }
+
+    // the windows runner starts a new thread to accept uds requests,
+    // so we need a multi-threaded runtime
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_skippable_task() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tempdir.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap()
+            .join_component("repo");
+        repo_root.create_dir_all().unwrap();
+        repo_root
+            .join_component("package.json")
+            .create_with_contents(r#"{"workspaces": ["packages/*"]}"#)
+            .unwrap();
+        repo_root
+            .join_component("package-lock.json")
+            .create_with_contents("")
+            .unwrap();
+
+        let (daemon_client, stop_daemon, daemon) = start_test_daemon(&repo_root).await;
+
+        let cache = AsyncCache::new(
+            &CacheOpts {
+                cache_dir: ".turbo/cache".into(),
+                skip_remote: true,
+                workers: 1,
+                ..Default::default()
+            },
+            &repo_root,
+            APIClient::new("http://localhost", None, None, "2.0.0", false).unwrap(),
+            None,
+            None,
+        )
+        .unwrap();
+        let run_cache = Arc::new(RunCache::new(
+            cache,
+            &repo_root,
+            &RunCacheOpts {
+                task_output_logs_override: Some(OutputLogsMode::HashOnly),
+                ..Default::default()
+            },
+            ColorSelector::default(),
+            Some(daemon_client),
+            ColorConfig::new(true),
+            false,
+        ));
+
+        // A lint task with no outputs, like the example in the docs
+        let task_definition = TaskDefinition {
+            cache: CachePolicy::Disabled,
+            skippable: true,
+            ..Default::default()
+        };
+        let package_info = PackageInfo {
+            package_json_path: AnchoredSystemPathBuf::from_raw("package.json").unwrap(),
+            ..Default::default()
+        };
+        let task_cache = |hash: &str| {
+            run_cache.task_cache(
+                &task_definition,
+                &package_info,
+                TaskId::new("//", "lint").into_owned(),
+                hash,
+            )
+        };
+        let telemetry = PackageTaskEventBuilder::new("//", "lint");
+
+        // Nothing has succeeded with the hash yet, so the task runs
+        let mut statuses = Statuses::default();
+        let mut first_run = task_cache("first");
+        assert!(first_run
+            .restore_outputs(&mut statuses, &telemetry)
+            .await
+            .unwrap()
+            .is_none());
+        first_run
+            .save_outputs(Duration::from_secs(1), &telemetry)
+            .await
+            .unwrap();
+
+        // Once it has, it's skipped
+        let mut statuses = Statuses::default();
+        assert!(task_cache("first")
+            .restore_outputs(&mut statuses, &telemetry)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(statuses.0[0].starts_with("unchanged since last run, skipping"));
+
+        // Until the hash changes
+        assert!(task_cache("second")
+            .restore_outputs(&mut Statuses::default(), &telemetry)
+            .await
+            .unwrap()
+            .is_none());
+
+        stop_daemon.send(CloseReason::Interrupt).unwrap();
+        daemon.await.unwrap().unwrap();
+    }
+}
@@ -44,6 +44,8 @@ pub enum Error {
     #[error(transparent)]
     Path(#[from] turbopath::PathError),
     #[error(transparent)]
+    Scm(#[from] turborepo_scm::Error),
+    #[error(transparent)]
     Scope(#[from] scope::ResolutionError),
     #[error(transparent)]
     GlobalHash(#[from] global_hash::Error),
@@ -60,4 +62,13 @@ pub enum Error {
     UI(#[from] turborepo_ui::Error),
     #[error(transparent)]
     Tui(#[from] tui::Error),
+    #[error(
+        "No package found with name '{0}' in workspace. The repository is a sparse checkout, so \
+         the package may not be checked out"
+    )]
+    #[diagnostic(help("check out the package's directory with `git sparse-checkout add`"))]
+    NotInSparseCheckout(String),
+    #[error("'{package}' depends on '{dependency}', which is not part of the sparse checkout")]
+    #[diagnostic(help("check out the package's directory with `git sparse-checkout add`"))]
+    DependencyNotInSparseCheckout { package: String, dependency: String },
 }
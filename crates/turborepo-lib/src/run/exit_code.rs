@@ -0,0 +1,57 @@
+//! Picks the exit code of a run. Without any `exitCodes` configured, a run
+//! exits with the highest exit code of its failed tasks, or 0.
+
+use crate::turbo_json::ExitCodes;
+
+/// What happened during a run, as far as its exit code is concerned
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RunOutcome {
+    /// The highest exit code of the failed tasks, if any failed
+    pub task_failure: Option<i32>,
+    pub no_tasks: bool,
+    pub remote_cache_errors: bool,
+    pub warnings: bool,
+}
+
+/// Checks the outcomes in order of severity, falling through to the next one
+/// when a run only has outcomes that don't have an exit code configured
+pub fn resolve(outcome: RunOutcome, exit_codes: ExitCodes) -> i32 {
+    if let Some(code) = outcome.task_failure {
+        return exit_codes.task_failure.unwrap_or(code);
+    }
+    [
+        (outcome.no_tasks, exit_codes.no_tasks),
+        (outcome.remote_cache_errors, exit_codes.remote_cache_error),
+        (outcome.warnings, exit_codes.warnings),
+    ]
+    .into_iter()
+    .find_map(|(happened, code)| code.filter(|_| happened))
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    const CONFIGURED: ExitCodes = ExitCodes {
+        task_failure: Some(10),
+        no_tasks: Some(11),
+        remote_cache_error: Some(12),
+        warnings: Some(13),
+    };
+
+    #[test_case(RunOutcome::default(), ExitCodes::default(), 0 ; "success")]
+    #[test_case(RunOutcome::default(), CONFIGURED, 0 ; "success with codes")]
+    #[test_case(RunOutcome { task_failure: Some(4), warnings: true, ..Default::default() }, ExitCodes::default(), 4 ; "task failure")]
+    #[test_case(RunOutcome { task_failure: Some(4), warnings: true, ..Default::default() }, CONFIGURED, 10 ; "task failure with codes")]
+    #[test_case(RunOutcome { no_tasks: true, ..Default::default() }, ExitCodes::default(), 0 ; "no tasks")]
+    #[test_case(RunOutcome { no_tasks: true, ..Default::default() }, CONFIGURED, 11 ; "no tasks with codes")]
+    #[test_case(RunOutcome { remote_cache_errors: true, warnings: true, ..Default::default() }, CONFIGURED, 12 ; "remote cache errors with codes")]
+    #[test_case(RunOutcome { remote_cache_errors: true, warnings: true, ..Default::default() }, ExitCodes { warnings: Some(13), ..Default::default() }, 13 ; "falls through to warnings")]
+    #[test_case(RunOutcome { warnings: true, ..Default::default() }, CONFIGURED, 13 ; "warnings with codes")]
+    fn test_resolve(outcome: RunOutcome, exit_codes: ExitCodes, expected: i32) {
+        assert_eq!(resolve(outcome, exit_codes), expected);
+    }
+}
@@ -4,12 +4,14 @@ use std::{
     process::{Command, Stdio},
 };
 
+use serde::Serialize;
 use thiserror::Error;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 use turborepo_ui::{cprintln, cwrite, cwriteln, ColorConfig, BOLD, BOLD_YELLOW_REVERSE, YELLOW};
 use which::which;
 
-use crate::{engine::Engine, opts::GraphOpts, spawn_child};
+use super::html::embed_json;
+use crate::{api::TaskStatus, engine::Engine, opts::GraphOpts, spawn_child};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -22,6 +24,8 @@ pub enum Error {
     },
     #[error("failed to spawn graphviz (dot): {0}")]
     Graphviz(io::Error),
+    #[error("failed to serialize graph: {0}")]
+    Serialize(#[from] serde_json::Error),
 }
 
 pub(crate) fn write_graph(
@@ -37,8 +41,6 @@ pub(crate) fn write_graph(
             let (filename, extension) = filename_and_extension(cwd, raw_filename)?;
             if extension == "mermaid" {
                 render_mermaid_graph(&filename, engine, single_package)?;
-            } else if extension == "html" {
-                render_html(&filename, engine, single_package)?;
             } else if let Ok(dot_path) = which("dot") {
                 let mut cmd = Command::new(dot_path);
                 cmd.stdin(Stdio::piped())
@@ -91,50 +93,53 @@ fn render_dot_graph<W: io::Write>(
         .map_err(Error::GraphOutput)
 }
 
-const HTML_PREFIX: &str = r#"
-<!DOCTYPE html>
-<html>
-<head>
-  <meta charset="utf-8">
-  <title>Graph</title>
-</head>
-<body>
-  <script src="https://cdn.jsdelivr.net/npm/viz.js@2.1.2-pre.1/viz.js"></script>
-  <script src="https://cdn.jsdelivr.net/npm/viz.js@2.1.2-pre.1/full.render.js"></script>
-  <script>
-"#;
-const HTML_SUFFIX: &str = r#"
-  </script>
-</body>
-</html>
-"#;
+// The page the graph is rendered into, with `{{GRAPH_DATA}}` standing in for
+// the tasks as JSON
+const RUN_GRAPH_HTML: &str = include_str!("graph.html");
 
-fn render_html(
+/// A task as it appears in the HTML graph written after a run
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunGraphTask {
+    pub id: String,
+    pub hash: String,
+    pub status: RunGraphTaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    pub time_saved_ms: u64,
+    pub dependencies: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum RunGraphTaskStatus {
+    Succeeded,
+    Cached,
+    Failed,
+    NotRun,
+}
+
+impl From<RunGraphTaskStatus> for TaskStatus {
+    fn from(status: RunGraphTaskStatus) -> Self {
+        match status {
+            RunGraphTaskStatus::Succeeded => TaskStatus::Succeeded,
+            RunGraphTaskStatus::Cached => TaskStatus::Cached,
+            RunGraphTaskStatus::Failed => TaskStatus::Failed,
+            RunGraphTaskStatus::NotRun => TaskStatus::NotRun,
+        }
+    }
+}
+
+/// Writes a self-contained HTML page showing the task graph of a run along
+/// with how each task ran
+pub(crate) fn write_run_graph(
     filename: &AbsoluteSystemPath,
-    engine: &Engine,
-    single_package: bool,
+    tasks: &[RunGraphTask],
 ) -> Result<(), Error> {
-    let mut opts = OpenOptions::new();
-    opts.truncate(true).create(true).write(true);
-    let mut file = filename
-        .open_with_options(opts)
-        .map_err(Error::GraphOutput)?;
-    let mut graph_buffer = Vec::new();
-    render_dot_graph(&mut graph_buffer, engine, single_package)?;
-    let graph_string = String::from_utf8(graph_buffer).expect("graph rendering should be UTF-8");
-
-    file.write_all(HTML_PREFIX.as_bytes())
-        .map_err(Error::GraphOutput)?;
-    write!(
-        &mut file,
-        "const s = `{graph_string}`.replace(/\\_\\_\\_ROOT\\_\\_\\_/g, \
-         \"Root\").replace(/\\[root\\]/g, \"\");new Viz().renderSVGElement(s).then(el => \
-         document.body.appendChild(el)).catch(e => console.error(e));"
-    )
-    .map_err(Error::GraphOutput)?;
-    file.write_all(HTML_SUFFIX.as_bytes())
-        .map_err(Error::GraphOutput)?;
-    Ok(())
+    let html = embed_json(RUN_GRAPH_HTML, "{{GRAPH_DATA}}", &tasks)?;
+    filename
+        .create_with_contents(html)
+        .map_err(Error::GraphOutput)
 }
 
 fn filename_and_extension(
@@ -0,0 +1,38 @@
+//! Helpers for the self-contained HTML pages `turbo` writes, like run graphs
+//! and run reports.
+
+use serde::Serialize;
+
+/// Fills in `placeholder` in an HTML `template` with `data` as JSON. `</` is
+/// escaped so strings in the data can't close the script tag the JSON is
+/// embedded in.
+pub(crate) fn embed_json(
+    template: &str,
+    placeholder: &str,
+    data: &impl Serialize,
+) -> Result<String, serde_json::Error> {
+    let json = serde_json::to_string(data)?.replace("</", "<\\/");
+    Ok(template.replace(placeholder, &json))
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::embed_json;
+
+    #[test]
+    fn test_embed_json() {
+        let template = "<script>const data = {{DATA}};</script>";
+        let html = embed_json(
+            template,
+            "{{DATA}}",
+            &json!({ "id": "web#</script><script>alert(1)" }),
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            r#"<script>const data = {"id":"web#<\/script><script>alert(1)"};</script>"#
+        );
+    }
+}
@@ -3,8 +3,10 @@
 pub mod builder;
 mod cache;
 mod error;
+mod exit_code;
 pub(crate) mod global_hash;
 mod graph_visualizer;
+mod html;
 pub(crate) mod package_discovery;
 pub(crate) mod scope;
 pub(crate) mod summary;
@@ -22,6 +24,7 @@ use std::{
 
 pub use cache::{CacheOutput, ConfigCache, Error as CacheError, RunCache, TaskCache};
 use chrono::{DateTime, Local};
+use either::Either;
 use rayon::iter::ParallelBridge;
 use tokio::{select, task::JoinHandle};
 use tracing::{debug, instrument};
@@ -33,14 +36,15 @@ use turborepo_repository::package_graph::{PackageGraph, PackageName, PackageNode
 use turborepo_scm::SCM;
 use turborepo_telemetry::events::generic::GenericEventBuilder;
 use turborepo_ui::{
-    cprint, cprintln, sender::UISender, tui, tui::TuiSender, wui::sender::WebUISender, ColorConfig,
-    BOLD_GREY, GREY,
+    cwrite, cwriteln, sender::UISender, tui, tui::TuiSender, wui::sender::WebUISender, ColorConfig,
+    LogStream, NdjsonWriter, BOLD_GREY, GREY,
 };
 
 pub use crate::run::error::Error;
 use crate::{
-    cli::EnvMode,
-    engine::Engine,
+    api::RunEventSender,
+    cli::{EnvMode, LogFormat},
+    engine::{Engine, TaskNode},
     opts::Opts,
     process::ProcessManager,
     run::{global_hash::get_global_hash_inputs, summary::RunTracker, task_access::TaskAccess},
@@ -73,6 +77,19 @@ pub struct Run {
     task_access: TaskAccess,
     daemon: Option<DaemonClient<DaemonConnector>>,
     should_print_prelude: bool,
+    prefetch: bool,
+    run_events: Option<RunEventSender>,
+}
+
+/// Where `turbo` writes its own output during a run. With structured logs each
+/// line is written as a JSON object, like the lines from tasks are.
+pub(crate) fn turbo_output(
+    log_format: LogFormat,
+) -> Either<std::io::Stdout, NdjsonWriter<std::io::Stdout>> {
+    match log_format {
+        LogFormat::Text => Either::Left(std::io::stdout()),
+        LogFormat::Ndjson => Either::Right(NdjsonWriter::new(std::io::stdout(), LogStream::Stdout)),
+    }
 }
 
 type UIResult<T> = Result<Option<(T, JoinHandle<Result<(), turborepo_ui::Error>>)>, Error>;
@@ -85,10 +102,16 @@ impl Run {
         self.engine.has_non_interruptible_tasks
     }
     fn print_run_prelude(&self) {
+        let mut out = turbo_output(self.opts.run_opts.log_format);
+        // Like `println!`, except a closed stdout isn't worth panicking over
+        let _ = self.write_run_prelude(&mut out);
+    }
+
+    fn write_run_prelude(&self, out: &mut impl Write) -> std::io::Result<()> {
         let targets_list = self.opts.run_opts.tasks.join(", ");
         if self.opts.run_opts.single_package {
-            cprint!(self.color_config, GREY, "{}", "• Running");
-            cprint!(self.color_config, BOLD_GREY, " {}\n", targets_list);
+            cwrite!(out, self.color_config, GREY, "{}", "• Running")?;
+            cwrite!(out, self.color_config, BOLD_GREY, " {}\n", targets_list)?;
         } else {
             let mut packages = self
                 .filtered_pkgs
@@ -96,28 +119,31 @@ impl Run {
                 .map(|workspace_name| workspace_name.to_string())
                 .collect::<Vec<String>>();
             packages.sort();
-            cprintln!(
+            cwriteln!(
+                out,
                 self.color_config,
                 GREY,
                 "• Packages in scope: {}",
                 packages.join(", ")
-            );
-            cprint!(self.color_config, GREY, "{} ", "• Running");
-            cprint!(self.color_config, BOLD_GREY, "{}", targets_list);
-            cprint!(
+            )?;
+            cwrite!(out, self.color_config, GREY, "{} ", "• Running")?;
+            cwrite!(out, self.color_config, BOLD_GREY, "{}", targets_list)?;
+            cwrite!(
+                out,
                 self.color_config,
                 GREY,
                 " in {} packages\n",
                 self.filtered_pkgs.len()
-            );
+            )?;
         }
 
         let use_http_cache = !self.opts.cache_opts.skip_remote;
         if use_http_cache {
-            cprintln!(self.color_config, GREY, "• Remote caching enabled");
+            cwriteln!(out, self.color_config, GREY, "• Remote caching enabled")?;
         } else {
-            cprintln!(self.color_config, GREY, "• Remote caching disabled");
+            cwriteln!(out, self.color_config, GREY, "• Remote caching disabled")?;
         }
+        out.flush()
     }
 
     pub fn opts(&self) -> &Opts {
@@ -348,16 +374,19 @@ impl Run {
         }
 
         if let Some(graph_opts) = &self.opts.run_opts.graph {
-            graph_visualizer::write_graph(
-                self.color_config,
-                graph_opts,
-                &self.engine,
-                self.opts.run_opts.single_package,
-                // Note that cwd used to be pulled from CommandBase, which had it set
-                // as the repo root.
-                &self.repo_root,
-            )?;
-            return Ok(0);
+            // HTML graphs are written by the run summary once tasks have run
+            if graph_opts.html_file().is_none() {
+                graph_visualizer::write_graph(
+                    self.color_config,
+                    graph_opts,
+                    &self.engine,
+                    self.opts.run_opts.single_package,
+                    // Note that cwd used to be pulled from CommandBase, which had it set
+                    // as the repo root.
+                    &self.repo_root,
+                )?;
+                return Ok(0);
+            }
         }
 
         let workspaces = self.pkg_dep_graph.packages().collect();
@@ -439,10 +468,12 @@ impl Run {
             &self.repo_root,
             self.version,
             self.opts.run_opts.experimental_space_id.clone(),
+            self.opts.run_opts.summary_upload.as_ref(),
             self.api_client.clone(),
             self.api_auth.clone(),
             Vendor::get_user(),
             &self.scm,
+            self.run_events.clone(),
         );
 
         let mut visitor = Visitor::new(
@@ -463,7 +494,9 @@ impl Run {
         )
         .await;
 
-        if self.opts.run_opts.dry_run.is_some() {
+        if self.prefetch {
+            visitor.prefetch();
+        } else if self.opts.run_opts.dry_run.is_some() {
             visitor.dry_run();
         }
 
@@ -475,20 +508,37 @@ impl Run {
             .visit(self.engine.clone(), &self.run_telemetry)
             .await?;
 
-        let exit_code = errors
-            .iter()
-            .filter_map(|err| err.exit_code())
-            .max()
-            // We hit some error, it shouldn't be exit code 0
-            .unwrap_or(if errors.is_empty() { 0 } else { 1 });
+        if self.prefetch {
+            visitor.finish_prefetch(&self.engine);
+            return Ok(0);
+        }
 
-        let error_prefix = if self.opts.run_opts.is_github_actions {
-            "::error::"
-        } else {
-            ""
+        let exit_codes = self.opts.run_opts.exit_codes;
+        let task_failure = (!errors.is_empty()).then(|| {
+            errors
+                .iter()
+                .filter_map(|err| err.exit_code())
+                .max()
+                // We hit some error, it shouldn't be exit code 0
+                .unwrap_or(1)
+        });
+        let outcome = exit_code::RunOutcome {
+            task_failure,
+            no_tasks: !self
+                .engine
+                .tasks()
+                .any(|node| matches!(node, TaskNode::Task(_))),
+            // Only wait on uploads when it could change the exit code
+            remote_cache_errors: exit_codes.remote_cache_error.is_some()
+                && self.run_cache.remote_errors().await > 0,
+            warnings: visitor.has_warnings() || self.run_cache.has_warnings(),
         };
+        let exit_code = exit_code::resolve(outcome, exit_codes);
+
+        // On GitHub Actions, failures are also reported as annotations once the run
+        // summary has their logs
         for err in &errors {
-            writeln!(std::io::stderr(), "{error_prefix}{err}").ok();
+            writeln!(std::io::stderr(), "{err}").ok();
         }
 
         visitor
@@ -6,7 +6,9 @@ use std::{
 
 use miette::Diagnostic;
 use tracing::debug;
-use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+use turbopath::{
+    AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
+};
 use turborepo_repository::{
     change_mapper::{merge_changed_packages, ChangeMapError, PackageInclusionReason},
     package_graph::{self, PackageGraph, PackageName},
@@ -585,6 +587,19 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                         },
                     );
                 }
+
+                // A literal path inside of a package, such as `./apps/web/src`, selects
+                // the package that contains it
+                if entry_packages.is_empty() && parent_dir_globber.variance().is_invariant() {
+                    if let Some(name) = self.containing_package(parent_dir) {
+                        entry_packages.insert(
+                            name,
+                            PackageInclusionReason::InFilteredDirectory {
+                                directory: parent_dir.to_owned(),
+                            },
+                        );
+                    }
+                }
             }
         }
 
@@ -642,6 +657,18 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
         packages.insert(PackageName::Root);
         packages
     }
+
+    /// Finds the innermost package whose directory contains `dir`
+    fn containing_package(&self, dir: &AnchoredSystemPath) -> Option<PackageName> {
+        self.pkg_graph
+            .packages()
+            .filter(|(name, info)| {
+                !matches!(name, PackageName::Root)
+                    && dir.as_path().starts_with(info.package_path().as_path())
+            })
+            .max_by_key(|(_, info)| info.package_path().components().count())
+            .map(|(name, _)| name.to_owned())
+    }
 }
 
 /// match the provided name pattern against the provided set of packages
@@ -1018,6 +1045,28 @@ mod test {
         &["project-5"] ;
         "select by parentDir with no glob"
     )]
+    #[test_case(
+        vec![
+            TargetSelector {
+                parent_dir:
+    Some(AnchoredSystemPathBuf::try_from("project-5/packages").unwrap()),
+    ..Default::default()         }
+        ],
+        None,
+        &["project-5"] ;
+        "select by parentDir inside of a package"
+    )]
+    #[test_case(
+        vec![
+            TargetSelector {
+                parent_dir:
+    Some(AnchoredSystemPathBuf::try_from("project-5/packages/*").unwrap()),
+    ..Default::default()         }
+        ],
+        None,
+        &["project-6"] ;
+        "select by parentDir using glob inside of a package"
+    )]
     #[test_case(
         vec![
             TargetSelector {
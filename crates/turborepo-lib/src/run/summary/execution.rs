@@ -1,13 +1,21 @@
-use std::fmt;
+use std::{fmt, io, io::Write, time::Duration};
 
 use chrono::{DateTime, Local};
 use serde::Serialize;
 use tokio::sync::mpsc;
 use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
-use turborepo_ui::{color, cprintln, ColorConfig, BOLD, BOLD_GREEN, BOLD_RED, MAGENTA, YELLOW};
+use turborepo_ui::{
+    color, cwriteln, ColorConfig, BOLD, BOLD_GREEN, BOLD_RED, GREY, MAGENTA, YELLOW,
+};
 
 use super::TurboDuration;
-use crate::run::{summary::task::TaskSummary, task_id::TaskId};
+use crate::{
+    api::{RunEvent, RunEventSender, TaskEvent, TaskStatus},
+    run::{
+        summary::task::{TaskFailureSummary, TaskSummary},
+        task_id::TaskId,
+    },
+};
 
 // Just used to make changing the type that gets passed to the state management
 // thread easy
@@ -26,7 +34,7 @@ pub struct ExecutionTracker {
 #[serde(rename_all = "camelCase")]
 pub struct ExecutionSummary<'a> {
     // a synthesized turbo command to produce this invocation
-    command: String,
+    pub(crate) command: String,
     // the (possibly empty) path from the turborepo root to where the command was run
     #[serde(rename = "repoPath")]
     repo_path: &'a AnchoredSystemPath,
@@ -70,14 +78,19 @@ impl<'a> ExecutionSummary<'a> {
         }
     }
 
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis((self.end_time - self.start_time).max(0) as u64)
+    }
+
     /// We implement this on `ExecutionSummary` and not `RunSummary` because
     /// the `execution` field is nullable (due to normalize).
     pub fn print(
         &self,
+        out: &mut impl Write,
         ui: ColorConfig,
         path: AbsoluteSystemPathBuf,
         failed_tasks: Vec<&TaskSummary>,
-    ) {
+    ) -> io::Result<()> {
         let maybe_full_turbo = if self.cached == self.attempted && self.attempted > 0 {
             match std::env::var("TERM_PROGRAM").as_deref() {
                 Ok("Apple_Terminal") => color!(ui, MAGENTA, ">>> FULL TURBO").to_string(),
@@ -149,16 +162,46 @@ impl<'a> ExecutionSummary<'a> {
             .collect();
 
         if self.attempted == 0 {
-            println!();
-            cprintln!(ui, YELLOW, "No tasks were executed as part of this run.");
+            writeln!(out)?;
+            cwriteln!(
+                out,
+                ui,
+                YELLOW,
+                "No tasks were executed as part of this run."
+            )?;
         }
 
-        println!();
+        writeln!(out)?;
         for line in lines {
-            println!("{}", line);
+            writeln!(out, "{}", line)?;
+        }
+
+        writeln!(out)
+    }
+
+    /// Prints each failed task with its exit code and the tail of its logs
+    pub(crate) fn print_failures(
+        out: &mut impl Write,
+        ui: ColorConfig,
+        failures: &[TaskFailureSummary<TaskId<'static>>],
+    ) -> io::Result<()> {
+        if failures.is_empty() {
+            return Ok(());
         }
 
-        println!();
+        cwriteln!(out, ui, BOLD_RED, "Failures ({}):", failures.len())?;
+        for failure in failures {
+            writeln!(
+                out,
+                "  {} {}",
+                color!(ui, BOLD, "{}", failure.task_id),
+                color!(ui, GREY, "({})", failure.status())
+            )?;
+            for line in &failure.log_tail {
+                writeln!(out, "    {line}")?;
+            }
+        }
+        writeln!(out)
     }
 
     fn successful(&self) -> usize {
@@ -204,11 +247,33 @@ pub struct TaskTracker<T> {
 
 #[derive(Debug, Clone)]
 struct TrackerMessage {
+    task_id: TaskId<'static>,
     event: Event,
     // Only present if task is finished
     state: Option<TaskState>,
 }
 
+impl TrackerMessage {
+    // The event sent to API callers for this message, if any
+    fn task_event(&self) -> Option<TaskEvent> {
+        let task_id = self.task_id.to_string();
+        let status = match self.event {
+            Event::Building => return Some(TaskEvent::Started { task_id }),
+            Event::Built => TaskStatus::Succeeded,
+            Event::Cached => TaskStatus::Cached,
+            Event::BuildFailed => TaskStatus::Failed,
+            Event::Canceled => return None,
+        };
+        let execution = self.state.as_ref()?.execution.as_ref()?;
+        Some(TaskEvent::Finished {
+            task_id,
+            status,
+            exit_code: execution.exit_code,
+            duration: execution.duration(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize)]
 enum Event {
     Building,
@@ -229,26 +294,61 @@ pub struct TaskExecutionSummary {
     pub exit_code: Option<i32>,
 }
 
+/// A restore of a task's outputs from the cache, or a save of them to it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CacheTransfer {
+    pub kind: CacheTransferKind,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheTransferKind {
+    Restore,
+    Save,
+}
+
+impl CacheTransfer {
+    pub fn new(kind: CacheTransferKind, start: DateTime<Local>, end: DateTime<Local>) -> Self {
+        Self {
+            kind,
+            start_time: start.timestamp_millis(),
+            end_time: end.timestamp_millis(),
+        }
+    }
+}
+
 impl TaskExecutionSummary {
     pub fn is_failure(&self) -> bool {
         // We consider None as a failure as it indicates the task failed to start
         // or was killed in a manner where we didn't collect an exit code.
         !matches!(self.exit_code, Some(0))
     }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis((self.end_time - self.start_time).max(0) as u64)
+    }
 }
 
 impl ExecutionTracker {
-    pub fn new() -> Self {
+    pub fn new(run_events: Option<RunEventSender>) -> Self {
         // This buffer size is probably overkill, but since messages are only a byte
         // it's worth the extra memory to avoid the channel filling up.
         let (sender, mut receiver) = mpsc::channel::<Message>(128);
         let state_thread = tokio::spawn(async move {
             let mut state = SummaryState::default();
-            while let Some(TrackerMessage {
-                event,
-                state: task_state,
-            }) = receiver.recv().await
-            {
+            while let Some(message) = receiver.recv().await {
+                if let Some(run_events) = &run_events {
+                    if let Some(task_event) = message.task_event() {
+                        // The receiver going away only means nobody is listening
+                        let _ = run_events.send(RunEvent::Task(task_event));
+                    }
+                }
+                let TrackerMessage {
+                    event,
+                    state: task_state,
+                    ..
+                } = message;
                 state.handle_event(event);
                 if let Some(task_state) = task_state {
                     state.tasks.push(task_state);
@@ -298,6 +398,7 @@ impl TaskTracker<()> {
         let started_at = Local::now();
         sender
             .send(TrackerMessage {
+                task_id: task_id.clone(),
                 event: Event::Building,
                 state: None,
             })
@@ -318,9 +419,10 @@ impl TaskTracker<()> {
 
         sender
             .send(TrackerMessage {
+                task_id: task_id.clone(),
                 event: Event::Canceled,
                 state: Some(TaskState {
-                    task_id,
+                    task_id: task_id.clone(),
                     execution: None,
                 }),
             })
@@ -351,11 +453,12 @@ impl TaskTracker<chrono::DateTime<Local>> {
         };
 
         let state = TaskState {
-            task_id,
+            task_id: task_id.clone(),
             execution: Some(execution.clone()),
         };
         sender
             .send(TrackerMessage {
+                task_id: task_id.clone(),
                 event: Event::Cached,
                 state: Some(state),
             })
@@ -380,11 +483,12 @@ impl TaskTracker<chrono::DateTime<Local>> {
         };
 
         let state = TaskState {
-            task_id,
+            task_id: task_id.clone(),
             execution: Some(execution.clone()),
         };
         sender
             .send(TrackerMessage {
+                task_id: task_id.clone(),
                 event: Event::Built,
                 state: Some(state),
             })
@@ -413,11 +517,12 @@ impl TaskTracker<chrono::DateTime<Local>> {
         };
 
         let state = TaskState {
-            task_id,
+            task_id: task_id.clone(),
             execution: Some(execution.clone()),
         };
         sender
             .send(TrackerMessage {
+                task_id: task_id.clone(),
                 event: Event::BuildFailed,
                 state: Some(state),
             })
@@ -437,7 +542,7 @@ mod test {
 
     #[tokio::test]
     async fn test_multiple_tasks() {
-        let summary = ExecutionTracker::new();
+        let summary = ExecutionTracker::new(None);
         let foo = TaskId::new("foo", "build");
         let bar = TaskId::new("bar", "build");
         let baz = TaskId::new("baz", "build");
@@ -493,9 +598,44 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_run_events() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let summary = ExecutionTracker::new(Some(sender));
+        let tracker = summary.task_tracker(TaskId::new("web", "build"));
+        let tracker = tracker.start().await;
+        tracker.build_failed(Some(2), "oops").await;
+        summary
+            .task_tracker(TaskId::new("docs", "build"))
+            .dry_run()
+            .await;
+        summary.finish().await.unwrap();
+
+        let mut events = Vec::new();
+        while let Some(RunEvent::Task(event)) = receiver.recv().await {
+            events.push(event);
+        }
+        assert_eq!(events.len(), 2, "dry runs don't send events");
+        assert_eq!(
+            events[0],
+            TaskEvent::Started {
+                task_id: "web#build".to_string()
+            }
+        );
+        assert!(matches!(
+            &events[1],
+            TaskEvent::Finished {
+                task_id,
+                status: TaskStatus::Failed,
+                exit_code: Some(2),
+                ..
+            } if task_id == "web#build"
+        ));
+    }
+
     #[tokio::test]
     async fn test_timing() {
-        let summary = ExecutionTracker::new();
+        let summary = ExecutionTracker::new(None);
         let tracker = summary.task_tracker(TaskId::new("foo", "build"));
         let post_construction_time = Local::now().timestamp_millis();
         let sleep_duration = Duration::milliseconds(5);
@@ -0,0 +1,239 @@
+//! JUnit XML reports of runs, so CI dashboards can show each task as its own
+//! test case instead of a single failed step. Tasks are grouped into a test
+//! suite per package, and failed tasks include the tail of their logs.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use super::task::{TaskFailureSummary, TaskSummary};
+use crate::run::task_id::TaskId;
+
+/// Renders a JUnit report of the tasks in a run
+pub(crate) fn junit_report(
+    command: &str,
+    tasks: &[TaskSummary],
+    failures: &[TaskFailureSummary<TaskId<'static>>],
+) -> String {
+    let failures = failures
+        .iter()
+        .map(|failure| (&failure.task_id, failure))
+        .collect::<BTreeMap<_, _>>();
+    let mut packages = BTreeMap::<&str, Vec<&TaskSummary>>::new();
+    for task in tasks {
+        packages.entry(&task.package).or_default().push(task);
+    }
+
+    let mut suites = String::new();
+    for (package, mut tasks) in packages {
+        tasks.sort_by(|a, b| a.task.cmp(&b.task));
+        let suite_failures = tasks
+            .iter()
+            .filter(|task| failures.contains_key(&task.task_id))
+            .count();
+        let skipped = tasks
+            .iter()
+            .filter(|task| task.shared.execution.is_none())
+            .count();
+        let time = tasks.iter().map(|task| duration_ms(task)).sum();
+        // Writing to a string can't fail
+        let _ = writeln!(
+            suites,
+            r#"  <testsuite name="{}" tests="{}" failures="{suite_failures}" skipped="{skipped}" time="{}">"#,
+            escape(package),
+            tasks.len(),
+            seconds(time),
+        );
+        for task in tasks {
+            let _ = write!(
+                suites,
+                r#"    <testcase classname="{}" name="{}" time="{}""#,
+                escape(package),
+                escape(&task.task),
+                seconds(duration_ms(task)),
+            );
+            if let Some(failure) = failures.get(&task.task_id) {
+                let _ = writeln!(
+                    suites,
+                    r#">
+      <failure message="{}">{}</failure>
+    </testcase>"#,
+                    escape(&failure.status()),
+                    escape(&failure.log_tail.join("\n")),
+                );
+            } else if task.shared.execution.is_none() {
+                let _ = writeln!(
+                    suites,
+                    r#">
+      <skipped message="did not run"/>
+    </testcase>"#
+                );
+            } else {
+                let _ = writeln!(suites, "/>");
+            }
+        }
+        let _ = writeln!(suites, "  </testsuite>");
+    }
+
+    let time = tasks.iter().map(duration_ms).sum();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="{}" tests="{}" failures="{}" time="{}">
+{suites}</testsuites>
+"#,
+        escape(command),
+        tasks.len(),
+        failures.len(),
+        seconds(time),
+    )
+}
+
+fn duration_ms(task: &TaskSummary) -> i64 {
+    task.shared
+        .execution
+        .as_ref()
+        .map_or(0, |execution| execution.end_time - execution.start_time)
+}
+
+fn seconds(ms: i64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+// Escapes text for XML, dropping terminal colors and any control characters
+// XML doesn't allow
+fn escape(text: &str) -> String {
+    let text = console::strip_ansi_codes(text);
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\r' | '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use turborepo_cache::{CacheHitMetadata, CacheSource};
+
+    use super::{escape, junit_report};
+    use crate::{
+        cli::EnvMode,
+        run::{
+            summary::{
+                execution::TaskExecutionSummary,
+                task::{
+                    SharedTaskSummary, TaskCacheSummary, TaskEnvConfiguration, TaskEnvVarSummary,
+                    TaskFailureSummary, TaskSummary, TaskSummaryTaskDefinition,
+                },
+            },
+            task_id::TaskId,
+        },
+        task_hash::TaskHashInputs,
+    };
+
+    fn task_summary(
+        package: &str,
+        task: &str,
+        cache: TaskCacheSummary,
+        duration: i64,
+        exit_code: i32,
+    ) -> TaskSummary {
+        TaskSummary {
+            task_id: TaskId::new(package, task).into_owned(),
+            task: task.to_string(),
+            package: package.to_string(),
+            shared: SharedTaskSummary {
+                hash: "hash".to_string(),
+                inputs: BTreeMap::new(),
+                hash_of_external_dependencies: String::new(),
+                external_dependencies: Vec::new(),
+                hash_inputs: TaskHashInputs {
+                    global_hash: String::new(),
+                    hash_of_files: String::new(),
+                    dependency_hashes: Vec::new(),
+                },
+                cache,
+                command: format!("{task}.sh"),
+                cli_arguments: Vec::new(),
+                outputs: None,
+                excluded_outputs: None,
+                log_file: String::new(),
+                directory: None,
+                dependencies: Vec::new(),
+                dependents: Vec::new(),
+                resolved_task_definition: TaskSummaryTaskDefinition::default(),
+                expanded_outputs: Vec::new(),
+                framework: String::new(),
+                env_mode: EnvMode::default(),
+                environment_variables: TaskEnvVarSummary {
+                    specified: TaskEnvConfiguration {
+                        env: Vec::new(),
+                        pass_through_env: None,
+                    },
+                    configured: Vec::new(),
+                    inferred: Vec::new(),
+                    pass_through: None,
+                },
+                execution: Some(TaskExecutionSummary {
+                    start_time: 1000,
+                    end_time: 1000 + duration,
+                    error: None,
+                    exit_code: Some(exit_code),
+                }),
+                cache_transfers: Vec::new(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_junit_report() {
+        let cache_hit = TaskCacheSummary::from(Some(CacheHitMetadata {
+            source: CacheSource::Local,
+            time_saved: 2000,
+        }));
+        let tasks = [
+            task_summary("web", "test", TaskCacheSummary::cache_miss(), 1500, 1),
+            task_summary("web", "build", TaskCacheSummary::cache_miss(), 2500, 0),
+            task_summary("docs", "build", cache_hit, 20, 0),
+        ];
+        let failures = [TaskFailureSummary {
+            task_id: TaskId::new("web", "test").into_owned(),
+            exit_code: Some(1),
+            error: None,
+            log_tail: vec!["expected <b> & got <c>".to_string()],
+        }];
+
+        assert_eq!(
+            junit_report("turbo run build test", &tasks, &failures),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="turbo run build test" tests="3" failures="1" time="4.020">
+  <testsuite name="docs" tests="1" failures="0" skipped="0" time="0.020">
+    <testcase classname="docs" name="build" time="0.020"/>
+  </testsuite>
+  <testsuite name="web" tests="2" failures="1" skipped="0" time="4.000">
+    <testcase classname="web" name="build" time="2.500"/>
+    <testcase classname="web" name="test" time="1.500">
+      <failure message="exit code 1">expected &lt;b&gt; &amp; got &lt;c&gt;</failure>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("\u{1b}[31m<b> & \"c\"\u{1b}[0m\u{7}\n'd'"),
+            "&lt;b&gt; &amp; &quot;c&quot;\n&apos;d&apos;"
+        );
+    }
+}
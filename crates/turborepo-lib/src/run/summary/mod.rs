@@ -7,15 +7,19 @@
 mod duration;
 mod execution;
 mod global_hash;
+mod junit;
+mod otel;
+mod report;
 mod scm;
 mod spaces;
 mod task;
 mod task_factory;
-use std::{collections::HashSet, io, io::Write};
+mod upload;
+use std::{collections::HashSet, io, io::Write, time::Duration};
 
 use chrono::{DateTime, Local};
 pub use duration::TurboDuration;
-pub use execution::{TaskExecutionSummary, TaskTracker};
+pub use execution::{CacheTransfer, CacheTransferKind, TaskExecutionSummary, TaskTracker};
 pub use global_hash::GlobalHashSummary;
 use itertools::Itertools;
 use serde::Serialize;
@@ -32,19 +36,29 @@ use turborepo_scm::SCM;
 use turborepo_ui::{color, cprintln, cwriteln, ColorConfig, BOLD, BOLD_CYAN, GREY};
 
 use self::{
-    execution::TaskState, task::SinglePackageTaskSummary, task_factory::TaskSummaryFactory,
+    execution::TaskState,
+    otel::{OtlpExporter, RunTrace},
+    report::RunReport,
+    task::{SinglePackageTaskSummary, TaskFailureSummary},
+    task_factory::TaskSummaryFactory,
+    upload::SummaryUploader,
 };
 use super::task_id::TaskId;
 use crate::{
+    api::{RunEvent, RunEventSender, RunResult},
     cli,
-    cli::{DryRunMode, EnvMode},
+    cli::{DryRunMode, EnvMode, LogFormat, RunOutputFormat},
     engine::Engine,
-    opts::RunOpts,
-    run::summary::{
-        execution::{ExecutionSummary, ExecutionTracker},
-        scm::SCMState,
-        spaces::{SpaceRequest, SpacesClient, SpacesClientHandle},
-        task::TaskSummary,
+    opts::{GraphOpts, RunOpts, SummaryUploadOpts},
+    run::{
+        graph_visualizer,
+        summary::{
+            execution::{ExecutionSummary, ExecutionTracker},
+            scm::SCMState,
+            spaces::{SpaceRequest, SpacesClient, SpacesClientHandle},
+            task::TaskSummary,
+        },
+        turbo_output,
     },
     task_hash::TaskHashTracker,
 };
@@ -98,16 +112,36 @@ pub struct RunSummary<'a> {
     env_mode: EnvMode,
     framework_inference: bool,
     tasks: Vec<TaskSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<TaskFailureSummary<TaskId<'static>>>,
     user: String,
     scm: SCMState,
     #[serde(skip)]
     repo_root: &'a AbsoluteSystemPath,
     #[serde(skip)]
+    continue_on_error: bool,
+    #[serde(skip)]
     should_save: bool,
     #[serde(skip)]
     run_type: RunType,
     #[serde(skip)]
     spaces_client_handle: Option<SpacesClientHandle>,
+    #[serde(skip)]
+    graph_file: Option<AbsoluteSystemPathBuf>,
+    #[serde(skip)]
+    otlp_exporter: Option<OtlpExporter>,
+    #[serde(skip)]
+    summary_uploader: Option<SummaryUploader>,
+    #[serde(skip)]
+    junit_file: Option<AbsoluteSystemPathBuf>,
+    #[serde(skip)]
+    html_report: bool,
+    #[serde(skip)]
+    is_github_actions: bool,
+    #[serde(skip)]
+    log_format: LogFormat,
+    #[serde(skip)]
+    run_events: Option<RunEventSender>,
 }
 
 /// We use this to track the run, so it's constructed before the run.
@@ -118,8 +152,11 @@ pub struct RunTracker {
     started_at: DateTime<Local>,
     execution_tracker: ExecutionTracker,
     spaces_client_handle: Option<SpacesClientHandle>,
+    otlp_exporter: Option<OtlpExporter>,
+    summary_uploader: Option<SummaryUploader>,
     user: String,
     synthesized_command: String,
+    run_events: Option<RunEventSender>,
 }
 
 impl RunTracker {
@@ -132,10 +169,12 @@ impl RunTracker {
         repo_root: &AbsoluteSystemPath,
         version: &'static str,
         spaces_id: Option<String>,
+        summary_upload: Option<&SummaryUploadOpts>,
         spaces_api_client: APIClient,
         api_auth: Option<APIAuth>,
         user: String,
         scm: &SCM,
+        run_events: Option<RunEventSender>,
     ) -> Self {
         let scm = SCMState::get(env_at_execution_start, scm, repo_root);
 
@@ -159,10 +198,14 @@ impl RunTracker {
             scm,
             version,
             started_at,
-            execution_tracker: ExecutionTracker::new(),
+            execution_tracker: ExecutionTracker::new(run_events.clone()),
             user,
             synthesized_command,
             spaces_client_handle,
+            otlp_exporter: OtlpExporter::from_env(env_at_execution_start),
+            summary_uploader: summary_upload
+                .and_then(|opts| SummaryUploader::new(opts, env_at_execution_start)),
+            run_events,
         }
     }
 
@@ -204,6 +247,11 @@ impl RunTracker {
             .cloned()
             .map(|TaskState { task_id, execution }| task_factory.task_summary(task_id, execution))
             .collect::<Result<Vec<_>, task_factory::Error>>()?;
+        let mut failures = tasks
+            .iter()
+            .filter_map(|task| TaskFailureSummary::from_task(task, repo_root))
+            .collect::<Vec<_>>();
+        failures.sort_by(|a, b| a.task_id.cmp(&b.task_id));
         let execution_summary = ExecutionSummary::new(
             self.synthesized_command.clone(),
             summary_state,
@@ -222,14 +270,31 @@ impl RunTracker {
             env_mode: global_env_mode,
             framework_inference: run_opts.framework_inference,
             tasks,
+            failures,
             global_hash_summary,
             scm: self.scm,
             user: self.user,
             monorepo: !single_package,
             repo_root,
+            continue_on_error: run_opts.continue_on_error,
             should_save,
             run_type,
             spaces_client_handle: self.spaces_client_handle,
+            graph_file: run_opts
+                .graph
+                .as_ref()
+                .and_then(GraphOpts::html_file)
+                .map(|file| AbsoluteSystemPathBuf::from_unknown(repo_root, file)),
+            otlp_exporter: self.otlp_exporter,
+            summary_uploader: self.summary_uploader,
+            junit_file: run_opts
+                .output_format
+                .contains(&RunOutputFormat::Junit)
+                .then(|| repo_root.join_components(&[".turbo", "junit.xml"])),
+            html_report: run_opts.output_format.contains(&RunOutputFormat::Html),
+            is_github_actions: run_opts.is_github_actions,
+            log_format: run_opts.log_format,
+            run_events: self.run_events,
         })
     }
 
@@ -324,6 +389,8 @@ struct SinglePackageRunSummary<'a> {
     env_mode: EnvMode,
     framework_inference: bool,
     tasks: Vec<SinglePackageTaskSummary>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<TaskFailureSummary<String>>,
     user: &'a str,
     pub scm: &'a SCMState,
 }
@@ -347,6 +414,12 @@ impl<'a> From<&'a RunSummary<'a>> for SinglePackageRunSummary<'a> {
             env_mode: run_summary.env_mode,
             framework_inference: run_summary.framework_inference,
             tasks,
+            failures: run_summary
+                .failures
+                .iter()
+                .cloned()
+                .map(TaskFailureSummary::from)
+                .collect(),
             user: &run_summary.user,
             scm: &run_summary.scm,
         }
@@ -363,8 +436,19 @@ impl<'a> RunSummary<'a> {
         ui: ColorConfig,
         is_watch: bool,
     ) -> Result<(), Error> {
+        let run_events = self.run_events.take();
+        if let Some(run_events) = &run_events {
+            // The receiver going away only means nobody is waiting on the result
+            let _ = run_events.send(RunEvent::Finished(self.run_result(exit_code)));
+        }
+
         if matches!(self.run_type, RunType::DryJson | RunType::DryText) {
-            return self.close_dry_run(pkg_dep_graph, ui);
+            // API callers get the tasks of a dry run in its result instead
+            if run_events.is_none() {
+                self.close_dry_run(pkg_dep_graph, ui)?;
+                self.write_graph(&mut io::stdout(), ui)?;
+            }
+            return Ok(());
         }
 
         if self.should_save {
@@ -373,14 +457,61 @@ impl<'a> RunSummary<'a> {
             }
         }
 
+        let mut out = turbo_output(self.log_format);
+
+        if let Some(junit_file) = &self.junit_file {
+            match self.write_junit(junit_file) {
+                Ok(()) if !is_watch => {
+                    write!(out, "\n✓ Generated JUnit report in ")?;
+                    cwriteln!(out, ui, BOLD, "{junit_file}")?;
+                }
+                Ok(()) => (),
+                Err(err) => warn!("Error writing JUnit report: {}", err),
+            }
+        }
+
+        if self.html_report {
+            let report_file = self.get_report_path();
+            match self.write_report(&report_file) {
+                Ok(()) if !is_watch => {
+                    write!(out, "\n✓ Generated run report in ")?;
+                    cwriteln!(out, ui, BOLD, "{report_file}")?;
+                }
+                Ok(()) => (),
+                Err(err) => warn!("Error writing run report: {}", err),
+            }
+        }
+
+        if self.is_github_actions {
+            for failure in &self.failures {
+                writeln!(out, "{}", failure.github_annotation())?;
+            }
+        }
+
         if !is_watch {
             if let Some(execution) = &self.execution {
                 let path = self.get_path();
                 let failed_tasks = self.get_failed_tasks();
-                execution.print(ui, path, failed_tasks);
+                execution.print(&mut out, ui, path, failed_tasks)?;
+                // When continuing past failures, their logs may be scattered throughout the
+                // output so we repeat the tail of each one here.
+                if self.continue_on_error {
+                    ExecutionSummary::print_failures(&mut out, ui, &self.failures)?;
+                }
             }
         }
 
+        self.write_graph(&mut out, ui)?;
+        out.flush()?;
+
+        if let Some(otlp_exporter) = self.otlp_exporter.take() {
+            self.export_trace(&otlp_exporter).await;
+        }
+
+        if let Some(summary_uploader) = self.summary_uploader.take() {
+            self.upload_summary(&summary_uploader).await;
+        }
+
         if let Some(spaces_client_handle) = self.spaces_client_handle.take() {
             self.send_to_space(spaces_client_handle, end_time, exit_code, is_watch)
                 .await;
@@ -427,6 +558,111 @@ impl<'a> RunSummary<'a> {
         }
     }
 
+    // Writes the HTML task graph, if one was requested, now that we know how
+    // each task ran
+    fn write_graph(&self, out: &mut impl Write, ui: ColorConfig) -> io::Result<()> {
+        let Some(graph_file) = &self.graph_file else {
+            return Ok(());
+        };
+        let tasks = self
+            .tasks
+            .iter()
+            .map(|task| task.graph_task(self.monorepo))
+            .collect::<Vec<_>>();
+        if let Err(err) = graph_visualizer::write_run_graph(graph_file, &tasks) {
+            warn!("Error writing task graph: {}", err);
+            return Ok(());
+        }
+        // Keep the output of `--dry=json` parseable
+        if !matches!(self.run_type, RunType::DryJson) {
+            write!(out, "\n✓ Generated task graph in ")?;
+            cwriteln!(out, ui, BOLD, "{graph_file}")?;
+        }
+        Ok(())
+    }
+
+    // Sends the run to an OpenTelemetry collector. Like spaces, a failed export
+    // shouldn't fail the run.
+    #[tracing::instrument(skip_all)]
+    async fn export_trace(&self, otlp_exporter: &OtlpExporter) {
+        let Some(execution) = &self.execution else {
+            return;
+        };
+        let trace = RunTrace {
+            run_id: self.id.to_string(),
+            command: &execution.command,
+            turbo_version: self.turbo_version,
+            start_time: execution.start_time,
+            end_time: execution.end_time,
+            exit_code: execution.exit_code,
+            tasks: self.tasks.iter().map(TaskSummary::trace_task).collect(),
+        };
+        if let Err(err) = otlp_exporter.export(&trace).await {
+            warn!("Error exporting trace: {}", err);
+        }
+    }
+
+    // Like exporting traces, a failed upload only gets a warning
+    #[tracing::instrument(skip_all)]
+    async fn upload_summary(&mut self, summary_uploader: &SummaryUploader) {
+        let summary = match self.format_json() {
+            Ok(summary) => summary,
+            Err(err) => {
+                warn!("Error uploading run summary: {}", err);
+                return;
+            }
+        };
+        if let Err(err) = summary_uploader.upload(summary).await {
+            warn!("Error uploading run summary: {}", err);
+        }
+    }
+
+    fn write_junit(&self, junit_file: &AbsoluteSystemPath) -> Result<(), Error> {
+        let command = self
+            .execution
+            .as_ref()
+            .map_or("turbo run", |execution| &execution.command);
+        let report = junit::junit_report(command, &self.tasks, &self.failures);
+        junit_file.ensure_dir()?;
+        Ok(junit_file.create_with_contents(report)?)
+    }
+
+    fn write_report(&self, report_file: &AbsoluteSystemPath) -> Result<(), Error> {
+        let Some(execution) = &self.execution else {
+            return Ok(());
+        };
+        let report = RunReport {
+            command: &execution.command,
+            start_time: execution.start_time,
+            end_time: execution.end_time,
+            exit_code: execution.exit_code,
+            tasks: self
+                .tasks
+                .iter()
+                .map(|task| task.report_task(self.monorepo, self.repo_root))
+                .collect(),
+        };
+        report::write_run_report(report_file, &report)
+    }
+
+    fn run_result(&self, exit_code: i32) -> RunResult {
+        RunResult {
+            exit_code,
+            tasks: self
+                .tasks
+                .iter()
+                .map(|task| task.task_result(self.repo_root))
+                .collect(),
+            duration: self
+                .execution
+                .as_ref()
+                .map_or(Duration::ZERO, ExecutionSummary::duration),
+            summary: matches!(self.run_type, RunType::DryJson | RunType::DryText)
+                .then(|| serde_json::to_value(self).ok())
+                .flatten(),
+        }
+    }
+
     fn print_errors(errors: &[Error]) {
         if errors.is_empty() {
             return;
@@ -662,6 +898,13 @@ impl<'a> RunSummary<'a> {
                 "  Inputs Files Considered\t=\t{}",
                 task.shared.inputs.len()
             )?;
+            cwriteln!(
+                tab_writer,
+                ui,
+                GREY,
+                "  External Dependencies\t=\t{}",
+                task.shared.external_dependencies.len()
+            )?;
 
             cwriteln!(
                 tab_writer,
@@ -780,6 +1023,13 @@ impl<'a> RunSummary<'a> {
             .join_components(&[".turbo", "runs", &filename])
     }
 
+    fn get_report_path(&self) -> AbsoluteSystemPathBuf {
+        let filename = format!("{}.html", self.id);
+
+        self.repo_root
+            .join_components(&[".turbo", "runs", &filename])
+    }
+
     fn get_failed_tasks(&self) -> Vec<&TaskSummary> {
         self.tasks
             .iter()
@@ -0,0 +1,551 @@
+//! Exports runs as OpenTelemetry traces. A run becomes a root span with a
+//! child span for each task that ran, and each task has child spans for the
+//! cache restores and saves it did. Traces are sent over OTLP/HTTP with JSON
+//! encoding and are configured through the standard `OTEL_*` environment
+//! variables.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tracing::warn;
+use turborepo_env::EnvironmentVariableMap;
+
+use super::execution::{CacheTransfer, CacheTransferKind, TaskExecutionSummary};
+
+const DEFAULT_SERVICE_NAME: &str = "turbo";
+const TRACES_PATH: &str = "/v1/traces";
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+// From the OTLP spec
+const SPAN_KIND_INTERNAL: u8 = 1;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+/// Where, and as what service, traces get sent
+#[derive(Debug, Clone, PartialEq)]
+pub struct OtlpExporter {
+    endpoint: String,
+    headers: Vec<(String, String)>,
+    service_name: String,
+}
+
+/// The parts of a run that end up in its trace
+#[derive(Debug)]
+pub(crate) struct RunTrace<'a> {
+    pub run_id: String,
+    pub command: &'a str,
+    pub turbo_version: &'a str,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub exit_code: i32,
+    pub tasks: Vec<TraceTask>,
+}
+
+/// A task as it appears in the trace of a run
+#[derive(Debug)]
+pub(crate) struct TraceTask {
+    pub task_id: String,
+    pub package: String,
+    pub task: String,
+    pub hash: String,
+    pub cache_status: &'static str,
+    pub cache_source: Option<&'static str>,
+    pub execution: Option<TaskExecutionSummary>,
+    pub cache_transfers: Vec<CacheTransfer>,
+}
+
+impl OtlpExporter {
+    /// Reads the exporter configuration from the environment. Returns `None`
+    /// unless an OTLP endpoint is set.
+    pub fn from_env(env: &EnvironmentVariableMap) -> Option<Self> {
+        let signal_or_general = |name: &str| {
+            env.get(&format!("OTEL_EXPORTER_OTLP_TRACES_{name}"))
+                .or_else(|| env.get(&format!("OTEL_EXPORTER_OTLP_{name}")))
+                .filter(|value| !value.is_empty())
+        };
+
+        // The traces endpoint is used as is, while the general one is a base URL
+        // that signal paths get appended to
+        let endpoint = match env
+            .get("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+            .filter(|endpoint| !endpoint.is_empty())
+        {
+            Some(endpoint) => endpoint.clone(),
+            None => format!(
+                "{}{TRACES_PATH}",
+                env.get("OTEL_EXPORTER_OTLP_ENDPOINT")
+                    .filter(|endpoint| !endpoint.is_empty())?
+                    .trim_end_matches('/')
+            ),
+        };
+
+        if let Some(protocol) = signal_or_general("PROTOCOL") {
+            if protocol != "http/json" {
+                warn!(
+                    "OTLP protocol '{protocol}' is not supported, only 'http/json' is. Not \
+                     exporting traces."
+                );
+                return None;
+            }
+        }
+
+        let headers = signal_or_general("HEADERS")
+            .map(|headers| parse_headers(headers))
+            .unwrap_or_default();
+        let service_name = env
+            .get("OTEL_SERVICE_NAME")
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_SERVICE_NAME.to_string());
+
+        Some(Self {
+            endpoint,
+            headers,
+            service_name,
+        })
+    }
+
+    pub async fn export(&self, run: &RunTrace<'_>) -> Result<(), reqwest::Error> {
+        let mut request = reqwest::Client::new()
+            .post(&self.endpoint)
+            .timeout(EXPORT_TIMEOUT)
+            .json(&self.request(run, &mut random_id));
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    fn request(&self, run: &RunTrace, id: &mut impl FnMut(usize) -> String) -> ExportRequest {
+        let trace_id = id(16);
+        let root_id = id(8);
+
+        let mut spans = vec![Span {
+            trace_id: trace_id.clone(),
+            span_id: root_id.clone(),
+            parent_span_id: String::new(),
+            name: "turbo run".to_string(),
+            kind: SPAN_KIND_INTERNAL,
+            start_time_unix_nano: unix_nanos(run.start_time),
+            end_time_unix_nano: unix_nanos(run.end_time),
+            attributes: vec![
+                KeyValue::string("turbo.run.id", &run.run_id),
+                KeyValue::string("turbo.command", run.command),
+                KeyValue::int("turbo.exit_code", run.exit_code.into()),
+            ],
+            status: Status::new(run.exit_code == 0, None),
+        }];
+
+        for task in &run.tasks {
+            // Tasks that never started don't have any timings to show
+            let Some(execution) = &task.execution else {
+                continue;
+            };
+            let task_span_id = id(8);
+            let mut attributes = vec![
+                KeyValue::string("turbo.task.id", &task.task_id),
+                KeyValue::string("turbo.package", &task.package),
+                KeyValue::string("turbo.task", &task.task),
+                KeyValue::string("turbo.hash", &task.hash),
+                KeyValue::string("turbo.cache.status", task.cache_status),
+            ];
+            if let Some(source) = task.cache_source {
+                attributes.push(KeyValue::string("turbo.cache.source", source));
+            }
+            if let Some(exit_code) = execution.exit_code {
+                attributes.push(KeyValue::int("turbo.exit_code", exit_code.into()));
+            }
+            spans.push(Span {
+                trace_id: trace_id.clone(),
+                span_id: task_span_id.clone(),
+                parent_span_id: root_id.clone(),
+                name: task.task_id.clone(),
+                kind: SPAN_KIND_INTERNAL,
+                start_time_unix_nano: unix_nanos(execution.start_time),
+                end_time_unix_nano: unix_nanos(execution.end_time),
+                attributes,
+                status: Status::new(!execution.is_failure(), execution.error.clone()),
+            });
+
+            for transfer in &task.cache_transfers {
+                let name = match transfer.kind {
+                    CacheTransferKind::Restore => "cache restore",
+                    CacheTransferKind::Save => "cache save",
+                };
+                spans.push(Span {
+                    trace_id: trace_id.clone(),
+                    span_id: id(8),
+                    parent_span_id: task_span_id.clone(),
+                    name: name.to_string(),
+                    kind: SPAN_KIND_INTERNAL,
+                    start_time_unix_nano: unix_nanos(transfer.start_time),
+                    end_time_unix_nano: unix_nanos(transfer.end_time),
+                    attributes: vec![KeyValue::string("turbo.hash", &task.hash)],
+                    status: Status::new(true, None),
+                });
+            }
+        }
+
+        ExportRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: Resource {
+                    attributes: vec![
+                        KeyValue::string("service.name", &self.service_name),
+                        KeyValue::string("service.version", run.turbo_version),
+                    ],
+                },
+                scope_spans: vec![ScopeSpans {
+                    scope: Scope {
+                        name: DEFAULT_SERVICE_NAME,
+                        version: run.turbo_version.to_string(),
+                    },
+                    spans,
+                }],
+            }],
+        }
+    }
+}
+
+// Headers are comma separated `key=value` pairs with URL encoded values
+fn parse_headers(headers: &str) -> Vec<(String, String)> {
+    headers
+        .split(',')
+        .filter_map(|header| {
+            let (name, value) = header.split_once('=')?;
+            let value = percent_encoding::percent_decode_str(value.trim())
+                .decode_utf8()
+                .ok()?;
+            Some((name.trim().to_string(), value.into_owned()))
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+// Random hex encoded id that is `bytes` long
+fn random_id(bytes: usize) -> String {
+    hex::encode((0..bytes).map(|_| rand::random::<u8>()).collect::<Vec<_>>())
+}
+
+// OTLP timestamps are nanoseconds, which JSON encodes as strings
+fn unix_nanos(millis: i64) -> String {
+    (i128::from(millis) * 1_000_000).to_string()
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportRequest {
+    resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceSpans {
+    resource: Resource,
+    scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeSpans {
+    scope: Scope,
+    spans: Vec<Span>,
+}
+
+#[derive(Debug, Serialize)]
+struct Scope {
+    name: &'static str,
+    version: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Span {
+    trace_id: String,
+    span_id: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    parent_span_id: String,
+    name: String,
+    kind: u8,
+    start_time_unix_nano: String,
+    end_time_unix_nano: String,
+    attributes: Vec<KeyValue>,
+    status: Status,
+}
+
+#[derive(Debug, Serialize)]
+struct Status {
+    code: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+impl Status {
+    fn new(ok: bool, message: Option<String>) -> Self {
+        Self {
+            code: if ok {
+                STATUS_CODE_OK
+            } else {
+                STATUS_CODE_ERROR
+            },
+            message,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: &'static str,
+    value: AnyValue,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum AnyValue {
+    StringValue(String),
+    // 64 bit integers are encoded as strings in OTLP JSON
+    IntValue(String),
+}
+
+impl KeyValue {
+    fn string(key: &'static str, value: &str) -> Self {
+        Self {
+            key,
+            value: AnyValue::StringValue(value.to_string()),
+        }
+    }
+
+    fn int(key: &'static str, value: i64) -> Self {
+        Self {
+            key,
+            value: AnyValue::IntValue(value.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+    use test_case::test_case;
+    use turborepo_env::EnvironmentVariableMap;
+
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> EnvironmentVariableMap {
+        EnvironmentVariableMap::from(
+            vars.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test_case(&[], None ; "no endpoint")]
+    #[test_case(
+        &[("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318/")],
+        Some("http://localhost:4318/v1/traces")
+        ; "general endpoint"
+    )]
+    #[test_case(
+        &[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318"),
+            ("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT", "http://collector/traces"),
+        ],
+        Some("http://collector/traces")
+        ; "traces endpoint"
+    )]
+    #[test_case(
+        &[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318"),
+            ("OTEL_EXPORTER_OTLP_PROTOCOL", "grpc"),
+        ],
+        None
+        ; "unsupported protocol"
+    )]
+    fn test_endpoint(vars: &[(&str, &str)], expected: Option<&str>) {
+        let exporter = OtlpExporter::from_env(&env(vars));
+        assert_eq!(
+            exporter.as_ref().map(|exporter| exporter.endpoint.as_str()),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_headers_and_service_name() {
+        let exporter = OtlpExporter::from_env(&env(&[
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", "http://localhost:4318"),
+            (
+                "OTEL_EXPORTER_OTLP_HEADERS",
+                "Authorization=Bearer%20secret, x-team = ci,malformed",
+            ),
+            ("OTEL_SERVICE_NAME", "monorepo-ci"),
+        ]))
+        .unwrap();
+        assert_eq!(
+            exporter.headers,
+            vec![
+                ("Authorization".to_string(), "Bearer secret".to_string()),
+                ("x-team".to_string(), "ci".to_string()),
+            ]
+        );
+        assert_eq!(exporter.service_name, "monorepo-ci");
+    }
+
+    #[test]
+    fn test_request() {
+        let exporter = OtlpExporter::from_env(&env(&[(
+            "OTEL_EXPORTER_OTLP_ENDPOINT",
+            "http://localhost:4318",
+        )]))
+        .unwrap();
+        let run = RunTrace {
+            run_id: "run".to_string(),
+            command: "turbo run build",
+            turbo_version: "2.0.0",
+            start_time: 1000,
+            end_time: 5000,
+            exit_code: 1,
+            tasks: vec![
+                TraceTask {
+                    task_id: "web#build".to_string(),
+                    package: "web".to_string(),
+                    task: "build".to_string(),
+                    hash: "abc".to_string(),
+                    cache_status: "HIT",
+                    cache_source: Some("LOCAL"),
+                    execution: Some(TaskExecutionSummary {
+                        start_time: 1100,
+                        end_time: 1200,
+                        error: None,
+                        exit_code: Some(0),
+                    }),
+                    cache_transfers: vec![CacheTransfer {
+                        kind: CacheTransferKind::Restore,
+                        start_time: 1100,
+                        end_time: 1150,
+                    }],
+                },
+                TraceTask {
+                    task_id: "docs#build".to_string(),
+                    package: "docs".to_string(),
+                    task: "build".to_string(),
+                    hash: "def".to_string(),
+                    cache_status: "MISS",
+                    cache_source: None,
+                    execution: Some(TaskExecutionSummary {
+                        start_time: 1100,
+                        end_time: 4900,
+                        error: Some("command exited (1)".to_string()),
+                        exit_code: Some(1),
+                    }),
+                    cache_transfers: vec![],
+                },
+                TraceTask {
+                    task_id: "docs#deploy".to_string(),
+                    package: "docs".to_string(),
+                    task: "deploy".to_string(),
+                    hash: "ghi".to_string(),
+                    cache_status: "MISS",
+                    cache_source: None,
+                    execution: None,
+                    cache_transfers: vec![],
+                },
+            ],
+        };
+
+        let mut next_id = 0;
+        let request = exporter.request(&run, &mut |bytes| {
+            next_id += 1;
+            format!("{next_id:0width$}", width = bytes * 2)
+        });
+        let trace_id = "00000000000000000000000000000001";
+        let string = |key: &str, value: &str| json!({"key": key, "value": {"stringValue": value}});
+        let int = |key: &str, value: &str| json!({"key": key, "value": {"intValue": value}});
+
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            json!({
+                "resourceSpans": [{
+                    "resource": {
+                        "attributes": [
+                            string("service.name", "turbo"),
+                            string("service.version", "2.0.0"),
+                        ]
+                    },
+                    "scopeSpans": [{
+                        "scope": {"name": "turbo", "version": "2.0.0"},
+                        "spans": [
+                            {
+                                "traceId": trace_id,
+                                "spanId": "0000000000000002",
+                                "name": "turbo run",
+                                "kind": 1,
+                                "startTimeUnixNano": "1000000000",
+                                "endTimeUnixNano": "5000000000",
+                                "attributes": [
+                                    string("turbo.run.id", "run"),
+                                    string("turbo.command", "turbo run build"),
+                                    int("turbo.exit_code", "1"),
+                                ],
+                                "status": {"code": 2},
+                            },
+                            {
+                                "traceId": trace_id,
+                                "spanId": "0000000000000003",
+                                "parentSpanId": "0000000000000002",
+                                "name": "web#build",
+                                "kind": 1,
+                                "startTimeUnixNano": "1100000000",
+                                "endTimeUnixNano": "1200000000",
+                                "attributes": [
+                                    string("turbo.task.id", "web#build"),
+                                    string("turbo.package", "web"),
+                                    string("turbo.task", "build"),
+                                    string("turbo.hash", "abc"),
+                                    string("turbo.cache.status", "HIT"),
+                                    string("turbo.cache.source", "LOCAL"),
+                                    int("turbo.exit_code", "0"),
+                                ],
+                                "status": {"code": 1},
+                            },
+                            {
+                                "traceId": trace_id,
+                                "spanId": "0000000000000004",
+                                "parentSpanId": "0000000000000003",
+                                "name": "cache restore",
+                                "kind": 1,
+                                "startTimeUnixNano": "1100000000",
+                                "endTimeUnixNano": "1150000000",
+                                "attributes": [string("turbo.hash", "abc")],
+                                "status": {"code": 1},
+                            },
+                            {
+                                "traceId": trace_id,
+                                "spanId": "0000000000000005",
+                                "parentSpanId": "0000000000000002",
+                                "name": "docs#build",
+                                "kind": 1,
+                                "startTimeUnixNano": "1100000000",
+                                "endTimeUnixNano": "4900000000",
+                                "attributes": [
+                                    string("turbo.task.id", "docs#build"),
+                                    string("turbo.package", "docs"),
+                                    string("turbo.task", "build"),
+                                    string("turbo.hash", "def"),
+                                    string("turbo.cache.status", "MISS"),
+                                    int("turbo.exit_code", "1"),
+                                ],
+                                "status": {"code": 2, "message": "command exited (1)"},
+                            },
+                        ]
+                    }]
+                }]
+            })
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Self-contained HTML reports of runs, written next to run summaries in
+//! `.turbo/runs`. They show a timeline of the tasks, how much of the run was
+//! cached, which tasks were slowest, and the logs of every task.
+
+use serde::Serialize;
+use turbopath::AbsoluteSystemPath;
+
+use super::Error;
+use crate::run::{graph_visualizer::RunGraphTaskStatus, html::embed_json};
+
+// The page the report is rendered into, with `{{REPORT_DATA}}` standing in for
+// the run as JSON
+const RUN_REPORT_HTML: &str = include_str!("report.html");
+
+// Only the end of longer logs is included to keep reports a reasonable size
+const MAX_LOG_LINES: usize = 1000;
+
+/// A run as it appears in its HTML report
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RunReport<'a> {
+    pub command: &'a str,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub exit_code: i32,
+    pub tasks: Vec<ReportTask>,
+}
+
+/// A task as it appears in the HTML report of a run
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReportTask {
+    pub id: String,
+    pub hash: String,
+    pub status: RunGraphTaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_source: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<i64>,
+    pub time_saved_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    pub logs: String,
+}
+
+pub(crate) fn write_run_report(
+    filename: &AbsoluteSystemPath,
+    report: &RunReport,
+) -> Result<(), Error> {
+    let html = embed_json(RUN_REPORT_HTML, "{{REPORT_DATA}}", report)?;
+    filename.ensure_dir()?;
+    Ok(filename.create_with_contents(html)?)
+}
+
+/// Prepares a task's logs for the report by dropping terminal colors and all
+/// but the last lines
+pub(crate) fn report_logs(logs: &str) -> String {
+    let logs = console::strip_ansi_codes(logs);
+    let lines = logs.lines().collect::<Vec<_>>();
+    match lines.len().checked_sub(MAX_LOG_LINES) {
+        Some(omitted) if omitted > 0 => format!(
+            "... {omitted} earlier lines omitted\n{}",
+            lines[omitted..].join("\n")
+        ),
+        _ => lines.join("\n"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_report_logs() {
+        assert_eq!(report_logs("\u{1b}[32mok\u{1b}[0m\ndone\n"), "ok\ndone");
+
+        let logs = (0..MAX_LOG_LINES + 2)
+            .map(|line| line.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let report_logs = report_logs(&logs);
+        assert!(report_logs.starts_with("... 2 earlier lines omitted\n2\n"));
+        assert_eq!(report_logs.lines().count(), MAX_LOG_LINES + 1);
+    }
+}
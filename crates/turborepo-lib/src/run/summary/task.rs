@@ -1,15 +1,25 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, fmt};
 
 use serde::Serialize;
-use turbopath::{AnchoredSystemPathBuf, RelativeUnixPathBuf};
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf, RelativeUnixPathBuf};
 use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{DetailedMap, EnvironmentVariableMap};
 
-use super::{execution::TaskExecutionSummary, EnvMode};
+use super::{
+    execution::{CacheTransfer, TaskExecutionSummary},
+    otel::TraceTask,
+    report::{self, ReportTask},
+    EnvMode,
+};
 use crate::{
+    api::TaskResult,
     cli::OutputLogsMode,
-    run::task_id::TaskId,
-    task_graph::{TaskDefinition, TaskOutputs},
+    run::{
+        graph_visualizer::{RunGraphTask, RunGraphTaskStatus},
+        task_id::TaskId,
+    },
+    task_graph::{CachePolicy, TaskDefinition, TaskOutputs},
+    task_hash::TaskHashInputs,
 };
 
 #[derive(Debug, Serialize, Clone)]
@@ -41,6 +51,15 @@ enum CacheSource {
     Remote,
 }
 
+impl CacheSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            CacheSource::Local => "LOCAL",
+            CacheSource::Remote => "REMOTE",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TaskSummary {
@@ -66,6 +85,8 @@ pub(crate) struct SharedTaskSummary<T> {
     pub hash: String,
     pub inputs: BTreeMap<RelativeUnixPathBuf, String>,
     pub hash_of_external_dependencies: String,
+    pub external_dependencies: Vec<turborepo_lockfiles::Package>,
+    pub hash_inputs: TaskHashInputs,
     pub cache: TaskCacheSummary,
     pub command: String,
     pub cli_arguments: Vec<String>,
@@ -83,6 +104,23 @@ pub(crate) struct SharedTaskSummary<T> {
     pub environment_variables: TaskEnvVarSummary,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub execution: Option<TaskExecutionSummary>,
+    #[serde(skip)]
+    pub cache_transfers: Vec<CacheTransfer>,
+}
+
+// Number of trailing log lines to include for each failed task
+const FAILURE_LOG_TAIL_LINES: usize = 10;
+
+/// A failed task along with the last lines of its logs. These are collected
+/// so every failure in a run can be reported in one place once the run ends.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TaskFailureSummary<T> {
+    pub task_id: T,
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub log_tail: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -96,15 +134,22 @@ pub struct TaskEnvConfiguration {
 #[serde(rename_all = "camelCase")]
 pub struct TaskSummaryTaskDefinition {
     outputs: Vec<String>,
-    cache: bool,
+    cache: CachePolicy,
     depends_on: Vec<String>,
     inputs: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    inputs_from: Vec<String>,
+    dotfiles: bool,
     output_logs: OutputLogsMode,
     persistent: bool,
     interruptible: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    skippable: bool,
     env: Vec<String>,
     pass_through_env: Option<Vec<String>>,
     interactive: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    matrix: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     env_mode: Option<EnvMode>,
 }
@@ -220,6 +265,8 @@ impl From<SharedTaskSummary<TaskId<'static>>> for SharedTaskSummary<String> {
             hash,
             inputs,
             hash_of_external_dependencies,
+            external_dependencies,
+            hash_inputs,
             cache,
             command,
             cli_arguments,
@@ -234,12 +281,15 @@ impl From<SharedTaskSummary<TaskId<'static>>> for SharedTaskSummary<String> {
             execution,
             env_mode,
             environment_variables,
+            cache_transfers,
             ..
         } = value;
         Self {
             hash,
             inputs,
             hash_of_external_dependencies,
+            external_dependencies,
+            hash_inputs,
             cache,
             command,
             cli_arguments,
@@ -261,10 +311,191 @@ impl From<SharedTaskSummary<TaskId<'static>>> for SharedTaskSummary<String> {
             execution,
             env_mode,
             environment_variables,
+            cache_transfers,
+        }
+    }
+}
+
+impl TaskSummary {
+    /// How the task appears in the HTML graph written after a run. Single
+    /// package runs leave out the package, like their summaries do.
+    pub(crate) fn graph_task(&self, monorepo: bool) -> RunGraphTask {
+        let name = |task_id: &TaskId| match monorepo {
+            true => task_id.to_string(),
+            false => task_id.task().to_string(),
+        };
+        let execution = self.shared.execution.as_ref();
+        RunGraphTask {
+            id: name(&self.task_id),
+            hash: self.shared.hash.clone(),
+            status: self.run_status(),
+            duration_ms: execution.map(|execution| execution.end_time - execution.start_time),
+            time_saved_ms: self.shared.cache.time_saved,
+            dependencies: self.shared.dependencies.iter().map(name).collect(),
+        }
+    }
+
+    /// How the task appears in the HTML report written after a run
+    pub(crate) fn report_task(&self, monorepo: bool, repo_root: &AbsoluteSystemPath) -> ReportTask {
+        let execution = self.shared.execution.as_ref();
+        ReportTask {
+            id: match monorepo {
+                true => self.task_id.to_string(),
+                false => self.task_id.task().to_string(),
+            },
+            hash: self.shared.hash.clone(),
+            status: self.run_status(),
+            cache_source: self.shared.cache.source.map(CacheSource::as_str),
+            start_time: execution.map(|execution| execution.start_time),
+            end_time: execution.map(|execution| execution.end_time),
+            time_saved_ms: self.shared.cache.time_saved,
+            exit_code: execution.and_then(|execution| execution.exit_code),
+            logs: self
+                .logs(repo_root)
+                .map(|logs| report::report_logs(&logs))
+                .unwrap_or_default(),
+        }
+    }
+
+    fn run_status(&self) -> RunGraphTaskStatus {
+        match &self.shared.execution {
+            None => RunGraphTaskStatus::NotRun,
+            Some(execution) if execution.is_failure() => RunGraphTaskStatus::Failed,
+            Some(_) if matches!(self.shared.cache.status, CacheStatus::Hit) => {
+                RunGraphTaskStatus::Cached
+            }
+            Some(_) => RunGraphTaskStatus::Succeeded,
+        }
+    }
+
+    /// How the task appears in the result of a run started through the API
+    pub(crate) fn task_result(&self, repo_root: &AbsoluteSystemPath) -> TaskResult {
+        let execution = self.shared.execution.as_ref();
+        TaskResult {
+            task_id: self.task_id.to_string(),
+            package: self.package.clone(),
+            task: self.task.clone(),
+            hash: self.shared.hash.clone(),
+            status: self.run_status().into(),
+            exit_code: execution.and_then(|execution| execution.exit_code),
+            duration: execution.map(TaskExecutionSummary::duration),
+            log_file: AnchoredSystemPathBuf::from_raw(&self.shared.log_file)
+                .ok()
+                .map(|log_file| repo_root.resolve(&log_file)),
+        }
+    }
+
+    // The log file might not exist if the task didn't run or failed to start
+    fn logs(&self, repo_root: &AbsoluteSystemPath) -> Option<String> {
+        let log_file = AnchoredSystemPathBuf::from_raw(&self.shared.log_file).ok()?;
+        repo_root.resolve(&log_file).read_to_string().ok()
+    }
+
+    /// How the task appears in the trace exported after a run
+    pub(crate) fn trace_task(&self) -> TraceTask {
+        TraceTask {
+            task_id: self.task_id.to_string(),
+            package: self.package.clone(),
+            task: self.task.clone(),
+            hash: self.shared.hash.clone(),
+            cache_status: match self.shared.cache.status {
+                CacheStatus::Hit => "HIT",
+                CacheStatus::Miss => "MISS",
+            },
+            cache_source: self.shared.cache.source.map(CacheSource::as_str),
+            execution: self.shared.execution.clone(),
+            cache_transfers: self.shared.cache_transfers.clone(),
+        }
+    }
+}
+
+impl TaskFailureSummary<TaskId<'static>> {
+    /// Constructs a failure summary for the task if its execution failed
+    pub fn from_task(task: &TaskSummary, repo_root: &AbsoluteSystemPath) -> Option<Self> {
+        let execution = task
+            .shared
+            .execution
+            .as_ref()
+            .filter(|execution| execution.is_failure())?;
+        let log_tail = task
+            .logs(repo_root)
+            .map(|logs| tail_lines(&logs, FAILURE_LOG_TAIL_LINES))
+            .unwrap_or_default();
+
+        Some(Self {
+            task_id: task.task_id.clone(),
+            exit_code: execution.exit_code,
+            error: execution.error.clone(),
+            log_tail,
+        })
+    }
+}
+
+impl<T: fmt::Display> TaskFailureSummary<T> {
+    /// Why the task failed, e.g. `exit code 1`
+    pub fn status(&self) -> String {
+        match (self.exit_code, &self.error) {
+            (Some(code), _) => format!("exit code {code}"),
+            (None, Some(error)) => error.clone(),
+            (None, None) => "did not finish".to_string(),
+        }
+    }
+
+    /// The failure as a GitHub Actions workflow command, which shows up as an
+    /// error annotation on the workflow run
+    pub fn github_annotation(&self) -> String {
+        let title = format!("{} failed ({})", self.task_id, self.status());
+        let logs = console::strip_ansi_codes(&self.log_tail.join("\n")).into_owned();
+        format!(
+            "::error title={}::{}",
+            escape_workflow_property(&title),
+            escape_workflow_data(&logs)
+        )
+    }
+}
+
+// Workflow commands end at a newline, so messages need them encoded
+fn escape_workflow_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_workflow_property(property: &str) -> String {
+    escape_workflow_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+impl From<TaskFailureSummary<TaskId<'static>>> for TaskFailureSummary<String> {
+    fn from(value: TaskFailureSummary<TaskId<'static>>) -> Self {
+        let TaskFailureSummary {
+            task_id,
+            exit_code,
+            error,
+            log_tail,
+        } = value;
+        Self {
+            task_id: task_id.task().to_string(),
+            exit_code,
+            error,
+            log_tail,
         }
     }
 }
 
+fn tail_lines(contents: &str, count: usize) -> Vec<String> {
+    let mut lines = contents
+        .lines()
+        .rev()
+        .skip_while(|line| line.trim().is_empty())
+        .take(count)
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>();
+    lines.reverse();
+    lines
+}
+
 impl From<TaskDefinition> for TaskSummaryTaskDefinition {
     fn from(value: TaskDefinition) -> Self {
         let TaskDefinition {
@@ -279,10 +510,15 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             topological_dependencies,
             task_dependencies,
             mut inputs,
+            inputs_from,
+            dotfiles,
             output_logs,
             persistent,
             interruptible,
+            skippable,
             interactive,
+            matrix,
+            matrix_variant: _,
             env_mode,
         } = value;
 
@@ -307,18 +543,26 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
         outputs.sort();
         env.sort();
         inputs.sort();
+        let inputs_from = inputs_from
+            .into_iter()
+            .map(|task| task.into_inner().to_string())
+            .collect();
 
         Self {
             outputs,
             cache,
             depends_on,
             inputs,
+            inputs_from,
+            dotfiles,
             output_logs,
             persistent,
             interruptible,
+            skippable,
             interactive,
             env,
             pass_through_env,
+            matrix,
             env_mode,
         }
     }
@@ -365,7 +609,7 @@ mod test {
     #[test_case(
         TaskSummaryTaskDefinition {
             outputs: vec!["foo".into()],
-            cache: true,
+            cache: CachePolicy::ReadWrite,
             ..Default::default()
         },
         json!({
@@ -373,6 +617,7 @@ mod test {
             "cache": true,
             "dependsOn": [],
             "inputs": [],
+            "dotfiles": false,
             "outputLogs": "full",
             "persistent": false,
             "interruptible": false,
@@ -382,7 +627,66 @@ mod test {
         })
         ; "resolved task definition"
     )]
+    #[test_case(
+        TaskSummaryTaskDefinition {
+            cache: CachePolicy::ReadOnly,
+            ..Default::default()
+        },
+        json!({
+            "outputs": [],
+            "cache": "read-only",
+            "dependsOn": [],
+            "inputs": [],
+            "dotfiles": false,
+            "outputLogs": "full",
+            "persistent": false,
+            "interruptible": false,
+            "interactive": false,
+            "env": [],
+            "passThroughEnv": null,
+        })
+        ; "read only cache policy"
+    )]
+    #[test_case(
+        TaskHashInputs {
+            global_hash: "global".into(),
+            hash_of_files: "files".into(),
+            dependency_hashes: vec!["dep1".into(), "dep2".into()],
+        },
+        json!({
+            "globalHash": "global",
+            "hashOfFiles": "files",
+            "dependencyHashes": ["dep1", "dep2"],
+        })
+        ; "hash inputs"
+    )]
     fn test_serialization(value: impl serde::Serialize, expected: serde_json::Value) {
         assert_eq!(serde_json::to_value(value).unwrap(), expected);
     }
+
+    #[test_case("", 2, &[] ; "empty logs")]
+    #[test_case("a\nb\nc\n", 2, &["b", "c"] ; "more lines than count")]
+    #[test_case("a\nb\n", 5, &["a", "b"] ; "fewer lines than count")]
+    #[test_case("a\nb\n\n  \n", 1, &["b"] ; "trailing blank lines")]
+    fn test_tail_lines(contents: &str, count: usize, expected: &[&str]) {
+        assert_eq!(tail_lines(contents, count), expected);
+    }
+
+    #[test]
+    fn test_github_annotation() {
+        let failure = TaskFailureSummary {
+            task_id: "web#build",
+            exit_code: Some(1),
+            error: None,
+            log_tail: vec![
+                "\u{1b}[31merror\u{1b}[0m: 100% broken".to_string(),
+                "at src/index.ts:1".to_string(),
+            ],
+        };
+        assert_eq!(
+            failure.github_annotation(),
+            "::error title=web#build failed (exit code 1)::error: 100%25 broken%0Aat \
+             src/index.ts:1"
+        );
+    }
 }
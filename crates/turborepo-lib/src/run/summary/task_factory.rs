@@ -14,7 +14,7 @@ use crate::{
     opts::RunOpts,
     run::task_id::TaskId,
     task_graph::TaskDefinition,
-    task_hash::{get_external_deps_hash, TaskHashTracker},
+    task_hash::{get_external_deps, get_external_deps_hash, TaskHashTracker},
 };
 
 pub struct TaskSummaryFactory<'a> {
@@ -109,17 +109,16 @@ impl<'a> TaskSummaryFactory<'a> {
         workspace_info: &PackageInfo,
         display_task: impl Fn(&TaskNode) -> Option<T> + Copy,
     ) -> Result<SharedTaskSummary<T>, Error> {
+        let task_definition = self.task_definition(task_id)?;
+
         // TODO: command should be optional
-        let command = workspace_info
-            .package_json
-            .scripts
-            .get(task_id.task())
+        let command = task_definition
+            .script(task_id)
+            .and_then(|script| workspace_info.package_json.scripts.get(script))
             .map(|script| script.as_inner())
             .cloned()
             .unwrap_or_else(|| "<NONEXISTENT>".to_string());
 
-        let task_definition = self.task_definition(task_id)?;
-
         let expanded_outputs = self
             .hash_tracker
             .expanded_outputs(task_id)
@@ -142,7 +141,13 @@ impl<'a> TaskSummaryFactory<'a> {
             .env_vars(task_id)
             .expect("env var map is inserted at the same time as hash");
 
+        let hash_inputs = self
+            .hash_tracker
+            .hash_inputs(task_id)
+            .expect("hash inputs are inserted at the same time as hash");
+
         let cache_summary = self.hash_tracker.cache_status(task_id).into();
+        let cache_transfers = self.hash_tracker.cache_transfers(task_id);
 
         let (dependencies, dependents) = self.dependencies_and_dependents(task_id, display_task);
 
@@ -158,6 +163,8 @@ impl<'a> TaskSummaryFactory<'a> {
             hash_of_external_dependencies: get_external_deps_hash(
                 &workspace_info.transitive_dependencies,
             ),
+            external_dependencies: get_external_deps(&workspace_info.transitive_dependencies),
+            hash_inputs,
             cache: cache_summary,
             command,
             cli_arguments: self.run_opts.pass_through_args.to_vec(),
@@ -184,6 +191,7 @@ impl<'a> TaskSummaryFactory<'a> {
             )
             .expect("invalid glob in task definition should have been caught earlier"),
             execution,
+            cache_transfers,
         })
     }
 
@@ -0,0 +1,199 @@
+//! Sends run summaries to an endpoint of the user's choosing, like an ingestion
+//! endpoint for a data warehouse. The summary is POSTed as the same JSON that
+//! `--summarize` writes to disk, along with any configured headers.
+
+use std::{fmt, sync::OnceLock, time::Duration};
+
+use regex::{Captures, Regex};
+use tracing::warn;
+use turborepo_env::EnvironmentVariableMap;
+
+use crate::opts::SummaryUploadOpts;
+
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct SummaryUploader {
+    url: String,
+    headers: Vec<(String, String)>,
+}
+
+// Header values usually hold credentials, so keep them out of logs
+impl fmt::Debug for SummaryUploader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SummaryUploader")
+            .field("url", &self.url)
+            .field(
+                "headers",
+                &self
+                    .headers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl SummaryUploader {
+    /// Fills in the header templates from the environment. Returns `None` if a
+    /// header refers to a variable that isn't set, since the endpoint would
+    /// reject an upload without its credentials anyway, or to a variable
+    /// that doesn't start with `TEMPLATE_VAR_PREFIX`.
+    pub fn new(opts: &SummaryUploadOpts, env: &EnvironmentVariableMap) -> Option<Self> {
+        let mut headers = Vec::with_capacity(opts.headers.len());
+        for (name, template) in &opts.headers {
+            match expand_template(template, env) {
+                Ok(value) => headers.push((name.clone(), value)),
+                Err(TemplateError::Missing(var)) => {
+                    warn!(
+                        "Not uploading run summary: header '{name}' uses ${{{var}}}, which isn't \
+                         set."
+                    );
+                    return None;
+                }
+                Err(TemplateError::NotAllowed(var)) => {
+                    warn!(
+                        "Not uploading run summary: header '{name}' uses ${{{var}}}, but only \
+                         variables starting with {TEMPLATE_VAR_PREFIX} can be used."
+                    );
+                    return None;
+                }
+            }
+        }
+        Some(Self {
+            url: opts.url.clone(),
+            headers,
+        })
+    }
+
+    pub async fn upload(&self, summary: String) -> Result<(), reqwest::Error> {
+        let mut request = reqwest::Client::new()
+            .post(&self.url)
+            .timeout(UPLOAD_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(summary);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+/// The prefix of variables that header templates can use. The URL and headers
+/// can come from a committed `turbo.json`, so expanding any variable would let
+/// a change to it send the CI environment's secrets anywhere.
+const TEMPLATE_VAR_PREFIX: &str = "TURBO_RUN_SUMMARY_";
+
+#[derive(Debug, PartialEq)]
+enum TemplateError {
+    Missing(String),
+    NotAllowed(String),
+}
+
+// Replaces each `${VAR}` with the value of `VAR`, failing on the first variable
+// that isn't set or can't be used
+fn expand_template(template: &str, env: &EnvironmentVariableMap) -> Result<String, TemplateError> {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    let variable = VARIABLE
+        .get_or_init(|| Regex::new(r"\$\{([A-Za-z_][A-Za-z0-9_]*)\}").expect("regex is valid"));
+
+    let mut error = None;
+    let value = variable.replace_all(template, |captures: &Captures| {
+        let name = &captures[1];
+        if !name.starts_with(TEMPLATE_VAR_PREFIX) {
+            error.get_or_insert_with(|| TemplateError::NotAllowed(name.to_string()));
+            return String::new();
+        }
+        match env.get(name) {
+            Some(value) => value.clone(),
+            None => {
+                error.get_or_insert_with(|| TemplateError::Missing(name.to_string()));
+                String::new()
+            }
+        }
+    });
+    match error {
+        Some(error) => Err(error),
+        None => Ok(value.into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn env(vars: &[(&str, &str)]) -> EnvironmentVariableMap {
+        EnvironmentVariableMap::from(
+            vars.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<HashMap<_, _>>(),
+        )
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let env = env(&[
+            ("TURBO_RUN_SUMMARY_TOKEN", "secret"),
+            ("TURBO_RUN_SUMMARY_TEAM", "web"),
+            ("AWS_SECRET_ACCESS_KEY", "aws-secret"),
+        ]);
+        assert_eq!(
+            expand_template("Bearer ${TURBO_RUN_SUMMARY_TOKEN}", &env).as_deref(),
+            Ok("Bearer secret")
+        );
+        assert_eq!(
+            expand_template("${TURBO_RUN_SUMMARY_TEAM}/${TURBO_RUN_SUMMARY_TOKEN}", &env)
+                .as_deref(),
+            Ok("web/secret")
+        );
+        assert_eq!(
+            expand_template("$TOKEN {TOKEN}", &env).as_deref(),
+            Ok("$TOKEN {TOKEN}")
+        );
+        assert_eq!(
+            expand_template("Bearer ${TURBO_RUN_SUMMARY_MISSING}", &env),
+            Err(TemplateError::Missing(
+                "TURBO_RUN_SUMMARY_MISSING".to_string()
+            ))
+        );
+        assert_eq!(
+            expand_template("${AWS_SECRET_ACCESS_KEY}", &env),
+            Err(TemplateError::NotAllowed(
+                "AWS_SECRET_ACCESS_KEY".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_missing_variable() {
+        let opts = SummaryUploadOpts {
+            url: "https://example.com/runs".to_string(),
+            headers: vec![(
+                "Authorization".to_string(),
+                "Bearer ${TURBO_RUN_SUMMARY_TOKEN}".to_string(),
+            )],
+        };
+        assert!(SummaryUploader::new(&opts, &env(&[])).is_none());
+
+        let uploader =
+            SummaryUploader::new(&opts, &env(&[("TURBO_RUN_SUMMARY_TOKEN", "secret")])).unwrap();
+        assert_eq!(
+            uploader.headers,
+            [("Authorization".to_string(), "Bearer secret".to_string())]
+        );
+        assert!(!format!("{uploader:?}").contains("secret"));
+    }
+
+    #[test]
+    fn test_disallowed_variable() {
+        let opts = SummaryUploadOpts {
+            url: "https://example.com/runs".to_string(),
+            headers: vec![("X-Key".to_string(), "${AWS_SECRET_ACCESS_KEY}".to_string())],
+        };
+        let env = env(&[("AWS_SECRET_ACCESS_KEY", "aws-secret")]);
+        assert!(SummaryUploader::new(&opts, &env).is_none());
+    }
+}
@@ -6,6 +6,7 @@ use globwalk::{GlobError, ValidatedGlob};
 use serde::{Deserialize, Serialize};
 use turbopath::{AnchoredSystemPath, AnchoredSystemPathBuf, RelativeUnixPathBuf};
 use turborepo_errors::Spanned;
+use turborepo_scm::package_deps::INPUT_INCLUDE_DEFAULT_FILES;
 pub use visitor::{Error as VisitorError, Visitor};
 
 use crate::{
@@ -45,11 +46,58 @@ impl TaskOutputs {
     }
 }
 
+// CachePolicy controls how a task interacts with the cache. In `turbo.json`
+// it is written as `true`, `false`, `"read-only"` or `"write-only"`.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Eq)]
+pub enum CachePolicy {
+    #[default]
+    ReadWrite,
+    // Restore outputs from the cache, but never save new artifacts
+    ReadOnly,
+    // Always execute the task, but save its outputs to the cache
+    WriteOnly,
+    Disabled,
+}
+
+impl CachePolicy {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Disabled)
+    }
+
+    pub fn reads_enabled(&self) -> bool {
+        matches!(self, Self::ReadWrite | Self::ReadOnly)
+    }
+
+    pub fn writes_enabled(&self) -> bool {
+        matches!(self, Self::ReadWrite | Self::WriteOnly)
+    }
+}
+
+impl From<bool> for CachePolicy {
+    fn from(value: bool) -> Self {
+        match value {
+            true => Self::ReadWrite,
+            false => Self::Disabled,
+        }
+    }
+}
+
+impl Serialize for CachePolicy {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::ReadWrite => serializer.serialize_bool(true),
+            Self::Disabled => serializer.serialize_bool(false),
+            Self::ReadOnly => serializer.serialize_str("read-only"),
+            Self::WriteOnly => serializer.serialize_str("write-only"),
+        }
+    }
+}
+
 // Constructed from a RawTaskDefinition
 #[derive(Debug, PartialEq, Clone, Eq)]
 pub struct TaskDefinition {
     pub outputs: TaskOutputs,
-    pub(crate) cache: bool,
+    pub(crate) cache: CachePolicy,
 
     // This field is custom-marshalled from `env` and `depends_on``
     pub(crate) env: Vec<String>,
@@ -72,6 +120,15 @@ pub struct TaskDefinition {
     // we can conclude that any cached outputs or logs for this Task should be invalidated.
     pub(crate) inputs: Vec<String>,
 
+    // InputsFrom are tasks in the same package that this task depends on and
+    // whose outputs are added to its inputs.
+    // E.g. "codegen" feeding "build" in:
+    // inputsFrom: ['codegen']
+    pub(crate) inputs_from: Vec<Spanned<TaskName<'static>>>,
+
+    // Whether files with a path segment starting with `.` are hashed for the task
+    pub(crate) dotfiles: bool,
+
     // OutputMode determines how we should log the output.
     pub(crate) output_logs: OutputLogsMode,
 
@@ -83,29 +140,62 @@ pub struct TaskDefinition {
     // by watch mode
     pub interruptible: bool,
 
+    // Skippable tasks don't run again when their hash matches their last
+    // successful run and their outputs are untouched, even if they aren't cached.
+    // The daemon keeps track of the last run.
+    pub(crate) skippable: bool,
+
     // Interactive marks that a task can have its stdin written to.
     // Tasks that take stdin input cannot be cached as their outputs may depend on the
     // input.
     pub interactive: bool,
 
+    // Matrix lists the variants a task runs for. A task with a matrix doesn't run
+    // its own script, instead it depends on a `<task>:<value>` task for each value.
+    pub(crate) matrix: Vec<String>,
+
+    // MatrixVariant is set on the tasks that a matrix expands into
+    pub(crate) matrix_variant: Option<MatrixVariant>,
+
     // Override for global env mode setting
     pub env_mode: Option<EnvMode>,
 }
 
+// One variant of a task with a matrix
+#[derive(Debug, PartialEq, Clone, Eq)]
+pub struct MatrixVariant {
+    // The task the matrix is defined on, whose script the variant runs
+    pub(crate) task: String,
+    pub(crate) value: String,
+}
+
+// Environment variable that holds the matrix value of a variant task. It can
+// also be used in `inputs` and `outputs` to give each variant its own files.
+pub const MATRIX_ENV_VAR: &str = "TURBO_MATRIX";
+
+// Inputs that start with this token are relative to the repository root instead
+// of the package, e.g. `$TURBO_ROOT$/schema/**`
+pub const TURBO_ROOT_TOKEN: &str = "$TURBO_ROOT$";
+
 impl Default for TaskDefinition {
     fn default() -> Self {
         Self {
-            cache: true,
+            cache: CachePolicy::ReadWrite,
             outputs: Default::default(),
             env: Default::default(),
             pass_through_env: Default::default(),
             topological_dependencies: Default::default(),
             task_dependencies: Default::default(),
             inputs: Default::default(),
+            inputs_from: Default::default(),
+            dotfiles: true,
             output_logs: Default::default(),
             persistent: Default::default(),
             interruptible: Default::default(),
+            skippable: Default::default(),
             interactive: Default::default(),
+            matrix: Default::default(),
+            matrix_variant: Default::default(),
             env_mode: Default::default(),
         }
     }
@@ -124,6 +214,85 @@ impl FromIterator<RawTaskDefinition> for RawTaskDefinition {
 const LOG_DIR: &str = ".turbo";
 
 impl TaskDefinition {
+    /// The `package.json` script that runs for this task. Tasks with a matrix
+    /// don't run a script, and their variants run the script of the task the
+    /// matrix is defined on.
+    pub fn script<'a>(&'a self, task_id: &'a TaskId) -> Option<&'a str> {
+        if !self.matrix.is_empty() {
+            return None;
+        }
+        Some(
+            self.matrix_variant
+                .as_ref()
+                .map_or(task_id.task(), |variant| variant.task.as_str()),
+        )
+    }
+
+    /// The definition of the `value` variant of a task with a matrix
+    pub(crate) fn for_matrix_variant(&self, task: &str, value: &str) -> TaskDefinition {
+        let placeholder = format!("${MATRIX_ENV_VAR}");
+        let substitute = |globs: &[String]| -> Vec<String> {
+            globs
+                .iter()
+                .map(|glob| glob.replace(&placeholder, value))
+                .collect()
+        };
+        TaskDefinition {
+            outputs: TaskOutputs {
+                inclusions: substitute(&self.outputs.inclusions),
+                exclusions: substitute(&self.outputs.exclusions),
+            },
+            inputs: substitute(&self.inputs),
+            matrix: Vec::new(),
+            matrix_variant: Some(MatrixVariant {
+                task: task.to_owned(),
+                value: value.to_owned(),
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// Adds the outputs of a task this task takes inputs from to its inputs.
+    /// A task without inputs hashes its default files, so those stay included.
+    ///
+    /// Files are hashed before any task runs, so when the other task runs
+    /// first its outputs could be stale or missing. They're excluded instead,
+    /// and the other task's hash, which is part of this task's hash as a
+    /// dependency, accounts for them.
+    pub(crate) fn add_inputs_from(&mut self, task_definition: &TaskDefinition, runs_first: bool) {
+        if self.inputs.is_empty() {
+            self.inputs.push(INPUT_INCLUDE_DEFAULT_FILES.to_string());
+        }
+        let inclusions = task_definition.outputs.inclusions.iter();
+        if runs_first {
+            self.inputs
+                .extend(inclusions.map(|glob| format!("!{glob}")));
+        } else {
+            self.inputs.extend(inclusions.cloned());
+        }
+    }
+
+    /// Rewrites inputs that start with `$TURBO_ROOT$` to be relative to the
+    /// package at `package_path`, so they can be hashed like any other input
+    pub(crate) fn resolve_turbo_root(&mut self, package_path: &AnchoredSystemPath) {
+        let depth = package_path
+            .to_unix()
+            .as_str()
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .count();
+        let to_root = "../".repeat(depth);
+        for input in &mut self.inputs {
+            let (negation, glob) = match input.strip_prefix('!') {
+                Some(glob) => ("!", glob),
+                None => ("", input.as_str()),
+            };
+            if let Some(glob) = glob.strip_prefix(TURBO_ROOT_TOKEN) {
+                *input = format!("{negation}{to_root}{}", glob.trim_start_matches('/'));
+            }
+        }
+    }
+
     pub fn workspace_relative_log_file(task_name: &str) -> AnchoredSystemPathBuf {
         let log_dir = AnchoredSystemPath::new(LOG_DIR)
             .expect("LOG_DIR should be a valid AnchoredSystemPathBuf");
@@ -251,4 +420,38 @@ mod test {
         .unwrap();
         assert_eq!(build_log, build_expected);
     }
+
+    #[test]
+    fn test_resolve_turbo_root() {
+        let mut task_defn = TaskDefinition {
+            inputs: vec![
+                "$TURBO_DEFAULT$".to_string(),
+                "$TURBO_ROOT$/schema/**".to_string(),
+                "!$TURBO_ROOT$/schema/drafts/**".to_string(),
+            ],
+            ..Default::default()
+        };
+        let mut root_defn = task_defn.clone();
+
+        let workspace_dir = AnchoredSystemPath::new(match cfg!(windows) {
+            true => "apps\\foo",
+            false => "apps/foo",
+        })
+        .unwrap();
+        task_defn.resolve_turbo_root(workspace_dir);
+        assert_eq!(
+            task_defn.inputs,
+            vec![
+                "$TURBO_DEFAULT$",
+                "../../schema/**",
+                "!../../schema/drafts/**"
+            ]
+        );
+
+        root_defn.resolve_turbo_root(AnchoredSystemPath::empty());
+        assert_eq!(
+            root_defn.inputs,
+            vec!["$TURBO_DEFAULT$", "schema/**", "!schema/drafts/**"]
+        );
+    }
 }
@@ -33,6 +33,7 @@ impl<'a> CommandFactory<'a> {
     pub fn command(
         &self,
         task_id: &TaskId,
+        script: &str,
         environment: EnvironmentVariableMap,
     ) -> Result<Option<Command>, Error> {
         let workspace_info = self
@@ -47,14 +48,14 @@ impl<'a> CommandFactory<'a> {
         if workspace_info
             .package_json
             .scripts
-            .get(task_id.task())
+            .get(script)
             .map_or(true, |script| script.is_empty())
         {
             return Ok(None);
         }
         let package_manager_binary = self.package_manager_binary.as_deref().map_err(|e| *e)?;
         let mut cmd = Command::new(package_manager_binary);
-        let mut args = vec!["run".to_string(), task_id.task().to_string()];
+        let mut args = vec!["run".to_string(), script.to_string()];
         if let Some(pass_through_args) = self.task_args.args_for_task(task_id) {
             args.extend(
                 self.package_graph
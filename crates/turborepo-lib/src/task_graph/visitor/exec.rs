@@ -4,9 +4,10 @@ use std::{
     time::{Duration, Instant},
 };
 
+use chrono::Local;
 use console::StyledObject;
 use tokio::sync::oneshot;
-use tracing::{error, Instrument};
+use tracing::{error, warn, Instrument};
 use turborepo_env::{platform::PlatformEnv, EnvironmentVariableMap};
 use turborepo_repository::package_manager::PackageManager;
 use turborepo_telemetry::events::{task::PackageTaskEventBuilder, TrackedErrors};
@@ -19,11 +20,15 @@ use super::{
     TaskOutput, Visitor,
 };
 use crate::{
+    cli::LogFormat,
     config::UIMode,
     engine::{Engine, StopExecution},
     process::{ChildExit, Command, ProcessManager},
     run::{
-        summary::{SpacesTaskClient, SpacesTaskInformation, TaskExecutionSummary, TaskTracker},
+        summary::{
+            CacheTransfer, CacheTransferKind, SpacesTaskClient, SpacesTaskInformation,
+            TaskExecutionSummary, TaskTracker,
+        },
         task_access::TaskAccess,
         task_id::TaskId,
         CacheOutput, TaskCache,
@@ -64,6 +69,7 @@ impl<'a> ExecContextFactory<'a> {
     pub fn exec_context(
         &self,
         task_id: TaskId<'static>,
+        script: Option<String>,
         task_hash: String,
         task_cache: TaskCache,
         mut execution_env: EnvironmentVariableMap,
@@ -73,15 +79,19 @@ impl<'a> ExecContextFactory<'a> {
         let task_id_for_display = self.visitor.display_task_id(&task_id);
         let task_id_string = &task_id.to_string();
         self.populate_env(&mut execution_env, &task_hash, &task_access);
+        let Some(script) = script else {
+            return Ok(None);
+        };
         let Some(cmd) = self
             .command_factory
-            .command(&task_id, execution_env.clone())?
+            .command(&task_id, &script, execution_env.clone())?
         else {
             return Ok(None);
         };
         Ok(Some(ExecContext {
             engine: self.engine.clone(),
             ui_mode: self.visitor.run_opts.ui_mode,
+            log_format: self.visitor.run_opts.log_format,
             color_config: self.visitor.color_config,
             is_github_actions: self.visitor.run_opts.is_github_actions,
             pretty_prefix: self
@@ -147,6 +157,7 @@ pub struct ExecContext {
     engine: Arc<Engine>,
     color_config: ColorConfig,
     ui_mode: UIMode,
+    log_format: LogFormat,
     is_github_actions: bool,
     pretty_prefix: StyledObject<String>,
     task_id: TaskId<'static>,
@@ -202,7 +213,7 @@ impl ExecContext {
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<(), InternalError> {
         let tracker = tracker.start().await;
-        let span = tracing::debug_span!("execute_task", task = %self.task_id.task());
+        let span = tracing::debug_span!("execute_task", task = %self.task_id);
         span.follows_from(parent_span_id);
         let mut result = self
             .execute_inner(&output_client, telemetry)
@@ -327,12 +338,21 @@ impl ExecContext {
             }
         }
 
+        let restore_started_at = Local::now();
         match self
             .task_cache
             .restore_outputs(&mut prefixed_ui, telemetry)
             .await
         {
             Ok(Some(status)) => {
+                self.hash_tracker.insert_cache_transfer(
+                    self.task_id.clone(),
+                    CacheTransfer::new(
+                        CacheTransferKind::Restore,
+                        restore_started_at,
+                        Local::now(),
+                    ),
+                );
                 // we need to set expanded outputs
                 self.hash_tracker.insert_expanded_outputs(
                     self.task_id.clone(),
@@ -388,14 +408,21 @@ impl ExecContext {
             process.stdin();
         }
 
-        let mut stdout_writer = self
+        let (task_stdout, task_stderr) = prefixed_ui.task_writers();
+        let (mut stdout_writer, mut stderr_writer) = self
             .task_cache
-            .output_writer(prefixed_ui.task_writer())
+            .split_output_writers(task_stdout, task_stderr)
             .inspect_err(|_| {
                 telemetry.track_error(TrackedErrors::FailedToCaptureOutputs);
             })?;
 
-        let exit_status = match process.wait_with_piped_outputs(&mut stdout_writer).await {
+        // Structured logs record which stream each line was written to, otherwise
+        // stderr is interleaved with stdout
+        let stderr_pipe = (self.log_format == LogFormat::Ndjson).then_some(&mut stderr_writer);
+        let exit_status = match process
+            .wait_with_split_piped_outputs(&mut stdout_writer, stderr_pipe)
+            .await
+        {
             Ok(Some(exit_status)) => exit_status,
             Err(e) => {
                 telemetry.track_error(TrackedErrors::FailedToPipeOutputs);
@@ -414,18 +441,29 @@ impl ExecContext {
 
         match exit_status {
             ChildExit::Finished(Some(0)) => {
-                // Attempt to flush stdout_writer and log any errors encountered
-                if let Err(e) = stdout_writer.flush() {
+                // Attempt to flush the writers and log any errors encountered
+                if let Err(e) = stdout_writer.flush().and_then(|()| stderr_writer.flush()) {
                     error!("{e}");
                 } else if self
                     .task_access
                     .can_cache(&self.task_hash, &self.task_id_for_display)
                     .unwrap_or(true)
                 {
+                    let save_started_at = Local::now();
                     if let Err(e) = self.task_cache.save_outputs(task_duration, telemetry).await {
                         error!("error caching output: {e}");
                         return Err(e.into());
                     } else {
+                        if !self.task_cache.is_writing_disabled() {
+                            self.hash_tracker.insert_cache_transfer(
+                                self.task_id.clone(),
+                                CacheTransfer::new(
+                                    CacheTransferKind::Save,
+                                    save_started_at,
+                                    Local::now(),
+                                ),
+                            );
+                        }
                         // If no errors, update hash tracker with expanded outputs
                         self.hash_tracker.insert_expanded_outputs(
                             self.task_id.clone(),
@@ -439,7 +477,7 @@ impl ExecContext {
             }
             ChildExit::Finished(Some(code)) => {
                 // If there was an error, flush the buffered output
-                if let Err(e) = stdout_writer.flush() {
+                if let Err(e) = stdout_writer.flush().and_then(|()| stderr_writer.flush()) {
                     error!("error flushing logs: {e}");
                 }
                 if let Err(e) = self.task_cache.on_error(&mut prefixed_ui) {
@@ -521,4 +559,17 @@ impl DryRunExecContext {
         tracker.dry_run().await;
         Ok(())
     }
+
+    pub async fn execute_prefetch(&self, tracker: TaskTracker<()>) -> Result<(), InternalError> {
+        match self.task_cache.prefetch().await {
+            Ok(Some(status)) => self
+                .hash_tracker
+                .insert_cache_status(self.task_id.clone(), status),
+            Ok(None) => (),
+            // A failed download only means the task will miss the cache later
+            Err(err) => warn!("failed to prefetch {}: {err}", self.task_id),
+        }
+        tracker.dry_run().await;
+        Ok(())
+    }
 }
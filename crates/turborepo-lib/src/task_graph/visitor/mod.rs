@@ -21,6 +21,7 @@ use regex::Regex;
 use tokio::sync::mpsc;
 use tracing::{debug, error, warn, Span};
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPath};
+use turborepo_cache::CacheSource;
 use turborepo_ci::{Vendor, VendorBehavior};
 use turborepo_env::{platform::PlatformEnv, EnvironmentVariableMap};
 use turborepo_repository::package_graph::{PackageGraph, PackageName, ROOT_PKG_NAME};
@@ -28,12 +29,13 @@ use turborepo_telemetry::events::{
     generic::GenericEventBuilder, task::PackageTaskEventBuilder, EventBuilder, TrackedErrors,
 };
 use turborepo_ui::{
-    sender::UISender, ColorConfig, ColorSelector, OutputClient, OutputSink, PrefixedUI,
+    color, cprintln, sender::UISender, ColorConfig, ColorSelector, LogSource, OutputClient,
+    OutputSink, PrefixedUI, BOLD, BOLD_GREEN,
 };
 
 use crate::{
-    cli::EnvMode,
-    engine::{Engine, ExecutionOptions},
+    cli::{EnvMode, LogFormat},
+    engine::{Engine, ExecutionOptions, TaskNode},
     opts::RunOpts,
     process::ProcessManager,
     run::{
@@ -43,6 +45,7 @@ use crate::{
         task_id::TaskId,
         RunCache,
     },
+    task_graph::MATRIX_ENV_VAR,
     task_hash::{self, PackageInputsHashes, TaskHashTrackerState, TaskHasher},
 };
 
@@ -50,6 +53,7 @@ use crate::{
 pub struct Visitor<'a> {
     color_cache: ColorSelector,
     dry: bool,
+    prefetch: bool,
     global_env: EnvironmentVariableMap,
     global_env_mode: EnvMode,
     manager: ProcessManager,
@@ -140,6 +144,7 @@ impl<'a> Visitor<'a> {
         Self {
             color_cache,
             dry: false,
+            prefetch: false,
             global_env_mode: run_opts.env_mode,
             manager,
             run_opts,
@@ -197,7 +202,11 @@ impl<'a> Visitor<'a> {
 
             let package_task_event =
                 PackageTaskEventBuilder::new(info.package(), info.task()).with_parent(telemetry);
-            let command = workspace_info.package_json.scripts.get(info.task());
+            let task_definition = engine
+                .task_definition(&info)
+                .ok_or(Error::MissingDefinition)?;
+            let script = task_definition.script(&info);
+            let command = script.and_then(|script| workspace_info.package_json.scripts.get(script));
 
             match command {
                 Some(cmd) if info.package() == ROOT_PKG_NAME && turbo_regex().is_match(cmd) => {
@@ -213,10 +222,6 @@ impl<'a> Visitor<'a> {
                 _ => (),
             }
 
-            let task_definition = engine
-                .task_definition(&info)
-                .ok_or(Error::MissingDefinition)?;
-
             let task_env_mode = task_definition.env_mode.unwrap_or(self.global_env_mode);
             package_task_event.track_env_mode(&task_env_mode.to_string());
 
@@ -236,9 +241,12 @@ impl<'a> Visitor<'a> {
             // We do this calculation earlier than we do in Go due to the `task_hasher`
             // being !Send. In the future we can look at doing this right before
             // task execution instead.
-            let execution_env =
+            let mut execution_env =
                 self.task_hasher
                     .env(&info, task_env_mode, task_definition, &self.global_env)?;
+            if let Some(variant) = &task_definition.matrix_variant {
+                execution_env.insert(MATRIX_ENV_VAR.to_owned(), variant.value.clone());
+            }
 
             let task_cache = self.run_cache.task_cache(
                 task_definition,
@@ -256,14 +264,20 @@ impl<'a> Visitor<'a> {
                     let dry_run_exec_context =
                         factory.dry_run_exec_context(info.clone(), task_cache);
                     let tracker = self.run_tracker.track_task(info.into_owned());
+                    let prefetch = self.prefetch;
                     tasks.push(tokio::spawn(async move {
-                        dry_run_exec_context.execute_dry_run(tracker).await
+                        if prefetch {
+                            dry_run_exec_context.execute_prefetch(tracker).await
+                        } else {
+                            dry_run_exec_context.execute_dry_run(tracker).await
+                        }
                     }));
                 }
                 false => {
                     let takes_input = task_definition.interactive || task_definition.persistent;
                     let Some(mut exec_context) = factory.exec_context(
                         info.clone(),
+                        script.map(str::to_owned),
                         task_hash,
                         task_cache,
                         execution_env,
@@ -440,6 +454,14 @@ impl<'a> Visitor<'a> {
         };
 
         let mut logger = self.sink.logger(behavior);
+        if self.run_opts.log_format == LogFormat::Ndjson {
+            // CI log groups would be the only lines that aren't records
+            logger.with_ndjson(LogSource {
+                package: task_id.package().to_string(),
+                task: task_id.task().to_string(),
+            });
+            return logger;
+        }
         if let Some(vendor_behavior) = vendor_behavior {
             let group_name = if self.run_opts.single_package {
                 task_id.task().to_string()
@@ -513,11 +535,58 @@ impl<'a> Visitor<'a> {
         self.task_hasher.into_task_hash_tracker_state()
     }
 
+    /// Whether any tasks warned about missing platform environment variables
+    pub fn has_warnings(&self) -> bool {
+        self.warnings
+            .lock()
+            .map_or(false, |warnings| !warnings.is_empty())
+    }
+
     pub fn dry_run(&mut self) {
         self.dry = true;
         // No need to start a UI on dry run
         self.ui_sender = None;
     }
+
+    /// Visits the graph like a dry run, but downloads any remote artifacts
+    /// into the local cache along the way
+    pub fn prefetch(&mut self) {
+        self.dry_run();
+        self.prefetch = true;
+    }
+
+    /// Print where each visited task's artifact ended up
+    pub(crate) fn finish_prefetch(self, engine: &Engine) {
+        let ui = self.color_config;
+        let tracker = self.task_hasher.task_hash_tracker();
+        let (mut total, mut downloaded, mut cached) = (0, 0, 0);
+        for node in engine.tasks() {
+            let TaskNode::Task(task_id) = node else {
+                continue;
+            };
+            total += 1;
+            match tracker.cache_status(task_id).map(|status| status.source) {
+                Some(CacheSource::Remote) => downloaded += 1,
+                Some(CacheSource::Local) => cached += 1,
+                None => (),
+            }
+        }
+
+        println!();
+        cprintln!(
+            ui,
+            BOLD,
+            "Downloaded:    {}, {total} total",
+            color!(ui, BOLD_GREEN, "{downloaded} downloaded")
+        );
+        cprintln!(
+            ui,
+            BOLD,
+            "    Cached:    {}, {total} total",
+            color!(ui, BOLD, "{cached} already cached")
+        );
+        println!();
+    }
 }
 
 fn turbo_regex() -> &'static Regex {
@@ -47,6 +47,8 @@ impl<W: Write> TaskOutput<W> {
     }
 }
 
+type TaskWriter<'a, W> = Either<turborepo_ui::PrefixedWriter<&'a mut W>, TaskSender>;
+
 /// Struct for displaying information about task's cache
 pub enum TaskCacheOutput<W> {
     Direct(PrefixedUI<W>),
@@ -54,10 +56,14 @@ pub enum TaskCacheOutput<W> {
 }
 
 impl<W: Write> TaskCacheOutput<W> {
-    pub fn task_writer(&mut self) -> Either<turborepo_ui::PrefixedWriter<&mut W>, TaskSender> {
+    /// Writers for a task's stdout and stderr
+    pub fn task_writers(&mut self) -> (TaskWriter<W>, TaskWriter<W>) {
         match self {
-            TaskCacheOutput::Direct(prefixed) => Either::Left(prefixed.output_prefixed_writer()),
-            TaskCacheOutput::UI(task) => Either::Right(task.clone()),
+            TaskCacheOutput::Direct(prefixed) => {
+                let (stdout, stderr) = prefixed.output_prefixed_writers();
+                (Either::Left(stdout), Either::Left(stderr))
+            }
+            TaskCacheOutput::UI(task) => (Either::Right(task.clone()), Either::Right(task.clone())),
         }
     }
 
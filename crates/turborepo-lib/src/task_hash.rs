@@ -11,7 +11,7 @@ use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
 use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{BySource, DetailedMap, EnvironmentVariableMap};
 use turborepo_repository::package_graph::{PackageInfo, PackageName};
-use turborepo_scm::SCM;
+use turborepo_scm::{package_deps::remove_dotfiles, SCM};
 use turborepo_telemetry::events::{
     generic::GenericEventBuilder, task::PackageTaskEventBuilder, EventBuilder,
 };
@@ -22,7 +22,7 @@ use crate::{
     framework::infer_framework,
     hash::{FileHashes, LockFilePackages, TaskHashable, TurboHash},
     opts::RunOpts,
-    run::task_id::TaskId,
+    run::{summary::CacheTransfer, task_id::TaskId},
     task_graph::TaskDefinition,
     DaemonClient, DaemonConnector,
 };
@@ -118,8 +118,9 @@ impl PackageInputsHashes {
 
                 let scm_telemetry = package_task_event.child();
                 // Try hashing with the daemon, if we have a connection. If we don't, or if we
-                // timeout or get an error, fallback to local hashing
-                let hash_object = if cfg!(feature = "daemon-file-hashing") {
+                // timeout or get an error, fallback to local hashing. The daemon hashes with
+                // git when it can, so it isn't used when we've been asked not to.
+                let hash_object = if cfg!(feature = "daemon-file-hashing") && !scm.is_manual() {
                     let handle = tokio::runtime::Handle::current();
                     let mut daemon = daemon
                         .as_ref() // Option::ref
@@ -184,7 +185,7 @@ impl PackageInputsHashes {
                     None
                 };
 
-                let hash_object = match hash_object {
+                let mut hash_object = match hash_object {
                     Some(hash_object) => hash_object,
                     None => {
                         let local_hash_result = scm.get_package_file_hashes(
@@ -200,6 +201,12 @@ impl PackageInputsHashes {
                     }
                 };
 
+                if !task_definition.dotfiles {
+                    if let Err(err) = remove_dotfiles(&mut hash_object, &task_definition.inputs) {
+                        return Some(Err(err.into()));
+                    }
+                }
+
                 let file_hashes = FileHashes(hash_object);
                 let hash = file_hashes.clone().hash();
 
@@ -235,6 +242,21 @@ pub struct TaskHashTrackerState {
     package_task_cache: HashMap<TaskId<'static>, CacheHitMetadata>,
     #[serde(skip)]
     package_task_inputs_expanded_hashes: HashMap<TaskId<'static>, FileHashes>,
+    #[serde(skip)]
+    package_task_hash_inputs: HashMap<TaskId<'static>, TaskHashInputs>,
+    #[serde(skip)]
+    package_task_cache_transfers: HashMap<TaskId<'static>, Vec<CacheTransfer>>,
+}
+
+/// The intermediate hashes that were combined to produce a task's hash.
+/// Together with the resolved task definition and environment variables
+/// these fully describe the hash composition.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHashInputs {
+    pub global_hash: String,
+    pub hash_of_files: String,
+    pub dependency_hashes: Vec<String>,
 }
 
 /// Caches package-inputs hashes, and package-task hashes.
@@ -379,6 +401,12 @@ impl<'a> TaskHasher<'a> {
         // We wrap in an Option to mimic Go's serialization of nullable values
         let optional_package_dir = (!is_root_package).then_some(package_dir);
 
+        let hash_inputs = TaskHashInputs {
+            global_hash: self.global_hash.to_string(),
+            hash_of_files: hash_of_files.clone(),
+            dependency_hashes: task_dependency_hashes.clone(),
+        };
+
         let task_hashable = TaskHashable {
             global_hash: self.global_hash,
             task_dependency_hashes,
@@ -405,6 +433,7 @@ impl<'a> TaskHasher<'a> {
             env_vars,
             task_hash.clone(),
             framework_slug,
+            hash_inputs,
         );
 
         Ok(task_hash)
@@ -530,6 +559,18 @@ impl<'a> TaskHasher<'a> {
                 )?;
                 pass_through_env.union(&env_var_pass_through_map);
 
+                // Report which variables were withheld from the task so that hidden
+                // env dependencies can be tracked down with `-vv`.
+                let mut filtered_env = self.env_at_execution_start.clone();
+                filtered_env.difference(&pass_through_env);
+                if !filtered_env.is_empty() {
+                    debug!(
+                        "strict env mode filtered {} variables from {task_id}: {}",
+                        filtered_env.len(),
+                        filtered_env.names().join(", ")
+                    );
+                }
+
                 Ok(pass_through_env)
             }
             EnvMode::Loose => Ok(self.env_at_execution_start.clone()),
@@ -540,8 +581,19 @@ impl<'a> TaskHasher<'a> {
 pub fn get_external_deps_hash(
     transitive_dependencies: &Option<HashSet<turborepo_lockfiles::Package>>,
 ) -> String {
-    let Some(transitive_dependencies) = transitive_dependencies else {
+    if transitive_dependencies.is_none() {
         return "".into();
+    }
+
+    LockFilePackages(get_external_deps(transitive_dependencies)).hash()
+}
+
+/// Returns the lockfile entries of a package in the order they are hashed
+pub fn get_external_deps(
+    transitive_dependencies: &Option<HashSet<turborepo_lockfiles::Package>>,
+) -> Vec<turborepo_lockfiles::Package> {
+    let Some(transitive_dependencies) = transitive_dependencies else {
+        return Vec::new();
     };
 
     let mut transitive_deps = Vec::with_capacity(transitive_dependencies.len());
@@ -555,7 +607,7 @@ pub fn get_external_deps_hash(
         other => other,
     });
 
-    LockFilePackages(transitive_deps).hash()
+    transitive_deps
 }
 
 pub fn get_internal_deps_hash(
@@ -604,11 +656,15 @@ impl TaskHashTracker {
         env_vars: DetailedMap,
         hash: String,
         framework_slug: Option<String>,
+        hash_inputs: TaskHashInputs,
     ) {
         let mut state = self.state.lock().expect("hash tracker mutex poisoned");
         state
             .package_task_env_vars
             .insert(task_id.clone(), env_vars);
+        state
+            .package_task_hash_inputs
+            .insert(task_id.clone(), hash_inputs);
         if let Some(framework) = framework_slug {
             state
                 .package_task_framework
@@ -622,6 +678,11 @@ impl TaskHashTracker {
         state.package_task_env_vars.get(task_id).cloned()
     }
 
+    pub fn hash_inputs(&self, task_id: &TaskId) -> Option<TaskHashInputs> {
+        let state = self.state.lock().expect("hash tracker mutex poisoned");
+        state.package_task_hash_inputs.get(task_id).cloned()
+    }
+
     pub fn framework(&self, task_id: &TaskId) -> Option<String> {
         let state = self.state.lock().expect("hash tracker mutex poisoned");
         state.package_task_framework.get(task_id).cloned()
@@ -651,6 +712,24 @@ impl TaskHashTracker {
         state.package_task_cache.insert(task_id, cache_status);
     }
 
+    pub fn cache_transfers(&self, task_id: &TaskId) -> Vec<CacheTransfer> {
+        let state = self.state.lock().expect("hash tracker mutex poisoned");
+        state
+            .package_task_cache_transfers
+            .get(task_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    pub fn insert_cache_transfer(&self, task_id: TaskId<'static>, transfer: CacheTransfer) {
+        let mut state = self.state.lock().expect("hash tracker mutex poisoned");
+        state
+            .package_task_cache_transfers
+            .entry(task_id)
+            .or_default()
+            .push(transfer);
+    }
+
     pub fn get_expanded_inputs(&self, task_id: &TaskId) -> Option<FileHashes> {
         let state = self.state.lock().expect("hash tracker mutex poisoned");
         state
@@ -662,6 +741,8 @@ impl TaskHashTracker {
 
 #[cfg(test)]
 mod test {
+    use turbopath::AbsoluteSystemPathBuf;
+
     use super::*;
 
     #[test]
@@ -673,4 +754,163 @@ mod test {
         assert_send::<TaskHashTracker>();
         assert_sync::<TaskHashTracker>();
     }
+
+    #[test]
+    fn test_external_deps_are_sorted_and_hashed() {
+        let package = |key: &str, version: &str| turborepo_lockfiles::Package {
+            key: key.into(),
+            version: version.into(),
+        };
+        let transitive_dependencies = Some(HashSet::from([
+            package("node_modules/b", "1.0.0"),
+            package("node_modules/a", "2.0.0"),
+            package("node_modules/a", "1.0.0"),
+        ]));
+
+        let external_deps = get_external_deps(&transitive_dependencies);
+        assert_eq!(
+            external_deps,
+            vec![
+                package("node_modules/a", "1.0.0"),
+                package("node_modules/a", "2.0.0"),
+                package("node_modules/b", "1.0.0"),
+            ]
+        );
+        assert_eq!(
+            get_external_deps_hash(&transitive_dependencies),
+            LockFilePackages(external_deps).hash()
+        );
+        assert!(get_external_deps(&None).is_empty());
+    }
+
+    fn test_run_opts() -> RunOpts {
+        RunOpts {
+            tasks: vec!["build".to_string()],
+            concurrency: 10,
+            parallel: false,
+            env_mode: EnvMode::Strict,
+            cache_dir: camino::Utf8PathBuf::new(),
+            framework_inference: false,
+            profile: None,
+            continue_on_error: false,
+            pass_through_args: Vec::new(),
+            only: false,
+            dry_run: None,
+            graph: None,
+            ui_mode: crate::turbo_json::UIMode::Stream,
+            single_package: false,
+            log_prefix: crate::opts::ResolvedLogPrefix::Task,
+            log_order: crate::opts::ResolvedLogOrder::Stream,
+            log_format: crate::cli::LogFormat::Text,
+            summarize: false,
+            output_format: Vec::new(),
+            experimental_space_id: None,
+            summary_upload: None,
+            exit_codes: Default::default(),
+            is_github_actions: false,
+            daemon: None,
+        }
+    }
+
+    #[test]
+    fn test_inputs_from_hashes_producer() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(tmp.path()).unwrap();
+        let package_dir = repo_root.join_component("app");
+        for (file, contents) in [
+            ("package.json", r#"{"name": "app"}"#),
+            ("schema.graphql", "type Query { a: Int }"),
+            ("src/index.ts", "import './generated/types';"),
+            (
+                "src/generated/types.ts",
+                "export type Query = { a: number };",
+            ),
+        ] {
+            let path = package_dir.join_unix_path(turbopath::RelativeUnixPath::new(file).unwrap());
+            path.ensure_dir().unwrap();
+            path.create_with_contents(contents).unwrap();
+        }
+
+        let codegen = TaskId::new("app", "codegen");
+        let build = TaskId::new("app", "build");
+        let codegen_definition = TaskDefinition {
+            inputs: vec!["schema.graphql".to_string()],
+            outputs: crate::task_graph::TaskOutputs {
+                inclusions: vec!["src/generated/**".to_string()],
+                exclusions: Vec::new(),
+            },
+            ..Default::default()
+        };
+        let mut build_definition = TaskDefinition::default();
+        build_definition.add_inputs_from(&codegen_definition, true);
+        let task_definitions = HashMap::from([
+            (codegen.clone(), codegen_definition),
+            (build.clone(), build_definition),
+        ]);
+
+        let package_name = PackageName::from("app");
+        let package_info = PackageInfo {
+            package_json_path: AnchoredSystemPathBuf::from_raw("app/package.json").unwrap(),
+            ..Default::default()
+        };
+        let run_opts = test_run_opts();
+        let env = EnvironmentVariableMap::default();
+
+        let hash_tasks = || {
+            let tasks = [
+                TaskNode::Task(codegen.clone()),
+                TaskNode::Task(build.clone()),
+            ];
+            let package_inputs_hashes = PackageInputsHashes::calculate_file_hashes(
+                &SCM::new(&repo_root),
+                tasks.par_iter(),
+                HashMap::from([(&package_name, &package_info)]),
+                &task_definitions,
+                &repo_root,
+                &GenericEventBuilder::default(),
+                &None,
+            )
+            .unwrap();
+            let hasher = TaskHasher::new(package_inputs_hashes, &run_opts, &env, "global");
+            // Dependencies are hashed first, the way the visitor does
+            hasher
+                .calculate_task_hash(
+                    &codegen,
+                    &task_definitions[&codegen],
+                    EnvMode::Strict,
+                    &package_info,
+                    HashSet::new(),
+                    PackageTaskEventBuilder::new("app", "codegen"),
+                )
+                .unwrap();
+            hasher
+                .calculate_task_hash(
+                    &build,
+                    &task_definitions[&build],
+                    EnvMode::Strict,
+                    &package_info,
+                    HashSet::from([&tasks[0]]),
+                    PackageTaskEventBuilder::new("app", "build"),
+                )
+                .unwrap()
+        };
+        let write = |file: &str, contents: &str| {
+            package_dir
+                .join_unix_path(turbopath::RelativeUnixPath::new(file).unwrap())
+                .create_with_contents(contents)
+                .unwrap()
+        };
+
+        let initial = hash_tasks();
+
+        // Outputs left behind by an earlier run are hashed before codegen
+        // replaces them, so they can't affect the hash
+        write("src/generated/types.ts", "export type Stale = {};");
+        assert_eq!(hash_tasks(), initial);
+
+        // Changing what codegen outputs changes codegen's hash, so build
+        // misses the cache
+        write("schema.graphql", "type Query { a: Int, b: Int }");
+        assert_ne!(hash_tasks(), initial);
+    }
 }
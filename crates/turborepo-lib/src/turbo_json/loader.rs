@@ -1,18 +1,19 @@
 use std::collections::HashMap;
 
 use tracing::debug;
-use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPath};
 use turborepo_errors::Spanned;
 use turborepo_repository::{
     package_graph::{PackageInfo, PackageName},
     package_json::PackageJson,
 };
 
-use super::{Pipeline, RawTaskDefinition, TurboJson, CONFIG_FILE};
+use super::{Pipeline, RawTaskDefinition, TurboJson, CONFIG_FILE, PRESET_FILE};
 use crate::{
     cli::EnvMode,
     config::Error,
     run::{task_access::TASK_ACCESS_CONFIG_PATH, task_id::TaskName},
+    task_graph::CachePolicy,
 };
 
 /// Structure for loading TurboJson structures.
@@ -23,6 +24,8 @@ pub struct TurboJsonLoader {
     repo_root: AbsoluteSystemPathBuf,
     cache: HashMap<PackageName, TurboJson>,
     strategy: Strategy,
+    // Overlay to merge over the root turbo.json
+    overlay: Option<AbsoluteSystemPathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -58,6 +61,7 @@ impl TurboJsonLoader {
             repo_root,
             cache: HashMap::new(),
             strategy: Strategy::Workspace { packages },
+            overlay: None,
         }
     }
 
@@ -72,6 +76,7 @@ impl TurboJsonLoader {
             repo_root,
             cache: HashMap::new(),
             strategy: Strategy::WorkspaceNoTurboJson { packages },
+            overlay: None,
         }
     }
 
@@ -89,6 +94,7 @@ impl TurboJsonLoader {
                 root_turbo_json,
                 package_json,
             },
+            overlay: None,
         }
     }
 
@@ -106,6 +112,7 @@ impl TurboJsonLoader {
                 root_turbo_json,
                 package_json,
             },
+            overlay: None,
         }
     }
 
@@ -120,9 +127,16 @@ impl TurboJsonLoader {
                 .to_owned(),
             cache: turbo_jsons,
             strategy: Strategy::Noop,
+            overlay: None,
         }
     }
 
+    /// Merge the overlay at the given path over the root turbo.json
+    pub fn with_overlay(mut self, overlay: Option<AbsoluteSystemPathBuf>) -> Self {
+        self.overlay = overlay;
+        self
+    }
+
     /// Load a turbo.json for a given package
     pub fn load<'a>(&'a mut self, package: &PackageName) -> Result<&'a TurboJson, Error> {
         if !self.cache.contains_key(package) {
@@ -144,12 +158,24 @@ impl TurboJsonLoader {
                 if !matches!(package, PackageName::Root) {
                     Err(Error::InvalidTurboJsonLoad(package.clone()))
                 } else {
-                    load_from_root_package_json(&self.repo_root, root_turbo_json, package_json)
+                    let turbo_json = load_from_root_package_json(
+                        &self.repo_root,
+                        root_turbo_json,
+                        package_json,
+                    )?;
+                    self.apply_overlay(turbo_json)
                 }
             }
             Strategy::Workspace { packages } => {
                 let path = packages.get(package).ok_or_else(|| Error::NoTurboJSON)?;
-                load_from_file(&self.repo_root, path)
+                let turbo_json = load_from_file(&self.repo_root, path)?;
+                match package {
+                    PackageName::Root => {
+                        let turbo_json = apply_presets(&self.repo_root, turbo_json, packages)?;
+                        self.apply_overlay(turbo_json)
+                    }
+                    PackageName::Other(_) => Ok(turbo_json),
+                }
             }
             Strategy::WorkspaceNoTurboJson { packages } => {
                 let script_names = packages.get(package).ok_or(Error::NoTurboJSON)?;
@@ -166,16 +192,34 @@ impl TurboJsonLoader {
                 if !matches!(package, PackageName::Root) {
                     Err(Error::InvalidTurboJsonLoad(package.clone()))
                 } else {
-                    load_task_access_trace_turbo_json(
+                    let turbo_json = load_task_access_trace_turbo_json(
                         &self.repo_root,
                         root_turbo_json,
                         package_json,
-                    )
+                    )?;
+                    self.apply_overlay(turbo_json)
                 }
             }
             Strategy::Noop => Err(Error::NoTurboJSON),
         }
     }
+
+    /// Merges the selected config overlay over the root turbo.json
+    fn apply_overlay(&self, mut turbo_json: TurboJson) -> Result<TurboJson, Error> {
+        let Some(overlay_path) = &self.overlay else {
+            return Ok(turbo_json);
+        };
+        let overlay = match TurboJson::read(&self.repo_root, overlay_path) {
+            Err(Error::Io(_)) => return Err(Error::MissingConfigOverlay(overlay_path.to_string())),
+            result => result?,
+        };
+        if !overlay.extends.is_empty() {
+            let (span, text) = overlay.extends.span_and_text(overlay_path.as_str());
+            return Err(Error::OverlayExtends { span, text });
+        }
+        turbo_json.merge(overlay);
+        Ok(turbo_json)
+    }
 }
 
 /// Map all packages in the package graph to their turbo.json path
@@ -235,7 +279,9 @@ fn load_from_root_package_json(
     turbo_json_path: &AbsoluteSystemPath,
     root_package_json: &PackageJson,
 ) -> Result<TurboJson, Error> {
-    let mut turbo_json = match TurboJson::read(repo_root, turbo_json_path) {
+    let turbo_json = TurboJson::read(repo_root, turbo_json_path)
+        .and_then(|turbo_json| apply_presets(repo_root, turbo_json, &HashMap::new()));
+    let mut turbo_json = match turbo_json {
         // we're synthesizing, but we have a starting point
         // Note: this will have to change to support task inference in a monorepo
         // for now, we're going to error on any "root" tasks and turn non-root tasks into root
@@ -279,7 +325,7 @@ fn load_from_root_package_json(
             turbo_json.tasks.insert(
                 task_name,
                 Spanned::new(RawTaskDefinition {
-                    cache: Some(Spanned::new(false)),
+                    cache: Some(Spanned::new(CachePolicy::Disabled)),
                     ..RawTaskDefinition::default()
                 }),
             );
@@ -289,6 +335,89 @@ fn load_from_root_package_json(
     Ok(turbo_json)
 }
 
+/// Merges the presets that the root turbo.json extends into it. Presets are
+/// applied in order, followed by the root turbo.json itself.
+fn apply_presets(
+    repo_root: &AbsoluteSystemPath,
+    root_turbo_json: TurboJson,
+    packages: &HashMap<PackageName, AbsoluteSystemPathBuf>,
+) -> Result<TurboJson, Error> {
+    let mut presets = root_turbo_json.extends.iter().map(|preset| {
+        let preset_path = preset_path(repo_root, preset, packages).ok_or_else(|| {
+            let (span, text) = root_turbo_json.extends.span_and_text("turbo.json");
+            Error::InvalidPreset {
+                preset: preset.clone(),
+                span,
+                text,
+            }
+        })?;
+        let turbo_json = match TurboJson::read(repo_root, &preset_path) {
+            Err(Error::Io(_)) => {
+                let (span, text) = root_turbo_json.extends.span_and_text("turbo.json");
+                return Err(Error::MissingPreset {
+                    preset: preset.clone(),
+                    path: preset_path.to_string(),
+                    span,
+                    text,
+                });
+            }
+            result => result?,
+        };
+        if !turbo_json.extends.is_empty() {
+            let (span, text) = turbo_json.extends.span_and_text(preset_path.as_str());
+            return Err(Error::PresetExtends { span, text });
+        }
+        Ok(turbo_json)
+    });
+
+    let Some(mut turbo_json) = presets.next().transpose()? else {
+        return Ok(root_turbo_json);
+    };
+    for preset in presets {
+        turbo_json.merge(preset?);
+    }
+    turbo_json.merge(root_turbo_json);
+    Ok(turbo_json)
+}
+
+/// Finds the file for a preset, which is either a package name or a package
+/// name followed by a path to a file in that package. Workspace packages are
+/// used directly, anything else is looked up in the root `node_modules`.
+fn preset_path(
+    repo_root: &AbsoluteSystemPath,
+    preset: &str,
+    packages: &HashMap<PackageName, AbsoluteSystemPathBuf>,
+) -> Option<AbsoluteSystemPathBuf> {
+    // Scoped package names contain a slash of their own
+    let name_len = preset
+        .match_indices('/')
+        .nth(usize::from(preset.starts_with('@')))
+        .map_or(preset.len(), |(index, _)| index);
+    let (name, file) = preset.split_at(name_len);
+    let file = file
+        .strip_prefix('/')
+        .filter(|file| !file.is_empty())
+        .unwrap_or(PRESET_FILE);
+    if name.starts_with('.')
+        || (name.starts_with('@') && !name.contains('/'))
+        || name.split('/').any(str::is_empty)
+    {
+        return None;
+    }
+    let file = RelativeUnixPath::new(file).ok()?;
+
+    let package_dir = match packages
+        .get(&PackageName::from(name))
+        .and_then(|turbo_json| turbo_json.parent())
+    {
+        Some(package_dir) => package_dir.to_owned(),
+        None => repo_root
+            .join_component("node_modules")
+            .join_components(&name.split('/').collect::<Vec<_>>()),
+    };
+    Some(package_dir.join_unix_path(file))
+}
+
 fn root_turbo_json_from_scripts(scripts: &[String]) -> Result<TurboJson, Error> {
     let mut turbo_json = TurboJson {
         ..Default::default()
@@ -298,7 +427,7 @@ fn root_turbo_json_from_scripts(scripts: &[String]) -> Result<TurboJson, Error>
         turbo_json.tasks.insert(
             task_name,
             Spanned::new(RawTaskDefinition {
-                cache: Some(Spanned::new(false)),
+                cache: Some(Spanned::new(CachePolicy::Disabled)),
                 env_mode: Some(EnvMode::Loose),
                 ..Default::default()
             }),
@@ -317,7 +446,7 @@ fn workspace_turbo_json_from_scripts(scripts: &[String]) -> Result<TurboJson, Er
         turbo_json.tasks.insert(
             task_name,
             Spanned::new(RawTaskDefinition {
-                cache: Some(Spanned::new(false)),
+                cache: Some(Spanned::new(CachePolicy::Disabled)),
                 env_mode: Some(EnvMode::Loose),
                 ..Default::default()
             }),
@@ -386,6 +515,7 @@ mod test {
                     .into_iter()
                     .collect(),
             },
+            overlay: None,
         };
 
         let mut turbo_json = loader.load(&PackageName::Root)?.clone();
@@ -407,7 +537,7 @@ mod test {
             tasks: Pipeline([(
                 "//#build".into(),
                 Spanned::new(RawTaskDefinition {
-                    cache: Some(Spanned::new(false)),
+                    cache: Some(Spanned::new(CachePolicy::Disabled)),
                     ..RawTaskDefinition::default()
                 })
               )].into_iter().collect()
@@ -431,14 +561,14 @@ mod test {
             tasks: Pipeline([(
                 "//#build".into(),
                 Spanned::new(RawTaskDefinition {
-                    cache: Some(Spanned::new(true).with_range(81..85)),
+                    cache: Some(Spanned::new(CachePolicy::ReadWrite).with_range(81..85)),
                     ..RawTaskDefinition::default()
                 }).with_range(50..103)
             ),
             (
                 "//#test".into(),
                 Spanned::new(RawTaskDefinition {
-                     cache: Some(Spanned::new(false)),
+                     cache: Some(Spanned::new(CachePolicy::Disabled)),
                     ..RawTaskDefinition::default()
                 })
             )].into_iter().collect()),
@@ -493,7 +623,7 @@ mod test {
     #[test_case(
         None,
         None,
-        TaskDefinition { cache: false, .. Default::default() }
+        TaskDefinition { cache: CachePolicy::Disabled, .. Default::default() }
     ; "both missing")]
     fn test_task_access_loading(
         trace_contents: Option<&str>,
@@ -582,6 +712,7 @@ mod test {
             repo_root: repo_root.to_owned(),
             cache: HashMap::new(),
             strategy: Strategy::Workspace { packages },
+            overlay: None,
         };
         let result = loader.load(&PackageName::from("a"));
         assert!(
@@ -611,6 +742,7 @@ mod test {
             repo_root: repo_root.to_owned(),
             cache: HashMap::new(),
             strategy: Strategy::Workspace { packages },
+            overlay: None,
         };
         a_turbo_json
             .create_with_contents(r#"{"tasks": {"build": {}}}"#)
@@ -622,6 +754,177 @@ mod test {
         assert!(loader.load(&PackageName::from("a")).is_ok());
     }
 
+    fn preset_loader(
+        repo_root: &AbsoluteSystemPath,
+        root_turbo_json: &str,
+        workspace_packages: &[(&str, &str)],
+    ) -> TurboJsonLoader {
+        let root_turbo_json_path = repo_root.join_component(CONFIG_FILE);
+        root_turbo_json_path
+            .create_with_contents(root_turbo_json)
+            .unwrap();
+        let mut packages: HashMap<_, _> = workspace_packages
+            .iter()
+            .map(|(name, dir)| {
+                (
+                    PackageName::from(*name),
+                    repo_root.join_unix_path(
+                        RelativeUnixPath::new(dir)
+                            .unwrap()
+                            .join_component(CONFIG_FILE),
+                    ),
+                )
+            })
+            .collect();
+        packages.insert(PackageName::Root, root_turbo_json_path);
+
+        TurboJsonLoader {
+            repo_root: repo_root.to_owned(),
+            cache: HashMap::new(),
+            strategy: Strategy::Workspace { packages },
+            overlay: None,
+        }
+    }
+
+    #[test]
+    fn test_root_turbo_json_presets() {
+        let root_dir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(root_dir.path()).unwrap();
+        let shared_preset =
+            repo_root.join_components(&["node_modules", "@acme", "turbo-preset", PRESET_FILE]);
+        shared_preset.ensure_dir().unwrap();
+        shared_preset
+            .create_with_contents(
+                r#"{
+                    "globalEnv": ["CI", "NODE_ENV"],
+                    "tasks": {
+                        "build": { "outputs": ["dist/**"], "cache": false },
+                        "lint": {}
+                    }
+                }"#,
+            )
+            .unwrap();
+        let local_preset = repo_root.join_components(&["tooling", "config", "test.json"]);
+        local_preset.ensure_dir().unwrap();
+        local_preset
+            .create_with_contents(r#"{ "tasks": { "test": { "dependsOn": ["build"] } } }"#)
+            .unwrap();
+
+        let mut loader = preset_loader(
+            repo_root,
+            r#"{
+                "extends": ["@acme/turbo-preset", "config/test.json"],
+                "globalEnv": ["API_URL", "CI"],
+                "tasks": { "build": { "cache": true } }
+            }"#,
+            &[("config", "tooling/config")],
+        );
+        let turbo_json = loader.load(&PackageName::Root).unwrap();
+
+        assert_eq!(
+            turbo_json.global_env,
+            vec!["API_URL".to_owned(), "CI".to_owned(), "NODE_ENV".to_owned()]
+        );
+        let mut tasks: Vec<_> = turbo_json
+            .tasks
+            .keys()
+            .map(|task| task.to_string())
+            .collect();
+        tasks.sort();
+        assert_eq!(tasks, vec!["build", "lint", "test"]);
+        let build = turbo_json.tasks.get(&TaskName::from("build")).unwrap();
+        assert_eq!(
+            build.cache.as_ref().map(|cache| *cache.as_inner()),
+            Some(CachePolicy::ReadWrite)
+        );
+        assert!(build.outputs.is_some(), "expected outputs from the preset");
+        let test = turbo_json.tasks.get(&TaskName::from("test")).unwrap();
+        assert!(
+            test.depends_on.is_some(),
+            "expected dependsOn from the preset"
+        );
+    }
+
+    #[test_case(r#"{ "extends": ["missing-preset"] }"#, &[], "Unable to find preset \"missing-preset\"" ; "missing preset")]
+    #[test_case(r#"{ "extends": ["//"] }"#, &[], "\"//\" is not a package name" ; "root package")]
+    #[test_case(r#"{ "extends": ["@acme"] }"#, &[], "\"@acme\" is not a package name" ; "scope without name")]
+    #[test_case(r#"{ "extends": ["nested"] }"#, &[("nested", "packages/nested")], "Presets can't extend other configuration" ; "nested extends")]
+    fn test_root_turbo_json_preset_errors(
+        root_turbo_json: &str,
+        packages: &[(&str, &str)],
+        expected: &str,
+    ) {
+        let root_dir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(root_dir.path()).unwrap();
+        let nested_preset = repo_root.join_components(&["packages", "nested", PRESET_FILE]);
+        nested_preset.ensure_dir().unwrap();
+        nested_preset
+            .create_with_contents(r#"{ "extends": ["other-preset"] }"#)
+            .unwrap();
+
+        let mut loader = preset_loader(repo_root, root_turbo_json, packages);
+        let err = loader.load(&PackageName::Root).unwrap_err();
+        assert!(
+            err.to_string().starts_with(expected),
+            "expected \"{err}\" to start with \"{expected}\""
+        );
+    }
+
+    #[test]
+    fn test_root_turbo_json_overlay() {
+        let root_dir = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(root_dir.path()).unwrap();
+        let overlay_path = repo_root.join_component("turbo.ci.json");
+        overlay_path
+            .create_with_contents(
+                r#"{
+                    "globalEnv": ["CI"],
+                    "tasks": { "build": { "cache": false }, "deploy": {} }
+                }"#,
+            )
+            .unwrap();
+
+        let mut loader = preset_loader(
+            repo_root,
+            r#"{
+                "globalEnv": ["API_URL"],
+                "tasks": { "build": { "outputs": ["dist/**"] } }
+            }"#,
+            &[],
+        )
+        .with_overlay(Some(overlay_path.clone()));
+        let turbo_json = loader.load(&PackageName::Root).unwrap();
+
+        assert_eq!(
+            turbo_json.global_env,
+            vec!["API_URL".to_owned(), "CI".to_owned()]
+        );
+        assert!(turbo_json.tasks.contains_key(&TaskName::from("deploy")));
+        let build = turbo_json.tasks.get(&TaskName::from("build")).unwrap();
+        assert_eq!(
+            build.cache.as_ref().map(|cache| *cache.as_inner()),
+            Some(CachePolicy::Disabled)
+        );
+        assert!(build.outputs.is_some(), "expected outputs from turbo.json");
+
+        overlay_path
+            .create_with_contents(r#"{ "extends": ["//"] }"#)
+            .unwrap();
+        let mut loader =
+            preset_loader(repo_root, "{}", &[]).with_overlay(Some(overlay_path.clone()));
+        assert!(matches!(
+            loader.load(&PackageName::Root),
+            Err(Error::OverlayExtends { .. })
+        ));
+
+        overlay_path.remove().unwrap();
+        let mut loader = preset_loader(repo_root, "{}", &[]).with_overlay(Some(overlay_path));
+        assert!(matches!(
+            loader.load(&PackageName::Root),
+            Err(Error::MissingConfigOverlay(_))
+        ));
+    }
+
     #[test]
     fn test_no_turbo_json() {
         let root_dir = tempdir().unwrap();
@@ -643,6 +946,7 @@ mod test {
             repo_root: repo_root.to_owned(),
             cache: HashMap::new(),
             strategy: Strategy::WorkspaceNoTurboJson { packages },
+            overlay: None,
         };
 
         {
@@ -651,7 +955,7 @@ mod test {
                 if let Some(def) = root_json.tasks.get(&TaskName::from(task_name)) {
                     assert_eq!(
                         def.cache.as_ref().map(|cache| *cache.as_inner()),
-                        Some(false)
+                        Some(CachePolicy::Disabled)
                     );
                 } else {
                     panic!("didn't find {task_name}");
@@ -665,7 +969,7 @@ mod test {
                 if let Some(def) = pkg_a_json.tasks.get(&TaskName::from(task_name)) {
                     assert_eq!(
                         def.cache.as_ref().map(|cache| *cache.as_inner()),
-                        Some(false)
+                        Some(CachePolicy::Disabled)
                     );
                 } else {
                     panic!("didn't find {task_name}");
@@ -1,5 +1,6 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    fmt,
     ops::{Deref, DerefMut},
     sync::Arc,
 };
@@ -11,18 +12,19 @@ use miette::{NamedSource, SourceSpan};
 use serde::{Deserialize, Serialize};
 use struct_iterable::Iterable;
 use turbopath::AbsoluteSystemPath;
+use turborepo_cache::SignatureMismatchPolicy;
 use turborepo_errors::Spanned;
 use turborepo_repository::package_graph::ROOT_PKG_NAME;
 use turborepo_unescape::UnescapedString;
 
 use crate::{
-    cli::{EnvMode, OutputLogsMode},
+    cli::{EnvMode, LogOrder, LogPrefix, OutputLogsMode},
     config::{ConfigurationOptions, Error, InvalidEnvPrefixError},
     run::{
         task_access::TaskAccessTraceFile,
         task_id::{TaskId, TaskName},
     },
-    task_graph::{TaskDefinition, TaskOutputs},
+    task_graph::{CachePolicy, TaskDefinition, TaskOutputs, TURBO_ROOT_TOKEN},
 };
 
 mod loader;
@@ -72,11 +74,60 @@ pub(crate) struct RawRemoteCacheOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     signature: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    signature_mismatch: Option<SignatureMismatchPolicy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     preflight: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     timeout: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    chunking: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     enabled: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oidc: Option<RawOidcOptions>,
+}
+
+// Authenticates with the Remote Cache using the CI provider's OIDC token
+#[derive(Clone, Debug, Default, Iterable, Serialize, Deserializable)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawOidcOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    audience: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issuer: Option<String>,
+}
+
+/// Exit codes for run outcomes that otherwise exit with the exit code of a
+/// failed task, or 0. Each applies only when none of the ones before it do.
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq, Eq, Clone, Copy, Deserializable)]
+#[serde(rename_all = "camelCase")]
+pub struct ExitCodes {
+    /// Used instead of the exit code of the failed task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_failure: Option<i32>,
+    /// Used when the filters and tasks didn't match any tasks to run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_tasks: Option<i32>,
+    /// Used when every task passed but a request to the remote cache failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_cache_error: Option<i32>,
+    /// Used when every task passed but the run finished with warnings
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warnings: Option<i32>,
+}
+
+// Where run summaries get sent, in addition to any that are saved locally
+#[derive(Clone, Debug, Default, Iterable, Serialize, Deserializable)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RawRunSummaryUpload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<BTreeMap<String, String>>,
 }
 
 impl From<&RawRemoteCacheOptions> for ConfigurationOptions {
@@ -87,9 +138,21 @@ impl From<&RawRemoteCacheOptions> for ConfigurationOptions {
             team_slug: remote_cache_opts.team_slug.clone(),
             team_id: remote_cache_opts.team_id.clone(),
             signature: remote_cache_opts.signature,
+            signature_mismatch: remote_cache_opts.signature_mismatch,
             preflight: remote_cache_opts.preflight,
             timeout: remote_cache_opts.timeout,
+            retries: remote_cache_opts.retries,
+            chunking: remote_cache_opts.chunking,
             enabled: remote_cache_opts.enabled,
+            storage_url: remote_cache_opts.storage_url.clone(),
+            oidc_audience: remote_cache_opts
+                .oidc
+                .as_ref()
+                .and_then(|oidc| oidc.audience.clone()),
+            oidc_issuer: remote_cache_opts
+                .oidc
+                .as_ref()
+                .and_then(|oidc| oidc.issuer.clone()),
             ..Self::default()
         }
     }
@@ -139,6 +202,28 @@ pub struct RawTurboJson {
     pub env_mode: Option<EnvMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cache_dir: Option<Spanned<UnescapedString>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_compression_level: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_max_size: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_max_age: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_order: Option<LogOrder>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_prefix: Option<LogPrefix>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependency_cycles: Option<DependencyCycles>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash_mode: Option<HashMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub watcher: Option<Watcher>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) run_summary_upload: Option<RawRunSummaryUpload>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exit_codes: Option<ExitCodes>,
 
     #[deserializable(rename = "//")]
     #[serde(skip)]
@@ -184,6 +269,78 @@ pub enum UIMode {
     Web,
 }
 
+#[derive(
+    Serialize, Deserialize, Debug, Default, Copy, Clone, Deserializable, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum DependencyCycles {
+    /// Fail when packages depend on each other in a cycle
+    #[default]
+    Error,
+    /// Warn about packages that depend on each other in a cycle, but continue
+    Warn,
+}
+
+impl fmt::Display for DependencyCycles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DependencyCycles::Error => "error",
+            DependencyCycles::Warn => "warn",
+        })
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Default, Copy, Clone, Deserializable, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum HashMode {
+    /// Hash files with git, falling back to walking the filesystem when the
+    /// repository isn't a git repository
+    #[default]
+    Git,
+    /// Hash files by walking the filesystem without using git
+    Filesystem,
+}
+
+impl fmt::Display for HashMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            HashMode::Git => "git",
+            HashMode::Filesystem => "filesystem",
+        })
+    }
+}
+
+#[derive(
+    Serialize, Deserialize, Debug, Default, Copy, Clone, Deserializable, PartialEq, Eq, ValueEnum,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Watcher {
+    /// Use the platform's file watching API, or poll when the repository is
+    /// on a network filesystem
+    #[default]
+    Auto,
+    /// Watch files with the platform's file watching API
+    Native,
+    /// Subscribe to a running Watchman server, falling back to the native
+    /// watcher when Watchman isn't available
+    Watchman,
+    /// Periodically scan the repository for changes
+    Poll,
+}
+
+impl fmt::Display for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Watcher::Auto => "auto",
+            Watcher::Native => "native",
+            Watcher::Watchman => "watchman",
+            Watcher::Poll => "poll",
+        })
+    }
+}
+
 impl Default for UIMode {
     fn default() -> Self {
         Self::Tui
@@ -207,7 +364,7 @@ impl UIMode {
 #[deserializable(unknown_fields = "deny")]
 pub struct RawTaskDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
-    cache: Option<Spanned<bool>>,
+    cache: Option<Spanned<CachePolicy>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     depends_on: Option<Spanned<Vec<Spanned<UnescapedString>>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -215,17 +372,25 @@ pub struct RawTaskDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
     inputs: Option<Vec<Spanned<UnescapedString>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    inputs_from: Option<Vec<Spanned<UnescapedString>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dotfiles: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pass_through_env: Option<Vec<Spanned<UnescapedString>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     persistent: Option<Spanned<bool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     interruptible: Option<Spanned<bool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    skippable: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     outputs: Option<Vec<Spanned<UnescapedString>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     output_logs: Option<Spanned<OutputLogsMode>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     interactive: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matrix: Option<Spanned<Vec<Spanned<UnescapedString>>>>,
     // TODO: Remove this once we have the ability to load task definitions directly
     // instead of deriving them from a TurboJson
     #[serde(skip)]
@@ -257,17 +422,23 @@ impl RawTaskDefinition {
         }
         set_field!(self, other, depends_on);
         set_field!(self, other, inputs);
+        set_field!(self, other, inputs_from);
+        set_field!(self, other, dotfiles);
         set_field!(self, other, output_logs);
         set_field!(self, other, persistent);
         set_field!(self, other, interruptible);
+        set_field!(self, other, skippable);
         set_field!(self, other, env);
         set_field!(self, other, pass_through_env);
         set_field!(self, other, interactive);
+        set_field!(self, other, matrix);
         set_field!(self, other, env_mode);
     }
 }
 
 pub const CONFIG_FILE: &str = "turbo.json";
+// The file a preset package provides when it's extended by name alone
+const PRESET_FILE: &str = "turbo.preset.json";
 const ENV_PIPELINE_DELIMITER: &str = "$";
 const TOPOLOGICAL_PIPELINE_DELIMITER: &str = "^";
 
@@ -319,7 +490,9 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
     fn try_from(raw_task: RawTaskDefinition) -> Result<Self, Error> {
         let outputs = raw_task.outputs.unwrap_or_default().try_into()?;
 
-        let cache = raw_task.cache.map_or(true, |c| c.into_inner());
+        let cache = raw_task
+            .cache
+            .map_or(CachePolicy::ReadWrite, |c| c.into_inner());
         let interactive = raw_task
             .interactive
             .as_ref()
@@ -328,7 +501,7 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
 
         if let Some(interactive) = raw_task.interactive {
             let (span, text) = interactive.span_and_text("turbo.json");
-            if cache && interactive.value {
+            if cache.is_enabled() && interactive.value {
                 return Err(Error::InteractiveNoCacheable { span, text });
             }
         }
@@ -384,6 +557,7 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
             .unwrap_or_default()
             .into_iter()
             .map(|input| {
+                let glob = input.strip_prefix('!').unwrap_or(&input);
                 if Utf8Path::new(&input.value).is_absolute() {
                     let (span, text) = input.span_and_text("turbo.json");
                     Err(Error::AbsolutePathInConfig {
@@ -391,12 +565,41 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
                         span,
                         text,
                     })
+                } else if glob.contains(TURBO_ROOT_TOKEN)
+                    && !glob.starts_with(&format!("{TURBO_ROOT_TOKEN}/"))
+                {
+                    let (span, text) = input.span_and_text("turbo.json");
+                    Err(Error::InvalidTurboRootUse { span, text })
                 } else {
                     Ok(input.to_string())
                 }
             })
             .collect::<Result<Vec<_>, _>>()?;
 
+        let inputs_from = raw_task
+            .inputs_from
+            .unwrap_or_default()
+            .into_iter()
+            .map(|task| {
+                let name: &str = &task;
+                if name.is_empty()
+                    || name.contains('#')
+                    || name.starts_with(TOPOLOGICAL_PIPELINE_DELIMITER)
+                    || name.starts_with(ENV_PIPELINE_DELIMITER)
+                {
+                    let (span, text) = task.span_and_text("turbo.json");
+                    Err(Error::InvalidInputsFrom {
+                        task: task.to_string(),
+                        span,
+                        text,
+                    })
+                } else {
+                    let (task, span) = task.split();
+                    Ok(span.to(TaskName::from(String::from(task))))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
         let pass_through_env = raw_task
             .pass_through_env
             .map(|env| -> Result<Vec<String>, Error> {
@@ -408,6 +611,28 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
             })
             .transpose()?;
 
+        let mut matrix: Vec<String> = Vec::new();
+        for value in raw_task
+            .matrix
+            .map(|matrix| matrix.into_inner())
+            .unwrap_or_default()
+        {
+            let value_str: &str = &value;
+            let is_valid = !value_str.is_empty()
+                && value_str
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !is_valid || matrix.iter().any(|existing| existing == value_str) {
+                let (span, text) = value.span_and_text("turbo.json");
+                return Err(Error::InvalidMatrixValue {
+                    value: value.to_string(),
+                    span,
+                    text,
+                });
+            }
+            matrix.push(value.into_inner().into());
+        }
+
         Ok(TaskDefinition {
             outputs,
             cache,
@@ -415,11 +640,20 @@ impl TryFrom<RawTaskDefinition> for TaskDefinition {
             task_dependencies,
             env,
             inputs,
+            inputs_from,
+            dotfiles: raw_task
+                .dotfiles
+                .map_or(true, |dotfiles| dotfiles.into_inner()),
             pass_through_env,
             output_logs: *raw_task.output_logs.unwrap_or_default(),
             persistent,
             interruptible: *interruptible,
+            skippable: raw_task
+                .skippable
+                .map_or(false, |skippable| skippable.into_inner()),
             interactive,
+            matrix,
+            matrix_variant: None,
             env_mode: raw_task.env_mode,
         })
     }
@@ -579,6 +813,42 @@ impl TurboJson {
         false
     }
 
+    /// Layers `other` on top of this configuration. Global environment
+    /// variables and dependencies are combined, while tasks defined in both
+    /// are merged field by field with `other` taking precedence.
+    pub(crate) fn merge(&mut self, other: TurboJson) {
+        fn union(base: &mut Vec<String>, other: Vec<String>) {
+            base.extend(other);
+            base.sort();
+            base.dedup();
+        }
+
+        union(&mut self.global_deps, other.global_deps);
+        union(&mut self.global_env, other.global_env);
+        match (
+            &mut self.global_pass_through_env,
+            other.global_pass_through_env,
+        ) {
+            (Some(base), Some(other)) => union(base, other),
+            (base @ None, other) => *base = other,
+            (Some(_), None) => (),
+        }
+        for (task_name, definition) in other.tasks {
+            let definition = match self.tasks.remove(&task_name) {
+                Some(base) => definition.map(|definition| {
+                    let mut merged = base.into_inner();
+                    merged.merge(definition);
+                    merged
+                }),
+                None => definition,
+            };
+            self.tasks.insert(task_name, definition);
+        }
+        self.text = other.text;
+        self.path = other.path;
+        self.extends = other.extends;
+    }
+
     /// Reads a `RawTurboJson` from the given path
     /// and then converts it into `TurboJson`
     pub(crate) fn read(
@@ -703,7 +973,7 @@ mod tests {
     use crate::{
         cli::OutputLogsMode,
         run::task_id::TaskName,
-        task_graph::{TaskDefinition, TaskOutputs},
+        task_graph::{CachePolicy, TaskDefinition, TaskOutputs},
         turbo_json::RawTaskDefinition,
     };
 
@@ -721,6 +991,59 @@ mod tests {
         TaskDefinition::default()
     ; "just persistent"
     )]
+    #[test_case(
+        r#"{ "dotfiles": false }"#,
+        RawTaskDefinition {
+            dotfiles: Some(Spanned::new(false).with_range(14..19)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            dotfiles: false,
+            ..TaskDefinition::default()
+        }
+    ; "just dotfiles"
+    )]
+    #[test_case(
+        r#"{ "matrix": ["node18", "node20"] }"#,
+        RawTaskDefinition {
+            matrix: Some(Spanned::new(vec![
+                Spanned::<UnescapedString>::new("node18".into()).with_range(13..21),
+                Spanned::<UnescapedString>::new("node20".into()).with_range(23..31),
+            ]).with_range(12..32)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            matrix: vec!["node18".to_string(), "node20".to_string()],
+            ..TaskDefinition::default()
+        }
+    ; "just matrix"
+    )]
+    #[test_case(
+        r#"{ "inputsFrom": ["codegen"] }"#,
+        RawTaskDefinition {
+            inputs_from: Some(vec![
+                Spanned::<UnescapedString>::new("codegen".into()).with_range(17..26),
+            ]),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            inputs_from: vec![Spanned::<TaskName<'_>>::new("codegen".into()).with_range(17..26)],
+            ..TaskDefinition::default()
+        }
+    ; "just inputsFrom"
+    )]
+    #[test_case(
+        r#"{ "skippable": true }"#,
+        RawTaskDefinition {
+            skippable: Some(Spanned::new(true).with_range(15..19)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            skippable: true,
+            ..TaskDefinition::default()
+        }
+    ; "just skippable"
+    )]
     #[test_case(
         r#"{
           "dependsOn": ["cli#build"],
@@ -739,12 +1062,16 @@ mod tests {
             env: Some(vec![Spanned::<UnescapedString>::new("OS".into()).with_range(58..62)]),
             pass_through_env: Some(vec![Spanned::<UnescapedString>::new("AWS_SECRET_KEY".into()).with_range(94..110)]),
             outputs: Some(vec![Spanned::<UnescapedString>::new("package/a/dist".into()).with_range(135..151)]),
-            cache: Some(Spanned::new(false).with_range(173..178)),
+            cache: Some(Spanned::new(CachePolicy::Disabled).with_range(173..178)),
             inputs: Some(vec![Spanned::<UnescapedString>::new("package/a/src/**".into()).with_range(201..219)]),
+            inputs_from: None,
+            dotfiles: None,
             output_logs: Some(Spanned::new(OutputLogsMode::Full).with_range(246..252)),
             persistent: Some(Spanned::new(true).with_range(278..282)),
             interactive: Some(Spanned::new(true).with_range(309..313)),
             interruptible: Some(Spanned::new(true).with_range(342..346)),
+            skippable: None,
+            matrix: None,
             env_mode: None,
         },
         TaskDefinition {
@@ -753,8 +1080,10 @@ mod tests {
               inclusions: vec!["package/a/dist".to_string()],
               exclusions: vec![],
           },
-          cache: false,
+          cache: CachePolicy::Disabled,
           inputs: vec!["package/a/src/**".to_string()],
+          inputs_from: vec![],
+          dotfiles: true,
           output_logs: OutputLogsMode::Full,
           pass_through_env: Some(vec!["AWS_SECRET_KEY".to_string()]),
           task_dependencies: vec![Spanned::<TaskName<'_>>::new("cli#build".into()).with_range(26..37)],
@@ -762,6 +1091,9 @@ mod tests {
           persistent: true,
           interactive: true,
           interruptible: true,
+          skippable: false,
+          matrix: vec![],
+          matrix_variant: None,
           env_mode: None,
         }
       ; "full"
@@ -783,12 +1115,16 @@ mod tests {
             env: Some(vec![Spanned::<UnescapedString>::new("OS".into()).with_range(66..70)]),
             pass_through_env: Some(vec![Spanned::<UnescapedString>::new("AWS_SECRET_KEY".into()).with_range(106..122)]),
             outputs: Some(vec![Spanned::<UnescapedString>::new("package\\a\\dist".into()).with_range(151..169)]),
-            cache: Some(Spanned::new(false).with_range(195..200)),
+            cache: Some(Spanned::new(CachePolicy::Disabled).with_range(195..200)),
             inputs: Some(vec![Spanned::<UnescapedString>::new("package\\a\\src\\**".into()).with_range(227..248)]),
+            inputs_from: None,
+            dotfiles: None,
             output_logs: Some(Spanned::new(OutputLogsMode::Full).with_range(279..285)),
             persistent: Some(Spanned::new(true).with_range(315..319)),
             interruptible: Some(Spanned::new(true).with_range(352..356)),
+            skippable: None,
             interactive: None,
+            matrix: None,
             env_mode: None,
         },
         TaskDefinition {
@@ -797,15 +1133,20 @@ mod tests {
                 inclusions: vec!["package\\a\\dist".to_string()],
                 exclusions: vec![],
             },
-            cache: false,
+            cache: CachePolicy::Disabled,
             inputs: vec!["package\\a\\src\\**".to_string()],
+            inputs_from: vec![],
+            dotfiles: true,
             output_logs: OutputLogsMode::Full,
             pass_through_env: Some(vec!["AWS_SECRET_KEY".to_string()]),
             task_dependencies: vec![Spanned::<TaskName<'_>>::new("cli#build".into()).with_range(30..41)],
             topological_dependencies: vec![],
             persistent: true,
             interruptible: true,
+            skippable: false,
             interactive: false,
+            matrix: vec![],
+            matrix_variant: None,
             env_mode: None,
         }
       ; "full (windows)"
@@ -830,6 +1171,63 @@ mod tests {
         Ok(())
     }
 
+    #[test_case(r#"{ "matrix": ["node 18"] }"# ; "whitespace")]
+    #[test_case(r#"{ "matrix": [""] }"# ; "empty")]
+    #[test_case(r#"{ "matrix": ["node18", "node18"] }"# ; "duplicate")]
+    fn test_invalid_matrix_value(task_definition_content: &str) {
+        let raw_task_definition: RawTaskDefinition = deserialize_from_json_str(
+            task_definition_content,
+            JsonParserOptions::default(),
+            "turbo.json",
+        )
+        .into_deserialized()
+        .unwrap();
+
+        let result = TaskDefinition::try_from(raw_task_definition);
+        assert!(matches!(
+            result,
+            Err(crate::config::Error::InvalidMatrixValue { .. })
+        ));
+    }
+
+    #[test_case(r#"{ "inputsFrom": ["^codegen"] }"# ; "topological")]
+    #[test_case(r#"{ "inputsFrom": ["web#codegen"] }"# ; "package task")]
+    #[test_case(r#"{ "inputsFrom": [""] }"# ; "empty")]
+    fn test_invalid_inputs_from(task_definition_content: &str) {
+        let raw_task_definition: RawTaskDefinition = deserialize_from_json_str(
+            task_definition_content,
+            JsonParserOptions::default(),
+            "turbo.json",
+        )
+        .into_deserialized()
+        .unwrap();
+
+        let result = TaskDefinition::try_from(raw_task_definition);
+        assert!(matches!(
+            result,
+            Err(crate::config::Error::InvalidInputsFrom { .. })
+        ));
+    }
+
+    #[test_case(r#"{ "inputs": ["schema/$TURBO_ROOT$/**"] }"# ; "not at start")]
+    #[test_case(r#"{ "inputs": ["$TURBO_ROOT$schema/**"] }"# ; "missing separator")]
+    #[test_case(r#"{ "inputs": ["!$TURBO_ROOT$"] }"# ; "negated without glob")]
+    fn test_invalid_turbo_root_use(task_definition_content: &str) {
+        let raw_task_definition: RawTaskDefinition = deserialize_from_json_str(
+            task_definition_content,
+            JsonParserOptions::default(),
+            "turbo.json",
+        )
+        .into_deserialized()
+        .unwrap();
+
+        let result = TaskDefinition::try_from(raw_task_definition);
+        assert!(matches!(
+            result,
+            Err(crate::config::Error::InvalidTurboRootUse { .. })
+        ));
+    }
+
     #[test_case("[]", TaskOutputs::default() ; "empty")]
     #[test_case(r#"["target/**"]"#, TaskOutputs { inclusions: vec!["target/**".to_string()], exclusions: vec![] })]
     #[test_case(
@@ -924,6 +1322,30 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test_case(json!(true), Some(CachePolicy::ReadWrite) ; "enabled")]
+    #[test_case(json!(false), Some(CachePolicy::Disabled) ; "disabled")]
+    #[test_case(json!("read-only"), Some(CachePolicy::ReadOnly) ; "read-only")]
+    #[test_case(json!("write-only"), Some(CachePolicy::WriteOnly) ; "write-only")]
+    #[test_case(json!("junk"), None ; "invalid value")]
+    fn test_parsing_cache_policy(cache: serde_json::Value, expected: Option<CachePolicy>) {
+        let json: Result<RawTurboJson, _> = RawTurboJson::parse_from_serde(json!({
+            "tasks": {
+                "build": {
+                    "cache": cache,
+                }
+            }
+        }));
+
+        let actual = json
+            .as_ref()
+            .ok()
+            .and_then(|j| j.tasks.as_ref())
+            .and_then(|pipeline| pipeline.0.get(&TaskName::from("build")))
+            .and_then(|build| build.value.cache.clone())
+            .map(|cache| cache.into_inner());
+        assert_eq!(actual, expected);
+    }
+
     #[test_case(r#"{ "ui": "tui" }"#, Some(UIMode::Tui) ; "tui")]
     #[test_case(r#"{ "ui": "stream" }"#, Some(UIMode::Stream) ; "stream")]
     #[test_case(r#"{}"#, None ; "missing")]
@@ -2,7 +2,7 @@ use std::{backtrace, collections::BTreeMap, fmt::Debug, sync::Arc};
 
 use biome_deserialize::{
     json::deserialize_from_json_str, Deserializable, DeserializableValue,
-    DeserializationDiagnostic, DeserializationVisitor, VisitableType,
+    DeserializationDiagnostic, DeserializationVisitor, Text, VisitableType,
 };
 use biome_diagnostics::DiagnosticExt;
 use biome_json_parser::JsonParserOptions;
@@ -16,6 +16,7 @@ use turborepo_unescape::UnescapedString;
 
 use crate::{
     run::task_id::TaskName,
+    task_graph::CachePolicy,
     turbo_json::{Pipeline, RawTaskDefinition, RawTurboJson, Spanned},
 };
 
@@ -55,6 +56,55 @@ impl Deserializable for TaskName<'static> {
     }
 }
 
+impl Deserializable for CachePolicy {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(CachePolicyVisitor, name, diagnostics)
+    }
+}
+
+struct CachePolicyVisitor;
+
+impl DeserializationVisitor for CachePolicyVisitor {
+    type Output = CachePolicy;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::BOOL.union(VisitableType::STR);
+
+    fn visit_bool(
+        self,
+        value: bool,
+        _range: TextRange,
+        _name: &str,
+        _diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        Some(CachePolicy::from(value))
+    }
+
+    fn visit_str(
+        self,
+        value: Text,
+        range: TextRange,
+        _name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        match value.text() {
+            "read-only" => Some(CachePolicy::ReadOnly),
+            "write-only" => Some(CachePolicy::WriteOnly),
+            unknown => {
+                diagnostics.push(DeserializationDiagnostic::new_unknown_value(
+                    unknown,
+                    range,
+                    &["read-only", "write-only"],
+                ));
+                None
+            }
+        }
+    }
+}
+
 impl Deserializable for Pipeline {
     fn deserialize(
         value: &impl DeserializableValue,
@@ -144,11 +194,18 @@ impl WithMetadata for RawTaskDefinition {
         }
         self.env.add_text(text.clone());
         self.inputs.add_text(text.clone());
+        self.inputs_from.add_text(text.clone());
+        self.dotfiles.add_text(text.clone());
         self.pass_through_env.add_text(text.clone());
         self.persistent.add_text(text.clone());
         self.interruptible.add_text(text.clone());
+        self.skippable.add_text(text.clone());
         self.outputs.add_text(text.clone());
         self.output_logs.add_text(text.clone());
+        self.matrix.add_text(text.clone());
+        if let Some(matrix) = &mut self.matrix {
+            matrix.value.add_text(text.clone());
+        }
         self.interactive.add_text(text);
     }
 
@@ -159,11 +216,18 @@ impl WithMetadata for RawTaskDefinition {
         }
         self.env.add_path(path.clone());
         self.inputs.add_path(path.clone());
+        self.inputs_from.add_path(path.clone());
+        self.dotfiles.add_path(path.clone());
         self.pass_through_env.add_path(path.clone());
         self.persistent.add_path(path.clone());
         self.interruptible.add_path(path.clone());
+        self.skippable.add_path(path.clone());
         self.outputs.add_path(path.clone());
         self.output_logs.add_path(path.clone());
+        self.matrix.add_path(path.clone());
+        if let Some(matrix) = &mut self.matrix {
+            matrix.value.add_path(path.clone());
+        }
         self.interactive.add_path(path);
     }
 }
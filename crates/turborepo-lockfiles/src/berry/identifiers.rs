@@ -167,6 +167,103 @@ impl<'a> Descriptor<'a> {
         // we extract the owned string.
         Some(reference.into_owned())
     }
+
+    /// Yarn binds descriptors that point at a path relative to the package
+    /// depending on them to that package's locator.
+    /// Returns the bound descriptor if this descriptor requires a parent
+    pub fn bind(&self, parent: &Locator) -> Option<Descriptor<'static>> {
+        if !self.is_parent_required() {
+            return None;
+        }
+        let range = format!(
+            "{}{BINDING_PREFIX}{}",
+            self.range,
+            encode_component(&parent.to_string())
+        );
+        Some(Descriptor {
+            ident: self.ident.to_owned(),
+            range: Cow::Owned(range),
+        })
+    }
+
+    fn is_parent_required(&self) -> bool {
+        // Already bound descriptors contain parameters
+        if self.range.contains("::") {
+            return false;
+        }
+        let path = match self.protocol() {
+            Some("portal" | "link" | "file") => Self::strip_protocol(&self.range),
+            Some("patch") => match self.range.split_once('#') {
+                Some((_, path)) => path,
+                None => return false,
+            },
+            _ => return false,
+        };
+        // Absolute paths and paths relative to the project root (~/) don't
+        // depend on the parent
+        !(path.starts_with('/') || path.starts_with('~'))
+    }
+}
+
+const BINDING_PREFIX: &str = "::locator=";
+
+// Matches the escaping of encodeURIComponent which is what yarn uses for
+// locator parameters
+fn encode_component(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'!'
+            | b'~'
+            | b'*'
+            | b'\''
+            | b'('
+            | b')' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn decode_component(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+// Joins a path onto a directory resolving any `.` or `..` segments
+fn join_path(dir: &str, path: &str) -> String {
+    let mut segments: Vec<&str> = dir
+        .split('/')
+        .filter(|segment| !segment.is_empty() && *segment != ".")
+        .collect();
+    for segment in path.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    segments.join("/")
 }
 
 impl<'a> TryFrom<&'a str> for Locator<'a> {
@@ -250,6 +347,38 @@ impl<'a> Locator<'a> {
             })
     }
 
+    /// Returns the locator this locator is bound to if it is present
+    pub fn parent(&self) -> Option<Locator<'static>> {
+        let (_, params) = self.reference.split_once("::")?;
+        let parent = params
+            .split('&')
+            .find_map(|param| param.strip_prefix("locator="))?;
+        let parent = decode_component(parent)?;
+        Locator::try_from(parent.as_str())
+            .ok()
+            .map(|locator| locator.as_owned())
+    }
+
+    /// Returns the path of the patch file relative to the project root.
+    /// Relative patch paths are resolved from the workspace the patch is
+    /// bound to.
+    pub fn project_patch_file(&self) -> Option<Cow<str>> {
+        let file = self.patch_file()?;
+        if Locator::is_patch_builtin(file) || self.reference.contains("#~/") {
+            return Some(Cow::Borrowed(file));
+        }
+        let workspace = self.parent().and_then(|parent| {
+            parent
+                .reference
+                .strip_prefix(WORKSPACE_PROTOCOL)
+                .map(|path| path.to_string())
+        });
+        match workspace {
+            Some(workspace) => Some(Cow::Owned(join_path(&workspace, file))),
+            None => Some(Cow::Borrowed(file)),
+        }
+    }
+
     pub fn patched_locator(&self) -> Option<Locator> {
         // THis has an issue of cutting off the last char
         Locator::from_patch_reference(&self.reference)
@@ -422,4 +551,112 @@ mod test {
             assert_eq!(patch_locator, expected, "{}", tc.locator);
         }
     }
+
+    #[test]
+    fn test_descriptor_bind() {
+        let parent = Locator::try_from("web@workspace:apps/web").unwrap();
+        struct TestCase {
+            descriptor: &'static str,
+            bound: Option<&'static str>,
+        }
+        let test_cases = [
+            TestCase {
+                descriptor: "utils@portal:../../tools/utils",
+                bound: Some("utils@portal:../../tools/utils::locator=web%40workspace%3Aapps%2Fweb"),
+            },
+            TestCase {
+                descriptor: "config@link:../config",
+                bound: Some("config@link:../config::locator=web%40workspace%3Aapps%2Fweb"),
+            },
+            TestCase {
+                descriptor: "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch",
+                bound: Some(
+                    "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch::locator=web%\
+                     40workspace%3Aapps%2Fweb",
+                ),
+            },
+            TestCase {
+                descriptor: "lodash@patch:lodash@npm%3A4.17.21#~/.yarn/patches/lodash.patch",
+                bound: None,
+            },
+            TestCase {
+                descriptor: "resolve@patch:resolve@^1.22.0#~builtin<compat/resolve>",
+                bound: None,
+            },
+            TestCase {
+                descriptor: "ui@workspace:*",
+                bound: None,
+            },
+            TestCase {
+                descriptor: "lodash@npm:^4.17.21",
+                bound: None,
+            },
+        ];
+        for tc in test_cases {
+            let descriptor = Descriptor::try_from(tc.descriptor).unwrap();
+            let bound = descriptor.bind(&parent).map(|d| d.to_string());
+            assert_eq!(bound.as_deref(), tc.bound, "{}", tc.descriptor);
+        }
+    }
+
+    #[test]
+    fn test_locator_parent() {
+        let locator = Locator::try_from(
+            "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch::version=4.17.21&\
+             hash=7d1ac5&locator=%40repo%2Fweb%40workspace%3Aapps%2Fweb",
+        )
+        .unwrap();
+        assert_eq!(
+            locator.parent(),
+            Some(Locator::try_from("@repo/web@workspace:apps/web").unwrap())
+        );
+        assert_eq!(
+            Locator::try_from("lodash@npm:4.17.21").unwrap().parent(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_locator_project_patch_file() {
+        struct TestCase {
+            locator: &'static str,
+            file: Option<&'static str>,
+        }
+        let test_cases = [
+            TestCase {
+                locator: "lodash@patch:lodash@npm%3A4.17.21#./.yarn/patches/lodash-npm-4.17.\
+                          21-6382451519.patch::version=4.17.21&hash=2c6e9e&locator=berry-patch%\
+                          40workspace%3A.",
+                file: Some(".yarn/patches/lodash-npm-4.17.21-6382451519.patch"),
+            },
+            TestCase {
+                locator: "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch::version=4.17.\
+                          21&hash=7d1ac5&locator=web%40workspace%3Aapps%2Fweb",
+                file: Some("apps/web/patches/lodash.patch"),
+            },
+            TestCase {
+                locator: "lodash@patch:lodash@npm%3A4.17.21#../../patches/lodash.patch::version=4.\
+                          17.21&hash=7d1ac5&locator=web%40workspace%3Aapps%2Fweb",
+                file: Some("patches/lodash.patch"),
+            },
+            TestCase {
+                locator: "is-odd@patch:is-odd@npm%3A3.0.1#~/.yarn/patches/is-odd.patch::version=3.\
+                          0.1&hash=9b90ad",
+                file: Some(".yarn/patches/is-odd.patch"),
+            },
+            TestCase {
+                locator: "lodash@npm:4.17.21",
+                file: None,
+            },
+        ];
+        for tc in test_cases {
+            let locator = Locator::try_from(tc.locator).unwrap();
+            assert_eq!(
+                locator.project_patch_file().as_deref(),
+                tc.file,
+                "{}",
+                tc.locator
+            );
+        }
+    }
 }
@@ -341,13 +341,15 @@ impl BerryLockfile {
         for patch in patches.values() {
             let patch_descriptors = reverse_lookup
                 .get(patch)
-                .unwrap_or_else(|| panic!("Unable to find {patch} in reverse lookup"));
+                .ok_or_else(|| Error::MissingPackageForLocator(patch.as_owned()))?;
 
             // For each patch descriptor we extract the primary descriptor that each patch
             // descriptor targets and check if that descriptor is present in the
             // pruned map and add it if it is present
             for patch_descriptor in patch_descriptors {
-                let version = patch_descriptor.primary_version().unwrap();
+                let version = patch_descriptor
+                    .primary_version()
+                    .ok_or_else(|| Error::InvalidPatchReference(patch_descriptor.to_string()))?;
                 let primary_descriptor = Descriptor {
                     ident: patch_descriptor.ident.clone(),
                     range: version.into(),
@@ -406,7 +408,19 @@ impl BerryLockfile {
         }
 
         // TODO Could we dedupe and wrap in Rc?
-        Ok(dependency.into_owned())
+        let dependency = dependency.into_owned();
+
+        // Dependencies on paths relative to the package are bound to the package's
+        // locator in the lockfile
+        if !self.resolutions.contains_key(&dependency) {
+            if let Some(bound) = dependency.bind(locator) {
+                if self.resolutions.contains_key(&bound) {
+                    return Ok(bound);
+                }
+            }
+        }
+
+        Ok(dependency)
     }
 
     fn locator_for_workspace_path(&self, workspace_path: &str) -> Option<&Locator> {
@@ -503,9 +517,9 @@ impl Lockfile for BerryLockfile {
         let mut patches = self
             .patches
             .values()
-            .filter_map(|patch| patch.patch_file())
+            .filter_map(|patch| patch.project_patch_file())
             .filter(|path| !Locator::is_patch_builtin(path))
-            .map(|s| RelativeUnixPathBuf::new(s.to_string()))
+            .map(|s| RelativeUnixPathBuf::new(s.into_owned()))
             .collect::<Result<Vec<_>, turbopath::PathError>>()?;
         patches.sort();
         Ok(patches)
@@ -1159,6 +1173,80 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_relative_protocols() {
+        let data = LockfileData::from_bytes(include_bytes!(
+            "../../fixtures/yarn4-relative-protocols.lock"
+        ))
+        .unwrap();
+        let lockfile = BerryLockfile::new(data, None).unwrap();
+
+        let portal_key =
+            "local-utils@portal:../../tools/local-utils::locator=web%40workspace%3Aapps%2Fweb";
+        let patch_key = "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch::version=4.17.\
+                         21&hash=7d1ac5&locator=web%40workspace%3Aapps%2Fweb";
+
+        let deps = transitive_closure(
+            &lockfile,
+            "apps/web",
+            vec![
+                ("local-utils", "portal:../../tools/local-utils"),
+                (
+                    "lodash",
+                    "patch:lodash@npm%3A4.17.21#./patches/lodash.patch",
+                ),
+                ("ui", "workspace:*"),
+            ]
+            .into_iter()
+            .map(|(name, range)| (name.to_string(), range.to_string()))
+            .collect(),
+            false,
+        )
+        .unwrap();
+        let mut keys = deps.iter().map(|pkg| pkg.key.as_str()).collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "is-number@npm:7.0.0",
+                portal_key,
+                patch_key,
+                "ui@workspace:packages/ui",
+            ]
+        );
+
+        let subgraph = lockfile
+            .subgraph(
+                &["apps/web".into(), "packages/ui".into()],
+                &keys.iter().map(|key| key.to_string()).collect::<Vec<_>>(),
+            )
+            .unwrap();
+        let sublockfile = subgraph.lockfile().unwrap();
+        let mut entries = sublockfile.packages.keys().cloned().collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![
+                "is-number@npm:^7.0.0".to_string(),
+                "local-utils@portal:../../tools/local-utils::locator=web%40workspace%3Aapps%2Fweb"
+                    .to_string(),
+                "lodash@npm:4.17.21".to_string(),
+                "lodash@patch:lodash@npm%3A4.17.21#./patches/lodash.patch::locator=web%\
+                 40workspace%3Aapps%2Fweb"
+                    .to_string(),
+                "ui@workspace:*, ui@workspace:packages/ui".to_string(),
+                "web@workspace:apps/web".to_string(),
+                "yarn4-relative-protocols@workspace:.".to_string(),
+            ]
+        );
+
+        // Patches are resolved from the workspace they're bound to
+        assert_eq!(
+            subgraph.patches().unwrap(),
+            vec![RelativeUnixPathBuf::new("apps/web/patches/lodash.patch").unwrap()]
+        );
+    }
+
     #[test]
     fn test_turbo_version() {
         let data = LockfileData::from_bytes(include_bytes!("../../fixtures/berry.lock")).unwrap();
@@ -5,6 +5,10 @@ use serde::Deserialize;
 use crate::Lockfile;
 
 mod de;
+mod ser;
+mod text;
+
+pub use text::BunTextLockfile;
 
 type Map<K, V> = std::collections::BTreeMap<K, V>;
 
@@ -16,7 +20,14 @@ pub enum Error {
     SymlStructure(#[from] serde_json::Error),
     #[error("unexpected non-utf8 yarn.lock")]
     NonUTF8(#[from] std::str::Utf8Error),
-    #[error("Turborepo cannot serialize Bun lockfiles.")]
+    #[error("unable to parse bun.lock: {0}")]
+    TextParse(serde_json::Error),
+    #[error("bun.lock entry for '{0}' is missing its identifier")]
+    InvalidPackage(String),
+    #[error(
+        "Turborepo cannot serialize binary Bun lockfiles. Run `bun install --save-text-lockfile` \
+         to switch to bun.lock."
+    )]
     NotImplemented(),
 }
 
@@ -0,0 +1,230 @@
+use std::io;
+
+use serde::Serialize;
+use serde_json::ser::Formatter;
+
+const INDENT: &[u8] = b"  ";
+
+/// Serializes a value in the layout Bun writes `bun.lock` in: objects and
+/// top level arrays are spread over multiple lines with trailing commas,
+/// while the arrays describing each package are kept on a single line and
+/// separated by blank lines.
+pub fn to_vec<T: Serialize>(value: &T) -> Result<Vec<u8>, serde_json::Error> {
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, BunFormatter::default());
+    value.serialize(&mut serializer)?;
+    out.push(b'\n');
+    Ok(out)
+}
+
+#[derive(Debug, Default)]
+struct BunFormatter {
+    frames: Vec<Frame>,
+    // Set after a single line package entry to separate it from the next one
+    blank_line: bool,
+}
+
+#[derive(Debug)]
+struct Frame {
+    inline: bool,
+    has_entries: bool,
+}
+
+impl BunFormatter {
+    // Arrays nested in an object field are the package entries, which are
+    // written on a single line along with everything in them
+    fn push(&mut self, is_array: bool) {
+        let inline = self.inline() || (is_array && self.frames.len() >= 2);
+        self.frames.push(Frame {
+            inline,
+            has_entries: false,
+        });
+    }
+
+    fn pop<W: ?Sized + io::Write>(&mut self, writer: &mut W, close: &[u8]) -> io::Result<()> {
+        let frame = self.frames.pop().expect("unbalanced json value");
+        if frame.has_entries {
+            if frame.inline {
+                // Arrays are written as `[a, b]` while objects are `{ a, b }`
+                if close == b"}" {
+                    writer.write_all(b" ")?;
+                }
+            } else {
+                writer.write_all(b"\n")?;
+                self.indent(writer, self.frames.len())?;
+            }
+        }
+        writer.write_all(close)?;
+        self.blank_line = frame.inline && !self.inline();
+        Ok(())
+    }
+
+    fn inline(&self) -> bool {
+        self.frames.last().map_or(false, |frame| frame.inline)
+    }
+
+    fn indent<W: ?Sized + io::Write>(&self, writer: &mut W, depth: usize) -> io::Result<()> {
+        for _ in 0..depth {
+            writer.write_all(INDENT)?;
+        }
+        Ok(())
+    }
+
+    fn begin_entry<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+        inline_separator: &[u8],
+    ) -> io::Result<()> {
+        let depth = self.frames.len();
+        let frame = self.frames.last_mut().expect("entry outside of json value");
+        frame.has_entries = true;
+        if frame.inline {
+            writer.write_all(if first { inline_separator } else { b", " })
+        } else {
+            // Bun doesn't write a trailing comma after the last top level field
+            if depth == 1 && !first {
+                writer.write_all(b",")?;
+            }
+            if std::mem::take(&mut self.blank_line) {
+                writer.write_all(b"\n")?;
+            }
+            writer.write_all(b"\n")?;
+            self.indent(writer, depth)
+        }
+    }
+
+    fn end_entry<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.frames.len() > 1 && !self.inline() {
+            writer.write_all(b",")?;
+        }
+        Ok(())
+    }
+}
+
+impl Formatter for BunFormatter {
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.push(true);
+        writer.write_all(b"[")
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.pop(writer, b"]")
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.begin_entry(writer, first, b"")
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end_entry(writer)
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.push(false);
+        writer.write_all(b"{")
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.pop(writer, b"}")
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.begin_entry(writer, first, b" ")
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(b": ")
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.end_entry(writer)
+    }
+}
+
+/// Removes the trailing commas Bun writes so the lockfile can be parsed as
+/// JSON
+pub fn strip_trailing_commas(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.char_indices();
+    let mut in_string = false;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => in_string = !in_string,
+            '\\' if in_string => {
+                output.push(c);
+                if let Some((_, escaped)) = chars.next() {
+                    output.push(escaped);
+                }
+                continue;
+            }
+            ',' if !in_string => {
+                let next = input[i + 1..].trim_start().chars().next();
+                if matches!(next, Some('}' | ']')) {
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        output.push(c);
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_strip_trailing_commas() {
+        assert_eq!(
+            strip_trailing_commas(r#"{ "a": ["b", "c,",], "d\",": {}, }"#),
+            r#"{ "a": ["b", "c,"], "d\",": {} }"#
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let value = json!({
+            "a": 1,
+            "b": { "c": { "d": "e" }, "f": {} },
+            "g": ["h"],
+            "packages": {
+                "i": ["i@1.0.0", "", { "dependencies": { "j": "1" } }, "sha"],
+                "k": ["k@workspace:k"],
+            },
+        });
+        let expected = r#"{
+  "a": 1,
+  "b": {
+    "c": {
+      "d": "e",
+    },
+    "f": {},
+  },
+  "g": [
+    "h",
+  ],
+  "packages": {
+    "i": ["i@1.0.0", "", { "dependencies": { "j": "1" } }, "sha"],
+
+    "k": ["k@workspace:k"],
+  }
+}
+"#;
+        assert_eq!(
+            String::from_utf8(to_vec(&value).unwrap()).unwrap(),
+            expected
+        );
+    }
+}
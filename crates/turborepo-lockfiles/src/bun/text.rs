@@ -0,0 +1,452 @@
+use std::{any::Any, collections::HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use turbopath::RelativeUnixPathBuf;
+
+use super::{ser, Error, Map};
+use crate::{Lockfile, Package};
+
+/// The text lockfile, `bun.lock`, written by Bun 1.2 and newer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BunTextLockfile {
+    lockfile_version: i32,
+    workspaces: Map<String, WorkspaceEntry>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    patched_dependencies: Map<String, RelativeUnixPathBuf>,
+    // Fields that affect resolution such as overrides and catalogs, we keep
+    // them as raw values to avoid describing the correct schema.
+    #[serde(flatten)]
+    other: Map<String, Value>,
+    packages: Map<String, PackageEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bin: Option<Value>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    dev_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    optional_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    peer_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    optional_peers: Vec<String>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+/// A package is stored as an array starting with its identifier, e.g.
+/// `["react@18.3.1", "", { ..info }, "sha512-.."]`. What follows depends on
+/// where the package comes from, so everything other than the info object is
+/// kept as is.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+struct PackageEntry(Vec<PackageField>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+enum PackageField {
+    Info(PackageInfo),
+    Other(Value),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageInfo {
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    dev_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    optional_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    peer_dependencies: Map<String, String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    optional_peers: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bin: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    os: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu: Option<Value>,
+    #[serde(flatten)]
+    other: Map<String, Value>,
+}
+
+impl BunTextLockfile {
+    pub fn from_bytes(input: &[u8]) -> Result<Self, crate::Error> {
+        let input = std::str::from_utf8(input).map_err(Error::from)?;
+        let lockfile: Self =
+            serde_json::from_str(&ser::strip_trailing_commas(input)).map_err(Error::TextParse)?;
+        if let Some((key, _)) = lockfile
+            .packages
+            .iter()
+            .find(|(_, entry)| entry.ident().is_none())
+        {
+            return Err(Error::InvalidPackage(key.clone()).into());
+        }
+        Ok(lockfile)
+    }
+
+    /// Returns if the contents are a text lockfile instead of the output of
+    /// `bun bun.lockb`
+    pub fn is_text(input: &[u8]) -> bool {
+        input
+            .iter()
+            .find(|byte| !byte.is_ascii_whitespace())
+            .map_or(false, |byte| *byte == b'{')
+    }
+
+    fn package(&self, key: &str) -> Option<Package> {
+        let entry = self.packages.get(key)?;
+        Some(Package::new(key, entry.version()?))
+    }
+
+    // Packages that can't use the hoisted version of a dependency get their
+    // own copy, keyed by the path to them, e.g. `next/postcss` or
+    // `web/react` for a copy only used by the `web` workspace. We check the
+    // most nested location first, just like node's module resolution.
+    fn possible_keys(key: &str, dependency: &str) -> Vec<String> {
+        let mut possible_keys = vec![format!("{key}/{dependency}")];
+        let mut curr = Some(key);
+        while let Some(key) = curr {
+            curr = key_parent(key);
+            possible_keys.push(match curr {
+                Some(parent) => format!("{parent}/{dependency}"),
+                None => dependency.to_string(),
+            });
+        }
+        possible_keys
+    }
+}
+
+impl Lockfile for BunTextLockfile {
+    #[tracing::instrument(skip(self))]
+    fn resolve_package(
+        &self,
+        workspace_path: &str,
+        name: &str,
+        version: &str,
+    ) -> Result<Option<Package>, crate::Error> {
+        let workspace = self
+            .workspaces
+            .get(workspace_path)
+            .ok_or_else(|| crate::Error::MissingWorkspace(workspace_path.to_string()))?;
+
+        // Dependencies returned by `all_dependencies` are already resolved to a
+        // key and carry the exact version of that entry. A workspace only has
+        // its own copy if the hoisted version doesn't satisfy it, so an exact
+        // match can't be meant for a workspace specific copy.
+        if let Some(package) = self.package(name).filter(|pkg| pkg.version == version) {
+            return Ok(Some(package));
+        }
+
+        let workspace_key = workspace
+            .name
+            .as_ref()
+            .map(|workspace_name| format!("{workspace_name}/{name}"));
+        Ok(workspace_key
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .find_map(|key| self.package(key)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    fn all_dependencies(&self, key: &str) -> Result<Option<HashMap<String, String>>, crate::Error> {
+        let Some(entry) = self.packages.get(key) else {
+            return Ok(None);
+        };
+        let Some(info) = entry.info() else {
+            return Ok(None);
+        };
+
+        // Optional dependencies for other platforms and unmet optional peers
+        // aren't in the lockfile, so missing entries are skipped.
+        let deps: HashMap<_, _> = info
+            .dep_names()
+            .filter_map(|dependency| {
+                Self::possible_keys(key, dependency)
+                    .into_iter()
+                    .find_map(|possible_key| self.package(&possible_key))
+            })
+            .map(|package| (package.key, package.version))
+            .collect();
+        Ok((!deps.is_empty()).then_some(deps))
+    }
+
+    fn subgraph(
+        &self,
+        workspace_packages: &[String],
+        packages: &[String],
+    ) -> Result<Box<dyn Lockfile>, crate::Error> {
+        let mut workspaces = Map::new();
+        let mut pruned_packages = Map::new();
+        for workspace_path in
+            std::iter::once("").chain(workspace_packages.iter().map(String::as_str))
+        {
+            let workspace = self
+                .workspaces
+                .get(workspace_path)
+                .ok_or_else(|| crate::Error::MissingWorkspace(workspace_path.to_string()))?;
+            workspaces.insert(workspace_path.to_string(), workspace.clone());
+
+            // Workspaces also appear as packages so other workspaces can depend on them
+            if let Some(name) = &workspace.name {
+                let ident = format!("{name}@workspace:{workspace_path}");
+                if let Some(entry) = self
+                    .packages
+                    .get(name)
+                    .filter(|entry| entry.ident() == Some(&ident))
+                {
+                    pruned_packages.insert(name.clone(), entry.clone());
+                }
+            }
+        }
+        for key in packages {
+            let entry = self
+                .packages
+                .get(key)
+                .ok_or_else(|| crate::Error::MissingPackage(key.clone()))?;
+            pruned_packages.insert(key.clone(), entry.clone());
+        }
+
+        let patched_dependencies = self
+            .patched_dependencies
+            .iter()
+            .filter(|(ident, _)| {
+                pruned_packages
+                    .values()
+                    .any(|entry| entry.ident() == Some(ident.as_str()))
+            })
+            .map(|(ident, patch)| (ident.clone(), patch.clone()))
+            .collect();
+
+        Ok(Box::new(Self {
+            lockfile_version: self.lockfile_version,
+            workspaces,
+            patched_dependencies,
+            other: self.other.clone(),
+            packages: pruned_packages,
+        }))
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, crate::Error> {
+        Ok(ser::to_vec(self)?)
+    }
+
+    fn patches(&self) -> Result<Vec<RelativeUnixPathBuf>, crate::Error> {
+        Ok(self.patched_dependencies.values().cloned().collect())
+    }
+
+    fn global_change(&self, other: &dyn Lockfile) -> bool {
+        let any_other = other as &dyn Any;
+        // Downcast returns none if the concrete type doesn't match
+        // if the types don't match then we changed package managers
+        any_other.downcast_ref::<Self>().map_or(true, |other| {
+            self.lockfile_version != other.lockfile_version || self.other != other.other
+        })
+    }
+
+    fn turbo_version(&self) -> Option<String> {
+        self.packages.get("turbo")?.version().map(str::to_string)
+    }
+}
+
+impl PackageEntry {
+    fn ident(&self) -> Option<&str> {
+        match self.0.first()? {
+            PackageField::Other(Value::String(ident)) => Some(ident),
+            _ => None,
+        }
+    }
+
+    fn version(&self) -> Option<&str> {
+        let (_, version) = self.ident()?.rsplit_once('@')?;
+        Some(version)
+    }
+
+    fn info(&self) -> Option<&PackageInfo> {
+        self.0.iter().find_map(|field| match field {
+            PackageField::Info(info) => Some(info),
+            PackageField::Other(_) => None,
+        })
+    }
+}
+
+impl PackageInfo {
+    fn dep_names(&self) -> impl Iterator<Item = &str> {
+        self.dependencies
+            .keys()
+            .chain(self.optional_dependencies.keys())
+            .chain(self.peer_dependencies.keys())
+            .map(String::as_str)
+    }
+}
+
+// Returns the key of the package a nested key is under, a scoped package
+// name takes up two segments of the key.
+fn key_parent(key: &str) -> Option<&str> {
+    let (rest, _) = key.rsplit_once('/')?;
+    match rest.rsplit_once('/') {
+        Some((parent, scope)) if scope.starts_with('@') => Some(parent),
+        None if rest.starts_with('@') => None,
+        _ => Some(rest),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    use super::*;
+    use crate::transitive_closure;
+
+    const LOCKFILE: &[u8] = include_bytes!("../../fixtures/bun.lock").as_slice();
+
+    #[test_case("react", None ; "top level")]
+    #[test_case("@babel/core", None ; "scoped")]
+    #[test_case("next/postcss", Some("next") ; "nested")]
+    #[test_case("@babel/core/semver", Some("@babel/core") ; "nested under scoped")]
+    #[test_case("web/@types/node", Some("web") ; "scoped nested")]
+    #[test_case("@a/b/@c/d", Some("@a/b") ; "scoped nested under scoped")]
+    fn test_key_parent(key: &str, expected: Option<&str>) {
+        assert_eq!(key_parent(key), expected);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let lockfile = BunTextLockfile::from_bytes(LOCKFILE).unwrap();
+        assert_eq!(
+            std::str::from_utf8(&lockfile.encode().unwrap()).unwrap(),
+            std::str::from_utf8(LOCKFILE).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_is_text() {
+        assert!(BunTextLockfile::is_text(LOCKFILE));
+        assert!(!BunTextLockfile::is_text(include_bytes!(
+            "../../fixtures/yarn1.lock"
+        )));
+    }
+
+    #[test_case(
+        "apps/web",
+        &[("react", "^19.0.0"), ("lodash", "^4.17.21"), ("is-number", "^7.0.0")],
+        &[("web/react", "19.0.0"), ("lodash", "4.17.21"), ("is-number", "7.0.0")]
+        ; "workspace specific version"
+    )]
+    #[test_case(
+        "apps/docs",
+        &[("react", "^18.2.0"), ("is-odd", "3.0.1")],
+        &[
+            ("react", "18.3.1"),
+            ("loose-envify", "1.4.0"),
+            ("js-tokens", "4.0.0"),
+            ("is-odd", "3.0.1"),
+            ("is-odd/is-number", "6.0.0"),
+        ]
+        ; "nested dependency"
+    )]
+    #[test_case(
+        "",
+        &[("turbo", "^2.3.3")],
+        &[("turbo", "2.3.3"), ("turbo-darwin-arm64", "2.3.3"), ("turbo-linux-64", "2.3.3")]
+        ; "optional dependencies"
+    )]
+    fn test_transitive_closure(
+        workspace: &str,
+        unresolved: &[(&str, &str)],
+        expected: &[(&str, &str)],
+    ) {
+        let lockfile = BunTextLockfile::from_bytes(LOCKFILE).unwrap();
+        let closure = transitive_closure(
+            &lockfile,
+            workspace,
+            unresolved
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+            false,
+        )
+        .unwrap();
+        let expected: HashSet<_> = expected
+            .iter()
+            .map(|(key, version)| Package::new(*key, *version))
+            .collect();
+        assert_eq!(closure, expected);
+    }
+
+    #[test]
+    fn test_missing_workspace() {
+        let lockfile = BunTextLockfile::from_bytes(LOCKFILE).unwrap();
+        assert!(matches!(
+            lockfile.resolve_package("apps/missing", "react", "^18.2.0"),
+            Err(crate::Error::MissingWorkspace(_))
+        ));
+    }
+
+    #[test]
+    fn test_subgraph() {
+        let lockfile = BunTextLockfile::from_bytes(LOCKFILE).unwrap();
+        let subgraph = lockfile
+            .subgraph(
+                &["apps/docs".into(), "packages/ui".into()],
+                &[
+                    "react".into(),
+                    "loose-envify".into(),
+                    "js-tokens".into(),
+                    "is-odd".into(),
+                    "is-odd/is-number".into(),
+                ],
+            )
+            .unwrap();
+        let subgraph = BunTextLockfile::from_bytes(&subgraph.encode().unwrap()).unwrap();
+
+        assert_eq!(
+            subgraph.workspaces.keys().collect::<Vec<_>>(),
+            ["", "apps/docs", "packages/ui"]
+        );
+        assert_eq!(
+            subgraph.packages.keys().collect::<Vec<_>>(),
+            [
+                "@repo/ui",
+                "docs",
+                "is-odd",
+                "is-odd/is-number",
+                "js-tokens",
+                "loose-envify",
+                "react"
+            ]
+        );
+        assert_eq!(
+            subgraph.patches().unwrap(),
+            [RelativeUnixPathBuf::new("patches/is-odd@3.0.1.patch").unwrap()]
+        );
+
+        let web = lockfile
+            .subgraph(&["apps/web".into()], &["web/react".into()])
+            .unwrap();
+        assert!(web.patches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_turbo_version() {
+        let lockfile = BunTextLockfile::from_bytes(LOCKFILE).unwrap();
+        assert_eq!(lockfile.turbo_version().as_deref(), Some("2.3.3"));
+    }
+}
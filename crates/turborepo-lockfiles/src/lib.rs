@@ -16,7 +16,7 @@ use std::{
 };
 
 pub use berry::{Error as BerryError, *};
-pub use bun::BunLockfile;
+pub use bun::{BunLockfile, BunTextLockfile};
 pub use error::Error;
 pub use npm::*;
 pub use pnpm::{pnpm_global_change, pnpm_subgraph, PnpmLockfile};
@@ -69,6 +69,7 @@ pub trait Lockfile: Send + Sync + Any + std::fmt::Debug {
 
 /// Takes a lockfile, and a map of workspace directory paths -> (package name,
 /// version) and calculates the transitive closures for all of them
+#[tracing::instrument(skip_all)]
 pub fn all_transitive_closures<L: Lockfile + ?Sized>(
     lockfile: &L,
     workspaces: HashMap<String, HashMap<String, String>>,
@@ -89,7 +90,7 @@ pub fn all_transitive_closures<L: Lockfile + ?Sized>(
 }
 
 // this should get replaced by petgraph in the future :)
-#[tracing::instrument(skip_all)]
+#[tracing::instrument(skip(lockfile, unresolved_deps))]
 pub fn transitive_closure<L: Lockfile + ?Sized>(
     lockfile: &L,
     workspace_path: &str,
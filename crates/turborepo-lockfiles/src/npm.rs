@@ -135,7 +135,10 @@ impl Lockfile for NpmLockfile {
     }
 
     fn encode(&self) -> Result<Vec<u8>, crate::Error> {
-        Ok(serde_json::to_vec_pretty(&self)?)
+        let mut contents = serde_json::to_vec_pretty(&self)?;
+        // npm always ends the lockfile with a newline
+        contents.push(b'\n');
+        Ok(contents)
     }
 
     fn global_change(&self, other: &dyn Lockfile) -> bool {
@@ -456,6 +459,54 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_subgraph() -> Result<(), Error> {
+        let lockfile = NpmLockfile::load(include_bytes!("../fixtures/npm-lock.json"))?;
+        let web_deps = [("lodash", "^4.17.21"), ("react", "18.2.0"), ("ui", "*")]
+            .into_iter()
+            .map(|(name, range)| (name.to_string(), range.to_string()))
+            .collect();
+        let closure = crate::transitive_closure(&lockfile, "apps/web", web_deps, false)?;
+        let mut keys = closure
+            .iter()
+            .map(|pkg| pkg.key.clone())
+            .collect::<Vec<_>>();
+        keys.sort();
+
+        let subgraph = lockfile.subgraph(&["apps/web".into(), "packages/ui".into()], &keys)?;
+        let contents = subgraph.encode()?;
+        assert!(contents.ends_with(b"}\n"));
+
+        let pruned = NpmLockfile::load(&contents)?;
+        assert_eq!(pruned.lockfile_version, 3);
+        for key in [
+            "",
+            "apps/web",
+            "apps/web/node_modules/lodash",
+            "node_modules/web",
+            "node_modules/react",
+            "packages/ui",
+            "node_modules/ui",
+        ] {
+            assert!(pruned.packages.contains_key(key), "missing {key}");
+        }
+        // The docs workspace and the lodash version hoisted for it are removed
+        for key in ["apps/docs", "node_modules/docs", "node_modules/lodash"] {
+            assert!(!pruned.packages.contains_key(key), "unexpected {key}");
+        }
+        // Every dependency of the workspace still resolves to the same package
+        let web_deps = [("lodash", "^4.17.21"), ("react", "18.2.0"), ("ui", "*")]
+            .into_iter()
+            .map(|(name, range)| (name.to_string(), range.to_string()))
+            .collect();
+        assert_eq!(
+            crate::transitive_closure(&pruned, "apps/web", web_deps, false)?,
+            closure
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_turbo_version() -> Result<(), Error> {
         let lockfile = NpmLockfile::load(include_bytes!("../fixtures/npm-lock.json"))?;
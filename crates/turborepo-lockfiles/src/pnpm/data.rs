@@ -20,15 +20,22 @@ pub struct PnpmLockfile {
     lockfile_version: LockfileVersion,
     #[serde(skip_serializing_if = "Option::is_none")]
     settings: Option<LockfileSettings>,
+    // Catalog name to the specifier and resolved version of each of its entries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    catalogs: Option<Map<String, Map<String, Dependency>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     never_built_dependencies: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     only_built_dependencies: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    ignored_optional_dependencies: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     overrides: Option<Map<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     package_extensions_checksum: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pnpmfile_checksum: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     patched_dependencies: Option<Map<String, PatchFile>>,
     importers: Map<String, ProjectSnapshot>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -166,8 +173,13 @@ pub struct PackageResolution {
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 #[serde(rename_all = "camelCase")]
 struct LockfileSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
     auto_install_peers: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     exclude_links_from_lockfile: Option<bool>,
+    // Added in pnpm 10
+    #[serde(skip_serializing_if = "Option::is_none")]
+    inject_workspace_packages: Option<bool>,
 }
 
 impl PnpmLockfile {
@@ -220,8 +232,16 @@ impl PnpmLockfile {
         if matches!(self.lockfile_version.format, super::VersionFormat::Float) {
             return SupportedLockfileVersion::V5;
         }
-        match self.lockfile_version.version.as_str() {
-            "7.0" | "9.0" => SupportedLockfileVersion::V7AndV9,
+        // pnpm 10 still writes lockfile version 9.0, compare against the major
+        // version so future minor bumps keep the same layout
+        let major = self
+            .lockfile_version
+            .version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok());
+        match major {
+            Some(major) if major >= 7 => SupportedLockfileVersion::V7AndV9,
             _ => SupportedLockfileVersion::V6,
         }
     }
@@ -296,23 +316,56 @@ impl PnpmLockfile {
         for dependency in pruned_packages.keys() {
             let dp = DepPath::parse(self.version(), dependency.as_str())?;
             let patch_key = format!("{}@{}", dp.name, dp.version);
-            if let Some(patch) = patches.get(&patch_key).filter(|patch| {
+            if matches!(self.version(), SupportedLockfileVersion::V7AndV9) {
                 // In V7 patch hash isn't included in packages key, so no need to check
-                matches!(self.version(), SupportedLockfileVersion::V7AndV9)
-                    || dp.patch_hash() == Some(&patch.hash)
-            }) {
+                if let Some((key, patch)) = Self::find_patch(patches, dp.name, dp.version) {
+                    pruned_patches.insert(key.clone(), patch.clone());
+                }
+            } else if let Some(patch) = patches
+                .get(&patch_key)
+                .filter(|patch| dp.patch_hash() == Some(&patch.hash))
+            {
                 pruned_patches.insert(patch_key, patch.clone());
             }
         }
         Ok(pruned_patches)
     }
 
+    // Newer pnpm versions allow patches to apply to a range of versions or to
+    // every version of a package. Like pnpm, an exact version takes precedence
+    // over a range which takes precedence over the bare package name.
+    fn find_patch<'a>(
+        patches: &'a Map<String, PatchFile>,
+        name: &str,
+        version: &str,
+    ) -> Option<(&'a String, &'a PatchFile)> {
+        if let Some(exact) = patches.get_key_value(&format!("{name}@{version}")) {
+            return Some(exact);
+        }
+        let parsed_version = semver::Version::parse(version).ok();
+        let range = patches.iter().find(|(key, _)| {
+            let Some(range) = key
+                .strip_prefix(name)
+                .and_then(|rest| rest.strip_prefix('@'))
+            else {
+                return false;
+            };
+            match (&parsed_version, semver::VersionReq::parse(range)) {
+                (Some(version), Ok(range)) => range.matches(version),
+                _ => false,
+            }
+        });
+        range.or_else(|| patches.get_key_value(name))
+    }
+
     // Create a projection of all fields in the lockfile that could affect all
     // workspaces
     fn global_fields(&self) -> GlobalFields {
         GlobalFields {
             version: &self.lockfile_version.version,
             checksum: self.package_extensions_checksum.as_deref(),
+            pnpmfile_checksum: self.pnpmfile_checksum.as_deref(),
+            ignored_optional_dependencies: self.ignored_optional_dependencies.as_deref(),
             overrides: self.overrides.as_ref(),
             patched_dependencies: self.patched_dependencies.as_ref(),
             settings: self.settings.as_ref(),
@@ -358,6 +411,8 @@ impl PnpmLockfile {
 struct GlobalFields<'a> {
     version: &'a str,
     checksum: Option<&'a str>,
+    pnpmfile_checksum: Option<&'a str>,
+    ignored_optional_dependencies: Option<&'a [String]>,
     overrides: Option<&'a BTreeMap<String, String>>,
     patched_dependencies: Option<&'a BTreeMap<String, PatchFile>>,
     settings: Option<&'a LockfileSettings>,
@@ -480,10 +535,13 @@ impl crate::Lockfile for PnpmLockfile {
                 true => None,
             },
             lockfile_version: self.lockfile_version.clone(),
+            catalogs: self.catalogs.clone(),
             never_built_dependencies: self.never_built_dependencies.clone(),
             only_built_dependencies: self.only_built_dependencies.clone(),
+            ignored_optional_dependencies: self.ignored_optional_dependencies.clone(),
             overrides: self.overrides.clone(),
             package_extensions_checksum: self.package_extensions_checksum.clone(),
+            pnpmfile_checksum: self.pnpmfile_checksum.clone(),
             patched_dependencies: patches,
             snapshots: pruned_snapshots,
             time: None,
@@ -590,6 +648,8 @@ pub fn pnpm_global_change(
     let curr_data = PnpmLockfile::from_bytes(curr_contents)?;
     Ok(prev_data.lockfile_version != curr_data.lockfile_version
         || prev_data.package_extensions_checksum != curr_data.package_extensions_checksum
+        || prev_data.pnpmfile_checksum != curr_data.pnpmfile_checksum
+        || prev_data.ignored_optional_dependencies != curr_data.ignored_optional_dependencies
         || prev_data.overrides != curr_data.overrides
         || prev_data.patched_dependencies != curr_data.patched_dependencies
         || prev_data.settings != curr_data.settings)
@@ -620,6 +680,7 @@ mod tests {
     const PNPM_V7_PEER: &[u8] = include_bytes!("../../fixtures/pnpm-v7-peer.yaml").as_slice();
     const PNPM_V7_PATCH: &[u8] = include_bytes!("../../fixtures/pnpm-v7-patch.yaml").as_slice();
     const PNPM_V9: &[u8] = include_bytes!("../../fixtures/pnpm-v9.yaml").as_slice();
+    const PNPM_V10: &[u8] = include_bytes!("../../fixtures/pnpm-v10.yaml").as_slice();
     const PNPM6_TURBO: &[u8] = include_bytes!("../../fixtures/pnpm6turbo.yaml").as_slice();
     const PNPM8_TURBO: &[u8] = include_bytes!("../../fixtures/pnpm8turbo.yaml").as_slice();
 
@@ -634,6 +695,7 @@ mod tests {
     #[test_case(PNPM_V7_PEER)]
     #[test_case(PNPM_V7_PATCH)]
     #[test_case(PNPM_V9)]
+    #[test_case(PNPM_V10)]
     fn test_roundtrip(fixture: &[u8]) {
         let lockfile = PnpmLockfile::from_bytes(fixture).unwrap();
         let serialized_lockfile = serde_yaml::to_string(&lockfile).unwrap();
@@ -1260,10 +1322,143 @@ c:
         );
     }
 
+    #[test]
+    fn test_lockfile_v10_closures() {
+        let lockfile = PnpmLockfile::from_bytes(PNPM_V10).unwrap();
+        assert_eq!(lockfile.version(), SupportedLockfileVersion::V7AndV9);
+        let mut workspaces = HashMap::new();
+        workspaces.insert(
+            "apps/web".into(),
+            vec![
+                ("react", "catalog:"),
+                ("react-dom", "^18.3.1"),
+                ("is-odd", "^3.0.1"),
+            ]
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect(),
+        );
+        let closures = crate::all_transitive_closures(&lockfile, workspaces, false).unwrap();
+        let closure = closures
+            .get("apps/web")
+            .unwrap()
+            .iter()
+            .cloned()
+            .sorted()
+            .collect::<Vec<_>>();
+        assert_eq!(
+            closure,
+            vec![
+                Package::new("is-number@6.0.0", "6.0.0"),
+                Package::new(
+                    "is-odd@3.0.1(patch_hash=\
+                     ea24eb27ee7aa31a3fb48e4a3b379af766342829c4d79778ae8f204ccb26cd79)",
+                    "3.0.1(patch_hash=\
+                     ea24eb27ee7aa31a3fb48e4a3b379af766342829c4d79778ae8f204ccb26cd79)"
+                ),
+                Package::new("js-tokens@4.0.0", "4.0.0"),
+                Package::new("loose-envify@1.4.0", "1.4.0"),
+                Package::new("react-dom@18.3.1(react@18.3.1)", "18.3.1(react@18.3.1)"),
+                Package::new("react@18.3.1", "18.3.1"),
+                Package::new("scheduler@0.23.2", "0.23.2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lockfile_v10_subgraph() {
+        let lockfile = PnpmLockfile::from_bytes(PNPM_V10).unwrap();
+        let pruned_lockfile = lockfile
+            .subgraph(
+                &["packages/ui".into()],
+                &["lodash@4.17.21(patch_hash=\
+                   ffc45bb930763f935650eb5b8b2148a49dfa3c59efdf855827be577a6f56f96f)"
+                    .into()],
+            )
+            .unwrap();
+        assert_eq!(
+            pruned_lockfile.patches().unwrap(),
+            vec![RelativeUnixPathBuf::new("patches/lodash@4.17.21.patch").unwrap()]
+        );
+
+        // Fields that apply to the whole workspace are preserved so pnpm
+        // doesn't consider the pruned lockfile out of date
+        let pruned_lockfile = PnpmLockfile::from_bytes(&pruned_lockfile.encode().unwrap()).unwrap();
+        assert_eq!(pruned_lockfile.catalogs, lockfile.catalogs);
+        assert_eq!(
+            pruned_lockfile.pnpmfile_checksum,
+            lockfile.pnpmfile_checksum
+        );
+        assert_eq!(
+            pruned_lockfile.ignored_optional_dependencies,
+            lockfile.ignored_optional_dependencies
+        );
+        assert_eq!(
+            pruned_lockfile.settings.unwrap().inject_workspace_packages,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_lockfile_v10_patch_without_version() {
+        let lockfile = PnpmLockfile::from_bytes(PNPM_V10).unwrap();
+        let pruned_lockfile = lockfile
+            .subgraph(
+                &["apps/web".into()],
+                &[
+                    "is-odd@3.0.1(patch_hash=\
+                     ea24eb27ee7aa31a3fb48e4a3b379af766342829c4d79778ae8f204ccb26cd79)"
+                        .into(),
+                    "is-number@6.0.0".into(),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            pruned_lockfile.patches().unwrap(),
+            vec![RelativeUnixPathBuf::new("patches/is-odd.patch").unwrap()]
+        );
+    }
+
+    #[test_case(&["lodash", "lodash@^4.0.0", "lodash@4.17.21"], Some("lodash@4.17.21") ; "exact version")]
+    #[test_case(&["lodash", "lodash@^4.0.0", "lodash@^3.0.0"], Some("lodash@^4.0.0") ; "range")]
+    #[test_case(&["lodash", "lodash@^3.0.0"], Some("lodash") ; "name only")]
+    #[test_case(&["lodash-es", "lodash@^3.0.0"], None ; "no match")]
+    fn test_find_patch(keys: &[&str], expected: Option<&str>) {
+        let patches = keys
+            .iter()
+            .map(|key| {
+                (
+                    key.to_string(),
+                    PatchFile {
+                        path: format!("patches/{key}.patch"),
+                        hash: "hash".into(),
+                    },
+                )
+            })
+            .collect();
+        let actual = PnpmLockfile::find_patch(&patches, "lodash", "4.17.21").map(|(key, _)| key);
+        assert_eq!(actual.map(String::as_str), expected);
+    }
+
+    #[test]
+    fn test_pnpmfile_checksum_global_change() {
+        let contents = std::str::from_utf8(PNPM_V10).unwrap();
+        let changed = contents.replace(
+            "pnpmfileChecksum: sha256-9QEjwBqXz9TzOrXiRC1SyB8hSMwzc1DkUBX5cmf1OdM=",
+            "pnpmfileChecksum: sha256-KmI3cQ2VXwvx2eBvD5FLMgwCVxKGqKy6I6Hx6YHZeKk=",
+        );
+        assert!(!pnpm_global_change(PNPM_V10, PNPM_V10).unwrap());
+        assert!(pnpm_global_change(PNPM_V10, changed.as_bytes()).unwrap());
+        let lockfile = PnpmLockfile::from_bytes(PNPM_V10).unwrap();
+        let changed = PnpmLockfile::from_bytes(changed.as_bytes()).unwrap();
+        assert!(lockfile.global_change(&changed));
+    }
+
     #[test_case(PNPM6, None ; "v6 missing")]
     #[test_case(PNPM6_TURBO, Some("2.0.3") ; "v6")]
     #[test_case(PNPM8_TURBO, Some("2.0.3") ; "v8")]
     #[test_case(PNPM_V9, Some("1.13.3-canary.1") ; "v9")]
+    #[test_case(PNPM_V10, Some("2.3.3") ; "v10")]
     fn test_turbo_version(lockfile: &[u8], expected: Option<&str>) {
         let lockfile = PnpmLockfile::from_bytes(lockfile).unwrap();
         assert_eq!(lockfile.turbo_version().as_deref(), expected);
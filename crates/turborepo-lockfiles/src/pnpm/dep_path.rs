@@ -196,6 +196,11 @@ mod tests {
         DepPath::new("eslint-module-utils", "2.8.0").with_peer_suffix(Some("(@typescript-eslint/parser@6.12.0(eslint@8.57.0)(typescript@5.3.3))"))
         ; "nested peer deps"
     )]
+    #[test_case(
+        "react-select@5.8.0(b1f1fba0d4cd4357f3db3cd5a41a7de0)",
+        DepPath::new("react-select", "5.8.0").with_peer_suffix(Some("(b1f1fba0d4cd4357f3db3cd5a41a7de0)"))
+        ; "hashed peer suffix"
+    )]
     fn dep_path_parse_v7_tests(s: &str, expected: DepPath) {
         let actual = parse_dep_path_v9(s).unwrap();
         assert_eq!(actual, expected);
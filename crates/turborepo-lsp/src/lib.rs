@@ -54,21 +54,8 @@ pub struct Backend {
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> LspResult<InitializeResult> {
         if let Some(uri) = params.root_uri {
-            if uri.scheme() != "file" {
-                return Err(Error::invalid_params("root is not a local file"));
-            }
-
-            let repo_root = uri
-                .to_file_path()
+            let repo_root = AbsoluteSystemPathBuf::from_file_url(uri.as_str())
                 .map_err(|_| Error::invalid_params("root is not a local file"))?;
-            let repo_root = repo_root
-                .as_os_str()
-                .to_str()
-                .ok_or(Error::invalid_params("root is not a valid utf-8 path"))?;
-
-            // convert uri file:///absolute-path to AbsoluteSystemPathBuf
-            let repo_root =
-                AbsoluteSystemPathBuf::new(repo_root).expect("file is always an absolute path");
 
             self.repo_root
                 .lock()
@@ -17,6 +17,7 @@ use path_clean::PathClean;
 use wax::CandidatePath;
 
 use crate::{
+    file_url::{self, Style},
     AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf, PathError, RelativeUnixPath,
 };
 
@@ -248,6 +249,27 @@ impl AbsoluteSystemPath {
         )
     }
 
+    /// Converts the path to a `file://` URL, percent-encoding any characters
+    /// that aren't allowed in a URL path. On Windows, drive letters become
+    /// `file:///C:/...` and UNC paths put the server in the URL's host.
+    ///
+    /// ```
+    /// use turbopath::AbsoluteSystemPath;
+    /// #[cfg(unix)]
+    /// assert_eq!(
+    ///     AbsoluteSystemPath::new("/my repo/index.js").unwrap().to_file_url(),
+    ///     "file:///my%20repo/index.js"
+    /// );
+    /// #[cfg(windows)]
+    /// assert_eq!(
+    ///     AbsoluteSystemPath::new("C:\\my repo\\index.js").unwrap().to_file_url(),
+    ///     "file:///C:/my%20repo/index.js"
+    /// );
+    /// ```
+    pub fn to_file_url(&self) -> String {
+        file_url::path_to_url(self.as_str(), Style::HOST)
+    }
+
     pub fn anchor(&self, path: &AbsoluteSystemPath) -> Result<AnchoredSystemPathBuf, PathError> {
         AnchoredSystemPathBuf::new(self, path)
     }
@@ -10,7 +10,10 @@ use fs_err as fs;
 use path_clean::PathClean;
 use serde::Serialize;
 
-use crate::{AbsoluteSystemPath, AnchoredSystemPathBuf, PathError};
+use crate::{
+    file_url::{self, Style},
+    AbsoluteSystemPath, AnchoredSystemPathBuf, PathError,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize)]
 pub struct AbsoluteSystemPathBuf(pub(crate) Utf8PathBuf);
@@ -75,6 +78,14 @@ impl AbsoluteSystemPathBuf {
         Ok(AbsoluteSystemPathBuf(unchecked_path.into()))
     }
 
+    /// Converts a `file://` URL to a path, decoding any percent-encoded
+    /// characters. `localhost` is treated as the local machine, other hosts
+    /// are only allowed on Windows where they refer to a UNC share. Errors if
+    /// the URL would decode to a path separator or NUL inside a segment.
+    pub fn from_file_url(url: &str) -> Result<Self, PathError> {
+        Self::new(file_url::url_to_path(url, Style::HOST)?)
+    }
+
     /// Takes in a system path of unknown type. If it's absolute, returns the
     /// path, If it's relative, appends it to the base after cleaning it.
     pub fn from_unknown(base: &AbsoluteSystemPath, unknown: impl Into<Utf8PathBuf>) -> Self {
@@ -0,0 +1,209 @@
+//! Conversions between absolute system paths and `file://` URLs.
+//!
+//! Both directions are implemented for either path style so the Windows rules
+//! (drive letters, UNC shares, verbatim prefixes) can be tested on any
+//! platform. Callers go through `AbsoluteSystemPath::to_file_url` and
+//! `AbsoluteSystemPathBuf::from_file_url`, which use the host's style.
+
+use std::fmt::Write;
+
+use crate::PathError;
+
+const SCHEME: &str = "file://";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Style {
+    Unix,
+    Windows,
+}
+
+impl Style {
+    pub(crate) const HOST: Self = if cfg!(windows) {
+        Style::Windows
+    } else {
+        Style::Unix
+    };
+
+    fn is_separator(self, c: char) -> bool {
+        match self {
+            Style::Unix => c == '/',
+            Style::Windows => c == '/' || c == '\\',
+        }
+    }
+}
+
+pub(crate) fn path_to_url(path: &str, style: Style) -> String {
+    let mut url = String::from(SCHEME);
+    match style {
+        Style::Unix => encode(&mut url, path),
+        Style::Windows => {
+            let path = match path.strip_prefix(r"\\?\") {
+                Some(verbatim) => match verbatim.strip_prefix(r"UNC\") {
+                    Some(unc) => format!(r"\\{unc}"),
+                    None => verbatim.to_string(),
+                },
+                None => path.to_string(),
+            }
+            .replace('\\', "/");
+            match path.strip_prefix("//") {
+                Some(unc) => {
+                    let (host, share) = unc.split_at(unc.find('/').unwrap_or(unc.len()));
+                    encode(&mut url, host);
+                    encode(&mut url, share);
+                }
+                // `C:/foo` becomes `file:///C:/foo`
+                None => {
+                    url.push('/');
+                    encode(&mut url, &path);
+                }
+            }
+        }
+    }
+    url
+}
+
+pub(crate) fn url_to_path(url: &str, style: Style) -> Result<String, PathError> {
+    let invalid = || PathError::InvalidFileUrl(url.to_string());
+
+    let rest = url
+        .get(..SCHEME.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(SCHEME))
+        .map(|_| &url[SCHEME.len()..])
+        .ok_or_else(invalid)?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (host, path) = rest.split_at(rest.find('/').ok_or_else(invalid)?);
+    let host = if host.eq_ignore_ascii_case("localhost") {
+        ""
+    } else {
+        host
+    };
+
+    // An encoded separator would silently change which file is referenced
+    let segments = path
+        .split('/')
+        .skip(1)
+        .map(|segment| {
+            decode(segment)
+                .filter(|segment| !segment.contains(|c| c == '\0' || style.is_separator(c)))
+                .ok_or_else(invalid)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match style {
+        Style::Unix if host.is_empty() => Ok(format!("/{}", segments.join("/"))),
+        Style::Unix => Err(invalid()),
+        Style::Windows if !host.is_empty() => {
+            let host = decode(host).ok_or_else(invalid)?;
+            Ok(format!(r"\\{host}\{}", segments.join("\\")))
+        }
+        Style::Windows => {
+            let (drive, rest) = segments.split_first().ok_or_else(invalid)?;
+            let drive = match drive.as_bytes() {
+                [letter, b':' | b'|'] if letter.is_ascii_alphabetic() => {
+                    format!("{}:", *letter as char)
+                }
+                _ => return Err(invalid()),
+            };
+            Ok(format!(r"{drive}\{}", rest.join("\\")))
+        }
+    }
+}
+
+// Everything outside of RFC 3986's `pchar` set, other than the `/` that
+// separates segments, is percent-encoded
+fn encode(url: &mut String, path: &str) {
+    for byte in path.bytes() {
+        match byte {
+            b'a'..=b'z'
+            | b'A'..=b'Z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b':'
+            | b'@'
+            | b'/' => url.push(byte as char),
+            _ => write!(url, "%{byte:02X}").expect("writing to a string can't fail"),
+        }
+    }
+}
+
+fn decode(segment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(segment.len());
+    let mut iter = segment.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let hex = [iter.next()?, iter.next()?];
+            let hex = std::str::from_utf8(&hex).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case("/", Style::Unix, "file:///" ; "unix root")]
+    #[test_case("/foo/bar.js", Style::Unix, "file:///foo/bar.js" ; "unix")]
+    #[test_case("/my repo/100%/#1?.js", Style::Unix, "file:///my%20repo/100%25/%231%3F.js" ; "unix reserved characters")]
+    #[test_case("/café/ñ", Style::Unix, "file:///caf%C3%A9/%C3%B1" ; "unix non ascii")]
+    #[test_case(r"C:\", Style::Windows, "file:///C:/" ; "windows root")]
+    #[test_case(r"C:\Users\me\my repo", Style::Windows, "file:///C:/Users/me/my%20repo" ; "windows drive")]
+    #[test_case(r"\\server\share\dir\a.js", Style::Windows, "file://server/share/dir/a.js" ; "windows unc")]
+    #[test_case(r"\\?\C:\foo", Style::Windows, "file:///C:/foo" ; "windows verbatim drive")]
+    #[test_case(r"\\?\UNC\server\share\foo", Style::Windows, "file://server/share/foo" ; "windows verbatim unc")]
+    fn test_path_to_url(path: &str, style: Style, expected: &str) {
+        assert_eq!(path_to_url(path, style), expected);
+    }
+
+    #[test_case("file:///foo/bar.js", Style::Unix, Some("/foo/bar.js") ; "unix")]
+    #[test_case("file://localhost/foo", Style::Unix, Some("/foo") ; "unix localhost")]
+    #[test_case("FILE:///my%20repo/caf%c3%a9", Style::Unix, Some("/my repo/café") ; "unix decoding")]
+    #[test_case("file:///foo/bar.js?query#fragment", Style::Unix, Some("/foo/bar.js") ; "unix query and fragment")]
+    #[test_case("file://server/share", Style::Unix, None ; "unix host")]
+    #[test_case("file:///foo%2Fbar", Style::Unix, None ; "unix encoded separator")]
+    #[test_case("file:///foo%00", Style::Unix, None ; "nul byte")]
+    #[test_case("file:///foo%zz", Style::Unix, None ; "invalid escape")]
+    #[test_case("file:///foo%ff", Style::Unix, None ; "invalid utf8")]
+    #[test_case("https://example.com/foo", Style::Unix, None ; "other scheme")]
+    #[test_case("file://", Style::Unix, None ; "no path")]
+    #[test_case("file:///C:/Users/me/my%20repo", Style::Windows, Some(r"C:\Users\me\my repo") ; "windows drive")]
+    #[test_case("file:///c|/foo", Style::Windows, Some(r"c:\foo") ; "windows legacy drive")]
+    #[test_case("file:///C:", Style::Windows, Some(r"C:\") ; "windows bare drive")]
+    #[test_case("file://server/share/a.js", Style::Windows, Some(r"\\server\share\a.js") ; "windows unc")]
+    #[test_case("file://localhost/C:/foo", Style::Windows, Some(r"C:\foo") ; "windows localhost")]
+    #[test_case("file:///foo/bar", Style::Windows, None ; "windows no drive")]
+    #[test_case("file:///C:/foo%5Cbar", Style::Windows, None ; "windows encoded separator")]
+    fn test_url_to_path(url: &str, style: Style, expected: Option<&str>) {
+        let actual = url_to_path(url, style);
+        match expected {
+            Some(expected) => assert_eq!(actual.unwrap(), expected),
+            None => assert!(matches!(actual, Err(PathError::InvalidFileUrl(_)))),
+        }
+    }
+
+    #[test_case("/my repo/100%/#1?.js", Style::Unix ; "unix")]
+    #[test_case(r"C:\my repo\café", Style::Windows ; "windows drive")]
+    #[test_case(r"\\server\share\my repo", Style::Windows ; "windows unc")]
+    fn test_round_trip(path: &str, style: Style) {
+        assert_eq!(url_to_path(&path_to_url(path, style), style).unwrap(), path);
+    }
+}
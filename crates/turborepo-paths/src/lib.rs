@@ -42,6 +42,7 @@ mod absolute_system_path;
 mod absolute_system_path_buf;
 mod anchored_system_path;
 mod anchored_system_path_buf;
+mod file_url;
 mod relative_unix_path;
 mod relative_unix_path_buf;
 
@@ -83,6 +84,8 @@ pub enum PathError {
     IO(#[from] io::Error),
     #[error("{0} is not a prefix for {1}")]
     PrefixError(String, String),
+    #[error("Invalid file URL: {0}")]
+    InvalidFileUrl(String),
 }
 
 impl From<std::string::FromUtf8Error> for PathError {
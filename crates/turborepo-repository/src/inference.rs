@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use thiserror::Error;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 
@@ -43,13 +45,33 @@ impl InferInfo {
         }
     }
 
+    /// Checks if `target_path` is one of our packages, including packages of
+    /// workspaces nested in ours. Nested roots are read with our package
+    /// manager as they don't have one of their own.
     pub fn is_workspace_root_of(&self, target_path: &AbsoluteSystemPath) -> bool {
-        match &self.workspace_globs {
-            Some(globs) => globs
-                .target_is_workspace(&self.path, target_path)
-                .unwrap_or(false),
-            None => false,
+        let (Some(globs), Ok(package_manager)) = (&self.workspace_globs, &self.package_manager)
+        else {
+            return false;
+        };
+        let mut root: &AbsoluteSystemPath = &self.path;
+        let mut globs = Cow::Borrowed(globs);
+        let mut below_root = target_path
+            .ancestors()
+            .take_while(|ancestor| *ancestor != &*self.path)
+            .collect::<Vec<_>>();
+        while let Some(path) = below_root.pop() {
+            if !globs.target_is_workspace(root, path).unwrap_or(false) {
+                continue;
+            }
+            if path == target_path {
+                return true;
+            }
+            if let Ok(nested_globs) = package_manager.get_workspace_globs(path) {
+                root = path;
+                globs = Cow::Owned(nested_globs);
+            }
         }
+        false
     }
 }
 
@@ -96,15 +118,14 @@ impl RepoState {
                     })
             })
             .reduce(|current, candidate| {
-                if current.repo_mode() == RepoMode::MultiPackage {
-                    // We already have a multi-package root, go with that
-                    current
-                } else if candidate.is_workspace_root_of(&current.path) {
+                if candidate.is_workspace_root_of(&current.path) {
                     // The next candidate is a multipackage root, and it contains current so it's
-                    // our root.
+                    // our root. This holds when current is the root of a workspace nested in it,
+                    // as its packages are part of the same package graph.
                     candidate
                 } else {
-                    // keep the current single package, it's the closest in
+                    // keep current, it's either a multi-package root or the closest single
+                    // package
                     current
                 }
             })
@@ -146,6 +167,11 @@ mod test {
         //       app-1/
         //         package.json
         //         src/
+        //       nested_monorepo/
+        //         package.json
+        //         apps/
+        //           app-3/
+        //             package.json
         //   single_root/
         //     package.json
         //     src/
@@ -174,6 +200,19 @@ mod test {
         let app_1_src = app_1.join_component("src");
         app_1_src.create_dir_all().unwrap();
 
+        let nested_monorepo = monorepo_root.join_components(&["packages", "nested_monorepo"]);
+        let nested_monorepo_pkg_json = nested_monorepo.join_component("package.json");
+        nested_monorepo_pkg_json.ensure_dir().unwrap();
+        nested_monorepo_pkg_json
+            .create_with_contents("{\"name\": \"nested\", \"workspaces\": [\"apps/*\"]}")
+            .unwrap();
+        let app_3 = nested_monorepo.join_components(&["apps", "app-3"]);
+        let app_3_pkg_json = app_3.join_component("package.json");
+        app_3_pkg_json.ensure_dir().unwrap();
+        app_3_pkg_json
+            .create_with_contents("{\"name\": \"app_3\"}")
+            .unwrap();
+
         let standalone = monorepo_root.join_component("standalone");
         let standalone_pkg_json = standalone.join_component("package.json");
         let standalone_contents = "{\"name\":\"standalone\"}";
@@ -247,6 +286,24 @@ mod test {
                     root_package_json: PackageJson::load(&monorepo_pkg_json).unwrap(),
                 }),
             ),
+            (
+                &nested_monorepo,
+                Some(RepoState {
+                    root: monorepo_root.clone(),
+                    mode: RepoMode::MultiPackage,
+                    package_manager: Ok(pnpm),
+                    root_package_json: PackageJson::load(&monorepo_pkg_json).unwrap(),
+                }),
+            ),
+            (
+                &app_3,
+                Some(RepoState {
+                    root: monorepo_root.clone(),
+                    mode: RepoMode::MultiPackage,
+                    package_manager: Ok(pnpm),
+                    root_package_json: PackageJson::load(&monorepo_pkg_json).unwrap(),
+                }),
+            ),
             (
                 &single_root,
                 Some(RepoState {
@@ -1,8 +1,9 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap};
 
+use itertools::Itertools;
 use miette::{Diagnostic, Report};
 use petgraph::graph::{Graph, NodeIndex};
-use tracing::{warn, Instrument};
+use tracing::{debug, warn, Instrument};
 use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
@@ -10,8 +11,8 @@ use turborepo_graph_utils as graph;
 use turborepo_lockfiles::Lockfile;
 
 use super::{
-    dep_splitter::DependencySplitter, npmrc::NpmRc, PackageGraph, PackageInfo, PackageName,
-    PackageNode,
+    dep_splitter::DependencySplitter, npmrc::NpmRc, DependencyCycle, PackageGraph, PackageInfo,
+    PackageName, PackageNode,
 };
 use crate::{
     discovery::{
@@ -54,6 +55,12 @@ pub enum Error {
     PackageJsonMissingName(AbsoluteSystemPathBuf),
     #[error("Invalid package dependency graph: {0}")]
     InvalidPackageGraph(#[source] graph::Error),
+    #[error(
+        "Invalid package dependency graph: cyclic dependency detected:\n{}",
+        .0.iter().format("\n")
+    )]
+    #[diagnostic(help("remove one of the dependencies listed above to break the cycle"))]
+    CyclicDependencies(Vec<DependencyCycle>),
     #[error(transparent)]
     Lockfile(#[from] turborepo_lockfiles::Error),
     #[error(transparent)]
@@ -352,16 +359,7 @@ impl<'a, T: PackageDiscovery> BuildState<'a, ResolvedWorkspaces, T> {
         &mut self,
         package_manager: PackageManager,
     ) -> Result<(), Error> {
-        let npmrc = match package_manager {
-            PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => {
-                let npmrc_path = self.repo_root.join_component(".npmrc");
-                match npmrc_path.read_existing_to_string().ok().flatten() {
-                    Some(contents) => NpmRc::from_reader(contents.as_bytes()).ok(),
-                    None => None,
-                }
-            }
-            _ => None,
-        };
+        let npmrc = NpmRc::read(self.repo_root, package_manager);
         let split_deps = self
             .workspaces
             .iter()
@@ -375,7 +373,7 @@ impl<'a, T: PackageDiscovery> BuildState<'a, ResolvedWorkspaces, T> {
                         &self.workspaces,
                         package_manager,
                         npmrc.as_ref(),
-                        entry.package_json.all_dependencies(),
+                        entry.package_json.all_dependencies_with_field(),
                     ),
                 )
             })
@@ -397,15 +395,16 @@ impl<'a, T: PackageDiscovery> BuildState<'a, ResolvedWorkspaces, T> {
                     .expect("root node should have index");
                 self.workspace_graph.add_edge(*node_idx, *root_idx, ());
             }
-            for dependency in internal {
+            for dependency in internal.keys() {
                 let dependency_idx = self
                     .node_lookup
-                    .get(&PackageNode::Workspace(dependency))
+                    .get(&PackageNode::Workspace(dependency.clone()))
                     .expect("unable to find workspace node index");
                 self.workspace_graph
                     .add_edge(*node_idx, *dependency_idx, ());
             }
             entry.unresolved_external_dependencies = Some(external);
+            entry.internal_dependency_fields = Some(internal);
         }
 
         Ok(())
@@ -514,13 +513,42 @@ impl<'a, T: PackageDiscovery> BuildState<'a, ResolvedLockfile, T> {
 
         // We cannot ignore missing packages in this context, it would indicate a
         // malformed or stale lockfile.
-        let mut closures = turborepo_lockfiles::all_transitive_closures(
+        match turborepo_lockfiles::all_transitive_closures(
             lockfile,
             self.all_external_dependencies()?,
             false,
-        )?;
-        for (_, entry) in self.workspaces.iter_mut() {
-            entry.transitive_dependencies = closures.remove(&entry.unix_dir_str()?);
+        ) {
+            Ok(mut closures) => {
+                for entry in self.workspaces.values_mut() {
+                    entry.transitive_dependencies = closures.remove(&entry.unix_dir_str()?);
+                }
+            }
+            // Resolve packages one at a time so a package that is missing from the
+            // lockfile doesn't leave every other package without a closure. Each
+            // package's task hashes depend on its own closure, so the rest of the
+            // repository keeps precise cache keys.
+            Err(e) => {
+                debug!("resolving transitive closures per package: {e}");
+                for (name, entry) in self.workspaces.iter_mut() {
+                    let unresolved_deps = entry
+                        .unresolved_external_dependencies
+                        .iter()
+                        .flatten()
+                        .map(|(name, version)| (name.clone(), version.clone()))
+                        .collect();
+                    match turborepo_lockfiles::transitive_closure(
+                        lockfile,
+                        &entry.unix_dir_str()?,
+                        unresolved_deps,
+                        false,
+                    ) {
+                        Ok(closure) => entry.transitive_dependencies = Some(closure),
+                        Err(e) => {
+                            warn!("Unable to calculate transitive closure for {name}: {e}")
+                        }
+                    }
+                }
+            }
         }
         Ok(())
     }
@@ -556,12 +584,13 @@ impl<'a, T: PackageDiscovery> BuildState<'a, ResolvedLockfile, T> {
 }
 
 struct Dependencies {
-    internal: HashSet<PackageName>,
+    // Package name and the package.json fields it is declared in
+    internal: BTreeMap<PackageName, Vec<&'static str>>,
     external: BTreeMap<String, String>, // Package name and version
 }
 
 impl Dependencies {
-    pub fn new<'a, I: IntoIterator<Item = (&'a String, &'a String)>>(
+    pub fn new<'a, I: IntoIterator<Item = (&'static str, &'a String, &'a String)>>(
         repo_root: &AbsoluteSystemPath,
         workspace_json_path: &AnchoredSystemPathBuf,
         workspaces: &HashMap<PackageName, PackageInfo>,
@@ -573,13 +602,13 @@ impl Dependencies {
         let workspace_dir = resolved_workspace_json_path
             .parent()
             .expect("package.json path should have parent");
-        let mut internal = HashSet::new();
+        let mut internal: BTreeMap<_, Vec<_>> = BTreeMap::new();
         let mut external = BTreeMap::new();
         let splitter =
             DependencySplitter::new(repo_root, workspace_dir, workspaces, package_manager, npmrc);
-        for (name, version) in dependencies.into_iter() {
+        for (field, name, version) in dependencies.into_iter() {
             if let Some(workspace) = splitter.is_internal(name, version) {
-                internal.insert(workspace);
+                internal.entry(workspace).or_default().push(field);
             } else {
                 external.insert(name.clone(), version.clone());
             }
@@ -266,6 +266,7 @@ mod test {
                     .unwrap(),
                     unresolved_external_dependencies: None,
                     transitive_dependencies: None,
+                    internal_dependency_fields: None,
                 },
             );
             map.insert(
@@ -281,6 +282,7 @@ mod test {
                     .unwrap(),
                     unresolved_external_dependencies: None,
                     transitive_dependencies: None,
+                    internal_dependency_fields: None,
                 },
             );
             map.insert(
@@ -296,6 +298,7 @@ mod test {
                     .unwrap(),
                     unresolved_external_dependencies: None,
                     transitive_dependencies: None,
+                    internal_dependency_fields: None,
                 },
             );
             map
@@ -1,9 +1,10 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet, VecDeque},
     fmt,
 };
 
 use itertools::Itertools;
+use petgraph::graph::NodeIndex;
 use serde::Serialize;
 use tracing::debug;
 use turbopath::{
@@ -12,6 +13,7 @@ use turbopath::{
 use turborepo_graph_utils as graph;
 use turborepo_lockfiles::Lockfile;
 
+use self::{dep_splitter::DependencySplitter, npmrc::NpmRc};
 use crate::{
     discovery::LocalPackageDiscoveryBuilder, package_json::PackageJson,
     package_manager::PackageManager,
@@ -66,6 +68,9 @@ pub struct PackageInfo {
     pub package_json_path: AnchoredSystemPathBuf,
     pub unresolved_external_dependencies: Option<BTreeMap<PackageKey, PackageVersion>>, /* name -> version */
     pub transitive_dependencies: Option<HashSet<turborepo_lockfiles::Package>>,
+    /// Internal dependencies along with the package.json fields that declare
+    /// them
+    pub internal_dependency_fields: Option<BTreeMap<PackageName, Vec<&'static str>>>,
 }
 
 impl PackageInfo {
@@ -141,11 +146,97 @@ impl PackageGraph {
                 return Err(Error::PackageJsonMissingName(package_json_path));
             }
         }
-        graph::validate_graph(&self.graph).map_err(Error::InvalidPackageGraph)?;
+        // Cycles between packages are reported separately by `dependency_cycles`
+        // so callers can decide whether they are fatal
+        for edge in self.graph.edge_indices() {
+            let (source, target) = self
+                .graph
+                .edge_endpoints(edge)
+                .expect("edge index should be valid");
+            if source == target {
+                let node = &self.graph[source];
+                return Err(Error::InvalidPackageGraph(graph::Error::SelfDependency(
+                    node.to_string(),
+                )));
+            }
+        }
 
         Ok(())
     }
 
+    /// Returns every cycle between workspace packages.
+    ///
+    /// One cycle is reported for each group of packages that depend on each
+    /// other, starting from the alphabetically first package in the group
+    /// and following the shortest path back to it.
+    pub fn dependency_cycles(&self) -> Vec<DependencyCycle> {
+        let mut cycles = petgraph::algo::tarjan_scc(&self.graph)
+            .into_iter()
+            .filter(|component| component.len() > 1)
+            .filter_map(|component| self.shortest_cycle(&component))
+            .collect::<Vec<_>>();
+        cycles.sort_by(|a, b| a.edges.cmp(&b.edges));
+        cycles
+    }
+
+    fn shortest_cycle(&self, component: &[NodeIndex]) -> Option<DependencyCycle> {
+        let members = component.iter().copied().collect::<HashSet<_>>();
+        let start = *component.iter().min_by_key(|index| &self.graph[**index])?;
+
+        // Breadth first search from the start back to itself without leaving
+        // the component
+        let mut previous = HashMap::new();
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let mut neighbors = self
+                .graph
+                .neighbors(node)
+                .filter(|neighbor| *neighbor != node && members.contains(neighbor))
+                .collect::<Vec<_>>();
+            neighbors.sort_by_key(|neighbor| &self.graph[*neighbor]);
+            for neighbor in neighbors {
+                if neighbor == start {
+                    let mut path = vec![start, node];
+                    let mut current = node;
+                    while let Some(prev) = previous.get(&current) {
+                        path.push(*prev);
+                        current = *prev;
+                    }
+                    path.reverse();
+                    let edges = path
+                        .windows(2)
+                        .map(|pair| self.cycle_edge(pair[0], pair[1]))
+                        .collect();
+                    return Some(DependencyCycle { edges });
+                }
+                if let Entry::Vacant(entry) = previous.entry(neighbor) {
+                    entry.insert(node);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+
+        None
+    }
+
+    fn cycle_edge(&self, from: NodeIndex, to: NodeIndex) -> CycleEdge {
+        let from = self.graph[from].as_package_name();
+        let to = self.graph[to].as_package_name();
+        let info = self.packages.get(from);
+        CycleEdge {
+            from: from.clone(),
+            to: to.clone(),
+            package_json_path: info
+                .map(|info| info.package_json_path.clone())
+                .unwrap_or_default(),
+            fields: info
+                .and_then(|info| info.internal_dependency_fields.as_ref())
+                .and_then(|fields| fields.get(to))
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
     pub fn remove_package_dependencies(&mut self) {
         let root_index = self
             .node_lookup
@@ -407,6 +498,37 @@ impl PackageGraph {
             .collect()
     }
 
+    /// Finds the dependencies of `dependents` on `packages`, which aren't part
+    /// of the graph, such as packages that aren't checked out. Dependencies
+    /// are resolved the same way as those between packages in the graph.
+    pub fn dependencies_outside_graph<'a, I: IntoIterator<Item = &'a PackageName>>(
+        &self,
+        dependents: I,
+        packages: &HashMap<PackageName, PackageInfo>,
+    ) -> Vec<(&'a PackageName, PackageName)> {
+        let npmrc = NpmRc::read(&self.repo_root, self.package_manager);
+        dependents
+            .into_iter()
+            .filter_map(|dependent| Some((dependent, self.packages.get(dependent)?)))
+            .flat_map(|(dependent, info)| {
+                let workspace_dir = self.repo_root.resolve(info.package_path());
+                let splitter = DependencySplitter::new(
+                    &self.repo_root,
+                    &workspace_dir,
+                    packages,
+                    self.package_manager,
+                    npmrc.as_ref(),
+                );
+                info.unresolved_external_dependencies
+                    .iter()
+                    .flatten()
+                    .filter_map(|(name, version)| splitter.is_internal(name, version))
+                    .map(|dependency| (dependent, dependency))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Returns a list of changed packages based on the contents of a previous
     /// `Lockfile`. This assumes that none of the package.json in the package
     /// change, it is the responsibility of the caller to verify this.
@@ -496,6 +618,48 @@ impl PackageGraph {
     }
 }
 
+/// A cycle of dependencies between workspace packages that starts and ends
+/// with the same package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyCycle {
+    pub edges: Vec<CycleEdge>,
+}
+
+/// A single dependency within a `DependencyCycle`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CycleEdge {
+    pub from: PackageName,
+    pub to: PackageName,
+    /// The package.json of `from`
+    pub package_json_path: AnchoredSystemPathBuf,
+    /// The package.json fields that declare the dependency on `to`
+    pub fields: Vec<&'static str>,
+}
+
+impl fmt::Display for DependencyCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(first) = self.edges.first() else {
+            return Ok(());
+        };
+        write!(f, "{}", first.from)?;
+        for edge in &self.edges {
+            write!(f, " -> {}", edge.to)?;
+        }
+        for edge in &self.edges {
+            write!(f, "\n\t{} -> {}", edge.from, edge.to)?;
+            if !edge.fields.is_empty() {
+                write!(
+                    f,
+                    " ({} in {})",
+                    edge.fields.iter().format(", "),
+                    edge.package_json_path.to_unix()
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ChangedPackagesError {
     #[error("No lockfile")]
@@ -596,6 +760,57 @@ mod test {
         assert!(result.is_ok(), "expected ok {:?}", result);
     }
 
+    #[tokio::test]
+    async fn test_dependencies_outside_graph() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { r"C:\repo" } else { "/repo" }).unwrap();
+        let pkg_graph = PackageGraph::builder(
+            &root,
+            PackageJson::from_value(json!({ "name": "root" })).unwrap(),
+        )
+        .with_package_discovery(MockDiscovery)
+        .with_package_jsons(Some(HashMap::from([(
+            root.join_components(&["package_a", "package.json"]),
+            PackageJson::from_value(json!({
+                "name": "a",
+                "dependencies": {
+                    "b": "*",
+                    "c": "^2.0.0",
+                    "lodash": "^4.17.21"
+                }
+            }))
+            .unwrap(),
+        )])))
+        .build()
+        .await
+        .unwrap();
+
+        let missing_package = |name: &str, path: &str| {
+            (
+                PackageName::from(name),
+                PackageInfo {
+                    package_json: PackageJson::from_value(
+                        json!({ "name": name, "version": "1.0.0" }),
+                    )
+                    .unwrap(),
+                    package_json_path: AnchoredSystemPathBuf::from_raw(path).unwrap(),
+                    ..Default::default()
+                },
+            )
+        };
+        let missing = HashMap::from([
+            missing_package("b", "package_b/package.json"),
+            missing_package("c", "package_c/package.json"),
+        ]);
+        let a = PackageName::from("a");
+        // `c` doesn't satisfy the requested version, so it would be installed
+        // from the registry instead
+        assert_eq!(
+            pkg_graph.dependencies_outside_graph([&a], &missing),
+            vec![(&a, PackageName::from("b"))]
+        );
+    }
+
     #[tokio::test]
     async fn test_internal_dependencies_get_split_out() {
         let root =
@@ -683,6 +898,9 @@ mod test {
             _version: &str,
         ) -> std::result::Result<Option<turborepo_lockfiles::Package>, turborepo_lockfiles::Error>
         {
+            if name == "unresolvable" {
+                return Err(turborepo_lockfiles::Error::MissingPackage(name.into()));
+            }
             Ok(match name {
                 "a" => Some(turborepo_lockfiles::Package::new("key:a", "1")),
                 "b" => Some(turborepo_lockfiles::Package::new("key:b", "1")),
@@ -802,6 +1020,56 @@ mod test {
         );
     }
 
+    #[tokio::test]
+    async fn test_lockfile_traversal_failure_is_per_package() {
+        let root =
+            AbsoluteSystemPathBuf::new(if cfg!(windows) { r"C:\repo" } else { "/repo" }).unwrap();
+        let pkg_graph = PackageGraph::builder(
+            &root,
+            PackageJson::from_value(json!({ "name": "root" })).unwrap(),
+        )
+        .with_package_discovery(MockDiscovery)
+        .with_package_jsons(Some({
+            let mut map = HashMap::new();
+            map.insert(
+                root.join_components(&["package_a", "package.json"]),
+                PackageJson::from_value(json!({
+                    "name": "foo",
+                    "dependencies": {
+                        "a": "1"
+                    }
+                }))
+                .unwrap(),
+            );
+            map.insert(
+                root.join_components(&["package_b", "package.json"]),
+                PackageJson::from_value(json!({
+                    "name": "bar",
+                    "dependencies": {
+                        "unresolvable": "1",
+                    }
+                }))
+                .unwrap(),
+            );
+            map
+        }))
+        .with_lockfile(Some(Box::new(MockLockfile {})))
+        .build()
+        .await
+        .unwrap();
+
+        let foo = pkg_graph.package_info(&PackageName::from("foo")).unwrap();
+        let bar = pkg_graph.package_info(&PackageName::from("bar")).unwrap();
+        assert_eq!(
+            foo.transitive_dependencies,
+            Some(HashSet::from_iter(vec![
+                turborepo_lockfiles::Package::new("key:a", "1"),
+                turborepo_lockfiles::Package::new("key:c", "1"),
+            ]))
+        );
+        assert_eq!(bar.transitive_dependencies, None);
+    }
+
     #[tokio::test]
     async fn test_circular_dependency() {
         let root =
@@ -819,6 +1087,9 @@ mod test {
                     "name": "foo",
                     "dependencies": {
                         "bar": "*"
+                    },
+                    "optionalDependencies": {
+                        "bar": "*"
                     }
                 }))
                 .unwrap(),
@@ -837,7 +1108,7 @@ mod test {
                 root.join_component("package_c"),
                 PackageJson::from_value(json!({
                     "name": "baz",
-                    "dependencies": {
+                    "devDependencies": {
                         "foo": "*",
                     }
                 }))
@@ -850,11 +1121,18 @@ mod test {
         .await
         .unwrap();
 
-        assert_matches!(
-            pkg_graph.validate(),
-            Err(builder::Error::InvalidPackageGraph(
-                graph::Error::CyclicDependencies(_)
-            ))
+        assert!(pkg_graph.validate().is_ok());
+        let cycles = pkg_graph.dependency_cycles();
+        assert_eq!(cycles.len(), 1);
+        assert_eq!(
+            cycles[0].to_string(),
+            [
+                "bar -> baz -> foo -> bar",
+                "\tbar -> baz (dependencies in package_b)",
+                "\tbaz -> foo (devDependencies in package_c)",
+                "\tfoo -> bar (optionalDependencies, dependencies in package_a)",
+            ]
+            .join("\n")
         );
     }
 
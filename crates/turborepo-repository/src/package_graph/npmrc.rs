@@ -1,6 +1,9 @@
 use std::io;
 
 use ini::Ini;
+use turbopath::AbsoluteSystemPath;
+
+use crate::package_manager::PackageManager;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -20,6 +23,19 @@ pub struct NpmRc {
 }
 
 impl NpmRc {
+    /// Reads the .npmrc at the root of the repository if the package manager
+    /// uses it to build the package graph
+    pub fn read(repo_root: &AbsoluteSystemPath, package_manager: PackageManager) -> Option<Self> {
+        match package_manager {
+            PackageManager::Pnpm | PackageManager::Pnpm6 | PackageManager::Pnpm9 => {
+                let npmrc_path = repo_root.join_component(".npmrc");
+                let contents = npmrc_path.read_existing_to_string().ok().flatten()?;
+                Self::from_reader(contents.as_bytes()).ok()
+            }
+            _ => None,
+        }
+    }
+
     pub fn from_reader(mut reader: impl io::Read) -> Result<Self, Error> {
         let ini = Ini::read_from(&mut reader)?;
         Ok(Self::from_ini(ini))
@@ -204,6 +204,27 @@ impl PackageJson {
             .chain(self.dependencies.iter().flatten())
     }
 
+    /// Like `all_dependencies`, but also yields the name of the field each
+    /// dependency is declared in
+    pub fn all_dependencies_with_field(
+        &self,
+    ) -> impl Iterator<Item = (&'static str, &String, &String)> + '_ {
+        fn with_field<'a>(
+            field: &'static str,
+            deps: &'a Option<BTreeMap<String, String>>,
+        ) -> impl Iterator<Item = (&'static str, &'a String, &'a String)> + 'a {
+            deps.iter()
+                .flatten()
+                .map(move |(name, version)| (field, name, version))
+        }
+        with_field("devDependencies", &self.dev_dependencies)
+            .chain(with_field(
+                "optionalDependencies",
+                &self.optional_dependencies,
+            ))
+            .chain(with_field("dependencies", &self.dependencies))
+    }
+
     /// Returns the command for script_name if it is non-empty
     pub fn command(&self, script_name: &str) -> Option<&str> {
         self.scripts
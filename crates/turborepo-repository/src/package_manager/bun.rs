@@ -1,8 +1,14 @@
-use turbopath::AbsoluteSystemPath;
+use std::collections::HashSet;
 
-use crate::package_manager::{Error, PackageManager};
+use turbopath::{AbsoluteSystemPath, RelativeUnixPath};
 
-pub const LOCKFILE: &str = "bun.lockb";
+use crate::{
+    package_json::PackageJson,
+    package_manager::{Error, PackageManager},
+};
+
+pub const LOCKFILE: &str = "bun.lock";
+pub const BINARY_LOCKFILE: &str = "bun.lockb";
 
 pub struct BunDetector<'a> {
     repo_root: &'a AbsoluteSystemPath,
@@ -27,14 +33,35 @@ impl<'a> Iterator for BunDetector<'a> {
         }
 
         self.found = true;
-        let package_json = self.repo_root.join_component(LOCKFILE);
+        let has_lockfile = [LOCKFILE, BINARY_LOCKFILE]
+            .into_iter()
+            .any(|lockfile| self.repo_root.join_component(lockfile).exists());
 
-        if package_json.exists() {
-            Some(Ok(PackageManager::Bun))
-        } else {
-            None
-        }
+        has_lockfile.then_some(Ok(PackageManager::Bun))
+    }
+}
+
+pub(crate) fn prune_patches<R: AsRef<RelativeUnixPath>>(
+    package_json: &PackageJson,
+    patches: &[R],
+) -> PackageJson {
+    let mut pruned_json = package_json.clone();
+    let patches = patches
+        .iter()
+        .map(|patch| patch.as_ref().as_str())
+        .collect::<HashSet<_>>();
+
+    if let Some(serde_json::Value::Object(existing_patches)) =
+        pruned_json.other.get_mut("patchedDependencies")
+    {
+        existing_patches.retain(|_, patch_path| {
+            patch_path
+                .as_str()
+                .map_or(false, |patch_path| patches.contains(patch_path))
+        });
     }
+
+    pruned_json
 }
 
 #[cfg(test)]
@@ -42,22 +69,63 @@ mod tests {
     use std::fs::File;
 
     use anyhow::Result;
+    use serde_json::json;
     use tempfile::tempdir;
-    use turbopath::AbsoluteSystemPathBuf;
+    use test_case::test_case;
+    use turbopath::{AbsoluteSystemPathBuf, RelativeUnixPathBuf};
 
-    use super::LOCKFILE;
-    use crate::package_manager::PackageManager;
+    use super::*;
 
-    #[test]
-    fn test_detect_bun() -> Result<()> {
+    #[test_case(LOCKFILE ; "text lockfile")]
+    #[test_case(BINARY_LOCKFILE ; "binary lockfile")]
+    fn test_detect_bun(lockfile: &str) -> Result<()> {
         let repo_root = tempdir()?;
         let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
 
-        let lockfile_path = repo_root.path().join(LOCKFILE);
+        let lockfile_path = repo_root.path().join(lockfile);
         File::create(lockfile_path)?;
         let package_manager = PackageManager::detect_package_manager(&repo_root_path)?;
         assert_eq!(package_manager, PackageManager::Bun);
+        assert_eq!(
+            package_manager.lockfile_path(&repo_root_path),
+            repo_root_path.join_component(lockfile)
+        );
 
         Ok(())
     }
+
+    #[test]
+    fn test_lockfile_path_prefers_text() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        File::create(repo_root.path().join(LOCKFILE))?;
+        File::create(repo_root.path().join(BINARY_LOCKFILE))?;
+
+        assert_eq!(
+            PackageManager::Bun.lockfile_path(&repo_root_path),
+            repo_root_path.join_component(LOCKFILE)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_patches() {
+        let package_json: PackageJson = PackageJson::from_value(json!({
+            "name": "bun-patches",
+            "patchedDependencies": {
+                "is-odd@3.0.1": "patches/is-odd@3.0.1.patch",
+                "lodash@4.17.21": "patches/lodash@4.17.21.patch",
+            }
+        }))
+        .unwrap();
+        let pruned = prune_patches(
+            &package_json,
+            &[RelativeUnixPathBuf::new("patches/is-odd@3.0.1.patch").unwrap()],
+        );
+        assert_eq!(
+            pruned.other.get("patchedDependencies"),
+            Some(&json!({ "is-odd@3.0.1": "patches/is-odd@3.0.1.patch" }))
+        );
+    }
 }
@@ -5,6 +5,7 @@ mod yarn;
 
 use std::{
     backtrace,
+    collections::BTreeSet,
     fmt::{self, Display},
     fs,
     process::Command,
@@ -526,19 +527,41 @@ impl PackageManager {
         }
     }
 
+    /// Returns the package.json of every workspace package.
+    ///
+    /// A package that declares workspaces of its own is treated as the root
+    /// of a nested workspace and the packages it contains are included as
+    /// well.
     pub fn get_package_jsons(
         &self,
         repo_root: &AbsoluteSystemPath,
     ) -> Result<impl Iterator<Item = AbsoluteSystemPathBuf>, Error> {
-        let globs = self.get_workspace_globs(repo_root)?;
+        let mut package_jsons = BTreeSet::new();
+        let mut workspace_roots = vec![repo_root.to_owned()];
+        while let Some(workspace_root) = workspace_roots.pop() {
+            let globs = match self.get_workspace_globs(&workspace_root) {
+                Ok(globs) => globs,
+                // Most packages don't declare any workspaces
+                Err(Error::Workspace(_)) if &*workspace_root != repo_root => continue,
+                Err(e) => return Err(e),
+            };
+
+            let files = globwalk::globwalk(
+                &workspace_root,
+                &globs.package_json_inclusions,
+                &globs.validated_exclusions,
+                globwalk::WalkType::Files,
+            )?;
+            for file in files {
+                let package_dir = file.parent().expect("package.json has a parent");
+                if package_dir != &*workspace_root && !package_jsons.contains(&file) {
+                    workspace_roots.push(package_dir.to_owned());
+                    package_jsons.insert(file);
+                }
+            }
+        }
 
-        let files = globwalk::globwalk(
-            repo_root,
-            &globs.package_json_inclusions,
-            &globs.validated_exclusions,
-            globwalk::WalkType::Files,
-        )?;
-        Ok(files.into_iter())
+        Ok(package_jsons.into_iter())
     }
 
     pub fn lockfile_name(&self) -> &'static str {
@@ -570,7 +593,8 @@ impl PackageManager {
     ) -> Result<Box<dyn Lockfile>, Error> {
         let lockfile_path = self.lockfile_path(root_path);
         let contents = match self {
-            PackageManager::Bun => {
+            // The binary lockfile is read through Bun, which prints it in the yarn v1 format
+            PackageManager::Bun if lockfile_path.file_name() == Some(bun::BINARY_LOCKFILE) => {
                 let binary = "bun";
                 Command::new(which(binary).map_err(|e| Error::Which(e, binary.to_string()))?)
                     .arg(lockfile_path.to_string())
@@ -599,6 +623,9 @@ impl PackageManager {
             PackageManager::Yarn => {
                 Box::new(turborepo_lockfiles::Yarn1Lockfile::from_bytes(contents)?)
             }
+            PackageManager::Bun if turborepo_lockfiles::BunTextLockfile::is_text(contents) => {
+                Box::new(turborepo_lockfiles::BunTextLockfile::from_bytes(contents)?)
+            }
             PackageManager::Bun => {
                 Box::new(turborepo_lockfiles::BunLockfile::from_bytes(contents)?)
             }
@@ -625,14 +652,23 @@ impl PackageManager {
             PackageManager::Pnpm9 | PackageManager::Pnpm6 | PackageManager::Pnpm => {
                 pnpm::prune_patches(package_json, patches)
             }
-            PackageManager::Yarn | PackageManager::Npm | PackageManager::Bun => {
-                unreachable!("bun, npm, and yarn 1 don't have a concept of patches")
+            PackageManager::Bun => bun::prune_patches(package_json, patches),
+            PackageManager::Yarn | PackageManager::Npm => {
+                unreachable!("npm and yarn 1 don't have a concept of patches")
             }
         }
     }
 
     pub fn lockfile_path(&self, turbo_root: &AbsoluteSystemPath) -> AbsoluteSystemPathBuf {
-        turbo_root.join_component(self.lockfile_name())
+        let lockfile_path = turbo_root.join_component(self.lockfile_name());
+        // Only fall back to Bun's binary lockfile if the repo hasn't moved to bun.lock
+        if matches!(self, PackageManager::Bun) && !lockfile_path.exists() {
+            let binary_lockfile_path = turbo_root.join_component(bun::BINARY_LOCKFILE);
+            if binary_lockfile_path.exists() {
+                return binary_lockfile_path;
+            }
+        }
+        lockfile_path
     }
 
     pub fn arg_separator(&self, user_args: &[String]) -> Option<&str> {
@@ -721,6 +757,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_package_jsons_nested_workspaces() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(tmp.path()).unwrap();
+        let write = |path: &[&str], contents: &str| {
+            let file = repo_root.join_components(path);
+            file.ensure_dir().unwrap();
+            file.create_with_contents(contents).unwrap();
+        };
+        write(&["package.json"], r#"{ "workspaces": ["packages/*"] }"#);
+        write(&["packages", "a", "package.json"], r#"{ "name": "a" }"#);
+        write(
+            &["packages", "acquired", "package.json"],
+            r#"{ "name": "acquired", "workspaces": ["apps/*", "libs/**"] }"#,
+        );
+        write(
+            &["packages", "acquired", "apps", "b", "package.json"],
+            r#"{ "name": "b" }"#,
+        );
+        write(
+            &["packages", "acquired", "libs", "c", "package.json"],
+            r#"{ "name": "c", "workspaces": ["d"] }"#,
+        );
+        write(
+            &["packages", "acquired", "libs", "c", "d", "package.json"],
+            r#"{ "name": "d" }"#,
+        );
+        write(
+            &[
+                "packages",
+                "acquired",
+                "apps",
+                "b",
+                "node_modules",
+                "e",
+                "package.json",
+            ],
+            r#"{ "name": "e" }"#,
+        );
+
+        let found = PackageManager::Npm
+            .get_package_jsons(repo_root)
+            .unwrap()
+            .collect::<Vec<_>>();
+        let mut expected = vec![
+            repo_root.join_components(&["packages", "a", "package.json"]),
+            repo_root.join_components(&["packages", "acquired", "package.json"]),
+            repo_root.join_components(&["packages", "acquired", "apps", "b", "package.json"]),
+            repo_root.join_components(&["packages", "acquired", "libs", "c", "package.json"]),
+            repo_root.join_components(&["packages", "acquired", "libs", "c", "d", "package.json"]),
+        ];
+        expected.sort();
+        assert_eq!(found, expected);
+    }
+
     #[test]
     fn test_get_workspace_ignores() {
         let root = repo_root();
@@ -126,6 +126,7 @@ mod test {
     #[test_case("8.0.0", PackageManager::Pnpm)]
     #[test_case("9.0.0", PackageManager::Pnpm9)]
     #[test_case("9.0.0-alpha.0", PackageManager::Pnpm9)]
+    #[test_case("10.0.0", PackageManager::Pnpm9)]
     fn test_version_detection(version: &str, expected: PackageManager) {
         let version = Version::parse(version).unwrap();
         assert_eq!(
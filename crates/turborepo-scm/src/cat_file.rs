@@ -0,0 +1,91 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Command, Stdio},
+};
+
+use crate::{wait_for_success, Error, Git};
+
+impl Git {
+    /// Reads the contents of the given blobs. In a partial clone, git fetches
+    /// any that aren't available locally.
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn cat_blobs(&self, hashes: &[String]) -> Result<Vec<Vec<u8>>, Error> {
+        let mut git = Command::new(self.bin.as_std_path())
+            .args(["cat-file", "--batch"])
+            .env("GIT_OPTIONAL_LOCKS", "0")
+            .current_dir(&self.root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let mut stdin = git
+            .stdin
+            .take()
+            .ok_or_else(|| Error::git_error("failed to get stdin for git cat-file"))?;
+        let stdout = git
+            .stdout
+            .take()
+            .ok_or_else(|| Error::git_error("failed to get stdout for git cat-file"))?;
+        let mut stderr = git
+            .stderr
+            .take()
+            .ok_or_else(|| Error::git_error("failed to get stderr for git cat-file"))?;
+
+        // git writes each blob as soon as it reads its hash, so the hashes are
+        // written from another thread to avoid both pipes filling up
+        let input = hashes.iter().fold(String::new(), |mut input, hash| {
+            input.push_str(hash);
+            input.push('\n');
+            input
+        });
+        let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+        let parse_result = read_blobs(stdout, hashes.len());
+        let write_result = writer.join().expect("git cat-file writer panicked");
+        let parse_result = write_result.map_err(Error::from).and(parse_result);
+        wait_for_success(git, &mut stderr, "git cat-file", &self.root, parse_result)
+    }
+}
+
+fn read_blobs<R: Read>(reader: R, count: usize) -> Result<Vec<Vec<u8>>, Error> {
+    let mut reader = BufReader::new(reader);
+    let mut blobs = Vec::with_capacity(count);
+    let mut header = String::new();
+    for _ in 0..count {
+        header.clear();
+        reader.read_line(&mut header)?;
+        let header = header.trim_end();
+        // Each blob is preceded by "<hash> blob <size>" and followed by a newline
+        let size = match header.split(' ').collect::<Vec<_>>()[..] {
+            [_, "blob", size] => size.parse::<usize>().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| {
+            Error::git_error(format!("failed to parse git cat-file header: {}", header))
+        })?;
+        let mut blob = vec![0; size + 1];
+        reader.read_exact(&mut blob)?;
+        blob.pop();
+        blobs.push(blob);
+    }
+    Ok(blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cat_file::read_blobs;
+
+    #[test]
+    fn test_read_blobs() {
+        let input = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 blob \
+                     0\n\n9ec2879b24ce2c817296eebe2cb3846f8e4751ea blob 7\nfoo\nbar\n";
+        let blobs = read_blobs(input.as_bytes(), 2).unwrap();
+        assert_eq!(blobs, vec![b"".to_vec(), b"foo\nbar".to_vec()]);
+    }
+
+    #[test]
+    fn test_read_missing_blob() {
+        let input = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391 missing\n";
+        assert!(read_blobs(input.as_bytes(), 1).is_err());
+    }
+}
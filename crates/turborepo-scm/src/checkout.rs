@@ -0,0 +1,89 @@
+//! Repositories that aren't fully present on disk. A sparse checkout only
+//! materializes part of the working tree, and a partial clone only has some of
+//! the objects locally, fetching the rest on demand.
+
+use std::collections::HashMap;
+
+use tracing::debug;
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
+
+use crate::{Error, Git};
+
+/// How much of a repository is available locally
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Checkout {
+    /// Only part of the working tree is checked out, see
+    /// `git sparse-checkout`
+    pub sparse: bool,
+    /// Objects are missing locally and are fetched from a promisor remote
+    /// when git needs them
+    pub partial_clone: bool,
+}
+
+impl Checkout {
+    pub(crate) fn detect(git_root: &AbsoluteSystemPath) -> Self {
+        let config = match Self::config(git_root) {
+            Ok(config) => config,
+            Err(e) => {
+                debug!("unable to read git config for {}: {}", git_root, e);
+                return Self::default();
+            }
+        };
+        let sparse = config.get_bool("core.sparseCheckout").unwrap_or(false);
+        let mut partial_clone = config.get_string("extensions.partialClone").is_ok();
+        if let Ok(mut remotes) = config.entries(Some(r"remote\..*\.promisor")) {
+            while let Some(Ok(remote)) = remotes.next() {
+                partial_clone |= remote.value() == Some("true");
+            }
+        }
+        let checkout = Self {
+            sparse,
+            partial_clone,
+        };
+        debug!("checkout of {}: {:?}", git_root, checkout);
+        checkout
+    }
+
+    // `git sparse-checkout` writes its settings to the per worktree config,
+    // which libgit2 doesn't load on its own
+    fn config(git_root: &AbsoluteSystemPath) -> Result<git2::Config, git2::Error> {
+        let repo = git2::Repository::open(git_root)?;
+        let mut config = repo.config()?;
+        let worktree_config = repo.path().join("config.worktree");
+        if config
+            .get_bool("extensions.worktreeConfig")
+            .unwrap_or(false)
+            && worktree_config.exists()
+        {
+            config.add_file(&worktree_config, git2::ConfigLevel::App, false)?;
+        }
+        Ok(config)
+    }
+}
+
+impl Git {
+    /// Reads every file named `file_name` below `root` that is in HEAD but
+    /// isn't on disk, such as those outside of a sparse checkout
+    pub fn read_outside_checkout(
+        &self,
+        root: &AbsoluteSystemPath,
+        file_name: &str,
+    ) -> Result<HashMap<AnchoredSystemPathBuf, String>, Error> {
+        let suffix = format!("/{file_name}");
+        let (paths, hashes): (Vec<_>, Vec<_>) = self
+            .git_ls_tree(&root.to_owned())?
+            .into_iter()
+            .filter(|(path, _)| path.as_str() == file_name || path.ends_with(&suffix))
+            .filter(|(path, _)| root.join_unix_path(path).symlink_metadata().is_err())
+            .unzip();
+        if paths.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let blobs = self.cat_blobs(&hashes)?;
+        paths
+            .into_iter()
+            .zip(blobs)
+            .map(|(path, blob)| Ok((path.to_anchored_system_path_buf(), String::from_utf8(blob)?)))
+            .collect()
+    }
+}
@@ -295,6 +295,13 @@ impl Git {
             args.push("--merge-base");
         }
 
+        // Detecting renames reads file contents, which a partial clone would
+        // have to download. Without it a rename shows up as a deletion and an
+        // addition, which covers the same packages.
+        if self.checkout.partial_clone {
+            args.push("--no-renames");
+        }
+
         let output = self.execute_git_command(&args, pathspec)?;
         self.add_files_from_stdout(&mut files, turbo_root, output);
 
@@ -9,6 +9,7 @@
 
 use std::{
     backtrace::{self, Backtrace},
+    collections::HashMap,
     io::Read,
     process::{Child, Command},
 };
@@ -16,14 +17,22 @@ use std::{
 use bstr::io::BufReadExt;
 use thiserror::Error;
 use tracing::debug;
-use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, PathError, RelativeUnixPathBuf};
+use turbopath::{
+    AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf, PathError,
+    RelativeUnixPathBuf,
+};
+
+pub use crate::checkout::Checkout;
 
+mod cat_file;
+mod checkout;
 pub mod git;
 mod hash_object;
 mod ls_tree;
 pub mod manual;
 pub mod package_deps;
 mod status;
+mod symlink;
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -65,6 +74,11 @@ pub enum Error {
     Walk(#[from] globwalk::WalkError),
     #[error("unable to resolve base branch, please set with TURBO_SCM_BASE")]
     UnableToResolveRef,
+    #[error(
+        "{0} is not part of the sparse checkout. Add it with `git sparse-checkout add {0}` to use \
+         it"
+    )]
+    NotMaterialized(String),
 }
 
 impl From<wax::BuildError> for Error {
@@ -149,6 +163,7 @@ pub(crate) fn wait_for_success<R: Read, T>(
 pub struct Git {
     root: AbsoluteSystemPathBuf,
     bin: AbsoluteSystemPathBuf,
+    checkout: Checkout,
 }
 
 #[derive(Debug, Error)]
@@ -167,7 +182,20 @@ impl Git {
         let bin = Self::find_bin()?;
         let root =
             find_git_root(path_in_repo).map_err(|e| GitError::Root(path_in_repo.to_owned(), e))?;
-        Ok(Self { root, bin })
+        Ok(Self::at_root(root, bin))
+    }
+
+    fn at_root(root: AbsoluteSystemPathBuf, bin: AbsoluteSystemPathBuf) -> Self {
+        let checkout = Checkout::detect(&root);
+        Self {
+            root,
+            bin,
+            checkout,
+        }
+    }
+
+    pub fn checkout(&self) -> Checkout {
+        self.checkout
     }
 
     pub fn find_bin() -> Result<AbsoluteSystemPathBuf, which::Error> {
@@ -227,6 +255,28 @@ impl SCM {
     pub fn is_manual(&self) -> bool {
         matches!(self, SCM::Manual)
     }
+
+    /// How much of the repository is available locally. Without git we
+    /// assume everything is.
+    pub fn checkout(&self) -> Checkout {
+        match self {
+            SCM::Git(git) => git.checkout(),
+            SCM::Manual => Checkout::default(),
+        }
+    }
+
+    /// Reads every file named `file_name` below `root` that is committed but
+    /// not checked out. Without git we assume every file is on disk.
+    pub fn read_outside_checkout(
+        &self,
+        root: &AbsoluteSystemPath,
+        file_name: &str,
+    ) -> Result<HashMap<AnchoredSystemPathBuf, String>, Error> {
+        match self {
+            SCM::Git(git) => git.read_outside_checkout(root, file_name),
+            SCM::Manual => Ok(HashMap::new()),
+        }
+    }
 }
 
 #[cfg(test)]
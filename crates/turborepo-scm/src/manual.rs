@@ -1,16 +1,14 @@
 use std::io::{ErrorKind, Read};
 
-use globwalk::fix_glob_pattern;
+use globwalk::GlobMatcher;
 use hex::ToHex;
 use ignore::WalkBuilder;
 use sha1::{Digest, Sha1};
-use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, IntoUnix};
-use wax::{any, Glob, Program};
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPath};
 
 use crate::{package_deps::GitHashes, Error};
 
-fn git_like_hash_file(path: &AbsoluteSystemPath) -> Result<String, Error> {
-    let mut hasher = Sha1::new();
+pub(crate) fn git_like_hash_file(path: &AbsoluteSystemPath) -> Result<String, Error> {
     let mut f = path.open()?;
     let mut buffer = Vec::new();
     // Note that read_to_end reads the target if f is a symlink. Currently, this can
@@ -19,20 +17,19 @@ fn git_like_hash_file(path: &AbsoluteSystemPath) -> Result<String, Error> {
     // will want to ensure that the target is better accounted for in the set of
     // inputs to the task. Manual hashing, as well as global deps and other
     // places that support globs all ignore symlinks.
-    let size = f.read_to_end(&mut buffer)?;
+    f.read_to_end(&mut buffer)?;
+    Ok(git_like_hash(&buffer))
+}
+
+/// Hashes `contents` the way git hashes a blob
+pub(crate) fn git_like_hash(contents: &[u8]) -> String {
+    let mut hasher = Sha1::new();
     hasher.update("blob ".as_bytes());
-    hasher.update(size.to_string().as_bytes());
+    hasher.update(contents.len().to_string().as_bytes());
     hasher.update([b'\0']);
-    hasher.update(buffer.as_slice());
+    hasher.update(contents);
     let result = hasher.finalize();
-    Ok(result.encode_hex::<String>())
-}
-
-fn to_glob(input: &str) -> Result<Glob, Error> {
-    let glob = fix_glob_pattern(input).into_unix();
-    let g = Glob::new(glob.as_str()).map(|g| g.into_owned())?;
-
-    Ok(g)
+    result.encode_hex::<String>()
 }
 
 pub(crate) fn hash_files(
@@ -68,43 +65,31 @@ pub(crate) fn get_package_file_hashes_without_git<S: AsRef<str>>(
     let mut excluded_file_hashes = GitHashes::new();
 
     let mut walker_builder = WalkBuilder::new(&full_package_path);
-    let mut includes = Vec::new();
-    let mut excludes = Vec::new();
-    for pattern in inputs {
-        let pattern = pattern.as_ref();
-        if let Some(exclusion) = pattern.strip_prefix('!') {
-            let g = to_glob(exclusion)?;
-            excludes.push(g);
-        } else {
-            let g = to_glob(pattern)?;
-            includes.push(g);
-        }
+    // Match what git considers part of the package: only `.gitignore` files
+    // and git's excludes apply, and git never looks inside `.git`, which is a
+    // file when the package is checked out as a submodule
+    walker_builder
+        .ignore(false)
+        .filter_entry(|entry| entry.file_name() != ".git");
+
+    // Add in package.json and turbo.json to input patterns. Both file paths are
+    // relative to pkgPath
+    //
+    // - package.json is an input because if the `scripts` in the package.json
+    //   change (i.e. the tasks that turbo executes), we want a cache miss, since
+    //   any existing cache could be invalid.
+    // - turbo.json because it's the definition of the tasks themselves. The root
+    //   turbo.json is similarly included in the global hash. This file may not
+    //   exist in the workspace, but that is ok, because it will get ignored
+    //   downstream.
+    let mut patterns = inputs
+        .iter()
+        .map(|input| input.as_ref())
+        .collect::<Vec<_>>();
+    if patterns.iter().any(|pattern| !pattern.starts_with('!')) {
+        patterns.extend(["package.json", "turbo.json"]);
     }
-    let include_pattern = if includes.is_empty() {
-        None
-    } else {
-        // Add in package.json and turbo.json to input patterns. Both file paths are
-        // relative to pkgPath
-        //
-        // - package.json is an input because if the `scripts` in the package.json
-        //   change (i.e. the tasks that turbo executes), we want a cache miss, since
-        //   any existing cache could be invalid.
-        // - turbo.json because it's the definition of the tasks themselves. The root
-        //   turbo.json is similarly included in the global hash. This file may not
-        //   exist in the workspace, but that is ok, because it will get ignored
-        //   downstream.
-        let turbo_g = to_glob("package.json")?;
-        let package_g = to_glob("turbo.json")?;
-        includes.push(turbo_g);
-        includes.push(package_g);
-
-        Some(any(includes)?)
-    };
-    let exclude_pattern = if excludes.is_empty() {
-        None
-    } else {
-        Some(any(excludes)?)
-    };
+    let matcher = GlobMatcher::from_patterns(&patterns)?;
 
     let walker = walker_builder
         .follow_links(false)
@@ -129,17 +114,13 @@ pub(crate) fn get_package_file_hashes_without_git<S: AsRef<str>>(
         let relative_path = relative_path.to_unix();
 
         // if we have includes, and this path doesn't match any of them, skip it
-        if let Some(include_pattern) = include_pattern.as_ref() {
-            if !include_pattern.is_match(relative_path.as_str()) {
-                continue;
-            }
+        if matcher.has_inclusions() && !matcher.is_included(relative_path.as_str()) {
+            continue;
         }
 
-        // if we have excludes, and this path matches one of them, skip it
-        if let Some(exclude_pattern) = exclude_pattern.as_ref() {
-            if exclude_pattern.is_match(relative_path.as_str()) {
-                continue;
-            }
+        // if this path matches one of the excludes, skip it
+        if matcher.is_excluded(relative_path.as_str()) {
+            continue;
         }
 
         // FIXME: we don't hash symlinks...
@@ -173,13 +154,11 @@ pub(crate) fn get_package_file_hashes_without_git<S: AsRef<str>>(
             let relative_path = full_package_path.anchor(path)?;
             let relative_path = relative_path.to_unix();
 
-            if let Some(exclude_pattern) = exclude_pattern.as_ref() {
-                if exclude_pattern.is_match(relative_path.as_str()) {
-                    // track excludes so we can exclude them to the hash map later
-                    if !metadata.is_symlink() {
-                        let hash = git_like_hash_file(path)?;
-                        excluded_file_hashes.insert(relative_path.clone(), hash);
-                    }
+            if matcher.is_excluded(relative_path.as_str()) {
+                // track excludes so we can exclude them to the hash map later
+                if !metadata.is_symlink() {
+                    let hash = git_like_hash_file(path)?;
+                    excluded_file_hashes.insert(relative_path.clone(), hash);
                 }
             }
 
@@ -331,6 +310,70 @@ mod tests {
         };
     }
 
+    #[test]
+    fn test_get_package_file_hashes_nested_gitignore() {
+        let (_tmp, turbo_root) = tmp_dir();
+        let pkg_path = AnchoredSystemPathBuf::from_raw("libA").unwrap();
+        let files = [
+            ("libA/.gitignore", "*.log\n!keep.log\n", true),
+            ("libA/keep.log", "keep", true),
+            ("libA/debug.log", "debug", false),
+            ("libA/src/.gitignore", "generated/\n", true),
+            ("libA/src/index.js", "index", true),
+            ("libA/src/generated/out.js", "out", false),
+            // git doesn't read `.ignore` files
+            ("libA/.ignore", "src/index.js\n", true),
+            // the gitlink left by checking the package out as a submodule
+            ("libA/.git", "gitdir: ../.git/modules/libA\n", false),
+        ];
+        for (path, contents, _) in files {
+            let file_path = turbo_root.join_unix_path(RelativeUnixPath::new(path).unwrap());
+            file_path.ensure_dir().unwrap();
+            file_path.create_with_contents(contents).unwrap();
+        }
+
+        let hashes =
+            get_package_file_hashes_without_git::<&str>(&turbo_root, &pkg_path, &[], false)
+                .unwrap();
+        let mut hashed = hashes.keys().map(|path| path.as_str()).collect::<Vec<_>>();
+        hashed.sort();
+        let mut expected = files
+            .iter()
+            .filter(|(_, _, included)| *included)
+            .map(|(path, _, _)| path.strip_prefix("libA/").unwrap())
+            .collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(hashed, expected);
+    }
+
+    #[test]
+    fn test_get_package_file_hashes_excluded_directory() {
+        let (_tmp, turbo_root) = tmp_dir();
+        let pkg_path = AnchoredSystemPathBuf::from_raw("libA").unwrap();
+        for path in [
+            "libA/package.json",
+            "libA/src/index.js",
+            "libA/src/generated/out.js",
+        ] {
+            let file_path = turbo_root.join_unix_path(RelativeUnixPath::new(path).unwrap());
+            file_path.ensure_dir().unwrap();
+            file_path.create_with_contents(path).unwrap();
+        }
+
+        // Excluding a directory excludes what's inside of it, the same as when
+        // inputs are found with git
+        let hashes = get_package_file_hashes_without_git(
+            &turbo_root,
+            &pkg_path,
+            &["src", "!src/generated"],
+            false,
+        )
+        .unwrap();
+        let mut hashed = hashes.keys().map(|path| path.as_str()).collect::<Vec<_>>();
+        hashed.sort();
+        assert_eq!(hashed, vec!["package.json", "src/index.js"]);
+    }
+
     #[test]
     fn test_get_package_file_hashes_from_processing_gitignore() {
         let root_ignore_contents = ["ignoreme", "ignorethisdir/"].join("\n");
@@ -1,8 +1,10 @@
 use std::{collections::HashMap, str::FromStr};
 
-use globwalk::ValidatedGlob;
+use globwalk::{GlobMatcher, ValidatedGlob};
 use tracing::debug;
-use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, PathError, RelativeUnixPathBuf};
+use turbopath::{
+    AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, PathError, RelativeUnixPathBuf,
+};
 use turborepo_telemetry::events::task::{FileHashMethod, PackageTaskEventBuilder};
 
 use crate::{hash_object::hash_objects, Error, Git, SCM};
@@ -11,6 +13,46 @@ pub type GitHashes = HashMap<RelativeUnixPathBuf, String>;
 
 pub const INPUT_INCLUDE_DEFAULT_FILES: &str = "$TURBO_DEFAULT$";
 
+/// Removes files with a path segment starting with `.` from `hashes`. Files
+/// matched by an input that spells out a dot segment itself, such as `.env` or
+/// `.github/**`, are kept.
+pub fn remove_dotfiles<S: AsRef<str>>(hashes: &mut GitHashes, inputs: &[S]) -> Result<(), Error> {
+    let explicit_dotfile_inputs = inputs
+        .iter()
+        .map(|input| input.as_ref())
+        .filter(|input| !input.starts_with('!') && *input != INPUT_INCLUDE_DEFAULT_FILES)
+        .filter(|input| input.split('/').any(is_dot_segment))
+        .collect::<Vec<_>>();
+    let explicit_dotfiles = GlobMatcher::from_patterns(&explicit_dotfile_inputs)?;
+
+    hashes.retain(|path, _| {
+        !path.as_str().split('/').any(is_dot_segment)
+            || explicit_dotfiles.is_included(path.as_str())
+    });
+    Ok(())
+}
+
+fn is_dot_segment(segment: &str) -> bool {
+    segment.starts_with('.') && segment != "." && segment != ".."
+}
+
+/// Returns where a package lives if it, or a directory between it and the
+/// repository root, is a symlink
+fn symlinked_package_target(
+    turbo_root: &AbsoluteSystemPath,
+    package_path: &AbsoluteSystemPath,
+) -> Result<Option<AbsoluteSystemPathBuf>, Error> {
+    let relative_path = turbo_root.anchor(package_path)?;
+    if relative_path.as_str().is_empty() {
+        return Ok(None);
+    }
+    let Ok(target) = package_path.to_realpath() else {
+        return Ok(None);
+    };
+    let unlinked_path = turbo_root.to_realpath()?.resolve(&relative_path);
+    Ok((target != unlinked_path).then_some(target))
+}
+
 impl SCM {
     pub fn get_hashes_for_files(
         &self,
@@ -42,32 +84,56 @@ impl SCM {
             .iter()
             .any(|input| input.as_ref() == INPUT_INCLUDE_DEFAULT_FILES);
 
-        match self {
+        // A package that is a symlink, like one added with `pnpm link`, is
+        // hashed where it actually lives
+        let full_package_path = turbo_root.resolve(package_path);
+        if let Some(target) = symlinked_package_target(turbo_root, &full_package_path)? {
+            debug!("hashing symlinked package {} at {}", package_path, target);
+            let scm = match self {
+                SCM::Manual => SCM::Manual,
+                SCM::Git(_) => SCM::new(&target),
+            };
+            return scm.get_package_file_hashes(
+                &target,
+                AnchoredSystemPath::empty(),
+                inputs,
+                telemetry,
+            );
+        }
+
+        // Symlinks are found in the index when git is available, walking the
+        // package is only needed when hashing without it
+        let (mut hashes, links) = match self {
             SCM::Manual => {
                 if let Some(telemetry) = telemetry {
                     telemetry.track_file_hash_method(FileHashMethod::Manual);
                 }
-                crate::manual::get_package_file_hashes_without_git(
+                let hashes = crate::manual::get_package_file_hashes_without_git(
                     turbo_root,
                     package_path,
                     inputs,
                     include_default_files,
-                )
+                )?;
+                (hashes, crate::symlink::find_symlinks(&full_package_path)?)
             }
             SCM::Git(git) => {
-                let result = git.get_package_file_hashes(
-                    turbo_root,
-                    package_path,
-                    inputs,
-                    include_default_files,
-                );
+                let result = git
+                    .get_package_file_hashes(
+                        turbo_root,
+                        package_path,
+                        inputs,
+                        include_default_files,
+                    )
+                    .and_then(|hashes| Ok((hashes, git.ls_symlinks(&full_package_path)?)));
                 match result {
-                    Ok(hashes) => {
+                    Ok(result) => {
                         if let Some(telemetry) = telemetry {
                             telemetry.track_file_hash_method(FileHashMethod::Git);
                         }
-                        Ok(hashes)
+                        result
                     }
+                    // There's nothing on disk to fall back to
+                    Err(err @ Error::NotMaterialized(_)) => return Err(err),
                     Err(err) => {
                         debug!(
                             "failed to use git to hash files: {}. Falling back to manual",
@@ -76,16 +142,25 @@ impl SCM {
                         if let Some(telemetry) = telemetry {
                             telemetry.track_file_hash_method(FileHashMethod::Manual);
                         }
-                        crate::manual::get_package_file_hashes_without_git(
+                        let hashes = crate::manual::get_package_file_hashes_without_git(
                             turbo_root,
                             package_path,
                             inputs,
                             include_default_files,
-                        )
+                        )?;
+                        (hashes, crate::symlink::find_symlinks(&full_package_path)?)
                     }
                 }
             }
-        }
+        };
+        crate::symlink::hash_symlinks(
+            &full_package_path,
+            links,
+            inputs,
+            include_default_files,
+            &mut hashes,
+        )?;
+        Ok(hashes)
     }
 
     pub fn hash_files(
@@ -119,6 +194,21 @@ impl Git {
         inputs: &[S],
         include_default_files: bool,
     ) -> Result<GitHashes, Error> {
+        // A package that is checked out as a submodule has its own index and
+        // ignore rules, so git needs to be run against the submodule instead
+        if let Some(submodule) = self.submodule_containing(&turbo_root.resolve(package_path)) {
+            return submodule.get_package_file_hashes(
+                turbo_root,
+                package_path,
+                inputs,
+                include_default_files,
+            );
+        }
+
+        if self.checkout.sparse && !turbo_root.resolve(package_path).exists() {
+            return Err(Error::NotMaterialized(package_path.to_string()));
+        }
+
         // no inputs, and no $TURBO_DEFAULT$
         if inputs.is_empty() {
             return self.get_package_file_hashes_from_index(turbo_root, package_path);
@@ -138,6 +228,14 @@ impl Git {
         self.get_package_file_hashes_from_inputs_and_index(turbo_root, package_path, inputs)
     }
 
+    /// Finds the innermost repository below our root that contains `dir`
+    fn submodule_containing(&self, dir: &AbsoluteSystemPath) -> Option<Git> {
+        dir.ancestors()
+            .take_while(|ancestor| *ancestor != &*self.root && self.root.contains(ancestor))
+            .find(|ancestor| ancestor.join_component(".git").exists())
+            .map(|root| Git::at_root(root.to_owned(), self.bin.clone()))
+    }
+
     #[tracing::instrument(skip(self, turbo_root))]
     fn get_package_file_hashes_from_index(
         &self,
@@ -148,6 +246,16 @@ impl Git {
         let git_to_pkg_path = self.root.anchor(&full_pkg_path)?;
         let pkg_prefix = git_to_pkg_path.to_unix();
         let mut hashes = self.git_ls_tree(&full_pkg_path)?;
+        if self.checkout.sparse {
+            // Files outside of the sparse checkout are still in HEAD, but
+            // aren't something a task can read
+            hashes.retain(|path, _| {
+                full_pkg_path
+                    .join_unix_path(path)
+                    .symlink_metadata()
+                    .is_ok()
+            });
+        }
         // Note: to_hash is *git repo relative*
         let to_hash = self.append_git_status(&full_pkg_path, &pkg_prefix, &mut hashes)?;
         hash_objects(&self.root, &full_pkg_path, to_hash, &mut hashes)?;
@@ -296,7 +404,7 @@ mod tests {
     use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 
     use super::*;
-    use crate::manual::get_package_file_hashes_without_git;
+    use crate::{manual::get_package_file_hashes_without_git, Checkout};
 
     fn tmp_dir() -> (tempfile::TempDir, AbsoluteSystemPathBuf) {
         let tmp_dir = tempfile::tempdir().unwrap();
@@ -342,8 +450,8 @@ mod tests {
         link.symlink_to_dir("inside").unwrap();
         let to_hash = vec![RelativeUnixPathBuf::new("link").unwrap()];
         let mut hashes = GitHashes::new();
-        // FIXME: This test verifies a bug: we don't hash symlinks.
-        // TODO: update this test to point at get_package_file_hashes
+        // Hashing individual files skips symlinks to directories, they are
+        // expanded by get_package_file_hashes
         hash_objects(&git_root, &git_root, to_hash, &mut hashes).unwrap();
         assert!(hashes.is_empty());
 
@@ -353,6 +461,325 @@ mod tests {
         assert!(manual_hashes.is_empty());
     }
 
+    #[test]
+    fn test_get_package_file_hashes_with_symlinks() -> Result<(), Error> {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        let shared = repo_root.join_component("shared");
+        shared.join_component("src").create_dir_all()?;
+        shared
+            .join_components(&["src", "index.js"])
+            .create_with_contents("shared")?;
+        shared
+            .join_component("README.md")
+            .create_with_contents("readme")?;
+        let pkg = repo_root.join_component("pkg");
+        pkg.create_dir_all()?;
+        pkg.join_component("package.json")
+            .create_with_contents("{}")?;
+        pkg.join_component("src").symlink_to_dir("../shared/src")?;
+        pkg.join_component("README.md")
+            .symlink_to_file("../shared/README.md")?;
+        pkg.join_component("broken")
+            .symlink_to_file("does-not-exist")?;
+        commit_all(&repo_root);
+
+        let hash = crate::manual::git_like_hash;
+        let expected = |paths: &[&str]| {
+            [
+                ("package.json", hash(b"{}")),
+                ("src", hash(b"../shared/src")),
+                ("src/index.js", hash(b"shared")),
+                ("README.md", hash(b"readme")),
+                ("broken", hash(b"does-not-exist")),
+            ]
+            .into_iter()
+            .filter(|(path, _)| paths.contains(path))
+            .map(|(path, hash)| (RelativeUnixPathBuf::new(path).unwrap(), hash))
+            .collect::<GitHashes>()
+        };
+
+        let pkg_path = repo_root.anchor(&pkg)?;
+        let git = SCM::new(&repo_root);
+        assert_matches!(git, SCM::Git(_));
+        for scm in [git, SCM::Manual] {
+            let hashes = scm.get_package_file_hashes::<&str>(&repo_root, &pkg_path, &[], None)?;
+            assert_eq!(
+                hashes,
+                expected(&["package.json", "src", "src/index.js", "README.md", "broken"])
+            );
+
+            let hashes = scm.get_package_file_hashes(&repo_root, &pkg_path, &["src/**"], None)?;
+            assert_eq!(hashes, expected(&["package.json", "src", "src/index.js"]));
+
+            let hashes = scm.get_package_file_hashes(
+                &repo_root,
+                &pkg_path,
+                &[INPUT_INCLUDE_DEFAULT_FILES, "!src/**"],
+                None,
+            )?;
+            assert_eq!(hashes, expected(&["package.json", "README.md", "broken"]));
+        }
+
+        // Changes behind the link change the package's hashes
+        shared
+            .join_components(&["src", "index.js"])
+            .create_with_contents("changed")?;
+        let hashes = SCM::new(&repo_root).get_package_file_hashes::<&str>(
+            &repo_root,
+            &pkg_path,
+            &[],
+            None,
+        )?;
+        assert_eq!(
+            hashes.get(&RelativeUnixPathBuf::new("src/index.js")?),
+            Some(&hash(b"changed"))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_package_file_hashes_skips_untracked_symlinks() -> Result<(), Error> {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        let shared = repo_root.join_component("shared");
+        shared.create_dir_all()?;
+        shared
+            .join_component("index.js")
+            .create_with_contents("shared")?;
+        let pkg = repo_root.join_component("pkg");
+        pkg.join_component("src").create_dir_all()?;
+        pkg.join_component("package.json")
+            .create_with_contents("{}")?;
+        pkg.join_component(".gitignore")
+            .create_with_contents("src/generated\n")?;
+        pkg.join_components(&["src", "index.js"])
+            .create_with_contents("pkg")?;
+        commit_all(&repo_root);
+        // Ignored links aren't in the index, so git mode never sees them
+        pkg.join_components(&["src", "generated"])
+            .symlink_to_dir("../../shared")?;
+
+        let pkg_path = repo_root.anchor(&pkg)?;
+        let hashes = SCM::new(&repo_root).get_package_file_hashes(
+            &repo_root,
+            &pkg_path,
+            &["src/**"],
+            None,
+        )?;
+        let mut paths = hashes.keys().map(|path| path.as_str()).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(paths, ["package.json", "src/index.js"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_package_file_hashes_for_symlinked_package() -> Result<(), Error> {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        repo_root
+            .join_component("package.json")
+            .create_with_contents("{}")?;
+        // One package lives elsewhere in the repository, the other outside
+        // of it entirely
+        let (_outside_tmp, outside) = tmp_dir();
+        for target in [repo_root.join_components(&["other", "a"]), outside.clone()] {
+            target.create_dir_all()?;
+            target
+                .join_component("package.json")
+                .create_with_contents("{}")?;
+            target
+                .join_component("index.js")
+                .create_with_contents("index")?;
+        }
+        let packages = repo_root.join_component("packages");
+        packages.create_dir_all()?;
+        packages.join_component("a").symlink_to_dir("../other/a")?;
+        packages
+            .join_component("b")
+            .symlink_to_dir(outside.as_str())?;
+        commit_all(&repo_root);
+
+        let hash = crate::manual::git_like_hash;
+        let expected = GitHashes::from_iter([
+            (RelativeUnixPathBuf::new("package.json")?, hash(b"{}")),
+            (RelativeUnixPathBuf::new("index.js")?, hash(b"index")),
+        ]);
+        for package in ["a", "b"] {
+            let pkg_path = repo_root.anchor(packages.join_component(package))?;
+            for scm in [SCM::new(&repo_root), SCM::Manual] {
+                let hashes =
+                    scm.get_package_file_hashes::<&str>(&repo_root, &pkg_path, &[], None)?;
+                assert_eq!(hashes, expected, "package {package}");
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_package_file_hashes_in_sparse_checkout() -> Result<(), Error> {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        for package in ["a", "b"] {
+            let package_path = repo_root.join_component(package);
+            package_path.create_dir_all()?;
+            package_path
+                .join_component("package.json")
+                .create_with_contents("{}")?;
+            package_path
+                .join_component("index.js")
+                .create_with_contents(package)?;
+        }
+        commit_all(&repo_root);
+        require_git_cmd(
+            &repo_root,
+            &[
+                "sparse-checkout",
+                "set",
+                "--no-cone",
+                "/a/",
+                "/b/package.json",
+            ],
+        );
+
+        let scm = SCM::new(&repo_root);
+        assert!(scm.checkout().sparse);
+
+        let hash = crate::manual::git_like_hash;
+        let hashes = |package: &str| {
+            let package_path = repo_root.anchor(repo_root.join_component(package))?;
+            scm.get_package_file_hashes::<&str>(&repo_root, &package_path, &[], None)
+        };
+        assert_eq!(
+            hashes("a")?,
+            GitHashes::from_iter([
+                (RelativeUnixPathBuf::new("package.json")?, hash(b"{}")),
+                (RelativeUnixPathBuf::new("index.js")?, hash(b"a")),
+            ])
+        );
+        // Only the files that are checked out contribute
+        assert_eq!(
+            hashes("b")?,
+            GitHashes::from_iter([(RelativeUnixPathBuf::new("package.json")?, hash(b"{}"))])
+        );
+
+        require_git_cmd(&repo_root, &["sparse-checkout", "set", "--no-cone", "/a/"]);
+        assert_matches!(hashes("b"), Err(Error::NotMaterialized(path)) if path == "b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_outside_sparse_checkout() -> Result<(), Error> {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        for package in ["a", "b"] {
+            let package_path = repo_root.join_components(&["packages", package]);
+            package_path.create_dir_all()?;
+            package_path
+                .join_component("package.json")
+                .create_with_contents(format!("{{\"name\": \"{package}\"}}"))?;
+        }
+        commit_all(&repo_root);
+        require_git_cmd(
+            &repo_root,
+            &["sparse-checkout", "set", "--no-cone", "/packages/a/"],
+        );
+
+        let contents = SCM::new(&repo_root).read_outside_checkout(&repo_root, "package.json")?;
+        assert_eq!(
+            contents,
+            HashMap::from([(
+                AnchoredSystemPathBuf::from_raw(
+                    ["packages", "b", "package.json"].join(std::path::MAIN_SEPARATOR_STR)
+                )?,
+                "{\"name\": \"b\"}".to_string()
+            )])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_detect_partial_clone() {
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        assert_eq!(SCM::new(&repo_root).checkout(), Checkout::default());
+
+        require_git_cmd(
+            &repo_root,
+            &["config", "--local", "remote.origin.promisor", "true"],
+        );
+        let checkout = SCM::new(&repo_root).checkout();
+        assert!(checkout.partial_clone);
+        assert!(!checkout.sparse);
+    }
+
+    #[test]
+    fn test_get_package_file_hashes_in_submodule() -> Result<(), Error> {
+        let (_sub_tmp, sub_root) = tmp_dir();
+        setup_repository(&sub_root);
+        sub_root
+            .join_component(".gitignore")
+            .create_with_contents("*.log\n")?;
+        sub_root
+            .join_component("package.json")
+            .create_with_contents("{}")?;
+        commit_all(&sub_root);
+
+        let (_repo_tmp, repo_root) = tmp_dir();
+        setup_repository(&repo_root);
+        require_git_cmd(
+            &repo_root,
+            &[
+                "-c",
+                "protocol.file.allow=always",
+                "submodule",
+                "add",
+                sub_root.as_str(),
+                "my-pkg",
+            ],
+        );
+        commit_all(&repo_root);
+
+        let pkg_dir = repo_root.join_component("my-pkg");
+        pkg_dir
+            .join_component("debug.log")
+            .create_with_contents("ignored")?;
+        pkg_dir
+            .join_component("untracked-file")
+            .create_with_contents("untracked")?;
+
+        let SCM::Git(git) = SCM::new(&repo_root) else {
+            panic!("expected git");
+        };
+        let package_path = AnchoredSystemPathBuf::from_raw("my-pkg")?;
+        let hashes = git.get_package_file_hashes::<&str>(&repo_root, &package_path, &[], false)?;
+        let mut files = hashes.keys().map(|path| path.as_str()).collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, vec![".gitignore", "package.json", "untracked-file"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_dotfiles() -> Result<(), Error> {
+        let mut hashes = to_hash_map(&[
+            ("package.json", "1"),
+            (".env", "2"),
+            ("src/index.ts", "3"),
+            ("src/.eslintrc.js", "4"),
+            (".github/workflows/ci.yml", "5"),
+            ("../shared/.env", "6"),
+        ]);
+        remove_dotfiles(&mut hashes, &["src/**", ".github/**", "!src/*.test.ts"])?;
+
+        let mut files = hashes.keys().map(|path| path.as_str()).collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![".github/workflows/ci.yml", "package.json", "src/index.ts"]
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_get_package_deps_fallback() {
         let (_repo_root_tmp, repo_root) = tmp_dir();
@@ -0,0 +1,217 @@
+//! Symlinks in a package are hashed by what they point at. Git only stores
+//! the target path of a symlink, so without this a symlinked source directory
+//! contributes the same hash no matter what's inside of it.
+
+use std::{
+    collections::HashSet,
+    io::{BufRead, BufReader, Read},
+    process::{Command, Stdio},
+};
+
+use globwalk::GlobMatcher;
+use ignore::WalkBuilder;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPathBuf};
+
+use crate::{
+    manual::{git_like_hash, git_like_hash_file},
+    package_deps::GitHashes,
+    wait_for_success, Error, Git,
+};
+
+// The mode git records for symlinks
+const SYMLINK_MODE: &[u8] = b"120000";
+
+/// Decides which package relative paths a set of task inputs selects
+struct InputFilter {
+    matcher: GlobMatcher,
+}
+
+impl InputFilter {
+    fn new<S: AsRef<str>>(inputs: &[S]) -> Result<Self, Error> {
+        let mut patterns = inputs
+            .iter()
+            .map(|input| input.as_ref())
+            .collect::<Vec<_>>();
+        if patterns.iter().any(|pattern| !pattern.starts_with('!')) {
+            // package.json and turbo.json are always inputs, see
+            // `get_package_file_hashes_without_git`
+            patterns.extend(["package.json", "turbo.json"]);
+        }
+        Ok(Self {
+            matcher: GlobMatcher::from_patterns(&patterns)?,
+        })
+    }
+
+    fn is_match(&self, path: &str, include_default_files: bool) -> bool {
+        if self.matcher.is_excluded(path) {
+            return false;
+        }
+        include_default_files || !self.matcher.has_inclusions() || self.matcher.is_included(path)
+    }
+}
+
+/// Adds the contents of `links`, the symlinks in a package, to `hashes`. A
+/// link to a file is hashed by the file's contents. A link to a directory is
+/// recorded by where it points, and the files in the directory are hashed as
+/// if they were part of the package. Links that don't resolve keep the hash of
+/// where they point.
+pub(crate) fn hash_symlinks<S: AsRef<str>>(
+    package_path: &AbsoluteSystemPath,
+    links: Vec<AbsoluteSystemPathBuf>,
+    inputs: &[S],
+    include_default_files: bool,
+    hashes: &mut GitHashes,
+) -> Result<(), Error> {
+    let filter = InputFilter::new(inputs)?;
+    // Links are default files, only selected by the inputs if those are
+    // given without $TURBO_DEFAULT$
+    let is_default = inputs.is_empty() || include_default_files;
+
+    let mut included_dirs = HashSet::new();
+    let mut empty_dirs = Vec::new();
+    for link in links {
+        let relative_path = package_path.anchor(&link)?.to_unix();
+        let link_hash = git_like_hash(link.read_link()?.as_str().as_bytes());
+        let Ok(target) = link.to_realpath() else {
+            if filter.is_match(relative_path.as_str(), is_default) {
+                hashes.insert(relative_path, link_hash);
+            }
+            continue;
+        };
+
+        if !target.symlink_metadata()?.is_dir() {
+            if filter.is_match(relative_path.as_str(), is_default) {
+                hashes.insert(relative_path, git_like_hash_file(&target)?);
+            }
+            continue;
+        }
+
+        // The link itself is only recorded alongside the files it contributes
+        let mut any_included = false;
+        for file in walk_files(&target) {
+            let relative_file = RelativeUnixPathBuf::new(format!(
+                "{}/{}",
+                relative_path,
+                target.anchor(&file)?.to_unix()
+            ))?;
+            if filter.is_match(relative_file.as_str(), is_default) {
+                hashes.insert(relative_file, git_like_hash_file(&file)?);
+                any_included = true;
+            }
+        }
+        if any_included {
+            hashes.insert(relative_path.clone(), link_hash);
+            included_dirs.insert(relative_path);
+        } else {
+            empty_dirs.push(relative_path);
+        }
+    }
+    // Git records directory links even if everything in them is excluded
+    for dir in empty_dirs {
+        if !included_dirs.contains(&dir) {
+            hashes.remove(&dir);
+        }
+    }
+    Ok(())
+}
+
+impl Git {
+    /// Lists the symlinks git tracks below `package_path`. Links in
+    /// submodules are listed by the submodule's index, and ones that aren't
+    /// on disk, like those outside of a sparse checkout, are left out.
+    #[tracing::instrument(skip(self))]
+    pub(crate) fn ls_symlinks(
+        &self,
+        package_path: &AbsoluteSystemPath,
+    ) -> Result<Vec<AbsoluteSystemPathBuf>, Error> {
+        let mut git = Command::new(self.bin.as_std_path())
+            .args(["ls-files", "--stage", "-z", "--", "."])
+            .env("GIT_OPTIONAL_LOCKS", "0")
+            .current_dir(package_path)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let stdout = git
+            .stdout
+            .as_mut()
+            .ok_or_else(|| Error::git_error("failed to get stdout for git ls-files"))?;
+        let mut stderr = git
+            .stderr
+            .take()
+            .ok_or_else(|| Error::git_error("failed to get stderr for git ls-files"))?;
+        let parse_result = read_symlinks(stdout, package_path);
+        wait_for_success(git, &mut stderr, "git ls-files", package_path, parse_result)
+    }
+}
+
+fn read_symlinks<R: Read>(
+    reader: R,
+    package_path: &AbsoluteSystemPath,
+) -> Result<Vec<AbsoluteSystemPathBuf>, Error> {
+    let mut links = Vec::new();
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    while reader.read_until(b'\0', &mut buffer)? != 0 {
+        // Entries look like `<mode> <hash> <stage>\t<path>`
+        let entry = buffer.strip_suffix(b"\0").unwrap_or(&buffer);
+        let Some(tab) = entry.iter().position(|b| *b == b'\t') else {
+            return Err(Error::git_error(format!(
+                "failed to parse git ls-files: {}",
+                String::from_utf8_lossy(entry)
+            )));
+        };
+        let (metadata, path) = (&entry[..tab], &entry[tab + 1..]);
+        if metadata.starts_with(SYMLINK_MODE) {
+            let path = RelativeUnixPathBuf::new(String::from_utf8(path.to_vec())?)?;
+            let link = package_path.join_unix_path(&path);
+            if link
+                .symlink_metadata()
+                .map_or(false, |meta| meta.is_symlink())
+            {
+                links.push(link);
+            }
+        }
+        buffer.clear();
+    }
+    Ok(links)
+}
+
+/// Finds the symlinks in a package by walking it, respecting ignore files.
+/// Links inside `node_modules` are left out, they are managed by the package
+/// manager and accounted for by the lockfile.
+pub(crate) fn find_symlinks(
+    package_path: &AbsoluteSystemPath,
+) -> Result<Vec<AbsoluteSystemPathBuf>, Error> {
+    let mut links = Vec::new();
+    for entry in walker(package_path, false) {
+        let entry = entry?;
+        if entry.path_is_symlink() && entry.depth() > 0 {
+            links.push(AbsoluteSystemPath::from_std_path(entry.path())?.to_owned());
+        }
+    }
+    Ok(links)
+}
+
+// Yields the files below a link target, following links that point further.
+// Entries we can't read, including link cycles, are skipped.
+fn walk_files(target: &AbsoluteSystemPath) -> impl Iterator<Item = AbsoluteSystemPathBuf> {
+    walker(target, true)
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().map_or(false, |ty| ty.is_file()))
+        .filter_map(|entry| {
+            AbsoluteSystemPath::from_std_path(entry.path())
+                .ok()
+                .map(ToOwned::to_owned)
+        })
+}
+
+fn walker(root: &AbsoluteSystemPath, follow_links: bool) -> ignore::Walk {
+    WalkBuilder::new(root)
+        .follow_links(follow_links)
+        .require_git(false)
+        .hidden(false)
+        .ignore(false)
+        .filter_entry(|entry| entry.file_name() != ".git" && entry.file_name() != "node_modules")
+        .build()
+}
@@ -7,6 +7,7 @@
 mod color_selector;
 mod line;
 mod logs;
+mod ndjson;
 mod output;
 mod prefixed;
 pub mod sender;
@@ -24,6 +25,7 @@ pub use crate::{
     color_selector::ColorSelector,
     line::LineWriter,
     logs::{replay_logs, LogWriter},
+    ndjson::{LogSource, LogStream, NdjsonWriter},
     output::{OutputClient, OutputClientBehavior, OutputSink, OutputWriter},
     prefixed::{PrefixedUI, PrefixedWriter},
     tui::{TaskTable, TerminalPane},
@@ -220,6 +222,8 @@ lazy_static! {
     pub static ref BOLD: Style = Style::new().bold();
     pub static ref MAGENTA: Style = Style::new().magenta();
     pub static ref YELLOW: Style = Style::new().yellow();
+    pub static ref GREEN: Style = Style::new().green();
+    pub static ref RED: Style = Style::new().red();
     pub static ref BOLD_YELLOW_REVERSE: Style = Style::new().yellow().bold().reverse();
     pub static ref UNDERLINE: Style = Style::new().underlined();
     pub static ref BOLD_CYAN: Style = Style::new().cyan().bold();
@@ -1,6 +1,7 @@
 use std::{
     fs::File,
     io::{BufRead, BufReader, BufWriter, Write},
+    sync::{Arc, Mutex},
 };
 
 use tracing::{debug, warn};
@@ -11,7 +12,9 @@ use crate::Error;
 /// Receives logs and multiplexes them to a log file and/or a prefixed
 /// writer
 pub struct LogWriter<W> {
-    log_file: Option<BufWriter<File>>,
+    // Shared so a task's stdout and stderr can go to different writers while
+    // still being logged to the same file
+    log_file: Option<Arc<Mutex<BufWriter<File>>>>,
     writer: Option<W>,
 }
 
@@ -38,7 +41,7 @@ impl<W: Write> LogWriter<W> {
             Error::CannotWriteLogs(err)
         })?;
 
-        self.log_file = Some(BufWriter::new(log_file));
+        self.log_file = Some(Arc::new(Mutex::new(BufWriter::new(log_file))));
 
         Ok(())
     }
@@ -46,6 +49,19 @@ impl<W: Write> LogWriter<W> {
     pub fn with_writer(&mut self, writer: W) {
         self.writer = Some(writer);
     }
+
+    pub fn has_writer(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Creates a log writer that writes to the same log file as this one, but
+    /// without a writer of its own
+    pub fn share_log_file<W2>(&self) -> LogWriter<W2> {
+        LogWriter {
+            log_file: self.log_file.clone(),
+            writer: None,
+        }
+    }
 }
 
 impl<W: Write> Write for LogWriter<W> {
@@ -53,9 +69,9 @@ impl<W: Write> Write for LogWriter<W> {
         match (&mut self.log_file, &mut self.writer) {
             (Some(log_file), Some(prefixed_writer)) => {
                 let _ = prefixed_writer.write(buf)?;
-                log_file.write(buf)
+                log_file.lock().expect("log file lock poisoned").write(buf)
             }
-            (Some(log_file), None) => log_file.write(buf),
+            (Some(log_file), None) => log_file.lock().expect("log file lock poisoned").write(buf),
             (None, Some(prefixed_writer)) => prefixed_writer.write(buf),
             (None, None) => {
                 // Should this be an error or even a panic?
@@ -66,8 +82,8 @@ impl<W: Write> Write for LogWriter<W> {
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        if let Some(log_file) = &mut self.log_file {
-            log_file.flush()?;
+        if let Some(log_file) = &self.log_file {
+            log_file.lock().expect("log file lock poisoned").flush()?;
         }
         if let Some(prefixed_writer) = &mut self.writer {
             prefixed_writer.flush()?;
@@ -164,6 +180,35 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_shared_log_file() -> Result<()> {
+        let dir = tempdir()?;
+        let log_file_path = AbsoluteSystemPathBuf::try_from(dir.path().join("test.txt"))?;
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let mut stdout_writer = LogWriter::default();
+        stdout_writer.with_log_file(&log_file_path)?;
+        stdout_writer.with_writer(&mut stdout);
+        let mut stderr_writer = stdout_writer.share_log_file();
+        stderr_writer.with_writer(&mut stderr);
+
+        writeln!(stdout_writer, "one fish")?;
+        writeln!(stderr_writer, "two fish")?;
+        writeln!(stdout_writer, "red fish")?;
+        stdout_writer.flush()?;
+        stderr_writer.flush()?;
+        drop((stdout_writer, stderr_writer));
+
+        assert_eq!(String::from_utf8(stdout)?, "one fish\nred fish\n");
+        assert_eq!(String::from_utf8(stderr)?, "two fish\n");
+        assert_eq!(
+            log_file_path.read_to_string()?,
+            "one fish\ntwo fish\nred fish\n"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_replay_logs() -> Result<()> {
         let color_config = ColorConfig::new(false);
@@ -0,0 +1,164 @@
+//! Structured logs for `--log-format=ndjson`, where every line of output is
+//! written as a JSON object on its own line so log pipelines don't have to
+//! parse prefixed text.
+
+use std::{
+    io::{self, Write},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+
+// Shared by every writer in the process so lines can be put back in the order
+// they were produced, even once they're split across streams
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// The stream a line of output was written to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// The task a line of output belongs to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogSource {
+    pub package: String,
+    pub task: String,
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    timestamp: String,
+    sequence: u64,
+    package: Option<&'a str>,
+    task: Option<&'a str>,
+    stream: LogStream,
+    line: String,
+}
+
+/// Wraps a single line of output in a JSON object, including the trailing
+/// newline. Lines that don't belong to a task have a `null` package and task.
+pub fn ndjson_line(source: Option<&LogSource>, stream: LogStream, line: &[u8]) -> Vec<u8> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim_end_matches(['\n', '\r']);
+    let record = Record {
+        timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        sequence: SEQUENCE.fetch_add(1, Ordering::Relaxed),
+        package: source.map(|source| source.package.as_str()),
+        task: source.map(|source| source.task.as_str()),
+        stream,
+        line: console::strip_ansi_codes(line).into_owned(),
+    };
+    let mut bytes = serde_json::to_vec(&record).expect("log records are always serializable");
+    bytes.push(b'\n');
+    bytes
+}
+
+/// Writes each line written to it as a JSON object, for output from `turbo`
+/// itself rather than from a task
+pub struct NdjsonWriter<W: Write> {
+    writer: W,
+    stream: LogStream,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W, stream: LogStream) -> Self {
+        Self {
+            writer,
+            stream,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for NdjsonWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for line in buf.split_inclusive(|b| *b == b'\n') {
+            self.buffer.extend_from_slice(line);
+            if line.ends_with(b"\n") {
+                self.writer
+                    .write_all(&ndjson_line(None, self.stream, &self.buffer))?;
+                self.buffer.clear();
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.writer
+                .write_all(&ndjson_line(None, self.stream, &self.buffer))?;
+            self.buffer.clear();
+        }
+        self.writer.flush()
+    }
+}
+
+impl<W: Write> Drop for NdjsonWriter<W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    fn records(bytes: &[u8]) -> Vec<Value> {
+        std::str::from_utf8(bytes)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_ndjson_line() {
+        let source = LogSource {
+            package: "web".to_string(),
+            task: "build".to_string(),
+        };
+        let line = ndjson_line(
+            Some(&source),
+            LogStream::Stderr,
+            b"\x1b[31m\"failed\"\x1b[0m\r\n",
+        );
+        assert!(line.ends_with(b"}\n"));
+
+        let record = &records(&line)[0];
+        assert_eq!(record["package"], "web");
+        assert_eq!(record["task"], "build");
+        assert_eq!(record["stream"], "stderr");
+        assert_eq!(record["line"], "\"failed\"");
+        assert!(record["sequence"].is_u64());
+        assert!(record["timestamp"].as_str().unwrap().ends_with('Z'));
+    }
+
+    #[test]
+    fn test_ndjson_writer() -> io::Result<()> {
+        let mut out = Vec::new();
+        {
+            let mut writer = NdjsonWriter::new(&mut out, LogStream::Stdout);
+            write!(writer, "• Running ")?;
+            writeln!(writer, "build\n")?;
+            write!(writer, "unfinished")?;
+        }
+
+        let records = records(&out);
+        let lines = records
+            .iter()
+            .map(|record| record["line"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["• Running build", "", "unfinished"]);
+        assert!(records[0]["package"].is_null());
+        assert!(records[0]["task"].is_null());
+        assert!(records[0]["sequence"].as_u64() < records[1]["sequence"].as_u64());
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ use std::{
 
 use turborepo_ci::GroupPrefixFn;
 
+use crate::ndjson::{ndjson_line, LogSource, LogStream};
+
 /// OutputSink represent a sink for outputs that can be written to from multiple
 /// threads through the use of Loggers.
 pub struct OutputSink<W> {
@@ -26,6 +28,8 @@ pub struct OutputClient<W> {
     writers: Arc<Mutex<SinkWriters<W>>>,
     primary: Marginals,
     error: Marginals,
+    // When set, each line is written as a JSON object attributed to this task
+    ndjson_source: Option<LogSource>,
 }
 
 #[derive(Default)]
@@ -92,6 +96,7 @@ impl<W: Write> OutputSink<W> {
             writers,
             primary: Default::default(),
             error: Default::default(),
+            ndjson_source: None,
         }
     }
 }
@@ -113,6 +118,12 @@ impl<W: Write> OutputClient<W> {
         self.error = Marginals { header, footer };
     }
 
+    /// Writes each line as a JSON object attributed to the given task instead
+    /// of as it was received
+    pub fn with_ndjson(&mut self, source: LogSource) {
+        self.ndjson_source = Some(source);
+    }
+
     /// A writer that will write to the underlying sink's out writer according
     /// to this client's behavior.
     pub fn stdout(&self) -> OutputWriter<W> {
@@ -142,6 +153,7 @@ impl<W: Write> OutputClient<W> {
             writers,
             primary,
             error,
+            ..
         } = self;
         let buffers = buffer.map(|cell| cell.into_inner().expect("lock poisoned"));
         let header = use_error
@@ -192,6 +204,25 @@ impl<W: Write> OutputClient<W> {
         }))
     }
 
+    fn handle_line(&self, line: &[u8], destination: Destination) -> io::Result<()> {
+        match &self.ndjson_source {
+            Some(source) => {
+                let stream = match destination {
+                    Destination::Stdout => LogStream::Stdout,
+                    Destination::Stderr => LogStream::Stderr,
+                };
+                self.handle_bytes(SinkBytes {
+                    buffer: ndjson_line(Some(source), stream, line).into(),
+                    destination,
+                })
+            }
+            None => self.handle_bytes(SinkBytes {
+                buffer: line.into(),
+                destination,
+            }),
+        }
+    }
+
     fn handle_bytes(&self, bytes: SinkBytes) -> io::Result<()> {
         if matches!(
             self.behavior,
@@ -245,10 +276,7 @@ impl<'a, W: Write> Write for OutputWriter<'a, W> {
             // If the line doesn't end in a newline we assume it isn't finished and add it
             // to the buffer
             if line.ends_with(b"\n") {
-                self.logger.handle_bytes(SinkBytes {
-                    buffer: self.buffer.as_slice().into(),
-                    destination: self.destination,
-                })?;
+                self.logger.handle_line(&self.buffer, self.destination)?;
                 self.buffer.clear();
             }
         }
@@ -256,10 +284,10 @@ impl<'a, W: Write> Write for OutputWriter<'a, W> {
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.logger.handle_bytes(SinkBytes {
-            buffer: self.buffer.as_slice().into(),
-            destination: self.destination,
-        })?;
+        // Structured logs would turn an empty buffer into an empty record
+        if !self.buffer.is_empty() || self.logger.ndjson_source.is_none() {
+            self.logger.handle_line(&self.buffer, self.destination)?;
+        }
         self.buffer.clear();
         Ok(())
     }
@@ -433,6 +461,36 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_ndjson() -> io::Result<()> {
+        let sink = OutputSink::new(Vec::new(), Vec::new());
+        let mut logger = sink.logger(OutputClientBehavior::Grouped);
+        logger.with_ndjson(LogSource {
+            package: "web".to_string(),
+            task: "build".to_string(),
+        });
+
+        let mut out = logger.stdout();
+        let mut err = logger.stderr();
+        writeln!(&mut out, "building")?;
+        write!(&mut err, "warning")?;
+        err.flush()?;
+        out.flush()?;
+        logger.finish(false)?;
+
+        let SinkWriters { out, err } = Arc::into_inner(sink.writers).unwrap().into_inner().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let err = String::from_utf8(err).unwrap();
+        assert_eq!(out.lines().count(), 1);
+        assert!(
+            out.contains(r#""package":"web","task":"build","stream":"stdout","line":"building"}"#)
+        );
+        assert_eq!(err.lines().count(), 1);
+        assert!(err.contains(r#""stream":"stderr","line":"warning"}"#));
+
+        Ok(())
+    }
+
     #[test]
     fn test_loggers_wait_for_newline() {
         let b1 = Arc::new(Barrier::new(2));
@@ -95,6 +95,19 @@ impl<W: Write> PrefixedUI<W> {
             &mut self.out,
         )
     }
+
+    /// Like `output_prefixed_writer`, but with a second writer for the error
+    /// stream so output can keep the stream it was written to
+    pub fn output_prefixed_writers(&mut self) -> (PrefixedWriter<&mut W>, PrefixedWriter<&mut W>) {
+        let prefix = self
+            .output_prefix
+            .clone()
+            .unwrap_or_else(|| Style::new().apply_to(String::new()));
+        (
+            PrefixedWriter::new(self.color_config, prefix.clone(), &mut self.out),
+            PrefixedWriter::new(self.color_config, prefix, &mut self.err),
+        )
+    }
 }
 
 //
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use ratatui::{
     layout::{Constraint, Rect},
     style::{Color, Style, Stylize},
@@ -17,6 +19,9 @@ pub struct TaskTable<'b> {
 }
 
 const TASK_NAVIGATE_INSTRUCTIONS: &str = "↑ ↓ to navigate";
+// Wide enough to fit the longest duration we render e.g. "59.9s" or "59m59s"
+// along with a leading space
+const DURATION_WIDTH: u16 = 7;
 
 impl<'b> TaskTable<'b> {
     /// Construct a new table with all of the planned tasks
@@ -36,8 +41,8 @@ impl<'b> TaskTable<'b> {
             // Task column width should be large enough to fit "↑ ↓ to navigate instructions
             // and truncate tasks with more than 40 chars.
             .clamp(TASK_NAVIGATE_INSTRUCTIONS.len(), 40) as u16;
-        // Add space for column divider and status emoji
-        task_name_width + 1
+        // Add space for duration, column divider and status emoji
+        task_name_width + DURATION_WIDTH + 1
     }
 
     /// Update the current time of the table
@@ -55,6 +60,10 @@ impl<'b> TaskTable<'b> {
 
             Row::new(vec![
                 name,
+                Cell::new(Text::styled(
+                    format_duration(task.duration()),
+                    Style::default().dim(),
+                )),
                 match task.result() {
                     // matches Next.js (and many other CLI tools) https://github.com/vercel/next.js/blob/1a04d94aaec943d3cce93487fea3b8c8f8898f31/packages/next/src/build/output/log.ts
                     TaskResult::Success => {
@@ -73,17 +82,23 @@ impl<'b> TaskTable<'b> {
 
     fn running_rows(&self) -> impl Iterator<Item = Row> + '_ {
         let spinner = self.spinner.current();
-        self.tasks_by_type
-            .running
-            .iter()
-            .map(move |task| Row::new(vec![Cell::new(task.name()), Cell::new(Text::raw(spinner))]))
+        self.tasks_by_type.running.iter().map(move |task| {
+            Row::new(vec![
+                Cell::new(task.name()),
+                Cell::new(Text::styled(
+                    format_duration(task.start().elapsed()),
+                    Style::default().dim(),
+                )),
+                Cell::new(Text::raw(spinner)),
+            ])
+        })
     }
 
     fn planned_rows(&self) -> impl Iterator<Item = Row> + '_ {
         self.tasks_by_type
             .planned
             .iter()
-            .map(move |task| Row::new(vec![Cell::new(task.name()), Cell::new(" ")]))
+            .map(move |task| Row::new(vec![Cell::new(task.name()), Cell::new(""), Cell::new(" ")]))
     }
 }
 
@@ -99,6 +114,7 @@ impl<'a> StatefulWidget for &'a TaskTable<'a> {
                 .chain(self.finished_rows()),
             [
                 Constraint::Min(15),
+                Constraint::Length(DURATION_WIDTH),
                 // Status takes one cell to render
                 Constraint::Length(1),
             ],
@@ -106,15 +122,24 @@ impl<'a> StatefulWidget for &'a TaskTable<'a> {
         .highlight_style(Style::default().fg(Color::Yellow))
         .column_spacing(0)
         .header(
-            vec![format!("Tasks\n{bar}"), " \n─".to_owned()]
-                .into_iter()
-                .map(Cell::from)
-                .collect::<Row>()
-                .height(2),
+            vec![
+                format!("Tasks\n{bar}"),
+                format!(
+                    "{:>width$}\n{bar}",
+                    "Time ",
+                    width = DURATION_WIDTH as usize
+                ),
+                " \n─".to_owned(),
+            ]
+            .into_iter()
+            .map(Cell::from)
+            .collect::<Row>()
+            .height(2),
         )
         .footer(
             vec![
                 format!("{bar}\n{TASK_NAVIGATE_INSTRUCTIONS}"),
+                format!("{bar}\n "),
                 format!("─\n "),
             ]
             .into_iter()
@@ -125,3 +150,43 @@ impl<'a> StatefulWidget for &'a TaskTable<'a> {
         StatefulWidget::render(table, area, buf, state);
     }
 }
+
+/// Formats a task duration compactly so that it fits in the duration column
+fn format_duration(duration: Duration) -> String {
+    let millis = duration.as_millis();
+    let formatted = if millis < 1000 {
+        format!("{millis}ms")
+    } else if millis < 60_000 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        match secs / 3600 {
+            0 => format!("{}m{:02}s", secs / 60, secs % 60),
+            hours @ 1..=99 => format!("{hours}h{:02}m", secs % 3600 / 60),
+            // Anything longer would overflow the column
+            _ => ">99h".to_string(),
+        }
+    };
+    format!("{formatted:>width$} ", width = DURATION_WIDTH as usize - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::*;
+
+    #[test_case(Duration::from_millis(12), "  12ms " ; "milliseconds")]
+    #[test_case(Duration::from_millis(1300), "  1.3s " ; "seconds")]
+    #[test_case(Duration::from_secs(59), " 59.0s " ; "just under a minute")]
+    #[test_case(Duration::from_secs(200), " 3m20s " ; "minutes")]
+    #[test_case(Duration::from_secs(3599), "59m59s " ; "just under an hour")]
+    #[test_case(Duration::from_secs(3600), " 1h00m " ; "an hour")]
+    #[test_case(Duration::from_secs(100 * 3600 - 1), "99h59m " ; "just under a hundred hours")]
+    #[test_case(Duration::from_secs(100 * 3600), "  >99h " ; "a hundred hours")]
+    fn test_format_duration(duration: Duration, expected: &str) {
+        let formatted = format_duration(duration);
+        assert_eq!(formatted, expected);
+        assert_eq!(formatted.len(), DURATION_WIDTH as usize);
+    }
+}
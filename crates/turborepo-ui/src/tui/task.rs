@@ -1,5 +1,9 @@
 #![allow(dead_code)]
-use std::{collections::HashSet, mem, time::Instant};
+use std::{
+    collections::HashSet,
+    mem,
+    time::{Duration, Instant},
+};
 
 use super::{event::TaskResult, Error};
 
@@ -95,6 +99,10 @@ impl Task<Finished> {
         self.state.result
     }
 
+    pub fn duration(&self) -> Duration {
+        self.state.end.duration_since(self.state.start)
+    }
+
     pub fn restart(self) -> Task<Planned> {
         Task {
             name: self.name,
@@ -0,0 +1,70 @@
+import { describe, test } from "node:test";
+import { strict as assert } from "node:assert";
+import * as path from "node:path";
+import { Workspace, Package } from "../js/dist/index.js";
+
+type PackageReduced = Pick<Package, "name" | "relativePath">;
+
+interface FilterPackagesTestParams {
+  filters: string[];
+  expected: PackageReduced[];
+  description: string;
+}
+
+describe("filterPackages", () => {
+  const tests: FilterPackagesTestParams[] = [
+    {
+      description: "no filters",
+      filters: [],
+      expected: [
+        { name: "app-a", relativePath: "apps/app" },
+        { name: "ui", relativePath: "packages/ui" },
+      ],
+    },
+    {
+      description: "package name",
+      filters: ["ui"],
+      expected: [{ name: "ui", relativePath: "packages/ui" }],
+    },
+    {
+      description: "package and its dependencies",
+      filters: ["app-a..."],
+      expected: [
+        { name: "app-a", relativePath: "apps/app" },
+        { name: "ui", relativePath: "packages/ui" },
+      ],
+    },
+    {
+      description: "package dependents",
+      filters: ["...^ui"],
+      expected: [{ name: "app-a", relativePath: "apps/app" }],
+    },
+    {
+      description: "directory",
+      filters: ["./apps/*"],
+      expected: [{ name: "app-a", relativePath: "apps/app" }],
+    },
+  ];
+
+  for (const { description, filters, expected } of tests) {
+    test(description, async () => {
+      const dir = path.resolve(__dirname, "./fixtures/monorepo");
+      const workspace = await Workspace.find(dir);
+
+      const reduced: PackageReduced[] = (
+        await workspace.filterPackages(filters)
+      )
+        .map((pkg) => ({ name: pkg.name, relativePath: pkg.relativePath }))
+        .sort((a, b) => a.name.localeCompare(b.name));
+
+      assert.deepEqual(reduced, expected);
+    });
+  }
+
+  test("unknown package", async () => {
+    const dir = path.resolve(__dirname, "./fixtures/monorepo");
+    const workspace = await Workspace.find(dir);
+
+    await assert.rejects(workspace.filterPackages(["does-not-exist"]));
+  });
+});
@@ -0,0 +1,36 @@
+import { describe, test } from "node:test";
+import { strict as assert } from "node:assert";
+import * as path from "node:path";
+import { Workspace } from "../js/dist/index.js";
+
+describe("taskHashes", () => {
+  const dir = path.resolve(__dirname, "./fixtures/monorepo");
+
+  test("hashes each task", async () => {
+    const workspace = await Workspace.find(dir);
+    const hashes = await workspace.taskHashes(["build"]);
+
+    assert.deepEqual(
+      hashes.map(({ taskId, package: pkg, task }) => ({ taskId, pkg, task })),
+      [
+        { taskId: "app-a#build", pkg: "app-a", task: "build" },
+        { taskId: "ui#build", pkg: "ui", task: "build" },
+      ]
+    );
+    for (const { hash } of hashes) {
+      assert.match(hash, /^[0-9a-f]{16}$/);
+    }
+  });
+
+  test("hashes are stable and respect filters", async () => {
+    const workspace = await Workspace.find(dir);
+    const all = await workspace.taskHashes(["build"]);
+    const filtered = await workspace.taskHashes(["build"], ["ui"]);
+
+    assert.equal(filtered.length, 1);
+    assert.equal(
+      filtered[0].hash,
+      all.find((task) => task.taskId === "ui#build")?.hash
+    );
+  });
+});
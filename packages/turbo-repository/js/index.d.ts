@@ -20,6 +20,16 @@ export class PackageDetails {
   /** the packages that depend on this package */
   readonly dependents: Array<string>;
 }
+/** The hash of a task, as computed by `turbo run`. */
+export class TaskHash {
+  /** The task's ID, e.g. `web#build`. */
+  readonly taskId: string;
+  /** The name of the package the task belongs to. */
+  readonly package: string;
+  /** The name of the task. */
+  readonly task: string;
+  readonly hash: string;
+}
 export class PackageManager {
   /** The package manager name in lower case. */
   readonly name: string;
@@ -49,6 +59,22 @@ export class Workspace {
    *  }
    */
   findPackagesWithGraph(): Promise<Record<string, PackageDetails>>;
+  /**
+   * Returns the packages matched by the given `--filter` patterns, resolved
+   * the same way `turbo run` resolves them. An empty list matches every
+   * package.
+   */
+  filterPackages(filters: Array<string>): Promise<Array<Package>>;
+  /**
+   * Computes the hashes of the given tasks, optionally limited to the
+   * packages matched by `--filter` patterns, as `turbo run --dry` would.
+   * Tasks aren't run and nothing is written to the cache, but the cache is
+   * still checked for existing entries to report which tasks would hit.
+   */
+  taskHashes(
+    tasks: Array<string>,
+    filters?: Array<string> | undefined | null
+  ): Promise<Array<TaskHash>>;
   /**
    * Given a set of "changed" files, returns a set of packages that are
    * "affected" by the changes. The `files` argument is expected to be a list
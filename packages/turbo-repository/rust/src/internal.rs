@@ -1,14 +1,15 @@
 use napi::Status;
 use thiserror::Error;
 use turbopath::{AbsoluteSystemPathBuf, PathError};
+use turborepo_lib::api::RunBuilder;
 use turborepo_repository::{
     inference::{self, RepoMode as WorkspaceType, RepoState as WorkspaceState},
-    package_graph::PackageGraphBuilder,
+    package_graph::{PackageGraphBuilder, PackageName},
     package_json::PackageJson,
     package_manager,
 };
 
-use crate::{Package, PackageManager, Workspace};
+use crate::{Package, PackageManager, TaskHash, Workspace};
 
 /// This module is used to isolate code with defined errors
 /// from code in lib.rs that needs to have errors coerced to strings /
@@ -38,6 +39,8 @@ pub(crate) enum Error {
     PackageGraph(#[from] turborepo_repository::package_graph::Error),
     #[error("package.json error: {0}")]
     PackageJson(#[from] turborepo_repository::package_json::Error),
+    #[error(transparent)]
+    Run(#[from] turborepo_lib::api::Error),
 }
 
 impl From<Error> for napi::Error<Status> {
@@ -140,4 +143,55 @@ impl Workspace {
 
         Ok(packages)
     }
+
+    pub(crate) async fn filter_packages_internal(
+        &self,
+        filters: Vec<String>,
+    ) -> Result<Vec<Package>, Error> {
+        let mut builder = RunBuilder::new(self.workspace_state.root.clone());
+        for filter in filters {
+            builder = builder.with_filter(filter);
+        }
+        let names = builder.resolve_filters().await?;
+
+        Ok(names
+            .into_iter()
+            .map(PackageName::from)
+            .filter(|name| !matches!(name, PackageName::Root))
+            .filter_map(|name| {
+                let info = self.graph.package_info(&name)?;
+                let package_path = self.workspace_state.root.resolve(info.package_path());
+                Some(Package::new(
+                    name.to_string(),
+                    &self.workspace_state.root,
+                    &package_path,
+                ))
+            })
+            .collect())
+    }
+
+    pub(crate) async fn task_hashes_internal(
+        &self,
+        tasks: Vec<String>,
+        filters: Vec<String>,
+    ) -> Result<Vec<TaskHash>, Error> {
+        let mut builder = RunBuilder::new(self.workspace_state.root.clone()).with_tasks(tasks);
+        for filter in filters {
+            builder = builder.with_filter(filter);
+        }
+        let result = builder.dry_run().await?;
+
+        let mut hashes = result
+            .tasks
+            .into_iter()
+            .map(|task| TaskHash {
+                task_id: task.task_id,
+                package: task.package,
+                task: task.task,
+                hash: task.hash,
+            })
+            .collect::<Vec<_>>();
+        hashes.sort_by(|a, b| a.task_id.cmp(&b.task_id));
+        Ok(hashes)
+    }
 }
@@ -38,6 +38,23 @@ pub struct PackageDetails {
     pub dependents: Vec<String>,
 }
 
+/// The hash of a task, as computed by `turbo run`.
+#[napi]
+#[derive(Debug)]
+pub struct TaskHash {
+    /// The task's ID, e.g. `web#build`.
+    #[napi(readonly)]
+    pub task_id: String,
+    /// The name of the package the task belongs to.
+    #[napi(readonly)]
+    pub package: String,
+    /// The name of the task.
+    #[napi(readonly)]
+    pub task: String,
+    #[napi(readonly)]
+    pub hash: String,
+}
+
 #[derive(Clone)]
 #[napi]
 pub struct PackageManager {
@@ -182,6 +199,31 @@ impl Workspace {
         Ok(map)
     }
 
+    /// Returns the packages matched by the given `--filter` patterns, resolved
+    /// the same way `turbo run` resolves them. An empty list matches every
+    /// package.
+    #[napi]
+    pub async fn filter_packages(&self, filters: Vec<String>) -> Result<Vec<Package>, Error> {
+        self.filter_packages_internal(filters)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Computes the hashes of the given tasks, optionally limited to the
+    /// packages matched by `--filter` patterns, as `turbo run --dry` would.
+    /// Tasks aren't run and nothing is written to the cache, but the cache is
+    /// still checked for existing entries to report which tasks would hit.
+    #[napi]
+    pub async fn task_hashes(
+        &self,
+        tasks: Vec<String>,
+        filters: Option<Vec<String>>,
+    ) -> Result<Vec<TaskHash>, Error> {
+        self.task_hashes_internal(tasks, filters.unwrap_or_default())
+            .await
+            .map_err(|e| e.into())
+    }
+
     /// Given a set of "changed" files, returns a set of packages that are
     /// "affected" by the changes. The `files` argument is expected to be a list
     /// of strings relative to the monorepo root and use the current system's
@@ -5,6 +5,9 @@ export type OutputLogs =
   | "errors-only"
   | "none";
 export type EnvMode = "strict" | "loose";
+export type LogOrder = "auto" | "stream" | "grouped";
+export type LogPrefix = "auto" | "none" | "task";
+export type CachePolicy = boolean | "read-only" | "write-only";
 export type UI = "tui" | "stream";
 
 /**
@@ -55,6 +58,22 @@ export interface WorkspaceSchema extends BaseSchema {
 }
 
 export interface RootSchema extends BaseSchema {
+  /**
+   * Presets to build this configuration on, from packages in your Workspace
+   * or your root `node_modules`. A package name refers to the
+   * `turbo.preset.json` file in that package, and a package name followed by
+   * a path refers to that file instead.
+   *
+   * Presets are applied in order, followed by this file. Tasks are merged
+   * key by key, while `globalEnv`, `globalPassThroughEnv` and
+   * `globalDependencies` are combined.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#presets
+   *
+   * @defaultValue `[]`
+   */
+  extends?: Array<string>;
+
   /**
    * A list of globs to include in the set of implicit global hash dependencies.
    *
@@ -135,6 +154,32 @@ export interface RootSchema extends BaseSchema {
    */
   cacheDir?: RelativeUnixPath;
 
+  /**
+   * The zstd compression level used for cache artifacts, from 1 to 19.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#cachecompressionlevel
+   *
+   * @defaultValue `3`
+   */
+  cacheCompressionLevel?: number;
+
+  /**
+   * The maximum size of the filesystem cache, as a number of bytes with an
+   * optional unit like "500MB" or "10GB". The least recently used artifacts
+   * are removed once a run finishes with the cache over this size.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#cachemaxsize
+   */
+  cacheMaxSize?: string;
+
+  /**
+   * How long an artifact may stay in the filesystem cache without being
+   * written or restored, like "12h" or "7d".
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#cachemaxage
+   */
+  cacheMaxAge?: string;
+
   /**
    * Turborepo runs a background process to pre-calculate some expensive operations. This standalone process (daemon) is a performance optimization, and not required for proper functioning of `turbo`.
    *
@@ -155,6 +200,129 @@ export interface RootSchema extends BaseSchema {
    * @defaultValue `"strict"`
    */
   envMode?: EnvMode;
+
+  /**
+   * Set the order of task logs for every run:
+   *
+   * - `"stream"`: Show output as soon as it is available.
+   * - `"grouped"`: Buffer each task's output and print it when the task finishes.
+   * - `"auto"`: Let turbo decide based on the execution environment.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#logorder
+   *
+   * @defaultValue `"auto"`
+   */
+  logOrder?: LogOrder;
+
+  /**
+   * Set the prefix applied to task logs for every run:
+   *
+   * - `"task"`: Prefix each line with the task id.
+   * - `"none"`: Print logs without a prefix.
+   * - `"auto"`: Let turbo decide based on the execution environment.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#logprefix
+   *
+   * @defaultValue `"auto"`
+   */
+  logPrefix?: LogPrefix;
+
+  /**
+   * What to do when packages depend on each other in a cycle. `"error"` stops
+   * the run, while `"warn"` logs each cycle and continues.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#dependencycycles
+   *
+   * @defaultValue `"error"`
+   */
+  dependencyCycles?: "error" | "warn";
+
+  /**
+   * How to hash the files in packages. `"git"` uses git when the repository
+   * is a git repository, while `"filesystem"` always walks and hashes files
+   * directly.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#hashmode
+   *
+   * @defaultValue `"git"`
+   */
+  hashMode?: "git" | "filesystem";
+
+  /**
+   * Where the daemon gets file change events from. `"auto"` uses the
+   * operating system's file watching API unless the repository is on a network
+   * filesystem, where it polls instead. `"native"` always uses the operating
+   * system's file watching API, `"poll"` always scans the repository for
+   * changes, and `"watchman"` subscribes to a running Watchman server and falls
+   * back to the native watcher when Watchman isn't available.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#watcher
+   *
+   * @defaultValue `"auto"`
+   */
+  watcher?: "auto" | "native" | "poll" | "watchman";
+
+  /**
+   * How often the daemon scans the repository for changes when it polls, like
+   * "500ms" or "5s". Skips `node_modules`, `.git` and `.turbo`.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#pollinterval
+   *
+   * @defaultValue `"1s"`
+   */
+  pollInterval?: string;
+
+  /**
+   * Sends the Run Summary of every run to an endpoint of your own in a POST
+   * request.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#runsummaryupload
+   */
+  runSummaryUpload?: RunSummaryUpload;
+
+  /**
+   * Exit codes for run outcomes that CI should treat differently. Outcomes
+   * without an exit code here fall through to the next one, ending with `0`.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#exitcodes
+   */
+  exitCodes?: ExitCodes;
+}
+
+export interface RunSummaryUpload {
+  /**
+   * Where to send run summaries. Must use https, except for localhost.
+   */
+  url: string;
+
+  /**
+   * Headers to send with each run summary. `${NAME}` in a value is replaced
+   * with the `NAME` environment variable, which must start with
+   * `TURBO_RUN_SUMMARY_`.
+   */
+  headers?: Record<string, string>;
+}
+
+export interface ExitCodes {
+  /**
+   * Used instead of the exit code of the failed task when a task fails.
+   */
+  taskFailure?: number;
+
+  /**
+   * Used when the filters and tasks don't match any tasks to run.
+   */
+  noTasks?: number;
+
+  /**
+   * Used when every task passes but a request to the Remote Cache fails.
+   */
+  remoteCacheError?: number;
+
+  /**
+   * Used when every task passes but the run finishes with warnings.
+   */
+  warnings?: number;
 }
 
 export interface Pipeline {
@@ -218,12 +386,14 @@ export interface Pipeline {
    * Whether or not to cache the outputs of the task.
    *
    * Setting cache to false is useful for long-running "watch" or development mode tasks.
+   * Use "read-only" to restore outputs without saving new artifacts, or "write-only"
+   * to always run the task while still saving its outputs.
    *
    * Documentation: https://turbo.build/repo/docs/reference/configuration#cache
    *
    * @defaultValue `true`
    */
-  cache?: boolean;
+  cache?: CachePolicy;
 
   /**
    * The set of glob patterns to consider as inputs to this task.
@@ -242,6 +412,30 @@ export interface Pipeline {
    */
   inputs?: Array<string>;
 
+  /**
+   * Tasks in the same package whose outputs are inputs to this task.
+   *
+   * This task depends on each of them, and the globs in their `outputs` are
+   * added to this task's `inputs`. Each task must declare `outputs`.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#inputsfrom
+   *
+   * @defaultValue `[]`
+   */
+  inputsFrom?: Array<string>;
+
+  /**
+   * Whether files with a path segment starting with `.` are considered inputs.
+   *
+   * When `false`, these files are only inputs if a glob in `inputs` names the
+   * dot segment itself, like `.env` or `.github/**`.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#dotfiles
+   *
+   * @defaultValue `true`
+   */
+  dotfiles?: boolean;
+
   /**
    * Output mode for the task.
    *
@@ -272,6 +466,18 @@ export interface Pipeline {
    */
   persistent?: boolean;
 
+  /**
+   * Skip the task when it last succeeded with the same hash and its outputs
+   * haven't changed since, even if it can't restore from the cache. Useful
+   * for `"cache": false` tasks like linting. The `turbo` daemon keeps track
+   * of the last run, so this has no effect without it.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#skippable
+   *
+   * @defaultValue `false`
+   */
+  skippable?: boolean;
+
   /**
    * Mark a task as interactive allowing it to receive input from stdin.
    * Interactive tasks must be marked with "cache": false as the input
@@ -282,6 +488,20 @@ export interface Pipeline {
    * @defaultValue `false`
    */
   interactive?: boolean;
+
+  /**
+   * Run the task once for each value in the matrix.
+   *
+   * Each value becomes a `<task>:<value>` task that runs this task's script
+   * with the value in the `TURBO_MATRIX` environment variable and
+   * `$TURBO_MATRIX` replaced in `inputs` and `outputs`. The task itself runs
+   * no script and depends on all of its variants.
+   *
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#matrix
+   *
+   * @defaultValue `[]`
+   */
+  matrix?: Array<string>;
 }
 
 export interface RemoteCache {
@@ -295,6 +515,16 @@ export interface RemoteCache {
    */
   signature?: boolean;
 
+  /**
+   * What to do when a downloaded artifact has an invalid signature or is
+   * missing one. `"error"` refuses to restore the artifact, while `"warn"`
+   * logs a warning and restores it anyway.
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#signaturemismatch
+   *
+   * @defaultValue `"error"`
+   */
+  signatureMismatch?: "error" | "warn";
+
   /**
    * Indicates if the remote cache is enabled. When `false`, Turborepo will disable
    * all remote cache operations, even if the repo has a valid token. If true, remote caching
@@ -336,13 +566,55 @@ export interface RemoteCache {
    * @defaultValue `30`
    */
   timeout?: number;
+  /**
+   * Sets how many times a failed upload or download is retried, with
+   * exponential backoff between attempts. If `0` is passed, then failed
+   * transfers are not retried.
+   *
+   * @defaultValue `3`
+   */
+  retries?: number;
+  /**
+   * Upload large artifacts as content-defined chunks, so a small change to a
+   * big output only uploads the chunks that changed.
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#chunking
+   *
+   * @defaultValue `false`
+   */
+  chunking?: boolean;
+  /**
+   * Store artifacts directly in a cloud storage bucket instead of going
+   * through a Remote Cache API. Accepts `gs://<bucket>[/<prefix>]` for Google
+   * Cloud Storage and `azblob://<account>/<container>[/<prefix>]` for Azure
+   * Blob Storage.
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#storageurl
+   */
+  storageUrl?: string;
+  /**
+   * Authenticate with the Remote Cache by exchanging the OIDC token your CI
+   * provider issues to the job, instead of using a long-lived token.
+   * Documentation: https://turbo.build/repo/docs/reference/configuration#oidc
+   */
+  oidc?: RemoteCacheOidc;
 }
 
-export const isRootSchemaV2 = (schema: Schema): schema is RootSchema =>
-  !("extends" in schema);
+export interface RemoteCacheOidc {
+  /**
+   * The audience the OIDC token must be issued for. Setting it enables OIDC
+   * authentication.
+   */
+  audience?: string;
+  /**
+   * Only accept OIDC tokens from this issuer.
+   */
+  issuer?: string;
+}
 
 export const isWorkspaceSchemaV2 = (
   schema: Schema
-): schema is WorkspaceSchema => !isRootSchemaV2(schema);
+): schema is WorkspaceSchema => Boolean(schema.extends?.includes("//"));
+
+export const isRootSchemaV2 = (schema: Schema): schema is RootSchema =>
+  !isWorkspaceSchemaV2(schema);
 
 export type Schema = RootSchema | WorkspaceSchema;
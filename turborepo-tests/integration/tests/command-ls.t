@@ -45,7 +45,9 @@ Run info with filter
 Run info on package `another`
   $ ${TURBO} ls another
    WARNING  ls command is experimental and may change in the future
-  another depends on: <no packages>
+  another packages[\/\\]another (re)
+  depends on: <no packages>
+  depended on by: <no packages>
   
   tasks:
     dev: echo building
@@ -54,7 +56,9 @@ Run info on package `another`
 Run info on package `my-app`
   $ ${TURBO} ls my-app
    WARNING  ls command is experimental and may change in the future
-  my-app depends on: util
+  my-app apps[\/\\]my-app (re)
+  depends on: util
+  depended on by: <no packages>
   
   tasks:
     build: echo building
@@ -67,6 +71,7 @@ Run info on package `my-app` with json output
     "packages": [
       {
         "name": "my-app",
+        "path": "apps(\/|\\\\)my-app", (re)
         "tasks": {
           "count": 2,
           "items": [
@@ -82,7 +87,20 @@ Run info on package `my-app` with json output
         },
         "dependencies": [
           "util"
-        ]
+        ],
+        "dependents": []
       }
     ]
   }
+
+Run info on package `util`
+  $ ${TURBO} ls util
+   WARNING  ls command is experimental and may change in the future
+  util packages[\/\\]util (re)
+  depends on: <no packages>
+  depended on by: my-app
+  
+  tasks:
+    build: echo building
+    maybefails: echo didnotfail
+  
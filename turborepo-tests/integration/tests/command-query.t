@@ -94,6 +94,36 @@ Query packages that have a task named `build` or `dev`
     }
   }
 
+Query packages that depend on `util` and have a task named `build`
+  $ ${TURBO} query "query { packages(filter: { and: [{ has: { field: DIRECT_DEPENDENCY, value: \"util\" } }, { has: { field: TASK_NAME, value: \"build\" } }] }) { items { name } } }" | jq
+   WARNING  query command is experimental and may change in the future
+  {
+    "data": {
+      "packages": {
+        "items": [
+          {
+            "name": "my-app"
+          }
+        ]
+      }
+    }
+  }
+
+Query packages that `my-app` depends on, directly or indirectly
+  $ ${TURBO} query "query { packages(filter: { has: { field: ALL_DEPENDENT, value: \"my-app\" } }) { items { name } } }" | jq
+   WARNING  query command is experimental and may change in the future
+  {
+    "data": {
+      "packages": {
+        "items": [
+          {
+            "name": "util"
+          }
+        ]
+      }
+    }
+  }
+
 Get dependents of `util`
   $ ${TURBO} query "query { packages(filter: { equal: { field: NAME, value: \"util\" } }) { items { directDependents { items { name } } } } }" | jq
    WARNING  query command is experimental and may change in the future
@@ -57,6 +57,7 @@ Setup
       "package.json": "1746e0db2361085b5953a6a3beab08c24af5bc08"
     },
     "hashOfExternalDependencies": "459c029558afe716",
+    "externalDependencies": [],
     "cache": {
       "local": false,
       "remote": false,
@@ -85,6 +86,7 @@ Setup
         "$TURBO_DEFAULT$",
         ".env.local"
       ],
+      "dotfiles": true,
       "outputLogs": "full",
       "persistent": false,
       "interruptible": false,
@@ -117,6 +119,7 @@ Setup
       "package.json": "e755064fd7893809d10fc067bb409c7ae516327f"
     },
     "hashOfExternalDependencies": "459c029558afe716",
+    "externalDependencies": [],
     "cache": {
       "local": false,
       "remote": false,
@@ -136,6 +139,7 @@ Setup
       "cache": true,
       "dependsOn": [],
       "inputs": [],
+      "dotfiles": true,
       "outputLogs": "full",
       "persistent": false,
       "interruptible": false,
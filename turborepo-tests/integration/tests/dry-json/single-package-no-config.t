@@ -42,6 +42,7 @@ Setup
           "somefile.txt": "45b983be36b73c0788dc9cbcb76cbb80fc7bb057"
         },
         "hashOfExternalDependencies": "",
+        "externalDependencies": [],
         "cache": {
           "local": false,
           "remote": false,
@@ -60,6 +61,7 @@ Setup
           "cache": false,
           "dependsOn": [],
           "inputs": [],
+          "dotfiles": true,
           "outputLogs": "full",
           "persistent": false,
           "interruptible": false,
@@ -42,6 +42,7 @@ Setup
           "turbo.json": "ce5bdbed55601768de641f5d8d005a8f5be8d3f7"
         },
         "hashOfExternalDependencies": "",
+        "externalDependencies": [],
         "cache": {
           "local": false,
           "remote": false,
@@ -66,6 +67,7 @@ Setup
           "cache": true,
           "dependsOn": [],
           "inputs": [],
+          "dotfiles": true,
           "outputLogs": "full",
           "persistent": false,
           "interruptible": false,
@@ -98,6 +100,7 @@ Setup
           "turbo.json": "ce5bdbed55601768de641f5d8d005a8f5be8d3f7"
         },
         "hashOfExternalDependencies": "",
+        "externalDependencies": [],
         "cache": {
           "local": false,
           "remote": false,
@@ -120,6 +123,7 @@ Setup
             "build"
           ],
           "inputs": [],
+          "dotfiles": true,
           "outputLogs": "full",
           "persistent": false,
           "interruptible": false,
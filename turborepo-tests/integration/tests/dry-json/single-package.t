@@ -42,6 +42,7 @@ Setup
           "turbo.json": "ce5bdbed55601768de641f5d8d005a8f5be8d3f7"
         },
         "hashOfExternalDependencies": "",
+        "externalDependencies": [],
         "cache": {
           "local": false,
           "remote": false,
@@ -64,6 +65,7 @@ Setup
           "cache": true,
           "dependsOn": [],
           "inputs": [],
+          "dotfiles": true,
           "outputLogs": "full",
           "persistent": false,
           "interruptible": false,
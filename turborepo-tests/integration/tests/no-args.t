@@ -8,21 +8,26 @@ Make sure exit code is 2 when no args are passed
   Usage: turbo(\.exe)? \[OPTIONS\] \[COMMAND\] (re)
   
   Commands:
-    bin         Get the path to the Turbo binary
-    completion  Generate the autocompletion script for the specified shell
-    daemon      Runs the Turborepo background daemon
-    generate    Generate a new app / package
-    telemetry   Enable or disable anonymous telemetry
-    scan        Turbo your monorepo by running a number of 'repo lints' to identify common issues, suggest fixes, and improve performance
-    ls          EXPERIMENTAL: List packages in your monorepo
-    link        Link your local directory to a Vercel organization and enable remote caching
-    login       Login to your Vercel account
-    logout      Logout to your Vercel account
-    prune       Prepare a subset of your monorepo
-    run         Run tasks across projects in your monorepo
-    query       Query your monorepo using GraphQL. If no query is provided, spins up a GraphQL server with GraphiQL
-    watch       Arguments used in run and watch
-    unlink      Unlink the current directory from your Vercel organization and disable Remote Caching
+    bin          Get the path to the Turbo binary
+    cache        Manage the local cache
+    check-globs  Print the files that globs select, matched the same way as task `inputs` and `outputs`
+    completion   Generate the autocompletion script for the specified shell
+    daemon       Runs the Turborepo background daemon
+    explain      Show what went into a task's hash, or what changed since a previous run
+    generate     Generate a new app / package
+    telemetry    Enable or disable anonymous telemetry
+    scan         Turbo your monorepo by running a number of 'repo lints' to identify common issues, suggest fixes, and improve performance
+    ls           EXPERIMENTAL: List packages in your monorepo
+    graph        Output the package graph of your monorepo, and optionally the task graph for the given tasks, without running anything
+    link         Link your local directory to a Vercel organization and enable remote caching
+    login        Login to your Vercel account
+    logout       Logout to your Vercel account
+    prefetch     Download the remote cache artifacts for the selected tasks into the local cache without running them
+    prune        Prepare a subset of your monorepo
+    run          Run tasks across projects in your monorepo
+    query        Query your monorepo using GraphQL. If no query is provided, spins up a GraphQL server with GraphiQL
+    watch        Arguments used in run and watch
+    unlink       Unlink the current directory from your Vercel organization and disable Remote Caching
   
   Options:
         --version
@@ -61,6 +66,8 @@ Make sure exit code is 2 when no args are passed
             Allow for missing `packageManager` in `package.json`
         --root-turbo-json <ROOT_TURBO_JSON>
             Use the `turbo.json` located at the provided path instead of one at the root of the repository
+        --config-overlay <NAME>
+            Merge the `turbo.<NAME>.json` overlay next to the root `turbo.json` over it
     -h, --help
             Print help (see more with '--help')
   
@@ -70,7 +77,7 @@ Make sure exit code is 2 when no args are passed
         --dry-run [<DRY_RUN>]
             [possible values: text, json]
         --graph [<GRAPH>]
-            Generate a graph of the task execution and output to a file when a filename is specified (.svg, .png, .jpg, .pdf, .json, .html, .mermaid, .dot). Outputs dot graph to stdout when if no filename is provided
+            Generate a graph of the task execution and output to a file when a filename is specified (.svg, .png, .jpg, .pdf, .json, .html, .mermaid, .dot). Outputs dot graph to stdout when if no filename is provided. An .html graph is written after the tasks run and shows how each of them ran
         --no-cache
             Avoid saving task results to the cache. Useful for development/watch tasks
         --daemon
@@ -83,8 +90,12 @@ Make sure exit code is 2 when no args are passed
             File to write turbo's performance profile output into. All identifying data omitted from the profile
         --remote-cache-read-only [<REMOTE_CACHE_READ_ONLY>]
             Treat remote cache as read only [possible values: true, false]
+        --remote-cache-write-only [<REMOTE_CACHE_WRITE_ONLY>]
+            Treat remote cache as write only [possible values: true, false]
         --summarize [<SUMMARIZE>]
             Generate a summary of the turbo run [possible values: true, false]
+        --output-format <OUTPUT_FORMAT>
+            Write reports of how each task ran to `.turbo/`. Separate multiple formats with commas [possible values: junit, html]
         --parallel
             Execute all tasks in parallel
         --cache-dir <CACHE_DIR>
@@ -117,6 +128,8 @@ Make sure exit code is 2 when no args are passed
             Ignore the local filesystem cache for all tasks. Only allow reading and caching artifacts using the remote cache [possible values: true, false]
         --log-prefix <LOG_PREFIX>
             Use "none" to remove prefixes from task logs. Use "task" to get task id prefixing. Use "auto" to let turbo decide how to prefix the logs based on the execution environment. In most cases this will be the same as "task". Note that tasks running in parallel interleave their logs, so removing prefixes can make it difficult to associate logs with tasks. Use --log-order=grouped to prevent interleaving. (default auto) [default: auto] [possible values: auto, none, task]
+        --log-format <LOG_FORMAT>
+            Use "ndjson" to write each line of output from turbo and from tasks as a JSON object with a timestamp, package, task, stream and sequence number, for log aggregation pipelines. (default text) [possible values: text, ndjson]
   [1]
 
 Run without any tasks, get a list of potential tasks to run
@@ -0,0 +1,24 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh monorepo_with_root_dep pnpm@7.25.1
+
+Pruning several workspaces produces a single output with the union of their dependencies
+  $ ${TURBO} prune docs web
+  Generating pruned monorepo for docs, web in .*(\/|\\)out (re)
+   - Added docs
+   - Added shared
+   - Added util
+   - Added web
+  $ ls out/apps
+  docs
+  web
+  $ grep "/is-number/" out/pnpm-lock.yaml
+    /is-number/7.0.0_4fcx2ubzko3upkndnus4sjwpd4:
+
+Workspaces can also be passed with repeated --scope flags and duplicates are ignored
+  $ rm -rf out
+  $ ${TURBO} prune --scope=docs --scope=web --scope=docs
+  Generating pruned monorepo for docs, web in .*(\/|\\)out (re)
+   - Added docs
+   - Added shared
+   - Added util
+   - Added web
@@ -0,0 +1,31 @@
+Setup
+  $ . ${TESTDIR}/../../../helpers/setup_integration_test.sh monorepo_with_root_dep pnpm@7.25.1
+
+Test that the pruned monorepo can be written to a tarball
+  $ ${TURBO} prune web --tar=web.tar.gz
+  Generating pruned monorepo for web in .*web.tar.gz (re)
+   - Added shared
+   - Added util
+   - Added web
+  $ tar -tzf web.tar.gz
+  apps/
+  apps/web/
+  apps/web/package.json
+  package.json
+  packages/
+  packages/shared/
+  packages/shared/package.json
+  packages/util/
+  packages/util/package.json
+  patches/
+  patches/is-number@7.0.0.patch
+  pnpm-lock.yaml
+  pnpm-workspace.yaml
+  turbo.json
+  $ test -d out
+  [1]
+
+Test that the tarball doesn't change when only timestamps do
+  $ sleep 1 && touch apps/web/package.json
+  $ ${TURBO} prune web --tar=again.tar.gz > /dev/null
+  $ cmp web.tar.gz again.tar.gz
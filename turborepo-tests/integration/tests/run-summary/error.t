@@ -38,6 +38,7 @@ Validate that we got a full task summary for the failed task with an error in .e
       "package.json": "1746e0db2361085b5953a6a3beab08c24af5bc08"
     },
     "hashOfExternalDependencies": "459c029558afe716",
+    "externalDependencies": [],
     "cache": {
       "local": false,
       "remote": false,
@@ -57,6 +58,7 @@ Validate that we got a full task summary for the failed task with an error in .e
       "cache": true,
       "dependsOn": [],
       "inputs": [],
+      "dotfiles": true,
       "outputLogs": "full",
       "persistent": false,
       "interruptible": false,
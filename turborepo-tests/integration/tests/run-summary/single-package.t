@@ -77,6 +77,7 @@ Check
     "excludedOutputs",
     "execution",
     "expandedOutputs",
+    "externalDependencies",
     "framework",
     "hash",
     "hashOfExternalDependencies",
@@ -18,6 +18,15 @@ Setup
   
   [1]
 
+# Misspelled tasks suggest the closest task
+  $ ${TURBO} run biuld
+    x missing tasks in project
+  
+  Error:   x could not find task `biuld` in project
+    help: did you mean `build`?
+  
+  [1]
+
 # One good and one bad task does not error
   $ ${TURBO} run build doesnotexist
     x missing tasks in project
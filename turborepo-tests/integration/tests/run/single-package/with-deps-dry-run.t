@@ -27,6 +27,7 @@ Check
     Dependencies                   =\s* (re)
     Dependents                     = test\s* (re)
     Inputs Files Considered        = 5\s* (re)
+    External Dependencies          = 0\s* (re)
     Env Vars                       = 
     Env Vars Values                = 
     Inferred Env Vars Values       = 
@@ -45,6 +46,7 @@ Check
     Dependencies                   = build\s* (re)
     Dependents                     =\s* (re)
     Inputs Files Considered        = 5\s* (re)
+    External Dependencies          = 0\s* (re)
     Env Vars                       = 
     Env Vars Values                = 
     Inferred Env Vars Values       = 
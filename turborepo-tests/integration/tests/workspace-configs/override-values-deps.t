@@ -30,6 +30,7 @@ Setup
     "cache": true,
     "dependsOn": [],
     "inputs": [],
+    "dotfiles": true,
     "outputLogs": "full",
     "persistent": false,
     "interruptible": false,
@@ -46,6 +47,7 @@ Setup
     "cache": true,
     "dependsOn": [],
     "inputs": [],
+    "dotfiles": true,
     "outputLogs": "full",
     "persistent": false,
     "interruptible": false,